@@ -28,12 +28,16 @@ pub enum Error {
 	RestClient(#[from] itc_rest_client::error::Error),
 	#[error("Could not retrieve any data from {0} for {1}")]
 	NoValidData(String, String),
+	#[error("Could not parse the response of {0}: {1}")]
+	InvalidResponse(String, String),
 	#[error("Value for exchange rate is null")]
 	EmptyExchangeRate(TradingPair),
 	#[error("Invalid id for crypto currency")]
 	InvalidCryptoCurrencyId,
 	#[error("Invalid id for fiat currency")]
 	InvalidFiatCurrencyId,
+	#[error("Only {agreeing} of {queried} sources agree on the exchange rate of {trading_pair}")]
+	NoConsensus { trading_pair: String, agreeing: usize, queried: usize },
 	#[error(transparent)]
 	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -32,9 +32,18 @@ pub mod sgx_reexport_prelude {
 	pub use url_sgx as url;
 }
 
-use crate::{error::Error, metrics_exporter::MetricsExporter};
+use crate::{
+	error::Error,
+	metrics_exporter::MetricsExporter,
+	oracles::{
+		aggregated_exchange_rate_oracle::{
+			AggregatedExchangeRateOracle, AggregationConfig, DEFAULT_MIN_AGREEING_SOURCES,
+		},
+		exchange_rate_oracle::GetExchangeRate,
+	},
+};
 use itp_ocall_api::EnclaveMetricsOCallApi;
-use std::sync::Arc;
+use std::{boxed::Box, cmp::min, sync::Arc, vec, vec::Vec};
 
 pub mod error;
 pub mod metrics_exporter;
@@ -42,11 +51,17 @@ pub mod traits;
 pub mod types;
 
 pub mod oracles;
-pub use oracles::{exchange_rate_oracle::ExchangeRateOracle, weather_oracle::WeatherOracle};
+pub use oracles::{
+	exchange_rate_oracle::ExchangeRateOracle, spot_price_oracle::SpotPriceOracle,
+	weather_oracle::WeatherOracle,
+};
 
 pub mod oracle_sources;
 pub use oracle_sources::{
-	coin_gecko::CoinGeckoSource, coin_market_cap::CoinMarketCapSource,
+	coin_gecko::CoinGeckoSource,
+	coin_market_cap::CoinMarketCapSource,
+	configurable_source::{ConfigurableSource, ConfigurableSourceConfig},
+	energy_charts::EnergyChartsSource,
 	weather_oracle_source::WeatherOracleSource,
 };
 
@@ -62,9 +77,15 @@ pub type CoinGeckoExchangeRateOracle<OCallApi> =
 pub type CoinMarketCapExchangeRateOracle<OCallApi> =
 	ExchangeRateOracle<CoinMarketCapSource, MetricsExporter<OCallApi>>;
 
+pub type ConfigurableExchangeRateOracle<OCallApi> =
+	ExchangeRateOracle<ConfigurableSource, MetricsExporter<OCallApi>>;
+
 pub type OpenMeteoWeatherOracle<OCallApi> =
 	WeatherOracle<WeatherOracleSource, MetricsExporter<OCallApi>>;
 
+pub type EnergyChartsSpotPriceOracle<OCallApi> =
+	SpotPriceOracle<EnergyChartsSource, MetricsExporter<OCallApi>>;
+
 pub fn create_coin_gecko_oracle<OCallApi: EnclaveMetricsOCallApi>(
 	ocall_api: Arc<OCallApi>,
 ) -> CoinGeckoExchangeRateOracle<OCallApi> {
@@ -77,8 +98,44 @@ pub fn create_coin_market_cap_oracle<OCallApi: EnclaveMetricsOCallApi>(
 	ExchangeRateOracle::new(CoinMarketCapSource {}, Arc::new(MetricsExporter::new(ocall_api)))
 }
 
+pub fn create_configurable_oracle<OCallApi: EnclaveMetricsOCallApi>(
+	config: ConfigurableSourceConfig,
+	ocall_api: Arc<OCallApi>,
+) -> ConfigurableExchangeRateOracle<OCallApi> {
+	ExchangeRateOracle::new(
+		ConfigurableSource::new(config),
+		Arc::new(MetricsExporter::new(ocall_api)),
+	)
+}
+
 pub fn create_open_meteo_weather_oracle<OCallApi: EnclaveMetricsOCallApi>(
 	ocall_api: Arc<OCallApi>,
 ) -> OpenMeteoWeatherOracle<OCallApi> {
 	WeatherOracle::new(WeatherOracleSource {}, Arc::new(MetricsExporter::new(ocall_api)))
 }
+
+pub fn create_energy_charts_spot_price_oracle<OCallApi: EnclaveMetricsOCallApi>(
+	ocall_api: Arc<OCallApi>,
+) -> EnergyChartsSpotPriceOracle<OCallApi> {
+	SpotPriceOracle::new(EnergyChartsSource {}, Arc::new(MetricsExporter::new(ocall_api)))
+}
+
+/// Aggregates the exchange rates of all supported sources, or only those of the configured
+/// source if there is one. A single configured source is trusted on its own.
+pub fn create_aggregated_exchange_rate_oracle<OCallApi: EnclaveMetricsOCallApi + 'static>(
+	ocall_api: Arc<OCallApi>,
+	configured_source: Option<ConfigurableSourceConfig>,
+) -> AggregatedExchangeRateOracle<MetricsExporter<OCallApi>> {
+	let oracles: Vec<Box<dyn GetExchangeRate>> = match configured_source {
+		Some(config) => vec![Box::new(create_configurable_oracle(config, ocall_api.clone()))],
+		None => vec![
+			Box::new(create_coin_gecko_oracle(ocall_api.clone())),
+			Box::new(create_coin_market_cap_oracle(ocall_api.clone())),
+		],
+	};
+	let config = AggregationConfig {
+		min_agreeing_sources: min(DEFAULT_MIN_AGREEING_SOURCES, oracles.len()),
+		..Default::default()
+	};
+	AggregatedExchangeRateOracle::new(oracles, Arc::new(MetricsExporter::new(ocall_api)), config)
+}
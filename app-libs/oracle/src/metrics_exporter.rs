@@ -15,7 +15,7 @@
 
 */
 
-use crate::types::{ExchangeRate, TradingPair};
+use crate::types::{ExchangeRate, ExchangeRateDeviation, TradingPair};
 use itp_enclave_metrics::{EnclaveMetric, ExchangeRateOracleMetric, OracleMetric};
 use itp_ocall_api::EnclaveMetricsOCallApi;
 use log::error;
@@ -34,6 +34,13 @@ pub trait ExportMetrics<MetricsInfo> {
 		trading_pair: TradingPair,
 	);
 
+	fn update_exchange_rate_deviation(
+		&self,
+		source: String,
+		deviation: ExchangeRateDeviation,
+		trading_pair: TradingPair,
+	);
+
 	fn update_weather(&self, source: String, metrics_info: MetricsInfo);
 }
 
@@ -98,6 +105,19 @@ where
 		));
 	}
 
+	fn update_exchange_rate_deviation(
+		&self,
+		source: String,
+		deviation: ExchangeRateDeviation,
+		trading_pair: TradingPair,
+	) {
+		self.update_metric(ExchangeRateOracleMetric::ExchangeRateDeviation(
+			source,
+			trading_pair.key(),
+			deviation,
+		));
+	}
+
 	fn update_weather(&self, _source: String, _metrics_info: MetricsInfo) {
 		// TODO: Implement me
 	}
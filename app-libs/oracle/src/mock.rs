@@ -24,13 +24,14 @@ use std::sync::RwLock;
 use crate::{
 	error::Error,
 	metrics_exporter::ExportMetrics,
-	traits::OracleSource,
-	types::{ExchangeRate, TradingPair},
+	traits::{OracleFeed, OracleRequest, OracleSource},
+	types::{ExchangeRate, ExchangeRateDeviation, TradingPair},
 };
 use itc_rest_client::{
 	http_client::{HttpClient, SendWithCertificateVerification},
 	rest_client::RestClient,
 };
+use serde_json::Value;
 use std::{
 	time::{Duration, Instant},
 	vec,
@@ -44,6 +45,7 @@ pub(crate) struct MetricsExporterMock {
 	number_requests: RwLock<u64>,
 	response_times: RwLock<Vec<u128>>,
 	exchange_rates: RwLock<Vec<(TradingPair, ExchangeRate)>>,
+	deviations: RwLock<Vec<(String, ExchangeRateDeviation)>>,
 }
 
 impl MetricsExporterMock {
@@ -58,6 +60,10 @@ impl MetricsExporterMock {
 	pub fn get_exchange_rates(&self) -> Vec<(TradingPair, ExchangeRate)> {
 		self.exchange_rates.read().unwrap().clone()
 	}
+
+	pub fn get_deviations(&self) -> Vec<(String, ExchangeRateDeviation)> {
+		self.deviations.read().unwrap().clone()
+	}
 }
 
 impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
@@ -78,6 +84,15 @@ impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
 		self.exchange_rates.write().unwrap().push((trading_pair, exchange_rate));
 	}
 
+	fn update_exchange_rate_deviation(
+		&self,
+		source: String,
+		deviation: ExchangeRateDeviation,
+		_trading_pair: TradingPair,
+	) {
+		self.deviations.write().unwrap().push((source, deviation));
+	}
+
 	fn update_weather(&self, _source: String, _metrics_info: MetricsInfo) {}
 }
 
@@ -85,9 +100,7 @@ impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
 #[derive(Default)]
 pub(crate) struct OracleSourceMock;
 
-impl<OracleSourceInfo> OracleSource<OracleSourceInfo> for OracleSourceMock {
-	type OracleRequestResult = Result<f32, Error>;
-
+impl OracleSource for OracleSourceMock {
 	fn metrics_id(&self) -> String {
 		"source_mock".to_string()
 	}
@@ -103,18 +116,24 @@ impl<OracleSourceInfo> OracleSource<OracleSourceInfo> for OracleSourceMock {
 	fn root_certificates_content(&self) -> Vec<String> {
 		vec!["MOCK_CERTIFICATE".to_string()]
 	}
-	fn execute_exchange_rate_request(
-		&self,
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		_trading_pair: TradingPair,
-	) -> Result<ExchangeRate, Error> {
+}
+
+impl OracleFeed<TradingPair> for OracleSourceMock {
+	type Value = ExchangeRate;
+
+	fn request(&self, _trading_pair: &TradingPair) -> Result<OracleRequest, Error> {
+		Ok(OracleRequest { path: "mock".to_string(), query: Vec::new() })
+	}
+
+	fn parse(&self, _response: Value, _trading_pair: &TradingPair) -> Result<ExchangeRate, Error> {
 		Ok(ExchangeRate::from_num(42.3f32))
 	}
 
 	fn execute_request(
+		&self,
 		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		_source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult {
-		Ok(42.3f32)
+		trading_pair: &TradingPair,
+	) -> Result<ExchangeRate, Error> {
+		self.parse(Value::Null, trading_pair)
 	}
 }
@@ -20,17 +20,13 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	error::Error,
-	traits::OracleSource,
-	types::{ExchangeRate, TradingInfo, TradingPair},
-};
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-	RestGet, RestPath,
+	oracle_sources::parse_utc_timestamp,
+	traits::{OracleFeed, OracleRequest, OracleSource},
+	types::{ExchangeRate, TradingPair},
 };
 use lazy_static::lazy_static;
-use log::{debug, error};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
 	collections::HashMap,
 	string::{String, ToString},
@@ -72,9 +68,7 @@ impl CoinGeckoSource {
 	}
 }
 
-impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for CoinGeckoSource {
-	type OracleRequestResult = Result<(), Error>;
-
+impl OracleSource for CoinGeckoSource {
 	fn metrics_id(&self) -> String {
 		"coin_gecko".to_string()
 	}
@@ -93,48 +87,43 @@ impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for Coi
 			COINGECKO_ROOT_CERTIFICATE_BALTIMORE.to_string(),
 		]
 	}
+}
 
-	fn execute_request(
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult {
-		let _trading_info: TradingInfo = source_info.into();
-		// TODO Implement me
-		Ok(())
-	}
+impl OracleFeed<TradingPair> for CoinGeckoSource {
+	type Value = ExchangeRate;
 
-	fn execute_exchange_rate_request(
-		&self,
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		trading_pair: TradingPair,
-	) -> Result<ExchangeRate, Error> {
+	fn request(&self, trading_pair: &TradingPair) -> Result<OracleRequest, Error> {
 		let fiat_id = trading_pair.fiat_currency.clone();
-		let crypto_id = Self::map_crypto_currency_id(&trading_pair)?;
-
-		let response = rest_client.get_with::<String, CoinGeckoMarket>(
-			COINGECKO_PATH.to_string(),
-			&[(COINGECKO_PARAM_CURRENCY, &fiat_id), (COINGECKO_PARAM_COIN, &crypto_id)],
-		);
+		let crypto_id = Self::map_crypto_currency_id(trading_pair)?;
 
-		let response = match response {
-			Ok(response) => response,
-			Err(e) => {
-				error!("coingecko execute_exchange_rate_request() failed with: {:?}", &e);
-				return Err(Error::RestClient(e))
-			},
-		};
+		Ok(OracleRequest {
+			path: COINGECKO_PATH.to_string(),
+			query: vec![
+				(COINGECKO_PARAM_CURRENCY.to_string(), fiat_id),
+				(COINGECKO_PARAM_COIN.to_string(), crypto_id),
+			],
+		})
+	}
 
-		debug!("coingecko received response: {:?}", &response);
-		let list = response.0;
+	fn parse(&self, response: Value, trading_pair: &TradingPair) -> Result<ExchangeRate, Error> {
+		let list: Vec<CoinGeckoMarketStruct> = serde_json::from_value(response)
+			.map_err(|e| Error::InvalidResponse(COINGECKO_URL.to_string(), e.to_string()))?;
 		if list.is_empty() {
-			return Err(Error::NoValidData(COINGECKO_URL.to_string(), trading_pair.key()))
+			return Err(Error::NoValidData(COINGECKO_URL.to_string(), trading_pair.clone().key()))
 		}
 
 		match list[0].current_price {
 			Some(r) => Ok(ExchangeRate::from_num(r)),
-			None => Err(Error::EmptyExchangeRate(trading_pair)),
+			None => Err(Error::EmptyExchangeRate(trading_pair.clone())),
 		}
 	}
+
+	fn last_updated(&self, response: &Value, _trading_pair: &TradingPair) -> Option<Duration> {
+		response
+			.pointer("/0/last_updated")
+			.and_then(Value::as_str)
+			.and_then(parse_utc_timestamp)
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -146,15 +135,6 @@ struct CoinGeckoMarketStruct {
 	last_updated: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct CoinGeckoMarket(pub Vec<CoinGeckoMarketStruct>);
-
-impl RestPath<String> for CoinGeckoMarket {
-	fn get_path(path: String) -> Result<String, itc_rest_client::error::Error> {
-		Ok(path)
-	}
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -214,6 +194,20 @@ mod tests {
 		assert_matches!(result, Err(Error::RestClient(_)));
 	}
 
+	#[test]
+	fn last_updated_is_read_from_the_first_market() {
+		let trading_pair =
+			TradingPair { crypto_currency: "DOT".to_string(), fiat_currency: "USD".to_string() };
+		let response = serde_json::json!([
+			{"id": "polkadot", "current_price": 5.2, "last_updated": "1970-01-01T00:01:00.525Z"}
+		]);
+
+		assert_eq!(
+			CoinGeckoSource {}.last_updated(&response, &trading_pair),
+			Some(Duration::from_secs(60))
+		);
+	}
+
 	fn create_coin_gecko_client() -> TestCoinGeckoClient {
 		TestCoinGeckoClient::new(CoinGeckoSource {}, Arc::new(MetricsExporterMock::default()))
 	}
@@ -19,16 +19,13 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	error::Error,
-	traits::OracleSource,
-	types::{ExchangeRate, TradingInfo, TradingPair},
-};
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-	RestGet, RestPath,
+	oracle_sources::parse_utc_timestamp,
+	traits::{OracleFeed, OracleRequest, OracleSource},
+	types::{ExchangeRate, TradingPair},
 };
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
 	collections::{BTreeMap, HashMap},
 	env,
@@ -76,10 +73,7 @@ impl CoinMarketCapSource {
 	}
 }
 
-impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for CoinMarketCapSource {
-	// TODO Change this to return something useful?
-	type OracleRequestResult = Result<(), Error>;
-
+impl OracleSource for CoinMarketCapSource {
 	fn metrics_id(&self) -> String {
 		"coin_market_cap".to_string()
 	}
@@ -95,58 +89,64 @@ impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for Coi
 	fn root_certificates_content(&self) -> Vec<String> {
 		vec![COINMARKETCAP_ROOT_CERTIFICATE.to_string()]
 	}
+}
+
+impl OracleFeed<TradingPair> for CoinMarketCapSource {
+	type Value = ExchangeRate;
+
+	fn request(&self, trading_pair: &TradingPair) -> Result<OracleRequest, Error> {
+		let fiat_id = Self::map_fiat_currency_id(trading_pair)?;
+		let crypto_id = Self::map_crypto_currency_id(trading_pair)?;
+
+		Ok(OracleRequest {
+			path: COINMARKETCAP_PATH.to_string(),
+			query: vec![
+				(FIAT_CURRENCY_PARAM.to_string(), fiat_id),
+				(CRYPTO_CURRENCY_PARAM.to_string(), crypto_id),
+				(COINMARKETCAP_KEY_PARAM.to_string(), COINMARKETCAP_KEY.clone()),
+			],
+		})
+	}
+
+	fn parse(&self, response: Value, trading_pair: &TradingPair) -> Result<ExchangeRate, Error> {
+		let fiat_id = Self::map_fiat_currency_id(trading_pair)?;
+		let crypto_id = Self::map_crypto_currency_id(trading_pair)?;
 
-	fn execute_request(
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult {
-		let trading_info: TradingInfo = source_info.into();
-		let _fiat_currency = trading_info.trading_pair.fiat_currency;
-		let _crypto_currency = trading_info.trading_pair.crypto_currency;
-		// TODO Implement me
-		Ok(())
-	}
-
-	fn execute_exchange_rate_request(
-		&self,
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		trading_pair: TradingPair,
-	) -> Result<ExchangeRate, Error> {
-		let fiat_id = Self::map_fiat_currency_id(&trading_pair)?;
-		let crypto_id = Self::map_crypto_currency_id(&trading_pair)?;
-
-		let response = rest_client
-			.get_with::<String, CoinMarketCapMarket>(
-				COINMARKETCAP_PATH.to_string(),
-				&[
-					(FIAT_CURRENCY_PARAM, &fiat_id),
-					(CRYPTO_CURRENCY_PARAM, &crypto_id),
-					(COINMARKETCAP_KEY_PARAM, &COINMARKETCAP_KEY),
-				],
-			)
-			.map_err(Error::RestClient)?;
-
-		let data_struct = response.0;
+		let data_struct: CoinMarketCapMarketStruct = serde_json::from_value(response)
+			.map_err(|e| Error::InvalidResponse(COINMARKETCAP_URL.to_string(), e.to_string()))?;
 
 		let data = match data_struct.data.get(&crypto_id) {
 			Some(d) => d,
 			None =>
 				return Err(Error::NoValidData(
 					COINMARKETCAP_URL.to_string(),
-					trading_pair.crypto_currency,
+					trading_pair.crypto_currency.clone(),
 				)),
 		};
 
 		let quote = match data.quote.get(&fiat_id) {
 			Some(q) => q,
 			None =>
-				return Err(Error::NoValidData(COINMARKETCAP_URL.to_string(), trading_pair.key())),
+				return Err(Error::NoValidData(
+					COINMARKETCAP_URL.to_string(),
+					trading_pair.clone().key(),
+				)),
 		};
 		match quote.price {
 			Some(r) => Ok(ExchangeRate::from_num(r)),
-			None => Err(Error::EmptyExchangeRate(trading_pair)),
+			None => Err(Error::EmptyExchangeRate(trading_pair.clone())),
 		}
 	}
+
+	fn last_updated(&self, response: &Value, trading_pair: &TradingPair) -> Option<Duration> {
+		let fiat_id = Self::map_fiat_currency_id(trading_pair).ok()?;
+		let crypto_id = Self::map_crypto_currency_id(trading_pair).ok()?;
+
+		response
+			.pointer(&format!("/data/{}/quote/{}/last_updated", crypto_id, fiat_id))
+			.and_then(Value::as_str)
+			.and_then(parse_utc_timestamp)
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -168,15 +168,6 @@ struct CoinMarketCapMarketStruct {
 	data: BTreeMap<String, DataStruct>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct CoinMarketCapMarket(pub CoinMarketCapMarketStruct);
-
-impl RestPath<String> for CoinMarketCapMarket {
-	fn get_path(path: String) -> Result<String, itc_rest_client::error::Error> {
-		Ok(path)
-	}
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -236,6 +227,20 @@ mod tests {
 		assert_matches!(result, Err(Error::InvalidFiatCurrencyId));
 	}
 
+	#[test]
+	fn last_updated_is_read_from_the_quote() {
+		let trading_pair =
+			TradingPair { crypto_currency: "DOT".to_string(), fiat_currency: "USD".to_string() };
+		let response = serde_json::json!({
+			"data": {"6636": {"quote": {"2781": {"price": 5.2, "last_updated": "1970-01-01T00:01:00.000Z"}}}}
+		});
+
+		assert_eq!(
+			CoinMarketCapSource {}.last_updated(&response, &trading_pair),
+			Some(Duration::from_secs(60))
+		);
+	}
+
 	fn create_client() -> TestClient {
 		TestClient::new(CoinMarketCapSource {}, Arc::new(MetricsExporterMock::default()))
 	}
@@ -0,0 +1,228 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	error::Error,
+	traits::{OracleFeed, OracleRequest, OracleSource},
+	types::{ExchangeRate, TradingPair},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	string::{String, ToString},
+	time::Duration,
+	vec::Vec,
+};
+use url::Url;
+
+const CRYPTO_CURRENCY_PLACEHOLDER: &str = "{crypto_currency}";
+const FIAT_CURRENCY_PLACEHOLDER: &str = "{fiat_currency}";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3u64);
+
+/// Configuration of a [`ConfigurableSource`].
+///
+/// The placeholders `{crypto_currency}` and `{fiat_currency}` in `path_template` and
+/// `value_pointer` are replaced with the currencies of the requested trading pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigurableSourceConfig {
+	/// Id of the source in the metrics.
+	pub metrics_id: String,
+	pub base_url: String,
+	/// Path of the request relative to `base_url`, e.g. `api/{crypto_currency}/{fiat_currency}`.
+	pub path_template: String,
+	/// JSON pointer (RFC 6901) to the exchange rate in the response, e.g. `/data/0/price`.
+	/// The exchange rate may be a JSON number or a string containing a number.
+	pub value_pointer: String,
+	/// PEM encoded root certificate of the server, empty for a plain HTTP server.
+	pub root_certificate: String,
+	#[serde(default)]
+	pub request_timeout: Option<Duration>,
+}
+
+impl ConfigurableSourceConfig {
+	/// Parses the configuration from its JSON representation, as passed in by the worker.
+	pub fn from_json(json: &str) -> Result<Self, Error> {
+		serde_json::from_str(json).map_err(|e| Error::Other(format!("{:?}", e).into()))
+	}
+}
+
+impl Default for ConfigurableSourceConfig {
+	/// Local stand-in server, its root certificate needs to be configured for HTTPS.
+	fn default() -> Self {
+		ConfigurableSourceConfig {
+			metrics_id: "configurable".to_string(),
+			base_url: "https://localhost:8443".to_string(),
+			path_template: "exchange_rate/{crypto_currency}/{fiat_currency}".to_string(),
+			value_pointer: "/exchange_rate".to_string(),
+			root_certificate: String::new(),
+			request_timeout: None,
+		}
+	}
+}
+
+/// Exchange rate oracle source, whose server and response format are configured at runtime.
+///
+/// Allows running the oracle flows against any server, e.g. a local stand-in server in tests.
+#[derive(Default)]
+pub struct ConfigurableSource {
+	config: ConfigurableSourceConfig,
+}
+
+impl ConfigurableSource {
+	pub fn new(config: ConfigurableSourceConfig) -> Self {
+		ConfigurableSource { config }
+	}
+
+	fn path(&self, trading_pair: &TradingPair) -> String {
+		replace_placeholders(&self.config.path_template, trading_pair)
+	}
+
+	fn extract_exchange_rate(
+		&self,
+		response: &Value,
+		trading_pair: &TradingPair,
+	) -> Result<ExchangeRate, Error> {
+		let pointer = replace_placeholders(&self.config.value_pointer, trading_pair);
+		let value = match response.pointer(&pointer) {
+			Some(Value::Number(number)) => number.as_f64(),
+			Some(Value::String(string)) => string.parse::<f64>().ok(),
+			Some(Value::Null) => return Err(Error::EmptyExchangeRate(trading_pair.clone())),
+			_ => None,
+		};
+
+		value
+			.and_then(ExchangeRate::checked_from_num)
+			.ok_or_else(|| Error::NoValidData(self.config.base_url.clone(), trading_pair.key()))
+	}
+}
+
+fn replace_placeholders(template: &str, trading_pair: &TradingPair) -> String {
+	template
+		.replace(CRYPTO_CURRENCY_PLACEHOLDER, &trading_pair.crypto_currency)
+		.replace(FIAT_CURRENCY_PLACEHOLDER, &trading_pair.fiat_currency)
+}
+
+impl OracleSource for ConfigurableSource {
+	fn metrics_id(&self) -> String {
+		self.config.metrics_id.clone()
+	}
+
+	fn request_timeout(&self) -> Option<Duration> {
+		Some(self.config.request_timeout.unwrap_or(DEFAULT_TIMEOUT))
+	}
+
+	fn base_url(&self) -> Result<Url, Error> {
+		Url::parse(&self.config.base_url).map_err(|e| Error::Other(format!("{:?}", e).into()))
+	}
+
+	/// No root certificate is needed for a plain HTTP server.
+	fn root_certificates_content(&self) -> Vec<String> {
+		if self.config.root_certificate.is_empty() {
+			return Vec::new()
+		}
+		vec![self.config.root_certificate.clone()]
+	}
+}
+
+impl OracleFeed<TradingPair> for ConfigurableSource {
+	type Value = ExchangeRate;
+
+	fn request(&self, trading_pair: &TradingPair) -> Result<OracleRequest, Error> {
+		Ok(OracleRequest { path: self.path(trading_pair), query: Vec::new() })
+	}
+
+	fn parse(&self, response: Value, trading_pair: &TradingPair) -> Result<ExchangeRate, Error> {
+		self.extract_exchange_rate(&response, trading_pair)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn trading_pair() -> TradingPair {
+		TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "USD".to_string() }
+	}
+
+	fn source(value_pointer: &str) -> ConfigurableSource {
+		ConfigurableSource::new(ConfigurableSourceConfig {
+			value_pointer: value_pointer.to_string(),
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn config_is_parsed_from_json_without_request_timeout() {
+		let config = ConfigurableSourceConfig::from_json(
+			r#"{"metrics_id": "configurable", "base_url": "http://localhost:8080",
+			"path_template": "rates/{crypto_currency}", "value_pointer": "/{fiat_currency}",
+			"root_certificate": ""}"#,
+		)
+		.unwrap();
+
+		assert_eq!(config.base_url, "http://localhost:8080");
+		assert_eq!(config.path_template, "rates/{crypto_currency}");
+		assert_eq!(config.request_timeout, None);
+		assert!(ConfigurableSourceConfig::from_json(r#"{"base_url": "http://localhost"}"#).is_err());
+	}
+
+	#[test]
+	fn placeholders_are_replaced() {
+		assert_eq!(source("").path(&trading_pair()), "exchange_rate/TEER/USD");
+	}
+
+	#[test]
+	fn exchange_rate_is_extracted_from_number_or_string() {
+		let response = json!({ "data": { "TEER": { "USD": 0.25, "CHF": "0.5" } } });
+
+		assert_eq!(
+			source("/data/{crypto_currency}/{fiat_currency}")
+				.extract_exchange_rate(&response, &trading_pair())
+				.unwrap(),
+			ExchangeRate::from_num(0.25)
+		);
+
+		let trading_pair =
+			TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "CHF".to_string() };
+		assert_eq!(
+			source("/data/{crypto_currency}/{fiat_currency}")
+				.extract_exchange_rate(&response, &trading_pair)
+				.unwrap(),
+			ExchangeRate::from_num(0.5)
+		);
+	}
+
+	#[test]
+	fn missing_or_invalid_exchange_rate_is_rejected() {
+		let response = json!({ "price": -1.0, "empty": null, "text": "abc" });
+
+		for pointer in ["/price", "/text", "/missing"] {
+			assert!(matches!(
+				source(pointer).extract_exchange_rate(&response, &trading_pair()),
+				Err(Error::NoValidData(..))
+			));
+		}
+		assert!(matches!(
+			source("/empty").extract_exchange_rate(&response, &trading_pair()),
+			Err(Error::EmptyExchangeRate(_))
+		));
+	}
+}
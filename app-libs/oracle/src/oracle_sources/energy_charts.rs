@@ -0,0 +1,154 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	error::Error,
+	traits::{OracleFeed, OracleRequest, OracleSource},
+	types::{HourlySpotPrice, SpotPrice, SpotPriceQuery, SpotPrices},
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	string::{String, ToString},
+	time::Duration,
+	vec::Vec,
+};
+use url::Url;
+
+const ENERGY_CHARTS_URL: &str = "https://api.energy-charts.info";
+const ENERGY_CHARTS_PATH: &str = "price";
+const ENERGY_CHARTS_PARAM_BIDDING_ZONE: &str = "bzn";
+const ENERGY_CHARTS_PARAM_START: &str = "start";
+const ENERGY_CHARTS_PARAM_END: &str = "end";
+const ENERGY_CHARTS_TIMEOUT: Duration = Duration::from_secs(3u64);
+const ENERGY_CHARTS_ROOT_CERTIFICATE: &str =
+	include_str!("../certificates/lets_encrypt_root_cert.pem");
+
+/// Day-ahead spot prices of the Energy-Charts API of Fraunhofer ISE.
+#[derive(Default)]
+pub struct EnergyChartsSource;
+
+impl OracleSource for EnergyChartsSource {
+	fn metrics_id(&self) -> String {
+		"energy_charts".to_string()
+	}
+
+	fn request_timeout(&self) -> Option<Duration> {
+		Some(ENERGY_CHARTS_TIMEOUT)
+	}
+
+	fn base_url(&self) -> Result<Url, Error> {
+		Url::parse(ENERGY_CHARTS_URL).map_err(|e| Error::Other(format!("{:?}", e).into()))
+	}
+
+	/// The server's root certificate. A valid certificate is required to open a tls connection
+	fn root_certificates_content(&self) -> Vec<String> {
+		vec![ENERGY_CHARTS_ROOT_CERTIFICATE.to_string()]
+	}
+}
+
+impl OracleFeed<SpotPriceQuery> for EnergyChartsSource {
+	type Value = SpotPrices;
+
+	fn request(&self, query: &SpotPriceQuery) -> Result<OracleRequest, Error> {
+		Ok(OracleRequest {
+			path: ENERGY_CHARTS_PATH.to_string(),
+			query: vec![
+				(ENERGY_CHARTS_PARAM_BIDDING_ZONE.to_string(), query.bidding_zone.clone()),
+				(ENERGY_CHARTS_PARAM_START.to_string(), query.start_date.clone()),
+				(ENERGY_CHARTS_PARAM_END.to_string(), query.end_date.clone()),
+			],
+		})
+	}
+
+	fn parse(&self, response: Value, query: &SpotPriceQuery) -> Result<SpotPrices, Error> {
+		let response: EnergyChartsPrices = serde_json::from_value(response)
+			.map_err(|e| Error::InvalidResponse(ENERGY_CHARTS_URL.to_string(), e.to_string()))?;
+		response.into_spot_prices(query.clone().key())
+	}
+}
+
+/// Prices of the day-ahead auction, a price may be null if it is not available yet.
+#[derive(Serialize, Deserialize, Debug)]
+struct EnergyChartsPrices {
+	unix_seconds: Vec<u64>,
+	price: Vec<Option<f64>>,
+}
+
+impl EnergyChartsPrices {
+	/// Converts the prices into fixed point types. Hours without a price are skipped.
+	fn into_spot_prices(self, query_key: String) -> Result<SpotPrices, Error> {
+		if self.unix_seconds.len() != self.price.len() {
+			return Err(Error::NoValidData(ENERGY_CHARTS_URL.to_string(), query_key))
+		}
+
+		let mut hourly = Vec::with_capacity(self.price.len());
+		for (start, price) in self.unix_seconds.into_iter().zip(self.price) {
+			let price = match price {
+				Some(price) => price,
+				None => {
+					debug!("Skipping {}, the spot price is not available", start);
+					continue
+				},
+			};
+			let price = SpotPrice::checked_from_num(price).ok_or_else(|| {
+				Error::NoValidData(ENERGY_CHARTS_URL.to_string(), query_key.clone())
+			})?;
+			hourly.push(HourlySpotPrice { start, price });
+		}
+		hourly.sort_by_key(|p| p.start);
+
+		Ok(SpotPrices { hourly })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RESPONSE: &str = r#"{
+		"license_info": "CC BY 4.0 (creativecommons.org/licenses/by/4.0) from Bundesnetzagentur | SMARD.de",
+		"unix_seconds": [1677884400, 1677888000, 1677891600],
+		"price": [132.1, null, -5.02],
+		"unit": "EUR / MWh",
+		"deprecated": false
+	}"#;
+
+	#[test]
+	fn energy_charts_response_is_converted_to_fixed_point_prices() {
+		let response: EnergyChartsPrices = serde_json::from_str(RESPONSE).unwrap();
+
+		let prices = response.into_spot_prices("key".into()).unwrap();
+
+		assert_eq!(prices.hourly.len(), 2);
+		assert_eq!(prices.hourly[0].start, 1677884400);
+		assert_eq!(prices.hourly[0].price, SpotPrice::from_num(132.1));
+		assert_eq!(prices.hourly[1].start, 1677891600);
+		assert_eq!(prices.hourly[1].price, SpotPrice::from_num(-5.02));
+	}
+
+	#[test]
+	fn mismatching_number_of_values_is_rejected() {
+		let mut response: EnergyChartsPrices = serde_json::from_str(RESPONSE).unwrap();
+		response.price.pop();
+
+		assert!(response.into_spot_prices("key".into()).is_err());
+	}
+}
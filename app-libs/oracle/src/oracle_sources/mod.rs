@@ -16,4 +16,75 @@
 */
 pub mod coin_gecko;
 pub mod coin_market_cap;
+pub mod configurable_source;
+pub mod energy_charts;
 pub mod weather_oracle_source;
+
+use core::time::Duration;
+
+/// Parses an ISO 8601 UTC timestamp, as reported by the exchange rate sources, e.g.
+/// `2023-05-03T09:43:18.525Z`, into the duration since the unix epoch. Fractions of a
+/// second are ignored.
+pub(crate) fn parse_utc_timestamp(timestamp: &str) -> Option<Duration> {
+	let timestamp = timestamp.strip_suffix('Z')?;
+	let (date, time) = timestamp.split_once('T')?;
+	let time = time.split('.').next()?;
+
+	let mut date_parts = date.splitn(3, '-').map(|part| part.parse::<u64>().ok());
+	let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+	let mut time_parts = time.splitn(3, ':').map(|part| part.parse::<u64>().ok());
+	let (hour, minute, second) = (time_parts.next()??, time_parts.next()??, time_parts.next()??);
+
+	if year < 1970 || !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+		return None
+	}
+	if hour > 23 || minute > 59 || second > 59 {
+		return None
+	}
+
+	let days_before_year: u64 = (1970..year).map(|y| if is_leap_year(y) { 366 } else { 365 }).sum();
+	let days_before_month: u64 = (1..month).map(|m| days_in_month(year, m)).sum();
+	let days = days_before_year + days_before_month + day - 1;
+
+	Some(Duration::from_secs(days * 86_400 + hour * 3_600 + minute * 60 + second))
+}
+
+fn is_leap_year(year: u64) -> bool {
+	(year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+	match month {
+		2 if is_leap_year(year) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_utc_timestamp_works() {
+		assert_eq!(parse_utc_timestamp("1970-01-01T00:00:00Z"), Some(Duration::from_secs(0)));
+		assert_eq!(
+			parse_utc_timestamp("2023-05-03T09:43:18.525Z"),
+			Some(Duration::from_secs(1_683_106_998))
+		);
+		assert_eq!(
+			parse_utc_timestamp("2024-02-29T23:59:59.000Z"),
+			Some(Duration::from_secs(1_709_251_199))
+		);
+	}
+
+	#[test]
+	fn parse_utc_timestamp_rejects_invalid_timestamps() {
+		assert_eq!(parse_utc_timestamp("2023-05-03T09:43:18"), None);
+		assert_eq!(parse_utc_timestamp("2023-02-29T09:43:18Z"), None);
+		assert_eq!(parse_utc_timestamp("2023-13-01T09:43:18Z"), None);
+		assert_eq!(parse_utc_timestamp("2023-05-03T24:00:00Z"), None);
+		assert_eq!(parse_utc_timestamp("not a timestamp"), None);
+	}
+}
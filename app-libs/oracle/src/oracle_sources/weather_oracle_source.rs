@@ -20,37 +20,35 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	error::Error,
-	traits::OracleSource,
-	types::{ExchangeRate, TradingPair, WeatherInfo},
-};
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-	RestGet, RestPath,
+	traits::{OracleFeed, OracleRequest, OracleSource},
+	types::{CloudCover, HourlyWeather, Irradiance, Temperature, WeatherForecast, WeatherQuery},
 };
+use log::*;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
 	string::{String, ToString},
 	time::Duration,
 	vec::Vec,
 };
+use substrate_fixed::traits::Fixed;
 use url::Url;
 
 const WEATHER_URL: &str = "https://api.open-meteo.com";
 const WEATHER_PARAM_LONGITUDE: &str = "longitude";
 const WEATHER_PARAM_LATITUDE: &str = "latitude";
-// const WEATHER_PARAM_HOURLY: &str = "hourly"; // TODO: Add to Query
+const WEATHER_PARAM_HOURLY: &str = "hourly";
+const WEATHER_PARAM_START_DATE: &str = "start_date";
+const WEATHER_PARAM_END_DATE: &str = "end_date";
+const WEATHER_HOURLY_VARIABLES: &str = "temperature_2m,shortwave_radiation,cloudcover";
 const WEATHER_PATH: &str = "v1/forecast";
 const WEATHER_TIMEOUT: Duration = Duration::from_secs(3u64);
 const WEATHER_ROOT_CERTIFICATE: &str = include_str!("../certificates/open_meteo_root.pem");
 
-// TODO: Change f32 types to appropriate Substrate Fixed Type
 #[derive(Default)]
 pub struct WeatherOracleSource;
 
-impl<OracleSourceInfo: Into<WeatherInfo>> OracleSource<OracleSourceInfo> for WeatherOracleSource {
-	type OracleRequestResult = Result<f32, Error>; // TODO: Change from f32 type
-
+impl OracleSource for WeatherOracleSource {
 	fn metrics_id(&self) -> String {
 		"weather".to_string()
 	}
@@ -67,39 +65,28 @@ impl<OracleSourceInfo: Into<WeatherInfo>> OracleSource<OracleSourceInfo> for Wea
 	fn root_certificates_content(&self) -> Vec<String> {
 		vec![WEATHER_ROOT_CERTIFICATE.to_string()]
 	}
+}
 
-	fn execute_exchange_rate_request(
-		&self,
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		_trading_pair: TradingPair,
-	) -> Result<ExchangeRate, Error> {
-		Err(Error::NoValidData("None".into(), "None".into()))
+impl OracleFeed<WeatherQuery> for WeatherOracleSource {
+	type Value = WeatherForecast;
+
+	fn request(&self, query: &WeatherQuery) -> Result<OracleRequest, Error> {
+		Ok(OracleRequest {
+			path: WEATHER_PATH.to_string(),
+			query: vec![
+				(WEATHER_PARAM_LATITUDE.to_string(), query.latitude.clone()),
+				(WEATHER_PARAM_LONGITUDE.to_string(), query.longitude.clone()),
+				(WEATHER_PARAM_HOURLY.to_string(), WEATHER_HOURLY_VARIABLES.to_string()),
+				(WEATHER_PARAM_START_DATE.to_string(), query.start_date.clone()),
+				(WEATHER_PARAM_END_DATE.to_string(), query.end_date.clone()),
+			],
+		})
 	}
 
-	// TODO: Make this take a variant perhaps or a Closure so that it is more generic
-	fn execute_request(
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult {
-		let weather_info: WeatherInfo = source_info.into();
-		let query = weather_info.weather_query;
-
-		// TODO:
-		// This part is opinionated towards a hard coded query need to make more generic
-		let response = rest_client
-			.get_with::<String, OpenMeteo>(
-				WEATHER_PATH.into(),
-				&[
-					(WEATHER_PARAM_LATITUDE, &query.latitude),
-					(WEATHER_PARAM_LONGITUDE, &query.longitude),
-					//(WEATHER_PARAM_HOURLY), &query.hourly),
-				],
-			)
-			.map_err(Error::RestClient)?;
-
-		let open_meteo_weather_struct = response.0;
-
-		Ok(open_meteo_weather_struct.longitude)
+	fn parse(&self, response: Value, query: &WeatherQuery) -> Result<WeatherForecast, Error> {
+		let response: OpenMeteoWeatherStruct = serde_json::from_value(response)
+			.map_err(|e| Error::InvalidResponse(WEATHER_URL.to_string(), e.to_string()))?;
+		response.hourly.into_forecast(query.clone().key())
 	}
 }
 
@@ -107,14 +94,99 @@ impl<OracleSourceInfo: Into<WeatherInfo>> OracleSource<OracleSourceInfo> for Wea
 struct OpenMeteoWeatherStruct {
 	latitude: f32,
 	longitude: f32,
-	//hourly: String,
+	hourly: OpenMeteoHourly,
 }
 
+/// Hourly values, the values of an hour may be null if they are not available.
 #[derive(Serialize, Deserialize, Debug)]
-struct OpenMeteo(pub OpenMeteoWeatherStruct);
+struct OpenMeteoHourly {
+	time: Vec<String>,
+	temperature_2m: Vec<Option<f64>>,
+	shortwave_radiation: Vec<Option<f64>>,
+	cloudcover: Vec<Option<f64>>,
+}
+
+impl OpenMeteoHourly {
+	/// Converts the values into fixed point types. Hours with missing values are skipped.
+	fn into_forecast(self, query_key: String) -> Result<WeatherForecast, Error> {
+		let hours = self.time.len();
+		if self.temperature_2m.len() != hours
+			|| self.shortwave_radiation.len() != hours
+			|| self.cloudcover.len() != hours
+		{
+			return Err(Error::NoValidData(WEATHER_URL.to_string(), query_key))
+		}
+
+		let mut hourly = Vec::with_capacity(hours);
+		for (i, time) in self.time.into_iter().enumerate() {
+			let (temperature, shortwave_radiation, cloud_cover) =
+				match (self.temperature_2m[i], self.shortwave_radiation[i], self.cloudcover[i]) {
+					(Some(t), Some(r), Some(c)) => (t, r, c),
+					_ => {
+						debug!("Skipping {}, not all weather values are available", time);
+						continue
+					},
+				};
+
+			hourly.push(HourlyWeather {
+				time,
+				temperature: to_fixed::<Temperature>(temperature, &query_key)?,
+				shortwave_radiation: to_fixed::<Irradiance>(shortwave_radiation, &query_key)?,
+				cloud_cover: to_fixed::<CloudCover>(cloud_cover, &query_key)?,
+			});
+		}
+
+		Ok(WeatherForecast { hourly })
+	}
+}
+
+fn to_fixed<F: Fixed>(value: f64, query_key: &str) -> Result<F, Error> {
+	F::checked_from_num(value)
+		.ok_or_else(|| Error::NoValidData(WEATHER_URL.to_string(), query_key.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RESPONSE: &str = r#"{
+		"latitude": 52.52,
+		"longitude": 13.419998,
+		"hourly": {
+			"time": ["2023-03-04T00:00", "2023-03-04T01:00", "2023-03-04T12:00"],
+			"temperature_2m": [-1.5, 0.2, 7.0],
+			"shortwave_radiation": [0.0, null, 312.0],
+			"cloudcover": [100.0, 80.0, 20.0]
+		}
+	}"#;
+
+	#[test]
+	fn open_meteo_response_is_converted_to_fixed_point_forecast() {
+		let response: OpenMeteoWeatherStruct = serde_json::from_str(RESPONSE).unwrap();
+
+		let forecast = response.hourly.into_forecast("key".into()).unwrap();
+
+		assert_eq!(forecast.hourly.len(), 2);
+		assert_eq!(forecast.hourly[0].time, "2023-03-04T00:00");
+		assert_eq!(forecast.hourly[0].temperature, Temperature::from_num(-1.5));
+		assert_eq!(forecast.hourly[1].time, "2023-03-04T12:00");
+		assert_eq!(forecast.hourly[1].shortwave_radiation, Irradiance::from_num(312));
+		assert_eq!(forecast.hourly[1].cloud_cover, CloudCover::from_num(20));
+	}
+
+	#[test]
+	fn negative_irradiance_is_rejected() {
+		let mut response: OpenMeteoWeatherStruct = serde_json::from_str(RESPONSE).unwrap();
+		response.hourly.shortwave_radiation[0] = Some(-1.0);
+
+		assert!(response.hourly.into_forecast("key".into()).is_err());
+	}
+
+	#[test]
+	fn mismatching_number_of_values_is_rejected() {
+		let mut response: OpenMeteoWeatherStruct = serde_json::from_str(RESPONSE).unwrap();
+		response.hourly.cloudcover.pop();
 
-impl RestPath<String> for OpenMeteo {
-	fn get_path(path: String) -> Result<String, itc_rest_client::error::Error> {
-		Ok(path)
+		assert!(response.hourly.into_forecast("key".into()).is_err());
 	}
 }
@@ -0,0 +1,403 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Exchange rate oracle that queries several sources and publishes the rate they agree on.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	metrics_exporter::ExportMetrics,
+	oracles::exchange_rate_oracle::GetExchangeRate,
+	types::{
+		AggregatedExchangeRate, ExchangeRate, ExchangeRateDeviation, TradingInfo, TradingPair,
+	},
+	Error,
+};
+use itp_time_utils::duration_now;
+use log::*;
+use std::{
+	boxed::Box,
+	string::{String, ToString},
+	sync::Arc,
+	time::Duration,
+	vec::Vec,
+};
+
+/// Name of the aggregation in errors and as data source of the published exchange rate.
+pub const AGGREGATED_EXCHANGE_RATE_SOURCE: &str = "aggregated";
+
+/// Number of sources that have to agree on an exchange rate, if at least as many are queried.
+pub const DEFAULT_MIN_AGREEING_SOURCES: usize = 2;
+
+/// Exchange rates that the source last updated longer ago than this are considered stale.
+/// The supported sources update the rates of the listed currencies every few minutes.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// Limits for the exchange rates that are taken into account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregationConfig {
+	/// Maximum time between the last update of an exchange rate by its source and the
+	/// aggregation. Only applies to sources that report the time of their last update.
+	pub max_age: Duration,
+	/// Maximum relative deviation from the median, e.g. 0.05 for 5%.
+	pub max_deviation: ExchangeRateDeviation,
+	/// Minimum number of sources that have to agree on an exchange rate, in addition to the
+	/// majority of the queried sources.
+	pub min_agreeing_sources: usize,
+}
+
+impl Default for AggregationConfig {
+	fn default() -> Self {
+		AggregationConfig {
+			max_age: DEFAULT_MAX_AGE,
+			max_deviation: ExchangeRateDeviation::from_num(0.05),
+			min_agreeing_sources: DEFAULT_MIN_AGREEING_SOURCES,
+		}
+	}
+}
+
+/// Exchange rate returned by a single source.
+#[derive(Debug, Clone, PartialEq)]
+struct SourceExchangeRate {
+	source: String,
+	exchange_rate: ExchangeRate,
+	/// Time of the last update by the source, as duration since the unix epoch.
+	last_updated: Option<Duration>,
+}
+
+pub struct AggregatedExchangeRateOracle<MetricsExporter> {
+	oracles: Vec<Box<dyn GetExchangeRate>>,
+	metrics_exporter: Arc<MetricsExporter>,
+	config: AggregationConfig,
+}
+
+impl<MetricsExporter> AggregatedExchangeRateOracle<MetricsExporter> {
+	pub fn new(
+		oracles: Vec<Box<dyn GetExchangeRate>>,
+		metrics_exporter: Arc<MetricsExporter>,
+		config: AggregationConfig,
+	) -> Self {
+		AggregatedExchangeRateOracle { oracles, metrics_exporter, config }
+	}
+}
+
+pub trait GetAggregatedExchangeRate {
+	/// Get the cryptocurrency/fiat_currency exchange rate, that the majority of the sources agree
+	/// on, together with the base URLs of the agreeing sources.
+	fn get_aggregated_exchange_rate(
+		&self,
+		trading_pair: TradingPair,
+	) -> Result<(AggregatedExchangeRate, Vec<String>), Error>;
+}
+
+impl<MetricsExporter> GetAggregatedExchangeRate for AggregatedExchangeRateOracle<MetricsExporter>
+where
+	MetricsExporter: ExportMetrics<TradingInfo>,
+{
+	fn get_aggregated_exchange_rate(
+		&self,
+		trading_pair: TradingPair,
+	) -> Result<(AggregatedExchangeRate, Vec<String>), Error> {
+		let exchange_rates: Vec<SourceExchangeRate> = self
+			.oracles
+			.iter()
+			.filter_map(|oracle| match oracle.get_timestamped_exchange_rate(trading_pair.clone()) {
+				Ok((exchange_rate, last_updated, base_url)) => Some(SourceExchangeRate {
+					source: base_url.to_string(),
+					exchange_rate,
+					last_updated,
+				}),
+				Err(e) => {
+					warn!("Ignoring exchange rate source for {:?}: {}", trading_pair, e);
+					None
+				},
+			})
+			.collect();
+
+		let (aggregated, deviations) = aggregate(
+			&exchange_rates,
+			self.oracles.len(),
+			&trading_pair,
+			duration_now(),
+			&self.config,
+		)?;
+
+		let agreeing_sources = deviations
+			.iter()
+			.filter(|(_, deviation)| deviation.abs() <= self.config.max_deviation)
+			.map(|(source, _)| source.clone())
+			.collect();
+
+		for (source, deviation) in deviations {
+			self.metrics_exporter.update_exchange_rate_deviation(
+				source,
+				deviation,
+				trading_pair.clone(),
+			);
+		}
+
+		Ok((aggregated, agreeing_sources))
+	}
+}
+
+/// Aggregates the exchange rates of the sources into their median.
+///
+/// Stale and zero exchange rates are discarded, `now` being the duration since the unix epoch.
+/// The median is taken of the exchange rates that are within `max_deviation` of the median of
+/// all remaining exchange rates. They need to come from the majority of the `queried_sources`
+/// and from at least `min_agreeing_sources`.
+///
+/// Returns the aggregated exchange rate and the deviation of every remaining source.
+fn aggregate(
+	exchange_rates: &[SourceExchangeRate],
+	queried_sources: usize,
+	trading_pair: &TradingPair,
+	now: Duration,
+	config: &AggregationConfig,
+) -> Result<(AggregatedExchangeRate, Vec<(String, ExchangeRateDeviation)>), Error> {
+	let responses: Vec<&SourceExchangeRate> = exchange_rates
+		.iter()
+		.filter(|r| {
+			let is_fresh = r
+				.last_updated
+				.map_or(true, |last_updated| now.saturating_sub(last_updated) <= config.max_age);
+			if !is_fresh {
+				warn!("Discarding stale exchange rate of {}", r.source);
+			}
+			is_fresh && r.exchange_rate > 0
+		})
+		.collect();
+
+	let preliminary_median = median(responses.iter().map(|r| r.exchange_rate).collect())
+		.ok_or_else(|| {
+			Error::NoValidData(AGGREGATED_EXCHANGE_RATE_SOURCE.into(), trading_pair.clone().key())
+		})?;
+
+	let deviations: Vec<(String, ExchangeRateDeviation)> = responses
+		.iter()
+		.map(|r| (r.source.clone(), relative_deviation(r.exchange_rate, preliminary_median)))
+		.collect();
+
+	let agreeing_rates: Vec<ExchangeRate> = responses
+		.iter()
+		.zip(deviations.iter())
+		.filter(|(r, (_, deviation))| {
+			let agrees = deviation.abs() <= config.max_deviation;
+			if !agrees {
+				warn!("Discarding outlier {} of {} ({})", r.exchange_rate, r.source, deviation);
+			}
+			agrees
+		})
+		.map(|(r, _)| r.exchange_rate)
+		.collect();
+
+	let agreeing = agreeing_rates.len();
+	if agreeing < config.min_agreeing_sources || agreeing * 2 <= queried_sources {
+		return Err(Error::NoConsensus {
+			trading_pair: trading_pair.clone().key(),
+			agreeing,
+			queried: queried_sources,
+		})
+	}
+
+	let exchange_rate = median(agreeing_rates).expect("At least one exchange rate agrees; qed");
+
+	Ok((
+		AggregatedExchangeRate {
+			exchange_rate,
+			agreeing_sources: agreeing as u32,
+			queried_sources: queried_sources as u32,
+		},
+		deviations,
+	))
+}
+
+fn median(mut exchange_rates: Vec<ExchangeRate>) -> Option<ExchangeRate> {
+	exchange_rates.sort();
+	let middle = exchange_rates.len() / 2;
+	match exchange_rates.len() {
+		0 => None,
+		n if n % 2 == 1 => Some(exchange_rates[middle]),
+		_ => Some(exchange_rates[middle - 1] / 2 + exchange_rates[middle] / 2),
+	}
+}
+
+fn relative_deviation(exchange_rate: ExchangeRate, median: ExchangeRate) -> ExchangeRateDeviation {
+	let difference = ExchangeRateDeviation::saturating_from_num(exchange_rate)
+		- ExchangeRateDeviation::saturating_from_num(median);
+	difference
+		.checked_div(ExchangeRateDeviation::saturating_from_num(median))
+		.unwrap_or(ExchangeRateDeviation::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::MetricsExporterMock;
+	use url::Url;
+
+	struct ExchangeRateOracleMock(Result<f32, ()>, &'static str);
+
+	impl GetExchangeRate for ExchangeRateOracleMock {
+		fn get_timestamped_exchange_rate(
+			&self,
+			_trading_pair: TradingPair,
+		) -> Result<(ExchangeRate, Option<Duration>, Url), Error> {
+			match self.0 {
+				Ok(rate) => Ok((
+					ExchangeRate::from_num(rate),
+					Some(duration_now()),
+					Url::parse(self.1).unwrap(),
+				)),
+				Err(()) => Err(Error::InvalidCryptoCurrencyId),
+			}
+		}
+	}
+
+	fn oracle_mock(rate: Result<f32, ()>, base_url: &'static str) -> Box<dyn GetExchangeRate> {
+		Box::new(ExchangeRateOracleMock(rate, base_url))
+	}
+
+	fn trading_pair() -> TradingPair {
+		TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "USD".to_string() }
+	}
+
+	fn exchange_rate(source: &str, rate: f32, last_updated: Duration) -> SourceExchangeRate {
+		SourceExchangeRate {
+			source: source.to_string(),
+			exchange_rate: ExchangeRate::from_num(rate),
+			last_updated: Some(last_updated),
+		}
+	}
+
+	#[test]
+	fn outlier_is_discarded_and_deviations_are_exported() {
+		let metrics_exporter = Arc::new(MetricsExporterMock::default());
+		let oracle = AggregatedExchangeRateOracle::new(
+			vec![
+				oracle_mock(Ok(1.0), "https://a.source"),
+				oracle_mock(Ok(1.02), "https://b.source"),
+				oracle_mock(Ok(3.0), "https://c.source"),
+				oracle_mock(Err(()), "https://d.source"),
+				oracle_mock(Ok(1.03), "https://e.source"),
+				oracle_mock(Ok(1.01), "https://f.source"),
+			],
+			metrics_exporter.clone(),
+			AggregationConfig::default(),
+		);
+
+		let (aggregated, agreeing_sources) =
+			oracle.get_aggregated_exchange_rate(trading_pair()).unwrap();
+
+		assert_eq!(
+			agreeing_sources,
+			vec![
+				"https://a.source/",
+				"https://b.source/",
+				"https://e.source/",
+				"https://f.source/"
+			]
+		);
+		assert_eq!(aggregated.agreeing_sources, 4);
+		assert_eq!(aggregated.queried_sources, 6);
+		assert!((aggregated.exchange_rate.to_num::<f64>() - 1.015).abs() < 0.0001);
+
+		let deviations = metrics_exporter.get_deviations();
+		assert_eq!(deviations.len(), 5);
+		assert_eq!(deviations[1].1, 0);
+		assert!(deviations[2].1 > 1);
+	}
+
+	#[test]
+	fn stale_exchange_rates_are_discarded() {
+		let now = Duration::from_secs(1_000);
+		let config = AggregationConfig { max_age: Duration::from_secs(10), ..Default::default() };
+		let exchange_rates = vec![
+			exchange_rate("a", 1.0, now),
+			exchange_rate("b", 5.0, now - Duration::from_secs(11)),
+			exchange_rate("c", 1.0, now - Duration::from_secs(10)),
+		];
+
+		let (aggregated, deviations) =
+			aggregate(&exchange_rates, 3, &trading_pair(), now, &config).unwrap();
+
+		assert_eq!(aggregated.exchange_rate, ExchangeRate::from_num(1));
+		assert_eq!(aggregated.agreeing_sources, 2);
+		assert_eq!(deviations.len(), 2);
+	}
+
+	#[test]
+	fn exchange_rates_without_last_update_are_not_considered_stale() {
+		let now = Duration::from_secs(1_000);
+		let config = AggregationConfig { max_age: Duration::from_secs(10), ..Default::default() };
+		let mut exchange_rate_without_last_update = exchange_rate("b", 1.0, now);
+		exchange_rate_without_last_update.last_updated = None;
+		let exchange_rates = vec![exchange_rate("a", 1.0, now), exchange_rate_without_last_update];
+
+		let (aggregated, _) = aggregate(&exchange_rates, 2, &trading_pair(), now, &config).unwrap();
+
+		assert_eq!(aggregated.agreeing_sources, 2);
+	}
+
+	#[test]
+	fn disagreeing_sources_yield_no_consensus() {
+		let now = Duration::from_secs(1_000);
+		let exchange_rates = vec![exchange_rate("a", 1.0, now), exchange_rate("b", 2.0, now)];
+
+		let result =
+			aggregate(&exchange_rates, 2, &trading_pair(), now, &AggregationConfig::default());
+
+		assert!(matches!(result, Err(Error::NoConsensus { agreeing: 0, queried: 2, .. })));
+	}
+
+	#[test]
+	fn agreeing_minority_of_the_queried_sources_yields_no_consensus() {
+		let now = Duration::from_secs(1_000);
+		let config = AggregationConfig::default();
+		let exchange_rates = vec![exchange_rate("a", 1.0, now), exchange_rate("b", 1.0, now)];
+
+		// The other sources did not respond.
+		let result = aggregate(&exchange_rates, 4, &trading_pair(), now, &config);
+		assert!(matches!(result, Err(Error::NoConsensus { agreeing: 2, queried: 4, .. })));
+
+		let result = aggregate(&exchange_rates, 3, &trading_pair(), now, &config);
+		assert_eq!(result.unwrap().0.agreeing_sources, 2);
+	}
+
+	#[test]
+	fn single_source_yields_no_consensus_unless_configured() {
+		let now = Duration::from_secs(1_000);
+		let exchange_rates = vec![exchange_rate("a", 1.0, now)];
+
+		let result =
+			aggregate(&exchange_rates, 1, &trading_pair(), now, &AggregationConfig::default());
+		assert!(matches!(result, Err(Error::NoConsensus { agreeing: 1, queried: 1, .. })));
+
+		let config = AggregationConfig { min_agreeing_sources: 1, ..Default::default() };
+		let (aggregated, _) = aggregate(&exchange_rates, 1, &trading_pair(), now, &config).unwrap();
+		assert_eq!(aggregated.agreeing_sources, 1);
+	}
+
+	#[test]
+	fn no_exchange_rate_yields_no_valid_data() {
+		let result =
+			aggregate(&[], 2, &trading_pair(), duration_now(), &AggregationConfig::default());
+
+		assert!(matches!(result, Err(Error::NoValidData(..))));
+	}
+}
@@ -20,7 +20,7 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	metrics_exporter::ExportMetrics,
-	traits::OracleSource,
+	traits::OracleFeed,
 	types::{ExchangeRate, TradingInfo, TradingPair},
 	Error,
 };
@@ -50,16 +50,29 @@ impl<OracleSourceType, MetricsExporter> ExchangeRateOracle<OracleSourceType, Met
 
 pub trait GetExchangeRate {
 	/// Get the cryptocurrency/fiat_currency exchange rate
-	fn get_exchange_rate(&self, trading_pair: TradingPair) -> Result<(ExchangeRate, Url), Error>;
+	fn get_exchange_rate(&self, trading_pair: TradingPair) -> Result<(ExchangeRate, Url), Error> {
+		self.get_timestamped_exchange_rate(trading_pair)
+			.map(|(exchange_rate, _, base_url)| (exchange_rate, base_url))
+	}
+
+	/// Get the cryptocurrency/fiat_currency exchange rate, together with the time the source
+	/// last updated it as duration since the unix epoch, if the source reports it.
+	fn get_timestamped_exchange_rate(
+		&self,
+		trading_pair: TradingPair,
+	) -> Result<(ExchangeRate, Option<Duration>, Url), Error>;
 }
 
 impl<OracleSourceType, MetricsExporter> GetExchangeRate
 	for ExchangeRateOracle<OracleSourceType, MetricsExporter>
 where
-	OracleSourceType: OracleSource<TradingInfo>,
+	OracleSourceType: OracleFeed<TradingPair, Value = ExchangeRate>,
 	MetricsExporter: ExportMetrics<TradingInfo>,
 {
-	fn get_exchange_rate(&self, trading_pair: TradingPair) -> Result<(ExchangeRate, Url), Error> {
+	fn get_timestamped_exchange_rate(
+		&self,
+		trading_pair: TradingPair,
+	) -> Result<(ExchangeRate, Option<Duration>, Url), Error> {
 		let source_id = self.oracle_source.metrics_id();
 		self.metrics_exporter.increment_number_requests(source_id.clone());
 
@@ -86,12 +99,11 @@ where
 		let mut tries = 0;
 		let result = loop {
 			tries += 1;
-			let exchange_result = self
-				.oracle_source
-				.execute_exchange_rate_request(&mut rest_client, trading_pair.clone());
+			let exchange_result =
+				self.oracle_source.execute_timestamped_request(&mut rest_client, &trading_pair);
 
 			match exchange_result {
-				Ok(exchange_rate) => {
+				Ok((exchange_rate, last_updated)) => {
 					self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
 					self.metrics_exporter.update_exchange_rate(
 						source_id,
@@ -100,7 +112,7 @@ where
 					);
 
 					debug!("Successfully executed exchange rate request");
-					break Ok((exchange_rate, base_url))
+					break Ok((exchange_rate, last_updated, base_url))
 				},
 				Err(e) =>
 					if tries < number_of_tries {
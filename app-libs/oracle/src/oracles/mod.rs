@@ -14,5 +14,7 @@
 	limitations under the License.
 
 */
+pub mod aggregated_exchange_rate_oracle;
 pub mod exchange_rate_oracle;
+pub mod spot_price_oracle;
 pub mod weather_oracle;
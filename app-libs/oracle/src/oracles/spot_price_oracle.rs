@@ -0,0 +1,89 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	metrics_exporter::ExportMetrics,
+	traits::{OracleFeed, OracleSource},
+	types::{SpotPriceQuery, SpotPrices},
+	Error,
+};
+use itc_rest_client::{
+	http_client::{HttpClient, SendWithCertificateVerification},
+	rest_client::RestClient,
+};
+use log::*;
+use std::{sync::Arc, time::Instant};
+use url::Url;
+
+pub struct SpotPriceOracle<OracleSourceType, MetricsExporter> {
+	oracle_source: OracleSourceType,
+	metrics_exporter: Arc<MetricsExporter>,
+}
+
+impl<OracleSourceType, MetricsExporter> SpotPriceOracle<OracleSourceType, MetricsExporter>
+where
+	OracleSourceType: OracleSource,
+{
+	pub fn new(oracle_source: OracleSourceType, metrics_exporter: Arc<MetricsExporter>) -> Self {
+		SpotPriceOracle { oracle_source, metrics_exporter }
+	}
+
+	pub fn get_base_url(&self) -> Result<Url, Error> {
+		self.oracle_source.base_url()
+	}
+}
+
+pub trait GetSpotPrices {
+	/// Get the hourly day-ahead spot prices for the bidding zone and date range of the query
+	fn get_spot_prices(&self, query: SpotPriceQuery) -> Result<(SpotPrices, Url), Error>;
+}
+
+impl<OracleSourceType, MetricsExporter> GetSpotPrices
+	for SpotPriceOracle<OracleSourceType, MetricsExporter>
+where
+	OracleSourceType: OracleFeed<SpotPriceQuery, Value = SpotPrices>,
+	MetricsExporter: ExportMetrics<SpotPriceQuery>,
+{
+	fn get_spot_prices(&self, query: SpotPriceQuery) -> Result<(SpotPrices, Url), Error> {
+		let source_id = self.oracle_source.metrics_id();
+		self.metrics_exporter.increment_number_requests(source_id.clone());
+
+		let base_url = self.oracle_source.base_url()?;
+		let root_certificates = self.oracle_source.root_certificates_content();
+
+		debug!("Get spot prices from URI: {}, query: {:?}", base_url, query);
+
+		let http_client = HttpClient::new(
+			SendWithCertificateVerification::new(root_certificates),
+			true,
+			self.oracle_source.request_timeout(),
+			None,
+			None,
+		);
+		let mut rest_client = RestClient::new(http_client, base_url.clone());
+
+		let timer_start = Instant::now();
+		let spot_prices = self.oracle_source.execute_request(&mut rest_client, &query)?;
+
+		self.metrics_exporter.record_response_time(source_id, timer_start);
+
+		debug!("Successfully executed spot price request");
+		Ok((spot_prices, base_url))
+	}
+}
@@ -18,13 +18,18 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{metrics_exporter::ExportMetrics, traits::OracleSource, types::WeatherInfo, Error};
+use crate::{
+	metrics_exporter::ExportMetrics,
+	traits::{OracleFeed, OracleSource},
+	types::{WeatherForecast, WeatherInfo, WeatherQuery},
+	Error,
+};
 use itc_rest_client::{
 	http_client::{HttpClient, SendWithCertificateVerification},
 	rest_client::RestClient,
 };
 use log::*;
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use url::Url;
 
 #[allow(unused)]
@@ -35,7 +40,7 @@ pub struct WeatherOracle<OracleSourceType, MetricsExporter> {
 
 impl<OracleSourceType, MetricsExporter> WeatherOracle<OracleSourceType, MetricsExporter>
 where
-	OracleSourceType: OracleSource<WeatherInfo>,
+	OracleSourceType: OracleSource,
 {
 	pub fn new(oracle_source: OracleSourceType, metrics_exporter: Arc<MetricsExporter>) -> Self {
 		WeatherOracle { oracle_source, metrics_exporter }
@@ -46,26 +51,34 @@ where
 	}
 }
 
-pub trait GetLongitude {
-	type LongitudeResult;
-	fn get_longitude(&self, weather_info: WeatherInfo) -> Self::LongitudeResult;
+pub trait GetWeatherForecast {
+	/// Get the hourly weather forecast for the location and date range of the query
+	fn get_weather_forecast(
+		&self,
+		weather_info: WeatherInfo,
+	) -> Result<(WeatherForecast, Url), Error>;
 }
 
-impl<OracleSourceType, MetricsExporter> GetLongitude
+impl<OracleSourceType, MetricsExporter> GetWeatherForecast
 	for WeatherOracle<OracleSourceType, MetricsExporter>
 where
-	OracleSourceType: OracleSource<WeatherInfo, OracleRequestResult = Result<f32, Error>>,
+	OracleSourceType: OracleFeed<WeatherQuery, Value = WeatherForecast>,
 	MetricsExporter: ExportMetrics<WeatherInfo>,
 {
-	type LongitudeResult = Result<f32, Error>;
-
-	fn get_longitude(&self, weather_info: WeatherInfo) -> Self::LongitudeResult {
-		let query = weather_info.weather_query.clone();
+	fn get_weather_forecast(
+		&self,
+		weather_info: WeatherInfo,
+	) -> Result<(WeatherForecast, Url), Error> {
+		let source_id = self.oracle_source.metrics_id();
+		self.metrics_exporter.increment_number_requests(source_id.clone());
 
 		let base_url = self.oracle_source.base_url()?;
 		let root_certificates = self.oracle_source.root_certificates_content();
 
-		debug!("Get longitude from URI: {}, query: {:?}", base_url, query);
+		debug!(
+			"Get weather forecast from URI: {}, query: {:?}",
+			base_url, weather_info.weather_query
+		);
 
 		let http_client = HttpClient::new(
 			SendWithCertificateVerification::new(root_certificates),
@@ -74,10 +87,17 @@ where
 			None,
 			None,
 		);
-		let mut rest_client = RestClient::new(http_client, base_url);
-		<OracleSourceType as OracleSource<WeatherInfo>>::execute_request(
-			&mut rest_client,
-			weather_info,
-		)
+		let mut rest_client = RestClient::new(http_client, base_url.clone());
+
+		let timer_start = Instant::now();
+		let forecast = self
+			.oracle_source
+			.execute_request(&mut rest_client, &weather_info.weather_query)?;
+
+		self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
+		self.metrics_exporter.update_weather(source_id, weather_info);
+
+		debug!("Successfully executed weather forecast request");
+		Ok((forecast, base_url))
 	}
 }
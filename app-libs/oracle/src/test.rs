@@ -19,21 +19,32 @@
 //! Uses real HTTP requests, so the sites must be available for these tests.
 
 use crate::{
+	create_aggregated_exchange_rate_oracle,
 	error::Error,
 	mock::MetricsExporterMock,
 	oracle_sources::{
-		coin_gecko::CoinGeckoSource, coin_market_cap::CoinMarketCapSource,
+		coin_gecko::CoinGeckoSource,
+		coin_market_cap::CoinMarketCapSource,
+		configurable_source::{ConfigurableSource, ConfigurableSourceConfig},
 		weather_oracle_source::WeatherOracleSource,
 	},
 	oracles::{
+		aggregated_exchange_rate_oracle::GetAggregatedExchangeRate,
 		exchange_rate_oracle::{ExchangeRateOracle, GetExchangeRate},
-		weather_oracle::{GetLongitude, WeatherOracle},
+		weather_oracle::{GetWeatherForecast, WeatherOracle},
 	},
-	traits::OracleSource,
-	types::{TradingInfo, TradingPair, WeatherInfo, WeatherQuery},
+	traits::{OracleFeed, OracleSource},
+	types::{ExchangeRate, TradingPair, WeatherInfo, WeatherQuery},
 };
+use chrono::Utc;
 use core::assert_matches::assert_matches;
-use std::sync::Arc;
+use itp_test::mock::metrics_ocall_mock::MetricsOCallMock;
+use std::{
+	io::{Read, Write},
+	net::TcpListener,
+	sync::Arc,
+	thread,
+};
 use substrate_fixed::transcendental::ZERO;
 
 type TestOracle<OracleSource> = ExchangeRateOracle<OracleSource, MetricsExporterMock>;
@@ -52,16 +63,84 @@ fn get_exchange_rate_from_coin_gecko_works() {
 }
 
 #[test]
-fn get_longitude_from_open_meteo_works() {
+fn get_weather_forecast_from_open_meteo_works() {
 	let oracle = create_weather_oracle::<WeatherOracleSource>();
-	let weather_query =
-		WeatherQuery { latitude: "52.52".into(), longitude: "13.41".into(), hourly: "none".into() };
-	// Todo: hourly param is temperature_2m to get temp or relativehumidity_2m to get humidity
+	// The forecast API only serves dates around today.
+	let today = Utc::now().date_naive().to_string();
+	let weather_query = WeatherQuery {
+		latitude: "52.52".into(),
+		longitude: "13.41".into(),
+		start_date: today.clone(),
+		end_date: today.clone(),
+	};
 	let weather_info = WeatherInfo { weather_query };
-	let expected_longitude = 13.41f32;
-	let response_longitude =
-		oracle.get_longitude(weather_info).expect("Can grab longitude from oracle");
-	assert!((response_longitude - expected_longitude) < 0.5);
+	let (forecast, _) = oracle
+		.get_weather_forecast(weather_info)
+		.expect("Can grab forecast from oracle");
+	assert_eq!(forecast.hourly.len(), 24);
+	assert_eq!(forecast.hourly[0].time, format!("{}T00:00", today));
+}
+
+#[test]
+fn get_exchange_rate_from_local_stand_in_server_works() {
+	let base_url = serve_once(r#"{"rates": {"TEER": {"USD": "0.42"}}}"#);
+	let oracle = ExchangeRateOracle::new(
+		ConfigurableSource::new(ConfigurableSourceConfig {
+			base_url,
+			path_template: "rates/{crypto_currency}".into(),
+			value_pointer: "/rates/{crypto_currency}/{fiat_currency}".into(),
+			..Default::default()
+		}),
+		Arc::new(MetricsExporterMock::default()),
+	);
+	let trading_pair =
+		TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "USD".to_string() };
+
+	let (exchange_rate, _) = oracle.get_exchange_rate(trading_pair).unwrap();
+
+	assert_eq!(exchange_rate, ExchangeRate::from_num(0.42));
+}
+
+#[test]
+fn aggregated_exchange_rate_of_a_single_configured_source_works() {
+	let base_url = serve_once(r#"{"rates": {"TEER": {"USD": "0.42"}}}"#);
+	let oracle = create_aggregated_exchange_rate_oracle(
+		Arc::new(MetricsOCallMock::default()),
+		Some(ConfigurableSourceConfig {
+			base_url,
+			path_template: "rates/{crypto_currency}".into(),
+			value_pointer: "/rates/{crypto_currency}/{fiat_currency}".into(),
+			..Default::default()
+		}),
+	);
+	let trading_pair =
+		TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "USD".to_string() };
+
+	let (aggregated, agreeing_sources) = oracle.get_aggregated_exchange_rate(trading_pair).unwrap();
+
+	assert_eq!(aggregated.exchange_rate, ExchangeRate::from_num(0.42));
+	assert_eq!((aggregated.agreeing_sources, aggregated.queried_sources), (1, 1));
+	assert_eq!(agreeing_sources.len(), 1);
+}
+
+/// Local stand-in server, that answers a single HTTP request with `body`. Returns its base URL.
+fn serve_once(body: &'static str) -> String {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+	thread::spawn(move || {
+		let (mut stream, _) = listener.accept().unwrap();
+		let mut request = [0u8; 1024];
+		let _ = stream.read(&mut request).unwrap();
+		let response = format!(
+			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+			body.len(),
+			body
+		);
+		stream.write_all(response.as_bytes()).unwrap();
+	});
+
+	base_url
 }
 
 #[test]
@@ -74,20 +153,20 @@ fn get_exchange_rate_for_undefined_coin_gecko_crypto_currency_fails() {
 	get_exchange_rate_for_undefined_crypto_currency_fails::<CoinGeckoSource>();
 }
 
-fn create_weather_oracle<OracleSourceType: OracleSource<WeatherInfo>>(
+fn create_weather_oracle<OracleSourceType: OracleSource + Default>(
 ) -> TestWeatherOracle<OracleSourceType> {
 	let oracle_source = OracleSourceType::default();
 	WeatherOracle::new(oracle_source, Arc::new(MetricsExporterMock::default()))
 }
 
-fn create_exchange_rate_oracle<OracleSourceType: OracleSource<TradingInfo>>(
+fn create_exchange_rate_oracle<OracleSourceType: OracleSource + Default>(
 ) -> TestOracle<OracleSourceType> {
 	let oracle_source = OracleSourceType::default();
 	ExchangeRateOracle::new(oracle_source, Arc::new(MetricsExporterMock::default()))
 }
 
 fn get_exchange_rate_for_undefined_crypto_currency_fails<
-	OracleSourceType: OracleSource<TradingInfo>,
+	OracleSourceType: OracleFeed<TradingPair, Value = ExchangeRate> + Default,
 >() {
 	let oracle = create_exchange_rate_oracle::<OracleSourceType>();
 	let trading_pair = TradingPair {
@@ -98,7 +177,9 @@ fn get_exchange_rate_for_undefined_crypto_currency_fails<
 	assert_matches!(result, Err(Error::InvalidCryptoCurrencyId));
 }
 
-fn test_suite_exchange_rates<OracleSourceType: OracleSource<TradingInfo>>() {
+fn test_suite_exchange_rates<
+	OracleSourceType: OracleFeed<TradingPair, Value = ExchangeRate> + Default,
+>() {
 	let oracle = create_exchange_rate_oracle::<OracleSourceType>();
 	let dot_to_usd =
 		TradingPair { crypto_currency: "DOT".to_string(), fiat_currency: "USD".to_string() };
@@ -18,21 +18,21 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{
-	types::{ExchangeRate, TradingPair},
-	Error,
-};
+use crate::Error;
 use core::time::Duration;
 use itc_rest_client::{
 	http_client::{HttpClient, SendWithCertificateVerification},
 	rest_client::RestClient,
+	RestGet, RestPath,
 };
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{string::String, vec::Vec};
 use url::Url;
 
-pub trait OracleSource<OracleSourceInfo>: Default {
-	type OracleRequestResult;
-
+/// Server of an oracle, independent of the data it provides.
+pub trait OracleSource {
 	fn metrics_id(&self) -> String;
 
 	fn request_timeout(&self) -> Option<Duration>;
@@ -41,15 +41,76 @@ pub trait OracleSource<OracleSourceInfo>: Default {
 
 	/// The server's root certificate(s). A valid certificate is required to open a tls connection
 	fn root_certificates_content(&self) -> Vec<String>;
+}
+
+/// GET request to an oracle source, relative to its base URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleRequest {
+	pub path: String,
+	pub query: Vec<(String, String)>,
+}
+
+/// Data of type `Value`, that an oracle source provides for a `Query`.
+///
+/// A source only needs to build the request and to parse the JSON response of the feeds it
+/// supports, e.g. `OracleFeed<TradingPair, Value = ExchangeRate>` for exchange rates.
+pub trait OracleFeed<Query>: OracleSource {
+	type Value;
+
+	fn request(&self, query: &Query) -> Result<OracleRequest, Error>;
+
+	fn parse(&self, response: Value, query: &Query) -> Result<Self::Value, Error>;
+
+	/// Time of the last update of the data in the response, as duration since the unix epoch.
+	/// `None` if the source doesn't report it.
+	fn last_updated(&self, _response: &Value, _query: &Query) -> Option<Duration> {
+		None
+	}
 
-	fn execute_exchange_rate_request(
+	/// Sends the request of the query and parses the response.
+	fn execute_request(
 		&self,
 		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		trading_pair: TradingPair,
-	) -> Result<ExchangeRate, Error>;
+		query: &Query,
+	) -> Result<Self::Value, Error> {
+		self.execute_timestamped_request(rest_client, query).map(|(value, _)| value)
+	}
 
-	fn execute_request(
+	/// Sends the request of the query and parses the response, together with the time of its
+	/// last update.
+	fn execute_timestamped_request(
+		&self,
 		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult;
+		query: &Query,
+	) -> Result<(Self::Value, Option<Duration>), Error> {
+		let request = self.request(query)?;
+		let params: Vec<(&str, &str)> = request
+			.query
+			.iter()
+			.map(|(key, value)| (key.as_str(), value.as_str()))
+			.collect();
+
+		let response = if params.is_empty() {
+			rest_client.get::<String, JsonResponse>(request.path)
+		} else {
+			rest_client.get_with::<String, JsonResponse>(request.path, &params)
+		}
+		.map_err(|e| {
+			error!("{} execute_request() failed with: {:?}", self.metrics_id(), &e);
+			Error::RestClient(e)
+		})?;
+
+		debug!("{} received response: {:?}", self.metrics_id(), &response);
+		let last_updated = self.last_updated(&response.0, query);
+		self.parse(response.0, query).map(|value| (value, last_updated))
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct JsonResponse(pub Value);
+
+impl RestPath<String> for JsonResponse {
+	fn get_path(path: String) -> Result<String, itc_rest_client::error::Error> {
+		Ok(path)
+	}
 }
@@ -16,27 +16,98 @@
 */
 
 use codec::{Decode, Encode};
-use std::string::String;
-use substrate_fixed::types::U32F32;
+use std::{string::String, vec::Vec};
+use substrate_fixed::types::{I32F32, U32F32};
 
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct WeatherInfo {
 	pub weather_query: WeatherQuery,
 }
 
+/// Location and date range of a weather forecast.
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct WeatherQuery {
 	pub longitude: String,
 	pub latitude: String,
-	pub hourly: String,
+	/// First day of the forecast in ISO 8601 format, e.g. `2023-03-04`.
+	pub start_date: String,
+	/// Last day of the forecast in ISO 8601 format, e.g. `2023-03-05`.
+	pub end_date: String,
 }
 
 impl WeatherQuery {
 	pub fn key(self) -> String {
-		format!("{}/{}", self.latitude, self.longitude)
+		format!("{}/{}/{}/{}", self.latitude, self.longitude, self.start_date, self.end_date)
 	}
 }
 
+/// Hourly weather data that is relevant for the PV generation of a location.
+#[derive(Debug, Clone, Default, Encode, Decode, Eq, PartialEq)]
+pub struct WeatherForecast {
+	pub hourly: Vec<HourlyWeather>,
+}
+
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct HourlyWeather {
+	/// Start of the hour in ISO 8601 format and GMT, e.g. `2023-03-04T05:00`.
+	pub time: String,
+	/// Air temperature at 2 meters above ground.
+	pub temperature: Temperature,
+	/// Global horizontal irradiation, the sum of direct and diffuse radiation.
+	pub shortwave_radiation: Irradiance,
+	pub cloud_cover: CloudCover,
+}
+
+/// Bidding zone and date range of day-ahead spot prices.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct SpotPriceQuery {
+	/// Bidding zone of the day-ahead market, e.g. `DE-LU`.
+	pub bidding_zone: String,
+	/// First day in ISO 8601 format, e.g. `2023-03-04`.
+	pub start_date: String,
+	/// Last day in ISO 8601 format, e.g. `2023-03-05`.
+	pub end_date: String,
+}
+
+impl SpotPriceQuery {
+	pub fn key(self) -> String {
+		format!("{}/{}/{}", self.bidding_zone, self.start_date, self.end_date)
+	}
+
+	/// Name of the oracle, with which the spot prices of the bidding zone are published.
+	pub fn oracle_name(&self) -> String {
+		format!("{}{}", SPOT_PRICE_ORACLE_PREFIX, self.bidding_zone)
+	}
+}
+
+/// Prefix of the oracle names of published spot prices, followed by the bidding zone.
+pub const SPOT_PRICE_ORACLE_PREFIX: &str = "spot_price/";
+
+/// Day-ahead spot prices of a bidding zone.
+#[derive(Debug, Clone, Default, Encode, Decode, Eq, PartialEq)]
+pub struct SpotPrices {
+	/// Prices ordered by the start of their hour.
+	pub hourly: Vec<HourlySpotPrice>,
+}
+
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct HourlySpotPrice {
+	/// Start of the hour in seconds since the unix epoch.
+	pub start: u64,
+	pub price: SpotPrice,
+}
+
+/// Exchange rate that the majority of the queried sources agree on.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct AggregatedExchangeRate {
+	/// Median of the agreeing exchange rates.
+	pub exchange_rate: ExchangeRate,
+	/// Number of sources, whose exchange rate is within the accepted deviation of the median.
+	pub agreeing_sources: u32,
+	/// Number of sources that were queried.
+	pub queried_sources: u32,
+}
+
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct TradingInfo {
 	pub trading_pair: TradingPair,
@@ -58,4 +129,16 @@ impl TradingPair {
 /// TODO Fix https://github.com/integritee-network/pallets/issues/71 and get it from https://github.com/integritee-network/pallets.git
 /// Teeracle types
 pub type ExchangeRate = U32F32;
+/// Relative deviation of an exchange rate from the aggregated exchange rate, e.g. 0.01 for +1%.
+pub type ExchangeRateDeviation = I32F32;
 // pub type Coordinate = U32F32;
+
+/// Temperature in °C.
+pub type Temperature = I32F32;
+/// Irradiance in W/m².
+pub type Irradiance = U32F32;
+/// Total cloud cover in %.
+pub type CloudCover = U32F32;
+
+/// Day-ahead spot price in €/MWh, which may be negative.
+pub type SpotPrice = I32F32;
@@ -0,0 +1,220 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Registry of the accounts that act on behalf of an `actor_id`.
+//!
+//! The `actor_id` of an order is a free-form string. Orders of an actor may only be submitted by
+//! its registered accounts, and the market data of an actor is only returned to them.
+
+use super::{
+	grid_fee::GridFeeMarketOutput,
+	read_grid_fee_market_results, read_market_results, read_orders,
+	reserve_price::{read_grid_settlements, GridSettlement},
+	settlement::{read_settlement_summary, SettlementSummary},
+	storage::actor_accounts_map_key,
+};
+use crate::{helpers::get_storage_by_key_hash, StfError};
+use codec::Encode;
+use itp_stf_primitives::types::{AccountId, OrderId};
+use itp_utils::stringify::account_id_to_string;
+use simplyr_lib::{MarketOutput, Match, Order};
+use std::{format, string::ToString, vec::Vec};
+
+pub fn read_actor_accounts(actor_id: &str) -> Vec<AccountId> {
+	get_storage_by_key_hash(actor_accounts_map_key(actor_id.to_string())).unwrap_or_default()
+}
+
+fn write_actor_accounts(actor_id: &str, accounts: &[AccountId]) {
+	let key = actor_accounts_map_key(actor_id.to_string());
+	if accounts.is_empty() {
+		sp_io::storage::clear(&key);
+	} else {
+		sp_io::storage::set(&key, &accounts.encode());
+	}
+}
+
+/// Allows `account` to act on behalf of `actor_id`. Registering an account twice is a no-op.
+pub fn register_actor_account(actor_id: &str, account: AccountId) {
+	let mut accounts = read_actor_accounts(actor_id);
+	if !accounts.contains(&account) {
+		accounts.push(account);
+		write_actor_accounts(actor_id, &accounts);
+	}
+}
+
+pub fn unregister_actor_account(actor_id: &str, account: &AccountId) -> Result<(), StfError> {
+	ensure_actor_account(actor_id, account)?;
+
+	let mut accounts = read_actor_accounts(actor_id);
+	accounts.retain(|a| a != account);
+	write_actor_accounts(actor_id, &accounts);
+	Ok(())
+}
+
+pub fn is_actor_account(actor_id: &str, account: &AccountId) -> bool {
+	read_actor_accounts(actor_id).contains(account)
+}
+
+pub fn ensure_actor_account(actor_id: &str, account: &AccountId) -> Result<(), StfError> {
+	if !is_actor_account(actor_id, account) {
+		return Err(StfError::Dispatch(format!(
+			"Account {} is not registered for actor {}",
+			account_id_to_string(account),
+			actor_id
+		)))
+	}
+	Ok(())
+}
+
+/// Ensures that `account` is registered for the actors of all `orders`.
+pub fn ensure_actor_account_for_orders(
+	orders: &[Order],
+	account: &AccountId,
+) -> Result<(), StfError> {
+	orders
+		.iter()
+		.try_for_each(|order| ensure_actor_account(&order.actor_id, account))
+}
+
+/// Market results of a timestamp, reduced to the matches that involve an order of an actor of
+/// `account`.
+pub fn read_market_results_of_account(
+	timestamp: &str,
+	account: &AccountId,
+) -> Result<MarketOutput, StfError> {
+	let own_order_ids = order_ids_of_account(account, &read_orders(timestamp)?);
+
+	let mut market_results = read_market_results(timestamp)?;
+	market_results.matches.retain(|m| involves_any(m, &own_order_ids));
+	Ok(market_results)
+}
+
+/// See `read_market_results_of_account`.
+pub fn read_grid_fee_market_results_of_account(
+	timestamp: &str,
+	account: &AccountId,
+) -> Result<GridFeeMarketOutput, StfError> {
+	let own_order_ids = order_ids_of_account(account, &read_orders(timestamp)?);
+
+	let mut market_results = read_grid_fee_market_results(timestamp)?;
+	market_results.matches.retain(|m| involves_any(&m.trade, &own_order_ids));
+	Ok(market_results)
+}
+
+/// Grid settlements of a timestamp, reduced to the orders of the actors of `account`.
+pub fn read_grid_settlements_of_account(
+	timestamp: &str,
+	account: &AccountId,
+) -> Result<Vec<GridSettlement>, StfError> {
+	let mut settlements = read_grid_settlements(timestamp)?;
+	settlements.retain(|s| is_actor_account(&s.actor_id, account));
+	Ok(settlements)
+}
+
+/// Settlement summary of a timestamp, reduced to the trades in which `account` pays or is paid.
+pub fn read_settlement_summary_of_account(
+	timestamp: &str,
+	account: &AccountId,
+) -> Result<SettlementSummary, StfError> {
+	Ok(read_settlement_summary(timestamp)?.of_account(account))
+}
+
+/// Ids of the orders, whose actor `account` is registered for.
+pub fn order_ids_of_account(account: &AccountId, orders: &[Order]) -> Vec<OrderId> {
+	orders
+		.iter()
+		.filter(|order| is_actor_account(&order.actor_id, account))
+		.map(|order| order.id)
+		.collect()
+}
+
+fn involves_any(m: &Match, order_ids: &[OrderId]) -> bool {
+	order_ids.contains(&m.bid_id) || order_ids.contains(&m.ask_id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::best_energy_helpers::{default_orders, write_orders, write_results};
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+
+	fn alice() -> AccountId {
+		AccountId::new([1u8; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::new([2u8; 32])
+	}
+
+	#[test]
+	fn actor_accounts_can_be_registered_and_unregistered() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_0", bob());
+
+			assert_eq!(read_actor_accounts("actor_0"), vec![alice(), bob()]);
+			assert!(is_actor_account("actor_0", &alice()));
+			assert!(!is_actor_account("actor_1", &alice()));
+
+			unregister_actor_account("actor_0", &alice()).unwrap();
+			assert!(unregister_actor_account("actor_0", &alice()).is_err());
+			assert_eq!(read_actor_accounts("actor_0"), vec![bob()]);
+		});
+	}
+
+	#[test]
+	fn account_needs_to_be_registered_for_every_order() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+
+		state.execute_with(|| {
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_1", alice());
+
+			assert!(ensure_actor_account_for_orders(&orders[..2], &alice()).is_ok());
+			assert!(ensure_actor_account_for_orders(&orders, &alice()).is_err());
+			assert!(ensure_actor_account_for_orders(&orders[..1], &bob()).is_err());
+		});
+	}
+
+	#[test]
+	fn only_matches_of_own_actors_are_returned() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+		let timestamp = orders[0].time_slot.clone();
+		let matches = vec![
+			Match { bid_id: 5, ask_id: 0, energy_kwh: 1.0, price_euro_per_kwh: 0.2 },
+			Match { bid_id: 5, ask_id: 1, energy_kwh: 1.0, price_euro_per_kwh: 0.2 },
+			Match { bid_id: 5, ask_id: 2, energy_kwh: 1.0, price_euro_per_kwh: 0.2 },
+		];
+
+		state.execute_with(|| {
+			write_orders(&timestamp, &orders);
+			write_results(&timestamp, &MarketOutput { matches: matches.clone() });
+			register_actor_account("actor_1", alice());
+
+			assert_eq!(
+				read_market_results_of_account(&timestamp, &alice()).unwrap().matches,
+				vec![matches[1].clone()]
+			);
+			assert!(read_market_results_of_account(&timestamp, &bob()).unwrap().matches.is_empty());
+		});
+	}
+}
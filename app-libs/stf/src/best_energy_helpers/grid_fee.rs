@@ -0,0 +1,200 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Pay-as-bid matching that respects the grid fees between the clusters of a bid and an ask.
+
+use crate::StfError;
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use simplyr_lib::{MarketInput, MarketOutput, Match, Order, OrderType};
+use std::{format, vec::Vec};
+
+/// Grid fees in €/kWh, indexed by `[ask_cluster][bid_cluster]`.
+pub type GridFeeMatrix = Vec<Vec<f64>>;
+
+/// A match together with the grid fee that has been applied to it.
+#[derive(Clone, Debug, PartialEq, Encode, Decode, Serialize, Deserialize)]
+pub struct GridFeeMatch {
+	/// The matched trade, priced at the bid.
+	pub trade: Match,
+	/// Grid fee in €/kWh between the clusters of the bid and the ask.
+	pub grid_fee_euro_per_kwh: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode, Serialize, Deserialize)]
+pub struct GridFeeMarketOutput {
+	pub matches: Vec<GridFeeMatch>,
+}
+
+impl From<GridFeeMarketOutput> for MarketOutput {
+	fn from(output: GridFeeMarketOutput) -> Self {
+		MarketOutput { matches: output.matches.into_iter().map(|m| m.trade).collect() }
+	}
+}
+
+/// Parses a grid fee matrix as it is given in `bin/orders/example_grid_fee_matrix.json`.
+pub fn parse_grid_fee_matrix(grid_fee_matrix: &str) -> Result<GridFeeMatrix, StfError> {
+	let matrix: GridFeeMatrix = serde_json::from_str(grid_fee_matrix)
+		.map_err(|e| StfError::Dispatch(format!("Error parsing grid fee matrix: {}", e)))?;
+
+	if matrix.iter().any(|row| row.len() != matrix.len()) {
+		return Err(StfError::Dispatch("Grid fee matrix is not square".into()))
+	}
+
+	if matrix.iter().flatten().any(|fee| !fee.is_finite() || *fee < 0.0) {
+		return Err(StfError::Dispatch("Grid fee matrix contains invalid fees".into()))
+	}
+
+	Ok(matrix)
+}
+
+/// Pay-as-bid matching, where a bid and an ask only match if the bid price covers the ask
+/// price plus the grid fee between their clusters.
+///
+/// Highest bids are served first, each by the asks that are cheapest after adding the grid fee.
+pub fn pay_as_bid_grid_fee_matching(
+	input: &MarketInput,
+	grid_fee_matrix: &GridFeeMatrix,
+) -> Result<GridFeeMarketOutput, StfError> {
+	let mut bids: Vec<&Order> =
+		input.orders.iter().filter(|o| matches!(o.order_type, OrderType::Bid)).collect();
+	let asks: Vec<&Order> =
+		input.orders.iter().filter(|o| matches!(o.order_type, OrderType::Ask)).collect();
+
+	bids.sort_by(|a, b| b.price_euro_per_kwh.total_cmp(&a.price_euro_per_kwh));
+
+	let ask_clusters = asks
+		.iter()
+		.map(|ask| cluster_index(ask, grid_fee_matrix))
+		.collect::<Result<Vec<_>, _>>()?;
+	let mut remaining_ask_energy: Vec<f64> = asks.iter().map(|ask| ask.energy_kwh).collect();
+
+	let mut matches = Vec::new();
+
+	for bid in bids {
+		let bid_cluster = cluster_index(bid, grid_fee_matrix)?;
+
+		let mut candidates: Vec<(usize, f64)> = ask_clusters
+			.iter()
+			.enumerate()
+			.map(|(i, ask_cluster)| (i, grid_fee_matrix[*ask_cluster][bid_cluster]))
+			.collect();
+		candidates.sort_by(|(i, fee_i), (j, fee_j)| {
+			(asks[*i].price_euro_per_kwh + fee_i).total_cmp(&(asks[*j].price_euro_per_kwh + fee_j))
+		});
+
+		let mut remaining_bid_energy = bid.energy_kwh;
+
+		for (i, grid_fee) in candidates {
+			if remaining_bid_energy <= 0.0
+				|| asks[i].price_euro_per_kwh + grid_fee > bid.price_euro_per_kwh
+			{
+				break
+			}
+
+			if remaining_ask_energy[i] <= 0.0 {
+				continue
+			}
+
+			let energy_kwh = remaining_bid_energy.min(remaining_ask_energy[i]);
+			remaining_bid_energy -= energy_kwh;
+			remaining_ask_energy[i] -= energy_kwh;
+
+			matches.push(GridFeeMatch {
+				trade: Match {
+					bid_id: bid.id,
+					ask_id: asks[i].id,
+					energy_kwh,
+					price_euro_per_kwh: bid.price_euro_per_kwh,
+				},
+				grid_fee_euro_per_kwh: grid_fee,
+			});
+		}
+	}
+
+	Ok(GridFeeMarketOutput { matches })
+}
+
+fn cluster_index(order: &Order, grid_fee_matrix: &GridFeeMatrix) -> Result<usize, StfError> {
+	order
+		.cluster_index
+		.filter(|index| *index < grid_fee_matrix.len())
+		.ok_or_else(|| {
+			StfError::Dispatch(format!(
+				"Order {} has cluster index {:?}, which is not in the grid fee matrix",
+				order.id, order.cluster_index
+			))
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn orders(raw: &str) -> MarketInput {
+		MarketInput { orders: serde_json::from_str(raw).unwrap() }
+	}
+
+	const TWO_CLUSTER_ORDERS: &str = r#"[
+		{"id":0,"order_type":"bid","time_slot":"2022-03-04T05:06:07+00:00","actor_id":"actor_0","cluster_index":0,"energy_kwh":5.0,"price_euro_per_kwh":0.30},
+		{"id":1,"order_type":"ask","time_slot":"2022-03-04T05:06:07+00:00","actor_id":"actor_1","cluster_index":1,"energy_kwh":5.0,"price_euro_per_kwh":0.20}
+	]"#;
+
+	#[test]
+	fn parse_grid_fee_matrix_rejects_non_square_matrix() {
+		assert!(parse_grid_fee_matrix("[[0,1],[1,0]]").is_ok());
+		assert!(parse_grid_fee_matrix("[[0,1],[1]]").is_err());
+		assert!(parse_grid_fee_matrix("[[0,-1],[1,0]]").is_err());
+	}
+
+	#[test]
+	fn orders_in_different_clusters_match_if_spread_covers_fee() {
+		let matrix = parse_grid_fee_matrix("[[0,0.05],[0.05,0]]").unwrap();
+
+		let output = pay_as_bid_grid_fee_matching(&orders(TWO_CLUSTER_ORDERS), &matrix).unwrap();
+
+		assert_eq!(output.matches.len(), 1);
+		assert_eq!(output.matches[0].trade.energy_kwh, 5.0);
+		assert_eq!(output.matches[0].grid_fee_euro_per_kwh, 0.05);
+	}
+
+	#[test]
+	fn orders_in_different_clusters_do_not_match_if_fee_exceeds_spread() {
+		let matrix = parse_grid_fee_matrix("[[0,0.2],[0.2,0]]").unwrap();
+
+		let output = pay_as_bid_grid_fee_matching(&orders(TWO_CLUSTER_ORDERS), &matrix).unwrap();
+
+		assert!(output.matches.is_empty());
+	}
+
+	#[test]
+	fn unknown_cluster_index_is_rejected() {
+		let matrix = parse_grid_fee_matrix("[[0]]").unwrap();
+
+		assert!(pay_as_bid_grid_fee_matching(&orders(TWO_CLUSTER_ORDERS), &matrix).is_err());
+	}
+
+	#[test]
+	fn grid_fee_match_encoding_roundtrips() {
+		let matrix = parse_grid_fee_matrix("[[0,0.05],[0.05,0]]").unwrap();
+		let output = pay_as_bid_grid_fee_matching(&orders(TWO_CLUSTER_ORDERS), &matrix).unwrap();
+
+		let decoded = GridFeeMarketOutput::decode(&mut output.encode().as_slice()).unwrap();
+
+		assert_eq!(decoded, output);
+	}
+}
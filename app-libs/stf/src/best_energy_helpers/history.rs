@@ -0,0 +1,317 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! History of the matched time slots, to query market results by time range.
+//!
+//! Matched time slots are indexed by the day of their start, so that a query only reads the days
+//! of its range. Time slots, whose start can't be parsed, are not indexed and can only be queried
+//! by their exact timestamp.
+
+use super::{
+	read_market_results, read_orders, storage::matched_time_slots_by_day_map_key,
+	time_slot::time_slot_unix_seconds,
+};
+use crate::{helpers::get_storage_by_key_hash, StfError};
+use codec::{Decode, Encode};
+use itp_stf_primitives::types::{OrderId, Timestamp};
+use simplyr_lib::Match;
+use std::{cmp::min, format, string::ToString, vec::Vec};
+
+/// Maximum number of items that are returned per page.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of days a query may span.
+pub const MAX_RANGE_DAYS: u64 = 366;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct Pagination {
+	/// Number of items to skip.
+	pub offset: u32,
+	/// Maximum number of items to return, at most `MAX_PAGE_SIZE`.
+	pub limit: u32,
+}
+
+/// Page of the items of a query, together with the total number of items.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct Page<T> {
+	pub items: Vec<T>,
+	pub total: u32,
+}
+
+impl<T> Page<T> {
+	fn paginate(items: Vec<T>, pagination: Pagination) -> Self {
+		let total = items.len() as u32;
+		let items = items
+			.into_iter()
+			.skip(pagination.offset as usize)
+			.take(min(pagination.limit, MAX_PAGE_SIZE) as usize)
+			.collect();
+		Page { items, total }
+	}
+}
+
+/// Page of the items of a query that stops reading once the page is filled, so the total number
+/// of items is unknown.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct OpenPage<T> {
+	pub items: Vec<T>,
+	/// Whether there are more items after the page.
+	pub has_more: bool,
+}
+
+impl<T> OpenPage<T> {
+	/// Number of items to read for a page, one more than it holds tells if there are more.
+	fn items_to_read(pagination: Pagination) -> usize {
+		pagination.offset as usize + min(pagination.limit, MAX_PAGE_SIZE) as usize + 1
+	}
+
+	fn paginate(items: Vec<T>, pagination: Pagination) -> Self {
+		let has_more = items.len() >= Self::items_to_read(pagination);
+		let items = items
+			.into_iter()
+			.skip(pagination.offset as usize)
+			.take(min(pagination.limit, MAX_PAGE_SIZE) as usize)
+			.collect();
+		OpenPage { items, has_more }
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct TimeSlotMatch {
+	pub time_slot: Timestamp,
+	pub trade: Match,
+}
+
+/// Adds a time slot to the index of the matched time slots.
+pub fn index_matched_time_slot(timestamp: &str) {
+	let start = match time_slot_unix_seconds(timestamp) {
+		Some(start) => start,
+		None => {
+			log::warn!("Time slot {} can't be indexed, its start is invalid", timestamp);
+			return
+		},
+	};
+
+	let day = start / SECONDS_PER_DAY;
+	let mut time_slots = read_matched_time_slots_of_day(day);
+	let entry = (start, timestamp.to_string());
+	if let Err(index) = time_slots.binary_search(&entry) {
+		time_slots.insert(index, entry);
+		sp_io::storage::set(&matched_time_slots_by_day_map_key(day), &time_slots.encode());
+	}
+}
+
+/// Indexed time slots that start on a given day, with their start, sorted by start.
+fn read_matched_time_slots_of_day(day: u64) -> Vec<(u64, Timestamp)> {
+	get_storage_by_key_hash(matched_time_slots_by_day_map_key(day)).unwrap_or_default()
+}
+
+/// Matched time slots, which start in `[from, to)`, in the order of time.
+///
+/// Fails if the range spans more than `MAX_RANGE_DAYS` days.
+pub fn matched_time_slots_in_range(from: &str, to: &str) -> Result<Vec<Timestamp>, StfError> {
+	let parse = |time_slot: &str| {
+		time_slot_unix_seconds(time_slot)
+			.ok_or_else(|| StfError::Dispatch(format!("Invalid time slot: {}", time_slot)))
+	};
+	let (from, to) = (parse(from)?, parse(to)?);
+	if from >= to {
+		return Ok(Vec::new())
+	}
+
+	let (first_day, last_day) = (from / SECONDS_PER_DAY, (to - 1) / SECONDS_PER_DAY);
+	if last_day - first_day >= MAX_RANGE_DAYS {
+		return Err(StfError::Dispatch(format!(
+			"Time range must not span more than {} days",
+			MAX_RANGE_DAYS
+		)))
+	}
+
+	Ok((first_day..=last_day)
+		.flat_map(read_matched_time_slots_of_day)
+		.filter(|(start, _)| (from..to).contains(start))
+		.map(|(_, time_slot)| time_slot)
+		.collect())
+}
+
+pub fn read_matched_time_slots_page(
+	from: &str,
+	to: &str,
+	pagination: Pagination,
+) -> Result<Page<Timestamp>, StfError> {
+	Ok(Page::paginate(matched_time_slots_in_range(from, to)?, pagination))
+}
+
+/// Matches of the orders of an actor in all time slots, which start in `[from, to)`.
+///
+/// The order books and market results are only read up to the time slot that fills the page.
+pub fn read_actor_matches_page(
+	actor_id: &str,
+	from: &str,
+	to: &str,
+	pagination: Pagination,
+) -> Result<OpenPage<TimeSlotMatch>, StfError> {
+	let items_to_read = OpenPage::<TimeSlotMatch>::items_to_read(pagination);
+	let mut actor_matches = Vec::new();
+
+	for time_slot in matched_time_slots_in_range(from, to)? {
+		if actor_matches.len() >= items_to_read {
+			break
+		}
+
+		let actor_order_ids: Vec<OrderId> = read_orders(&time_slot)?
+			.iter()
+			.filter(|order| order.actor_id == actor_id)
+			.map(|order| order.id)
+			.collect();
+
+		actor_matches.extend(
+			read_market_results(&time_slot)?
+				.matches
+				.into_iter()
+				.filter(|m| {
+					actor_order_ids.contains(&m.bid_id) || actor_order_ids.contains(&m.ask_id)
+				})
+				.map(|trade| TimeSlotMatch { time_slot: time_slot.clone(), trade }),
+		);
+	}
+
+	Ok(OpenPage::paginate(actor_matches, pagination))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::best_energy_helpers::{default_orders, write_orders, write_results};
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+	use simplyr_lib::MarketOutput;
+
+	fn store_time_slot(time_slot: &str) {
+		let orders: Vec<_> = default_orders()
+			.into_iter()
+			.map(|mut order| {
+				order.time_slot = time_slot.to_string();
+				order
+			})
+			.collect();
+		let trade = Match { bid_id: 0, ask_id: 1, energy_kwh: 1.0, price_euro_per_kwh: 0.2 };
+
+		write_orders(time_slot, &orders);
+		write_results(time_slot, &MarketOutput { matches: vec![trade] });
+	}
+
+	#[test]
+	fn time_slots_are_queried_by_range_in_pages() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			for time_slot in
+				["2022-03-04T07:00:00Z", "2022-03-04T05:00:00Z", "2022-03-04T07:00:00+01:00"]
+			{
+				store_time_slot(time_slot);
+			}
+			// Matching the same time slot again doesn't duplicate it.
+			index_matched_time_slot("2022-03-04T05:00:00Z");
+
+			let page = read_matched_time_slots_page(
+				"2022-03-04T05:00:00Z",
+				"2022-03-04T07:00:00Z",
+				Pagination { offset: 0, limit: 10 },
+			)
+			.unwrap();
+			assert_eq!(page.total, 2);
+			assert_eq!(page.items, vec!["2022-03-04T05:00:00Z", "2022-03-04T07:00:00+01:00"]);
+
+			let page = read_matched_time_slots_page(
+				"2022-03-04T00:00:00Z",
+				"2022-03-05T00:00:00Z",
+				Pagination { offset: 1, limit: 1 },
+			)
+			.unwrap();
+			assert_eq!(page, Page { items: vec!["2022-03-04T07:00:00+01:00".into()], total: 3 });
+
+			// Time slots that start across midnight are found on both days.
+			store_time_slot("2022-03-04T23:45:00Z");
+			store_time_slot("2022-03-05T00:00:00Z");
+			let page = read_matched_time_slots_page(
+				"2022-03-04T23:00:00Z",
+				"2022-03-05T01:00:00Z",
+				Pagination { offset: 0, limit: 10 },
+			)
+			.unwrap();
+			assert_eq!(page.items, vec!["2022-03-04T23:45:00Z", "2022-03-05T00:00:00Z"]);
+
+			assert!(read_matched_time_slots_page(
+				"2022-01-01T00:00:00Z",
+				"2023-01-02T00:00:00Z",
+				Pagination { offset: 0, limit: 10 }
+			)
+			.is_err());
+			assert!(read_matched_time_slots_page(
+				"yesterday",
+				"today",
+				Pagination { offset: 0, limit: 10 }
+			)
+			.is_err());
+		});
+	}
+
+	#[test]
+	fn matches_of_an_actor_are_collected_across_time_slots() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			store_time_slot("2022-03-04T05:00:00Z");
+			store_time_slot("2022-03-04T06:00:00Z");
+
+			let pagination = Pagination { offset: 0, limit: 10 };
+			let page = read_actor_matches_page(
+				"actor_1",
+				"2022-03-04T00:00:00Z",
+				"2022-03-05T00:00:00Z",
+				pagination,
+			)
+			.unwrap();
+			assert_eq!(page.items.len(), 2);
+			assert!(!page.has_more);
+			assert_eq!(page.items[1].time_slot, "2022-03-04T06:00:00Z");
+			assert_eq!(page.items[1].trade.ask_id, 1);
+
+			let page = read_actor_matches_page(
+				"actor_1",
+				"2022-03-04T00:00:00Z",
+				"2022-03-05T00:00:00Z",
+				Pagination { offset: 0, limit: 1 },
+			)
+			.unwrap();
+			assert_eq!(page.items.len(), 1);
+			assert_eq!(page.items[0].time_slot, "2022-03-04T05:00:00Z");
+			assert!(page.has_more);
+
+			let page = read_actor_matches_page(
+				"actor_2",
+				"2022-03-04T00:00:00Z",
+				"2022-03-05T00:00:00Z",
+				pagination,
+			)
+			.unwrap();
+			assert_eq!(page, OpenPage { items: vec![], has_more: false });
+		});
+	}
+}
@@ -0,0 +1,214 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Market calendar, which divides the time into time slots of equal length.
+//!
+//! Orders for a time slot are accepted until its gate closure, a configurable offset before the
+//! start of the slot. The orders of a time slot are matched in the first block after its gate
+//! closure. Without a calendar, any time slot is open until it is closed explicitly.
+
+use super::{storage::market_calendar_key, time_slot::time_slot_unix_seconds};
+use crate::{helpers::get_storage_by_key_hash, StfError};
+use codec::{Decode, Encode};
+use itp_storage::storage_value_key;
+use std::{format, string::String, vec::Vec};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct MarketCalendar {
+	/// Length of a time slot in seconds. Time slots start at multiples of it since the unix epoch.
+	pub slot_length_secs: u64,
+	/// Seconds before the start of a time slot, at which its gate closes.
+	pub gate_closure_secs: u64,
+}
+
+impl MarketCalendar {
+	pub fn new(slot_length_secs: u64, gate_closure_secs: u64) -> Result<Self, StfError> {
+		if slot_length_secs == 0 {
+			return Err(StfError::Dispatch("The slot length must not be zero".into()))
+		}
+		Ok(MarketCalendar { slot_length_secs, gate_closure_secs })
+	}
+
+	/// Start of a time slot of the calendar in seconds since the unix epoch.
+	pub fn slot_start(&self, time_slot: &str) -> Result<u64, StfError> {
+		let start = time_slot_unix_seconds(time_slot)
+			.ok_or_else(|| StfError::Dispatch(format!("Invalid time slot: {}", time_slot)))?;
+
+		if start % self.slot_length_secs != 0 {
+			return Err(StfError::Dispatch(format!(
+				"Time slot {} does not start at a multiple of {} seconds",
+				time_slot, self.slot_length_secs
+			)))
+		}
+		Ok(start)
+	}
+
+	/// Gate closure of a time slot of the calendar in seconds since the unix epoch.
+	pub fn gate_closure(&self, time_slot: &str) -> Result<u64, StfError> {
+		Ok(self.slot_start(time_slot)?.saturating_sub(self.gate_closure_secs))
+	}
+}
+
+pub fn write_market_calendar(calendar: &MarketCalendar) {
+	sp_io::storage::set(&market_calendar_key(), &calendar.encode());
+	log::info!("Set market calendar: {:?}", calendar);
+}
+
+pub fn read_market_calendar() -> Option<MarketCalendar> {
+	get_storage_by_key_hash(market_calendar_key())
+}
+
+/// Current time in seconds since the unix epoch.
+///
+/// This is the timestamp of the sidechain block, which all validateers agree on. Fails outside of
+/// a sidechain block, the clock of the enclave would make the state transition non-deterministic.
+pub fn now_secs() -> Result<u64, StfError> {
+	// Set by the block proposer, see `its_state::SidechainSystemExt`.
+	sp_io::storage::get(&storage_value_key("System", "Timestamp"))
+		.and_then(|millis| u64::decode(&mut millis.as_slice()).ok())
+		.map(|millis| millis / 1000)
+		.ok_or_else(|| StfError::Dispatch("No sidechain block timestamp in the state".into()))
+}
+
+/// Fails if the gate of the time slot has already closed.
+pub fn ensure_gate_is_open(time_slot: &str) -> Result<(), StfError> {
+	let calendar = match read_market_calendar() {
+		Some(calendar) => calendar,
+		None => return Ok(()),
+	};
+
+	let gate_closure = calendar.gate_closure(time_slot)?;
+	if now_secs()? >= gate_closure {
+		return Err(StfError::Dispatch(format!(
+			"The gate of time slot {} closed at {}",
+			time_slot, gate_closure
+		)))
+	}
+	Ok(())
+}
+
+/// Fails if the gate of the time slot is still open, i.e. trading has not ended yet.
+pub fn ensure_gate_is_closed(time_slot: &str) -> Result<(), StfError> {
+	let calendar = match read_market_calendar() {
+		Some(calendar) => calendar,
+		None => return Ok(()),
+	};
+
+	let gate_closure = calendar.gate_closure(time_slot)?;
+	if now_secs()? < gate_closure {
+		return Err(StfError::Dispatch(format!(
+			"Time slot {} can't be matched before its gate closes at {}",
+			time_slot, gate_closure
+		)))
+	}
+	Ok(())
+}
+
+/// The time slots, whose gate has closed by now and which are therefore due for matching.
+pub fn time_slots_due_for_matching(open_time_slots: Vec<String>) -> Vec<String> {
+	let calendar = match read_market_calendar() {
+		Some(calendar) => calendar,
+		None => return Vec::new(),
+	};
+
+	let now = match now_secs() {
+		Ok(now) => now,
+		Err(e) => {
+			log::warn!("Not closing any time slots: {:?}", e);
+			return Vec::new()
+		},
+	};
+	open_time_slots
+		.into_iter()
+		.filter(|time_slot| match calendar.gate_closure(time_slot) {
+			Ok(gate_closure) => gate_closure <= now,
+			Err(e) => {
+				log::warn!("Time slot is not in the market calendar: {:?}", e);
+				false
+			},
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+
+	// 2022-03-04T05:00:00Z
+	const SLOT_START: u64 = 1_646_370_000;
+
+	fn set_now(secs: u64) {
+		sp_io::storage::set(&storage_value_key("System", "Timestamp"), &(secs * 1000).encode());
+	}
+
+	#[test]
+	fn time_slots_must_start_at_a_multiple_of_the_slot_length() {
+		let calendar = MarketCalendar::new(900, 300).unwrap();
+
+		assert_eq!(calendar.slot_start("2022-03-04T05:15:00Z").unwrap(), SLOT_START + 900);
+		assert_eq!(calendar.gate_closure("2022-03-04T06:15:00+01:00").unwrap(), SLOT_START - 300);
+		assert!(calendar.slot_start("2022-03-04T05:06:07Z").is_err());
+		assert!(calendar.slot_start("yesterday").is_err());
+		assert!(MarketCalendar::new(0, 300).is_err());
+	}
+
+	#[test]
+	fn gate_closure_is_not_enforced_against_the_clock_of_the_enclave() {
+		let mut state = SgxExternalities::default();
+		let time_slot = "2022-03-04T05:00:00Z";
+
+		state.execute_with(|| {
+			write_market_calendar(&MarketCalendar::new(900, 300).unwrap());
+
+			assert!(now_secs().is_err());
+			assert!(ensure_gate_is_open(time_slot).is_err());
+			assert!(ensure_gate_is_closed(time_slot).is_err());
+			assert!(time_slots_due_for_matching(vec![time_slot.into()]).is_empty());
+		});
+	}
+
+	#[test]
+	fn gate_closure_is_enforced_against_the_block_timestamp() {
+		let mut state = SgxExternalities::default();
+		let time_slot = "2022-03-04T05:00:00Z";
+
+		state.execute_with(|| {
+			set_now(SLOT_START - 301);
+			// Without a calendar, the gate is never closed.
+			assert!(ensure_gate_is_closed(time_slot).is_ok());
+			assert!(time_slots_due_for_matching(vec![time_slot.into()]).is_empty());
+
+			write_market_calendar(&MarketCalendar::new(900, 300).unwrap());
+			assert!(ensure_gate_is_open(time_slot).is_ok());
+			assert!(ensure_gate_is_closed(time_slot).is_err());
+			assert!(time_slots_due_for_matching(vec![time_slot.into()]).is_empty());
+
+			set_now(SLOT_START - 300);
+			assert!(ensure_gate_is_open(time_slot).is_err());
+			assert!(ensure_gate_is_closed(time_slot).is_ok());
+			assert_eq!(
+				time_slots_due_for_matching(vec![
+					time_slot.into(),
+					"2022-03-04T05:15:00Z".into(),
+					"2022-03-04T05:06:07Z".into()
+				]),
+				vec![time_slot.to_string()]
+			);
+		});
+	}
+}
@@ -0,0 +1,487 @@
+use crate::{helpers::get_storage_by_key_hash, MerkleProofWithCodec, StfError};
+use binary_merkle_tree::{merkle_proof, merkle_root};
+use codec::{Decode, Encode};
+use grid_fee::GridFeeMarketOutput;
+use ita_sgx_runtime::BlockNumber;
+use itp_stf_primitives::error::StfError;
+use simplyr_lib::{MarketOutput, Match, Order};
+use sp_core::{blake2_256, H256};
+use sp_runtime::traits::Keccak256;
+use std::{format, string::ToString, vec::Vec};
+use storage::{
+	grid_fee_market_results_map_key, market_results_map_key, merkle_roots_map_key,
+	merkle_roots_parentchain_block_map_key, orders_map_key, results_merkle_roots_map_key,
+};
+
+pub mod actors;
+pub mod grid_fee;
+pub mod history;
+pub mod market_calendar;
+pub mod order_book;
+pub mod reserve_price;
+pub mod settlement;
+pub mod time_slot;
+pub mod validation;
+
+pub fn get_merkle_proofs_for_actor_from_state(
+	timestamp: &str,
+	actor_id: &str,
+) -> Result<Vec<MerkleProofWithCodec<H256, Vec<u8>>>, StfError> {
+	let orders = read_orders(timestamp)?;
+
+	let proofs = get_merkle_proofs_for_actor(actor_id, &orders);
+	if proofs.is_empty() {
+		return Err(StfError::Dispatch(format!("Leaf Index error: {:?}", actor_id)))
+	}
+	Ok(proofs)
+}
+
+pub fn get_merkle_proofs_for_actor_matches_from_state(
+	timestamp: &str,
+	actor_id: &str,
+) -> Result<Vec<MerkleProofWithCodec<H256, Vec<u8>>>, StfError> {
+	let orders = read_orders(timestamp)?;
+	let market_results = read_market_results(timestamp)?;
+
+	let proofs = get_merkle_proofs_for_actor_matches(actor_id, &orders, &market_results.matches);
+	if proofs.is_empty() {
+		return Err(StfError::Dispatch(format!("No matches for actor: {:?}", actor_id)))
+	}
+	Ok(proofs)
+}
+
+/// Returns true if an order book has already been stored for the given timestamp.
+pub fn orders_exist(timestamp: &str) -> bool {
+	sp_io::storage::exists(&orders_map_key(timestamp.to_string()))
+}
+
+pub fn read_market_results(timestamp: &str) -> Result<MarketOutput, StfError> {
+	get_storage_by_key_hash(market_results_map_key(timestamp.to_string())).ok_or_else(|| {
+		StfError::Dispatch(format!("No market results stored for timestamp: {:?}", timestamp))
+	})
+}
+
+pub fn read_grid_fee_market_results(timestamp: &str) -> Result<GridFeeMarketOutput, StfError> {
+	let key = grid_fee_market_results_map_key(timestamp.to_string());
+	get_storage_by_key_hash(key).ok_or_else(|| {
+		StfError::Dispatch(format!("No grid fee results stored for timestamp: {:?}", timestamp))
+	})
+}
+
+pub fn read_orders(timestamp: &str) -> Result<Vec<Order>, StfError> {
+	get_storage_by_key_hash(orders_map_key(timestamp.to_string())).ok_or_else(|| {
+		StfError::Dispatch(format!("No orders stored for timestamp: {:?}", timestamp))
+	})
+}
+
+/// Merkle roots of the orders and of the market results of a timestamp.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct MerkleRoots {
+	pub orders: H256,
+	pub market_results: H256,
+	/// Latest imported parentchain block when the roots were computed. The roots are published
+	/// in one of the following parentchain blocks. Not known for roots stored before it was
+	/// recorded.
+	pub parentchain_block_number: Option<BlockNumber>,
+}
+
+pub fn read_merkle_roots(timestamp: &str) -> Result<MerkleRoots, StfError> {
+	let orders = get_storage_by_key_hash(merkle_roots_map_key(timestamp.to_string()));
+	let market_results =
+		get_storage_by_key_hash(results_merkle_roots_map_key(timestamp.to_string()));
+
+	match (orders, market_results) {
+		(Some(orders), Some(market_results)) => Ok(MerkleRoots {
+			orders,
+			market_results,
+			parentchain_block_number: get_storage_by_key_hash(
+				merkle_roots_parentchain_block_map_key(timestamp.to_string()),
+			),
+		}),
+		_ => Err(StfError::Dispatch(format!(
+			"No merkle roots stored for timestamp: {:?}",
+			timestamp
+		))),
+	}
+}
+
+/// Topic of the `publish_hash` event, with which the merkle root of the orders of a timestamp
+/// is published on the parentchain.
+pub fn orders_merkle_root_topic(timestamp: &str) -> H256 {
+	merkle_root_topic(b"orders", timestamp)
+}
+
+/// Topic of the `publish_hash` event, with which the merkle root of the market results of a
+/// timestamp is published on the parentchain.
+pub fn results_merkle_root_topic(timestamp: &str) -> H256 {
+	merkle_root_topic(b"market_results", timestamp)
+}
+
+fn merkle_root_topic(kind: &[u8], timestamp: &str) -> H256 {
+	blake2_256(&(b"best_energy", kind, timestamp).encode()).into()
+}
+
+pub fn write_orders(timestamp: &str, orders: &[Order]) {
+	sp_io::storage::set(&orders_map_key(timestamp.to_string()), &orders.encode());
+	log::info!("Wrote {} orders for timestamp {}", orders.len(), timestamp);
+}
+
+pub fn write_results(timestamp: &str, market_results: &MarketOutput) {
+	sp_io::storage::set(&market_results_map_key(timestamp.to_string()), &market_results.encode());
+	history::index_matched_time_slot(timestamp);
+	log::info!("Wrote market results for timestamp {}", timestamp);
+}
+
+pub fn write_grid_fee_results(timestamp: &str, market_results: &GridFeeMarketOutput) {
+	sp_io::storage::set(
+		&grid_fee_market_results_map_key(timestamp.to_string()),
+		&market_results.encode(),
+	);
+	log::info!("Wrote grid fee market results for timestamp {}", timestamp);
+}
+
+/// Gets the merkle proofs of all orders of an `actor_id` in the order set.
+pub fn get_merkle_proofs_for_actor(
+	actor_id: &str,
+	orders: &[Order],
+) -> Vec<MerkleProofWithCodec<H256, Vec<u8>>> {
+	get_leaf_indices_for_actor(actor_id, orders)
+		.into_iter()
+		.map(|leaf_index| {
+			merkle_proof::<Keccak256, _, _>(orders.iter().map(Encode::encode), leaf_index).into()
+		})
+		.collect()
+}
+
+pub fn get_leaf_indices_for_actor(actor_id: &str, orders: &[Order]) -> Vec<usize> {
+	orders
+		.iter()
+		.enumerate()
+		.filter(|(_, order)| order.actor_id == actor_id)
+		.map(|(leaf_index, _)| leaf_index)
+		.collect()
+}
+
+/// Merkle root over the encoded matches of the market results.
+pub fn market_results_merkle_root(market_results: &MarketOutput) -> H256 {
+	merkle_root::<Keccak256, _>(market_results.matches.iter().map(Encode::encode))
+}
+
+/// Gets the merkle proofs of all matches, in which one of the orders of `actor_id` is involved.
+pub fn get_merkle_proofs_for_actor_matches(
+	actor_id: &str,
+	orders: &[Order],
+	matches: &[Match],
+) -> Vec<MerkleProofWithCodec<H256, Vec<u8>>> {
+	get_match_leaf_indices_for_actor(actor_id, orders, matches)
+		.into_iter()
+		.map(|leaf_index| {
+			merkle_proof::<Keccak256, _, _>(matches.iter().map(Encode::encode), leaf_index).into()
+		})
+		.collect()
+}
+
+pub fn get_match_leaf_indices_for_actor(
+	actor_id: &str,
+	orders: &[Order],
+	matches: &[Match],
+) -> Vec<usize> {
+	let is_actors_order =
+		|order_id| orders.iter().any(|order| order.id == order_id && order.actor_id == actor_id);
+
+	matches
+		.iter()
+		.enumerate()
+		.filter(|(_, m)| is_actors_order(m.bid_id) || is_actors_order(m.ask_id))
+		.map(|(leaf_index, _)| leaf_index)
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+	use simplyr_lib::{pay_as_bid_matching, MarketInput, OrderType};
+
+	#[test]
+	fn get_leaf_indices_of_orders_works() {
+		let orders = default_orders();
+
+		assert_eq!(get_leaf_indices_for_actor("actor_0", &orders), vec![0]);
+		assert_eq!(get_leaf_indices_for_actor("actor_1", &orders), vec![1]);
+		assert_eq!(get_leaf_indices_for_actor("actor_2", &orders), vec![2]);
+
+		assert!(get_leaf_indices_for_actor("I do not exist", &orders).is_empty());
+	}
+
+	#[test]
+	fn get_leaf_indices_returns_all_orders_of_actor() {
+		let mut orders = default_orders();
+		orders[2].actor_id = "actor_0".into();
+
+		assert_eq!(get_leaf_indices_for_actor("actor_0", &orders), vec![0, 2]);
+	}
+
+	#[test]
+	fn get_merkle_proof_for_actor_works() {
+		let orders = default_orders();
+		let actor_0_order = orders[0].clone();
+
+		let proofs = get_merkle_proofs_for_actor("actor_0", &orders);
+
+		// Test that we have returned the correct leaf. This is what a
+		// client can do to ensure that it has received a proof for the
+		// expected leaf.
+		assert_eq!(proofs.len(), 1);
+		assert_eq!(proofs[0].leaf, actor_0_order.encode());
+		assert_eq!(proofs[0].leaf_index, 0);
+	}
+
+	#[test]
+	fn get_merkle_proofs_for_actor_with_several_orders_works() {
+		let mut orders = default_orders();
+		orders[2].actor_id = "actor_0".into();
+
+		let proofs = get_merkle_proofs_for_actor("actor_0", &orders);
+
+		assert_eq!(proofs.len(), 2);
+		assert_eq!(proofs[0].leaf, orders[0].encode());
+		assert_eq!(proofs[1].leaf, orders[2].encode());
+		assert_eq!(proofs[1].leaf_index, 2);
+		assert_eq!(proofs[0].root, proofs[1].root);
+	}
+
+	#[test]
+	fn get_merkle_proofs_for_actor_matches_works() {
+		let mut orders = default_orders();
+		orders[2].order_type = OrderType::Bid;
+		orders[2].price_euro_per_kwh = 0.3;
+		let matches = pay_as_bid_matching(&MarketInput { orders: orders.clone() }).matches;
+
+		let indices = get_match_leaf_indices_for_actor("actor_2", &orders, &matches);
+		assert!(!indices.is_empty());
+		assert!(get_match_leaf_indices_for_actor("I do not exist", &orders, &matches).is_empty());
+
+		let proofs = get_merkle_proofs_for_actor_matches("actor_2", &orders, &matches);
+		let root = market_results_merkle_root(&MarketOutput { matches: matches.clone() });
+
+		assert_eq!(proofs.len(), indices.len());
+		for (proof, leaf_index) in proofs.iter().zip(indices) {
+			assert_eq!(proof.root, root);
+			assert_eq!(proof.leaf, matches[leaf_index].encode());
+		}
+	}
+
+	#[test]
+	fn orders_and_results_are_stored_in_state() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+		let timestamp = orders[0].time_slot.clone();
+		let market_output = MarketOutput { matches: Vec::new() };
+
+		state.execute_with(|| {
+			assert!(!orders_exist(&timestamp));
+			assert!(read_orders(&timestamp).is_err());
+
+			write_orders(&timestamp, &orders);
+			write_results(&timestamp, &market_output);
+
+			assert!(orders_exist(&timestamp));
+			assert_eq!(read_orders(&timestamp).unwrap(), orders);
+			assert_eq!(read_market_results(&timestamp).unwrap(), market_output);
+		});
+	}
+
+	#[test]
+	fn merkle_root_topics_differ_per_timestamp_and_kind() {
+		let timestamp = "2022-03-04T05:06:07+00:00";
+
+		assert_eq!(orders_merkle_root_topic(timestamp), orders_merkle_root_topic(timestamp));
+		assert_ne!(orders_merkle_root_topic(timestamp), results_merkle_root_topic(timestamp));
+		assert_ne!(
+			orders_merkle_root_topic(timestamp),
+			orders_merkle_root_topic("2022-03-04T05:21:07+00:00")
+		);
+	}
+}
+
+pub fn default_orders() -> Vec<Order> {
+	let orders_raw = r#"[{
+      "id": 0,
+      "order_type": "ask",
+      "time_slot": "2022-03-04T05:06:07+00:00",
+      "actor_id": "actor_0",
+      "cluster_index": 0,
+      "energy_kwh": 5.0,
+      "price_euro_per_kwh": 0.19
+    },
+    {
+      "id": 1,
+      "order_type": "ask",
+      "time_slot": "2022-03-04T05:06:07+00:00",
+      "actor_id": "actor_1",
+      "cluster_index": 0,
+      "energy_kwh": 8.8,
+      "price_euro_per_kwh": 0.23
+    },
+    {
+      "id": 2,
+      "order_type": "ask",
+      "time_slot": "2022-03-04T05:06:07+00:00",
+      "actor_id": "actor_2",
+      "cluster_index": 0,
+      "energy_kwh": 7.5,
+      "price_euro_per_kwh": 0.15
+    }]"#;
+
+	serde_json::from_str(orders_raw).unwrap()
+}
+
+/// SGX storage helpers for all best energy data.
+pub mod storage {
+	use itp_storage::{storage_map_key, storage_value_key, StorageHasher};
+	use std::{string::String, vec::Vec};
+
+	/// Module prefix to prevent accidental overwrite of storage for equally named storages.
+	const MODULE_PREFIX: &str = "best_energy";
+	const MERKLE_ROOTS_KEY: &str = "merkle_roots";
+	const ORDERS_KEY: &str = "orders";
+	const MARKET_RESULTS_KEY: &str = "market_results";
+	const RESULTS_MERKLE_ROOTS_KEY: &str = "results_merkle_roots";
+	const MERKLE_ROOTS_PARENTCHAIN_BLOCK_KEY: &str = "merkle_roots_parentchain_block";
+	const GRID_FEE_MARKET_RESULTS_KEY: &str = "grid_fee_market_results";
+	const OPEN_ORDERS_KEY: &str = "open_orders";
+	const ACTOR_ACCOUNTS_KEY: &str = "actor_accounts";
+	const RESERVE_PRICES_KEY: &str = "reserve_prices";
+	const GRID_SETTLEMENTS_KEY: &str = "grid_settlements";
+	const SETTLEMENTS_KEY: &str = "settlements";
+	const MARKET_CALENDAR_KEY: &str = "market_calendar";
+	const OPEN_TIME_SLOTS_KEY: &str = "open_time_slots";
+	const MATCHED_TIME_SLOTS_BY_DAY_KEY: &str = "matched_time_slots_by_day";
+	const BALANCE_UNITS_PER_EURO_KEY: &str = "balance_units_per_euro";
+
+	pub fn merkle_roots_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			MERKLE_ROOTS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the order book that was submitted for a given timestamp.
+	pub fn orders_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(MODULE_PREFIX, ORDERS_KEY, &timestamp, &StorageHasher::Blake2_128Concat)
+	}
+
+	/// Key of the `MarketOutput` that was computed for a given timestamp.
+	pub fn market_results_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			MARKET_RESULTS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the `GridFeeMarketOutput` that was computed for a given timestamp.
+	pub fn grid_fee_market_results_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			GRID_FEE_MARKET_RESULTS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the merkle root over the matches of the market results of a given timestamp.
+	pub fn results_merkle_roots_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			RESULTS_MERKLE_ROOTS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the parentchain block number, at which the merkle roots of a given timestamp have
+	/// been computed.
+	pub fn merkle_roots_parentchain_block_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			MERKLE_ROOTS_PARENTCHAIN_BLOCK_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the orders that have been submitted for a time slot, which is still open.
+	pub fn open_orders_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			OPEN_ORDERS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the accounts that are registered for a given actor id.
+	pub fn actor_accounts_map_key(actor_id: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			ACTOR_ACCOUNTS_KEY,
+			&actor_id,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the latest day-ahead spot prices, which are the reserve prices of the market.
+	pub fn reserve_prices_key() -> Vec<u8> {
+		storage_value_key(MODULE_PREFIX, RESERVE_PRICES_KEY)
+	}
+
+	/// Key of the grid settlements of the unmatched volume of a given timestamp.
+	pub fn grid_settlements_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			GRID_SETTLEMENTS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	/// Key of the settlement summary of the matches of a given timestamp.
+	pub fn settlements_map_key(timestamp: String) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			SETTLEMENTS_KEY,
+			&timestamp,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+
+	pub fn market_calendar_key() -> Vec<u8> {
+		storage_value_key(MODULE_PREFIX, MARKET_CALENDAR_KEY)
+	}
+
+	/// Key of the conversion rate from euro to balance units, at which trades are settled.
+	pub fn balance_units_per_euro_key() -> Vec<u8> {
+		storage_value_key(MODULE_PREFIX, BALANCE_UNITS_PER_EURO_KEY)
+	}
+
+	/// Key of the time slots, for which open orders have been submitted.
+	pub fn open_time_slots_key() -> Vec<u8> {
+		storage_value_key(MODULE_PREFIX, OPEN_TIME_SLOTS_KEY)
+	}
+
+	/// Key of the index of the time slots, for which market results have been stored, that
+	/// start on a given day since the unix epoch.
+	pub fn matched_time_slots_by_day_map_key(day: u64) -> Vec<u8> {
+		storage_map_key(
+			MODULE_PREFIX,
+			MATCHED_TIME_SLOTS_BY_DAY_KEY,
+			&day,
+			&StorageHasher::Blake2_128Concat,
+		)
+	}
+}
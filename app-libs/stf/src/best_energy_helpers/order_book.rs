@@ -0,0 +1,306 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Order book of a time slot that is still open for submissions.
+//!
+//! Orders are collected per time slot until the slot is closed and matched. The id of an order is
+//! assigned by the enclave when it is submitted, so that no account can occupy the id of another
+//! account's order. Every order is bound to the account that submitted it, only that account can
+//! amend or cancel it. Orders
+//! can only be submitted by an account that is registered for the actor of the order. If a
+//! market calendar is set, orders can only be submitted, amended or cancelled until the gate of
+//! their time slot closes.
+
+use super::{
+	actors::ensure_actor_account,
+	market_calendar::ensure_gate_is_open,
+	orders_exist,
+	storage::{open_orders_map_key, open_time_slots_key},
+	validation::validate_order,
+};
+use crate::{helpers::get_storage_by_key_hash, StfError};
+use codec::{Decode, Encode};
+use itp_stf_primitives::{
+	error::InvalidOrder,
+	types::{AccountId, OrderId},
+};
+use simplyr_lib::Order;
+use std::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct SubmittedOrder {
+	pub submitter: AccountId,
+	pub order: Order,
+}
+
+/// All orders that have been submitted for an open time slot.
+pub fn read_open_orders(timestamp: &str) -> Vec<SubmittedOrder> {
+	get_storage_by_key_hash(open_orders_map_key(timestamp.to_string())).unwrap_or_default()
+}
+
+fn write_open_orders(timestamp: &str, orders: &[SubmittedOrder]) {
+	let key = open_orders_map_key(timestamp.to_string());
+	let mut time_slots = read_open_time_slots();
+	time_slots.retain(|time_slot| time_slot != timestamp);
+
+	if orders.is_empty() {
+		sp_io::storage::clear(&key);
+	} else {
+		sp_io::storage::set(&key, &orders.encode());
+		time_slots.push(timestamp.to_string());
+	}
+	sp_io::storage::set(&open_time_slots_key(), &time_slots.encode());
+}
+
+/// All time slots, for which open orders have been submitted.
+pub fn read_open_time_slots() -> Vec<String> {
+	get_storage_by_key_hash(open_time_slots_key()).unwrap_or_default()
+}
+
+/// Removes the open orders of a time slot. Only to be called once they have been matched.
+pub fn clear_open_orders(timestamp: &str) {
+	write_open_orders(timestamp, &[]);
+}
+
+/// Adds an order to the open orders of its time slot. The id of the order is ignored and replaced
+/// by the next free id of the time slot.
+pub fn submit_order(submitter: &AccountId, mut order: Order) -> Result<(), StfError> {
+	ensure_slot_is_open(&order.time_slot)?;
+	ensure_order_is_valid(&order)?;
+	ensure_actor_account(&order.actor_id, submitter)?;
+
+	let mut orders = read_open_orders(&order.time_slot);
+	order.id = orders.iter().map(|o| o.order.id + 1).max().unwrap_or_default();
+
+	let timestamp = order.time_slot.clone();
+	orders.push(SubmittedOrder { submitter: submitter.clone(), order });
+	write_open_orders(&timestamp, &orders);
+	Ok(())
+}
+
+/// Replaces an open order of the `submitter` with the same id and time slot.
+pub fn amend_order(submitter: &AccountId, order: Order) -> Result<(), StfError> {
+	ensure_slot_is_open(&order.time_slot)?;
+	ensure_order_is_valid(&order)?;
+	ensure_actor_account(&order.actor_id, submitter)?;
+
+	let timestamp = order.time_slot.clone();
+	let mut orders = read_open_orders(&timestamp);
+	let submitted = find_own_order(&mut orders, submitter, &timestamp, order.id)?;
+	submitted.order = order;
+
+	write_open_orders(&timestamp, &orders);
+	Ok(())
+}
+
+pub fn cancel_order(
+	submitter: &AccountId,
+	timestamp: &str,
+	order_id: OrderId,
+) -> Result<(), StfError> {
+	ensure_slot_is_open(timestamp)?;
+
+	let mut orders = read_open_orders(timestamp);
+	find_own_order(&mut orders, submitter, timestamp, order_id)?;
+	orders.retain(|o| o.order.id != order_id);
+
+	write_open_orders(timestamp, &orders);
+	Ok(())
+}
+
+fn find_own_order<'a>(
+	orders: &'a mut [SubmittedOrder],
+	submitter: &AccountId,
+	timestamp: &str,
+	order_id: OrderId,
+) -> Result<&'a mut SubmittedOrder, StfError> {
+	let submitted = orders.iter_mut().find(|o| o.order.id == order_id).ok_or_else(|| {
+		StfError::Dispatch(format!("Order {} does not exist for time slot {}", order_id, timestamp))
+	})?;
+
+	if &submitted.submitter != submitter {
+		return Err(StfError::Dispatch(format!(
+			"Order {} of time slot {} has been submitted by another account",
+			order_id, timestamp
+		)))
+	}
+
+	Ok(submitted)
+}
+
+fn ensure_order_is_valid(order: &Order) -> Result<(), StfError> {
+	let invalid_orders: Vec<InvalidOrder> = validate_order(order, None)
+		.into_iter()
+		.map(|reason| InvalidOrder { order_id: order.id, reason })
+		.collect();
+
+	if invalid_orders.is_empty() {
+		Ok(())
+	} else {
+		Err(StfError::InvalidOrders(invalid_orders))
+	}
+}
+
+fn ensure_slot_is_open(timestamp: &str) -> Result<(), StfError> {
+	if orders_exist(timestamp) {
+		return Err(StfError::Dispatch(format!("Time slot {} is already closed", timestamp)))
+	}
+	ensure_gate_is_open(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::best_energy_helpers::{actors::register_actor_account, default_orders};
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+
+	fn alice() -> AccountId {
+		AccountId::new([1u8; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::new([2u8; 32])
+	}
+
+	#[test]
+	fn submitted_orders_are_collected_per_slot() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+		let timestamp = orders[0].time_slot.clone();
+
+		state.execute_with(|| {
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_1", bob());
+			register_actor_account("actor_2", bob());
+
+			submit_order(&alice(), orders[0].clone()).unwrap();
+			submit_order(&bob(), orders[1].clone()).unwrap();
+
+			let mut invalid = orders[2].clone();
+			invalid.energy_kwh = -1.0;
+			assert!(submit_order(&bob(), invalid).is_err());
+			assert_eq!(read_open_orders(&timestamp).len(), 2);
+			assert_eq!(read_open_time_slots(), vec![timestamp.clone()]);
+
+			let open_orders = read_open_orders(&timestamp);
+			assert_eq!(
+				open_orders[1],
+				SubmittedOrder { submitter: bob(), order: orders[1].clone() }
+			);
+			clear_open_orders(&timestamp);
+			assert!(read_open_orders(&timestamp).is_empty());
+			assert!(read_open_time_slots().is_empty());
+		});
+	}
+
+	#[test]
+	fn order_ids_are_assigned_by_the_enclave() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+		let timestamp = orders[0].time_slot.clone();
+
+		state.execute_with(|| {
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_1", bob());
+
+			// Bob tries to occupy the id that alice is going to use.
+			let mut squatting = orders[1].clone();
+			squatting.id = orders[0].id + 1;
+			submit_order(&bob(), squatting.clone()).unwrap();
+			submit_order(&bob(), squatting).unwrap();
+
+			let mut own = orders[0].clone();
+			own.id = orders[0].id + 1;
+			submit_order(&alice(), own).unwrap();
+
+			let open_orders = read_open_orders(&timestamp);
+			let ids: Vec<OrderId> = open_orders.iter().map(|o| o.order.id).collect();
+			assert_eq!(ids, vec![0, 1, 2]);
+			assert_eq!(open_orders[2].submitter, alice());
+
+			let mut amended = open_orders[2].order.clone();
+			amended.energy_kwh = 1.0;
+			amend_order(&alice(), amended).unwrap();
+		});
+	}
+
+	#[test]
+	fn only_submitter_can_amend_or_cancel_order() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+		let timestamp = orders[0].time_slot.clone();
+
+		state.execute_with(|| {
+			// Both accounts act on behalf of the same actor.
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_0", bob());
+
+			submit_order(&alice(), orders[0].clone()).unwrap();
+
+			let mut amended = orders[0].clone();
+			amended.energy_kwh = 1.0;
+
+			assert!(amend_order(&bob(), amended.clone()).is_err());
+			assert!(cancel_order(&bob(), &timestamp, orders[0].id).is_err());
+
+			amend_order(&alice(), amended.clone()).unwrap();
+			assert_eq!(read_open_orders(&timestamp)[0].order, amended);
+
+			cancel_order(&alice(), &timestamp, orders[0].id).unwrap();
+			assert!(read_open_orders(&timestamp).is_empty());
+		});
+	}
+
+	#[test]
+	fn only_registered_accounts_can_submit_orders_of_an_actor() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+
+		state.execute_with(|| {
+			register_actor_account("actor_0", alice());
+
+			assert!(submit_order(&bob(), orders[0].clone()).is_err());
+			assert!(submit_order(&alice(), orders[1].clone()).is_err());
+			submit_order(&alice(), orders[0].clone()).unwrap();
+		});
+	}
+
+	#[test]
+	fn orders_cannot_be_amended_on_behalf_of_another_actor() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+		let timestamp = orders[0].time_slot.clone();
+
+		state.execute_with(|| {
+			register_actor_account("actor_0", alice());
+			register_actor_account("actor_1", bob());
+			submit_order(&alice(), orders[0].clone()).unwrap();
+
+			let mut other_actor = orders[0].clone();
+			other_actor.actor_id = "actor_1".into();
+			assert!(amend_order(&alice(), other_actor).is_err());
+
+			let open_orders = read_open_orders(&timestamp);
+			assert_eq!(open_orders[0].submitter, alice());
+			assert_eq!(open_orders[0].order, orders[0]);
+		});
+	}
+}
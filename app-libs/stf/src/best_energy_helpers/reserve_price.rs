@@ -0,0 +1,210 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Reserve prices of the market, taken from the day-ahead spot prices that the teeracle publishes.
+//!
+//! Volume that has not been matched in the market is settled against the grid at the reserve
+//! price of its time slot.
+
+use super::{
+	storage::{grid_settlements_map_key, reserve_prices_key},
+	time_slot::time_slot_unix_seconds,
+};
+use crate::{helpers::get_storage_by_key_hash, StfError};
+use codec::{Decode, Encode};
+use itp_stf_primitives::types::OrderId;
+use serde::{Deserialize, Serialize};
+use simplyr_lib::{MarketOutput, Order, OrderType};
+use std::{format, string::String, vec::Vec};
+use substrate_fixed::types::I32F32;
+
+/// Prefix of the teeracle oracle names of spot prices, followed by the bidding zone.
+///
+/// Must match `ita_oracle::types::SPOT_PRICE_ORACLE_PREFIX`.
+pub const SPOT_PRICE_ORACLE_PREFIX: &str = "spot_price/";
+
+/// Day-ahead spot price in €/MWh, which may be negative.
+pub type SpotPrice = I32F32;
+
+/// Day-ahead spot prices, encoded like the `ita_oracle::types::SpotPrices` of the teeracle.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SpotPrices {
+	/// Prices ordered by the start of their hour.
+	pub hourly: Vec<HourlySpotPrice>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct HourlySpotPrice {
+	/// Start of the hour in seconds since the unix epoch.
+	pub start: u64,
+	pub price: SpotPrice,
+}
+
+const SECONDS_PER_HOUR: u64 = 3_600;
+const KWH_PER_MWH: f64 = 1_000.0;
+
+/// Unmatched energy below this is considered to be fully matched.
+const ENERGY_EPSILON_KWH: f64 = 1e-9;
+
+impl SpotPrices {
+	/// Price of the hour that contains `unix_seconds`.
+	pub fn price_at(&self, unix_seconds: u64) -> Option<SpotPrice> {
+		self.hourly
+			.iter()
+			.rev()
+			.find(|p| p.start <= unix_seconds)
+			.filter(|p| unix_seconds - p.start < SECONDS_PER_HOUR)
+			.map(|p| p.price)
+	}
+}
+
+/// Unmatched energy of an order, which is bought from or sold to the grid at the reserve price.
+#[derive(Clone, Debug, PartialEq, Encode, Decode, Serialize, Deserialize)]
+pub struct GridSettlement {
+	pub order_id: OrderId,
+	pub actor_id: String,
+	pub order_type: OrderType,
+	pub energy_kwh: f64,
+	pub price_euro_per_kwh: f64,
+}
+
+/// Replaces the reserve prices with the latest published spot prices.
+///
+/// The market has a single bidding zone, the prices of the zone published last are used.
+pub fn write_reserve_prices(spot_prices: &SpotPrices) {
+	sp_io::storage::set(&reserve_prices_key(), &spot_prices.encode());
+	log::info!("Wrote {} hourly reserve prices", spot_prices.hourly.len());
+}
+
+pub fn read_reserve_prices() -> SpotPrices {
+	get_storage_by_key_hash(reserve_prices_key()).unwrap_or_default()
+}
+
+/// Reserve price of a time slot in €/kWh.
+pub fn read_reserve_price_euro_per_kwh(time_slot: &str) -> Result<f64, StfError> {
+	let unix_seconds = time_slot_unix_seconds(time_slot)
+		.ok_or_else(|| StfError::Dispatch(format!("Invalid time slot: {:?}", time_slot)))?;
+	let price = read_reserve_prices().price_at(unix_seconds).ok_or_else(|| {
+		StfError::Dispatch(format!("No reserve price published for time slot: {:?}", time_slot))
+	})?;
+	Ok(price.to_num::<f64>() / KWH_PER_MWH)
+}
+
+/// Settles the energy of the orders that has not been matched against the grid.
+pub fn settle_unmatched_volume(
+	orders: &[Order],
+	market_output: &MarketOutput,
+	reserve_price_euro_per_kwh: f64,
+) -> Vec<GridSettlement> {
+	orders
+		.iter()
+		.filter_map(|order| {
+			let matched_kwh: f64 = market_output
+				.matches
+				.iter()
+				.filter(|m| match order.order_type {
+					OrderType::Bid => m.bid_id == order.id,
+					OrderType::Ask => m.ask_id == order.id,
+				})
+				.map(|m| m.energy_kwh)
+				.sum();
+			let unmatched_kwh = order.energy_kwh - matched_kwh;
+
+			(unmatched_kwh > ENERGY_EPSILON_KWH).then(|| GridSettlement {
+				order_id: order.id,
+				actor_id: order.actor_id.clone(),
+				order_type: order.order_type.clone(),
+				energy_kwh: unmatched_kwh,
+				price_euro_per_kwh: reserve_price_euro_per_kwh,
+			})
+		})
+		.collect()
+}
+
+pub fn write_grid_settlements(timestamp: &str, settlements: &[GridSettlement]) {
+	sp_io::storage::set(&grid_settlements_map_key(timestamp.into()), &settlements.encode());
+	log::info!("Wrote {} grid settlements for timestamp {}", settlements.len(), timestamp);
+}
+
+pub fn read_grid_settlements(timestamp: &str) -> Result<Vec<GridSettlement>, StfError> {
+	get_storage_by_key_hash(grid_settlements_map_key(timestamp.into())).ok_or_else(|| {
+		StfError::Dispatch(format!("No grid settlements stored for timestamp: {:?}", timestamp))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::best_energy_helpers::default_orders;
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+	use simplyr_lib::Match;
+
+	fn spot_prices() -> SpotPrices {
+		SpotPrices {
+			hourly: vec![
+				HourlySpotPrice { start: 1_646_370_000, price: SpotPrice::from_num(120) },
+				HourlySpotPrice { start: 1_646_373_600, price: SpotPrice::from_num(-10) },
+			],
+		}
+	}
+
+	#[test]
+	fn price_of_the_hour_of_a_time_slot_is_used() {
+		let prices = spot_prices();
+
+		assert_eq!(prices.price_at(1_646_370_367), Some(SpotPrice::from_num(120)));
+		assert_eq!(prices.price_at(1_646_373_600), Some(SpotPrice::from_num(-10)));
+		assert_eq!(prices.price_at(1_646_369_999), None);
+		assert_eq!(prices.price_at(1_646_377_200), None);
+	}
+
+	#[test]
+	fn reserve_price_is_read_in_euro_per_kwh() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			assert!(read_reserve_price_euro_per_kwh("2022-03-04T05:06:07+00:00").is_err());
+
+			write_reserve_prices(&spot_prices());
+
+			assert_eq!(read_reserve_price_euro_per_kwh("2022-03-04T05:06:07+00:00").unwrap(), 0.12);
+			assert_eq!(read_reserve_price_euro_per_kwh("2022-03-04T06:59:59Z").unwrap(), -0.01);
+		});
+	}
+
+	#[test]
+	fn unmatched_volume_is_settled_at_the_reserve_price() {
+		let mut orders = default_orders();
+		orders[2].order_type = OrderType::Bid;
+		orders[2].energy_kwh = 3.0;
+		let market_output = MarketOutput {
+			matches: vec![Match { bid_id: 2, ask_id: 0, energy_kwh: 3.0, price_euro_per_kwh: 0.2 }],
+		};
+
+		let settlements = settle_unmatched_volume(&orders, &market_output, 0.12);
+
+		assert_eq!(settlements.len(), 2);
+		assert_eq!(settlements[0].order_id, 0);
+		assert_eq!(settlements[0].energy_kwh, 2.0);
+		assert_eq!(settlements[1].order_id, 1);
+		assert_eq!(settlements[1].energy_kwh, 8.8);
+		assert_eq!(settlements[1].price_euro_per_kwh, 0.12);
+		assert_eq!(
+			Vec::<GridSettlement>::decode(&mut settlements.encode().as_slice()).unwrap(),
+			settlements
+		);
+	}
+}
@@ -0,0 +1,309 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Settlement of the matched trades of a time slot in the balances of the STF.
+//!
+//! For each match, the account that submitted the bid pays `energy × price` to the account that
+//! submitted the ask. Trades that can't be settled are reported in the settlement summary.
+//!
+//! If the match was priced with a grid fee, the fee is part of the amount. It is not deducted,
+//! the seller receives the whole amount.
+//!
+//! Prices are converted to balance units at the rate set by root. The amount is computed in
+//! integers, from the energy in Wh and the price in micro-euro per kWh.
+
+use super::{
+	grid_fee::GridFeeMatch,
+	storage::{balance_units_per_euro_key, settlements_map_key},
+};
+use crate::{helpers::get_storage_by_key_hash, StfError};
+use codec::{Decode, Encode};
+use itp_stf_primitives::types::{AccountId, OrderId};
+use itp_types::Balance;
+use simplyr_lib::{Match, Order};
+use std::{format, vec::Vec};
+
+const WH_PER_KWH: u128 = 1_000;
+const MICRO_EURO_PER_EURO: u128 = 1_000_000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum SettlementStatus {
+	Settled,
+	/// The buyer's free balance is lower than the price of the trade.
+	InsufficientFunds,
+	/// The bid or the ask has not been submitted by an account, e.g. because root matched the
+	/// orders directly.
+	MissingAccount,
+	/// The transfer failed for another reason, e.g. the existential deposit.
+	Failed,
+	/// Root has not set the conversion rate from euro to balance units.
+	MissingConversionRate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct TradeSettlement {
+	pub bid_id: OrderId,
+	pub ask_id: OrderId,
+	pub buyer: Option<AccountId>,
+	pub seller: Option<AccountId>,
+	pub amount: Balance,
+	/// Grid fee that is included in the amount. It is not deducted, the seller receives the whole
+	/// amount.
+	pub grid_fee: Balance,
+	pub status: SettlementStatus,
+}
+
+/// Settlements of all matches of a time slot.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SettlementSummary {
+	pub trades: Vec<TradeSettlement>,
+}
+
+impl SettlementSummary {
+	/// Sum of the amounts that have been transferred.
+	pub fn total_settled(&self) -> Balance {
+		self.trades
+			.iter()
+			.filter(|t| t.status == SettlementStatus::Settled)
+			.map(|t| t.amount)
+			.sum()
+	}
+
+	pub fn unsettled_trades(&self) -> impl Iterator<Item = &TradeSettlement> {
+		self.trades.iter().filter(|t| t.status != SettlementStatus::Settled)
+	}
+
+	/// Reduces the summary to the trades, in which `account` is the buyer or the seller.
+	pub fn of_account(mut self, account: &AccountId) -> Self {
+		self.trades
+			.retain(|t| t.buyer.as_ref() == Some(account) || t.seller.as_ref() == Some(account));
+		self
+	}
+}
+
+pub fn write_balance_units_per_euro(balance_units_per_euro: Balance) -> Result<(), StfError> {
+	if balance_units_per_euro == 0 {
+		return Err(StfError::Dispatch("The conversion rate must not be zero".into()))
+	}
+	sp_io::storage::set(&balance_units_per_euro_key(), &balance_units_per_euro.encode());
+	log::info!("Set conversion rate: 1 EUR = {} balance units", balance_units_per_euro);
+	Ok(())
+}
+
+pub fn read_balance_units_per_euro() -> Option<Balance> {
+	get_storage_by_key_hash(balance_units_per_euro_key())
+}
+
+/// Price of a match in balance units, rounded down. `None` if it is not representable.
+pub fn trade_amount(trade: &Match, balance_units_per_euro: Balance) -> Option<Balance> {
+	let energy_wh = to_fixed_point(trade.energy_kwh, WH_PER_KWH)?;
+	let price_micro_euro_per_kwh = to_fixed_point(trade.price_euro_per_kwh, MICRO_EURO_PER_EURO)?;
+	let amount = energy_wh
+		.checked_mul(price_micro_euro_per_kwh)?
+		.checked_mul(balance_units_per_euro)?;
+	Some(amount / (WH_PER_KWH * MICRO_EURO_PER_EURO))
+}
+
+/// `value` in units of `1 / units_per_one`, rounded to the nearest unit.
+fn to_fixed_point(value: f64, units_per_one: u128) -> Option<u128> {
+	let units = (value * units_per_one as f64).round();
+	(units.is_finite() && units >= 0.0 && units < u128::MAX as f64).then_some(units as u128)
+}
+
+/// Settles every match with `transfer`, from the submitter of the bid to the submitter of the
+/// ask, as given by `submitter_of`.
+///
+/// `grid_fee_matches` are the matches that were priced with a grid fee, if any.
+pub fn settle_matches(
+	orders: &[Order],
+	matches: &[Match],
+	grid_fee_matches: &[GridFeeMatch],
+	submitter_of: impl Fn(&Order) -> Option<AccountId>,
+	mut transfer: impl FnMut(&AccountId, &AccountId, Balance) -> Result<(), SettlementStatus>,
+) -> SettlementSummary {
+	let account_of = |order_id: OrderId| {
+		orders.iter().find(|order| order.id == order_id).and_then(&submitter_of)
+	};
+	let grid_fee_of = |trade: &Match| {
+		grid_fee_matches
+			.iter()
+			.find(|m| &m.trade == trade)
+			.map_or(0.0, |m| m.grid_fee_euro_per_kwh)
+	};
+
+	let balance_units_per_euro = read_balance_units_per_euro();
+
+	let trades = matches
+		.iter()
+		.map(|trade| {
+			let buyer = account_of(trade.bid_id);
+			let seller = account_of(trade.ask_id);
+			let amount = balance_units_per_euro.and_then(|rate| trade_amount(trade, rate));
+			let grid_fee = balance_units_per_euro
+				.and_then(|rate| {
+					let fee = Match { price_euro_per_kwh: grid_fee_of(trade), ..*trade };
+					trade_amount(&fee, rate)
+				})
+				.unwrap_or_default();
+
+			let status = match (&buyer, &seller, amount) {
+				(Some(buyer), Some(seller), Some(amount)) => transfer(buyer, seller, amount)
+					.map_or_else(|status| status, |_| SettlementStatus::Settled),
+				(Some(_), Some(_), None) if balance_units_per_euro.is_none() =>
+					SettlementStatus::MissingConversionRate,
+				(Some(_), Some(_), None) => SettlementStatus::Failed,
+				_ => SettlementStatus::MissingAccount,
+			};
+			if status != SettlementStatus::Settled {
+				log::warn!(
+					"Could not settle match of bid {} and ask {}: {:?}",
+					trade.bid_id,
+					trade.ask_id,
+					status
+				);
+			}
+
+			TradeSettlement {
+				bid_id: trade.bid_id,
+				ask_id: trade.ask_id,
+				buyer,
+				seller,
+				amount: amount.unwrap_or_default(),
+				grid_fee,
+				status,
+			}
+		})
+		.collect();
+
+	SettlementSummary { trades }
+}
+
+pub fn write_settlement_summary(timestamp: &str, summary: &SettlementSummary) {
+	sp_io::storage::set(&settlements_map_key(timestamp.into()), &summary.encode());
+	log::info!(
+		"Settled {} of {} trades for timestamp {}",
+		summary.trades.len() - summary.unsettled_trades().count(),
+		summary.trades.len(),
+		timestamp
+	);
+}
+
+pub fn read_settlement_summary(timestamp: &str) -> Result<SettlementSummary, StfError> {
+	get_storage_by_key_hash(settlements_map_key(timestamp.into())).ok_or_else(|| {
+		StfError::Dispatch(format!("No settlements stored for timestamp: {:?}", timestamp))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::best_energy_helpers::default_orders;
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+	use simplyr_lib::OrderType;
+
+	fn alice() -> AccountId {
+		AccountId::new([1u8; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::new([2u8; 32])
+	}
+
+	/// Alice submitted the order of actor_0, Bob the one of actor_1.
+	fn submitter_of(order: &Order) -> Option<AccountId> {
+		match order.actor_id.as_str() {
+			"actor_0" => Some(alice()),
+			"actor_1" => Some(bob()),
+			_ => None,
+		}
+	}
+
+	fn trade(bid_id: OrderId, ask_id: OrderId, energy_kwh: f64) -> Match {
+		Match { bid_id, ask_id, energy_kwh, price_euro_per_kwh: 0.25 }
+	}
+
+	const BALANCE_UNITS_PER_EURO: Balance = 1_000_000_000_000;
+
+	#[test]
+	fn trade_amount_is_energy_times_price() {
+		assert_eq!(trade_amount(&trade(0, 1, 2.0), BALANCE_UNITS_PER_EURO), Some(500_000_000_000));
+		assert_eq!(trade_amount(&trade(0, 1, 2.0), 100), Some(50));
+		// 0.1 kWh at 0.3 €/kWh is not exactly representable as a float.
+		let small_trade = Match { energy_kwh: 0.1, price_euro_per_kwh: 0.3, ..trade(0, 1, 0.0) };
+		assert_eq!(trade_amount(&small_trade, BALANCE_UNITS_PER_EURO), Some(30_000_000_000));
+		assert_eq!(trade_amount(&Match { price_euro_per_kwh: -0.1, ..small_trade }, 100), None);
+		assert_eq!(trade_amount(&Match { energy_kwh: f64::MAX, ..small_trade }, 100), None);
+	}
+
+	#[test]
+	fn conversion_rate_is_set_by_root_and_must_not_be_zero() {
+		let mut state = SgxExternalities::default();
+		let orders = default_orders();
+
+		state.execute_with(|| {
+			let summary =
+				settle_matches(&orders, &[trade(0, 1, 2.0)], &[], submitter_of, |_, _, _| Ok(()));
+			assert_eq!(summary.trades[0].status, SettlementStatus::MissingConversionRate);
+
+			assert!(write_balance_units_per_euro(0).is_err());
+			assert_eq!(read_balance_units_per_euro(), None);
+			write_balance_units_per_euro(BALANCE_UNITS_PER_EURO).unwrap();
+			assert_eq!(read_balance_units_per_euro(), Some(BALANCE_UNITS_PER_EURO));
+		});
+	}
+
+	#[test]
+	fn matches_are_settled_and_failures_reported() {
+		let mut state = SgxExternalities::default();
+		let mut orders = default_orders();
+		orders[0].order_type = OrderType::Bid;
+
+		state.execute_with(|| {
+			write_balance_units_per_euro(BALANCE_UNITS_PER_EURO).unwrap();
+
+			let mut transfers = Vec::new();
+			let grid_fee_match =
+				GridFeeMatch { trade: trade(0, 1, 2.0), grid_fee_euro_per_kwh: 0.05 };
+			let summary = settle_matches(
+				&orders,
+				&[trade(0, 1, 2.0), trade(0, 1, 4.0), trade(0, 2, 1.0)],
+				&[grid_fee_match],
+				submitter_of,
+				|from, to, amount| {
+					if amount > 500_000_000_000 {
+						return Err(SettlementStatus::InsufficientFunds)
+					}
+					transfers.push((from.clone(), to.clone(), amount));
+					Ok(())
+				},
+			);
+
+			assert_eq!(transfers, vec![(alice(), bob(), 500_000_000_000)]);
+			assert_eq!(summary.total_settled(), 500_000_000_000);
+			assert_eq!(summary.trades[0].grid_fee, 100_000_000_000);
+			assert_eq!(summary.trades[1].grid_fee, 0);
+			assert_eq!(summary.trades[1].status, SettlementStatus::InsufficientFunds);
+			assert_eq!(summary.trades[2].status, SettlementStatus::MissingAccount);
+			assert_eq!(summary.trades[2].seller, None);
+
+			assert_eq!(summary.clone().of_account(&bob()).trades.len(), 2);
+
+			write_settlement_summary("slot", &summary);
+			assert_eq!(read_settlement_summary("slot").unwrap(), summary);
+		});
+	}
+}
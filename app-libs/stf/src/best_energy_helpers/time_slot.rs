@@ -0,0 +1,60 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Time slots of the market, which are identified by the RFC 3339 timestamp of their start.
+
+use chrono::{DateTime, Timelike};
+
+/// Seconds since the unix epoch of an RFC 3339 time slot, e.g. `2022-03-04T05:06:07+01:00`.
+///
+/// `None` if the time slot is invalid, a leap second or before the unix epoch.
+pub fn time_slot_unix_seconds(time_slot: &str) -> Option<u64> {
+	let start = DateTime::parse_from_rfc3339(time_slot).ok()?;
+	if start.nanosecond() >= 1_000_000_000 {
+		return None
+	}
+	u64::try_from(start.timestamp()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn time_slots_are_converted_to_unix_seconds() {
+		assert_eq!(time_slot_unix_seconds("1970-01-01T00:00:00Z"), Some(0));
+		assert_eq!(time_slot_unix_seconds("2022-03-04T05:06:07+00:00"), Some(1_646_370_367));
+		assert_eq!(time_slot_unix_seconds("2022-03-04T06:06:07.250+01:00"), Some(1_646_370_367));
+		assert_eq!(time_slot_unix_seconds("2024-02-29T00:00:00-02:30"), Some(1_709_173_800));
+
+		assert_eq!(time_slot_unix_seconds("2022-03-04"), None);
+		assert_eq!(time_slot_unix_seconds("2022-13-04T05:06:07Z"), None);
+		assert_eq!(time_slot_unix_seconds("2022-03-04T05:06:07 CET"), None);
+		assert_eq!(time_slot_unix_seconds("1969-12-31T23:59:59Z"), None);
+	}
+
+	#[test]
+	fn time_slots_with_invalid_seconds_or_days_are_rejected() {
+		assert_eq!(time_slot_unix_seconds("2022-03-04T05:06:60Z"), None);
+		assert_eq!(time_slot_unix_seconds("2022-02-29T00:00:00Z"), None);
+		assert_eq!(time_slot_unix_seconds("2022-04-31T00:00:00Z"), None);
+		assert_eq!(time_slot_unix_seconds("1900-02-29T00:00:00Z"), None);
+
+		assert_eq!(time_slot_unix_seconds("2000-02-29T00:00:00Z"), Some(951_782_400));
+		assert_eq!(time_slot_unix_seconds("2022-12-31T23:59:59Z"), Some(1_672_531_199));
+	}
+}
@@ -0,0 +1,165 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Validation of an order book before it is matched.
+
+use crate::StfError;
+use itp_stf_primitives::error::{InvalidOrder, OrderValidationError};
+use simplyr_lib::Order;
+use std::{collections::BTreeSet, vec::Vec};
+
+/// Validates an order book, whose orders all need to be in the time slot of the first order.
+///
+/// `number_of_clusters` is the size of the grid fee matrix if the matching depends on the
+/// clusters of the orders, otherwise the cluster indices are not checked.
+///
+/// Returns `StfError::InvalidOrders` with every offending order.
+pub fn validate_orders(
+	orders: &[Order],
+	number_of_clusters: Option<usize>,
+) -> Result<(), StfError> {
+	let time_slot = match orders.first() {
+		Some(order) => &order.time_slot,
+		None => return Err(StfError::Dispatch("No [valid]orders supplied.".into())),
+	};
+
+	let mut invalid_orders = Vec::new();
+	let mut ids = BTreeSet::new();
+
+	for order in orders {
+		let mut reject = |reason| invalid_orders.push(InvalidOrder { order_id: order.id, reason });
+
+		for reason in validate_order(order, number_of_clusters) {
+			reject(reason);
+		}
+
+		if &order.time_slot != time_slot {
+			reject(OrderValidationError::TimeSlotMismatch {
+				expected: time_slot.clone(),
+				found: order.time_slot.clone(),
+			});
+		}
+
+		if !ids.insert(order.id) {
+			reject(OrderValidationError::DuplicateId);
+		}
+	}
+
+	if invalid_orders.is_empty() {
+		Ok(())
+	} else {
+		Err(StfError::InvalidOrders(invalid_orders))
+	}
+}
+
+/// Validates the fields of a single order, independently of the order book it belongs to.
+pub fn validate_order(
+	order: &Order,
+	number_of_clusters: Option<usize>,
+) -> Vec<OrderValidationError> {
+	let mut errors = Vec::new();
+
+	// The negated comparisons also reject `NaN`.
+	if !(order.energy_kwh > 0.0 && order.energy_kwh.is_finite()) {
+		errors.push(OrderValidationError::InvalidEnergy);
+	}
+
+	if !(order.price_euro_per_kwh >= 0.0 && order.price_euro_per_kwh.is_finite()) {
+		errors.push(OrderValidationError::InvalidPrice);
+	}
+
+	if let Some(number_of_clusters) = number_of_clusters {
+		if !order.cluster_index.map_or(false, |index| index < number_of_clusters) {
+			errors.push(OrderValidationError::UnknownClusterIndex(
+				order.cluster_index.map(|index| index as u64),
+			));
+		}
+	}
+
+	errors
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::best_energy_helpers::default_orders;
+
+	fn invalid_order(order_id: u64, reason: OrderValidationError) -> InvalidOrder {
+		InvalidOrder { order_id, reason }
+	}
+
+	#[test]
+	fn default_orders_are_valid() {
+		assert_eq!(validate_orders(&default_orders(), None), Ok(()));
+		assert_eq!(validate_orders(&default_orders(), Some(1)), Ok(()));
+	}
+
+	#[test]
+	fn empty_order_book_is_rejected() {
+		assert!(validate_orders(&[], None).is_err());
+	}
+
+	#[test]
+	fn every_offending_order_is_reported() {
+		let mut orders = default_orders();
+		orders[0].energy_kwh = 0.0;
+		orders[0].price_euro_per_kwh = -0.1;
+		orders[1].time_slot = "2022-03-04T05:21:07+00:00".into();
+		orders[2].id = orders[1].id;
+
+		assert_eq!(
+			validate_orders(&orders, None),
+			Err(StfError::InvalidOrders(vec![
+				invalid_order(0, OrderValidationError::InvalidEnergy),
+				invalid_order(0, OrderValidationError::InvalidPrice),
+				invalid_order(
+					1,
+					OrderValidationError::TimeSlotMismatch {
+						expected: "2022-03-04T05:06:07+00:00".into(),
+						found: "2022-03-04T05:21:07+00:00".into(),
+					}
+				),
+				invalid_order(1, OrderValidationError::DuplicateId),
+			]))
+		);
+	}
+
+	#[test]
+	fn non_finite_values_are_rejected() {
+		let mut order = default_orders()[0].clone();
+		order.energy_kwh = f64::NAN;
+		order.price_euro_per_kwh = f64::INFINITY;
+
+		assert_eq!(
+			validate_order(&order, None),
+			vec![OrderValidationError::InvalidEnergy, OrderValidationError::InvalidPrice]
+		);
+	}
+
+	#[test]
+	fn unknown_cluster_index_is_rejected() {
+		let mut order = default_orders()[0].clone();
+		order.cluster_index = Some(2);
+
+		assert!(validate_order(&order, None).is_empty());
+		assert!(validate_order(&order, Some(3)).is_empty());
+		assert_eq!(
+			validate_order(&order, Some(2)),
+			vec![OrderValidationError::UnknownClusterIndex(Some(2))]
+		);
+	}
+}
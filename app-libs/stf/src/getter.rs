@@ -11,7 +11,18 @@
 	limitations under the License.
 */
 
-use crate::best_energy_helpers::{get_merkle_proof_for_actor_from_file, read_market_results};
+use crate::best_energy_helpers::{
+	actors::{
+		is_actor_account, read_grid_fee_market_results_of_account,
+		read_grid_settlements_of_account, read_market_results_of_account,
+		read_settlement_summary_of_account,
+	},
+	get_merkle_proofs_for_actor_from_state, get_merkle_proofs_for_actor_matches_from_state,
+	history::{read_actor_matches_page, read_matched_time_slots_page, Pagination},
+	market_calendar::read_market_calendar,
+	order_book::read_open_orders,
+	read_merkle_roots,
+};
 use binary_merkle_tree::MerkleProof;
 use codec::{Decode, Encode};
 use ita_sgx_runtime::System;
@@ -29,6 +40,7 @@ use std::prelude::v1::*;
 
 // Oli only
 use itp_stf_primitives::types::{ActorId, Timestamp};
+use simplyr_lib::Order;
 use std::time::Instant;
 
 #[cfg(feature = "evm")]
@@ -138,6 +150,7 @@ impl PoolTransactionValidation for Getter {
 #[allow(non_camel_case_types)]
 pub enum PublicGetter {
 	some_value,
+	market_calendar,
 }
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
@@ -154,6 +167,14 @@ pub enum TrustedGetter {
 	evm_account_storages(AccountId, H160, H256),
 	pay_as_bid_proof(AccountId, Timestamp, ActorId),
 	get_market_results(AccountId, Timestamp),
+	get_grid_fee_market_results(AccountId, Timestamp),
+	get_open_orders(AccountId, Timestamp),
+	market_results_proof(AccountId, Timestamp, ActorId),
+	merkle_roots(AccountId, Timestamp),
+	get_grid_settlements(AccountId, Timestamp),
+	get_settlement_summary(AccountId, Timestamp),
+	get_matched_time_slots(AccountId, Timestamp, Timestamp, Pagination), // (Account, From, To, Pagination)
+	get_actor_matches(AccountId, ActorId, Timestamp, Timestamp, Pagination), // (Account, ActorId, From, To, Pagination)
 }
 
 impl TrustedGetter {
@@ -170,6 +191,14 @@ impl TrustedGetter {
 			TrustedGetter::evm_account_storages(sender_account, ..) => sender_account,
 			TrustedGetter::pay_as_bid_proof(sender_account, _timstamp, _actor_id) => sender_account,
 			TrustedGetter::get_market_results(sender_account, _timstamp) => sender_account,
+			TrustedGetter::get_grid_fee_market_results(sender_account, _timstamp) => sender_account,
+			TrustedGetter::get_open_orders(sender_account, _timstamp) => sender_account,
+			TrustedGetter::market_results_proof(sender_account, ..) => sender_account,
+			TrustedGetter::merkle_roots(sender_account, _timestamp) => sender_account,
+			TrustedGetter::get_grid_settlements(sender_account, _timestamp) => sender_account,
+			TrustedGetter::get_settlement_summary(sender_account, _timestamp) => sender_account,
+			TrustedGetter::get_matched_time_slots(sender_account, ..) => sender_account,
+			TrustedGetter::get_actor_matches(sender_account, ..) => sender_account,
 		}
 	}
 
@@ -251,11 +280,20 @@ impl ExecuteGetter for Getter {
 						None
 					},
 
-				TrustedGetter::pay_as_bid_proof(_who, timestamp, actor_id) => {
+				TrustedGetter::pay_as_bid_proof(who, timestamp, actor_id) => {
+					if !is_actor_account(actor_id, who) {
+						log::error!(
+							"{} is not registered for actor {}",
+							account_id_to_string(who),
+							actor_id
+						);
+						return None
+					}
+
 					let now = Instant::now();
 
-					let proof = match get_merkle_proof_for_actor_from_file(timestamp, actor_id) {
-						Ok(proof) => proof,
+					let proofs = match get_merkle_proofs_for_actor_from_state(timestamp, actor_id) {
+						Ok(proofs) => proofs,
 						Err(e) => {
 							log::error!("Getting Orders and Index Error, {:?}", e);
 							return None
@@ -265,11 +303,12 @@ impl ExecuteGetter for Getter {
 					let elapsed = now.elapsed();
 					info!("Time Elapsed for PayAsBid Proof is: {:.2?}", elapsed);
 
-					Some(proof.encode())
+					Some(proofs.encode())
 				},
 
-				TrustedGetter::get_market_results(_who, timestamp) => {
-					let market_results = match read_market_results(timestamp) {
+				// Only returns the matches that involve an order of an actor of `who`.
+				TrustedGetter::get_market_results(who, timestamp) => {
+					let market_results = match read_market_results_of_account(timestamp, who) {
 						Ok(market_results) => market_results,
 						Err(e) => {
 							log::error!("Getting Market Results Error, {:?}", e);
@@ -279,9 +318,128 @@ impl ExecuteGetter for Getter {
 
 					Some(market_results.encode())
 				},
+
+				// Only returns the matches that involve an order of an actor of `who`.
+				TrustedGetter::get_grid_fee_market_results(who, timestamp) => {
+					let market_results =
+						match read_grid_fee_market_results_of_account(timestamp, who) {
+							Ok(market_results) => market_results,
+							Err(e) => {
+								log::error!("Getting Grid Fee Market Results Error, {:?}", e);
+								return None
+							},
+						};
+
+					Some(market_results.encode())
+				},
+
+				// Only returns the open orders that have been submitted by `who`.
+				TrustedGetter::get_open_orders(who, timestamp) => {
+					let orders: Vec<Order> = read_open_orders(timestamp)
+						.into_iter()
+						.filter(|o| &o.submitter == who)
+						.map(|o| o.order)
+						.collect();
+
+					Some(orders.encode())
+				},
+
+				TrustedGetter::market_results_proof(who, timestamp, actor_id) => {
+					if !is_actor_account(actor_id, who) {
+						log::error!(
+							"{} is not registered for actor {}",
+							account_id_to_string(who),
+							actor_id
+						);
+						return None
+					}
+
+					let proofs =
+						match get_merkle_proofs_for_actor_matches_from_state(timestamp, actor_id) {
+							Ok(proofs) => proofs,
+							Err(e) => {
+								log::error!("Getting Market Results Proofs Error, {:?}", e);
+								return None
+							},
+						};
+
+					Some(proofs.encode())
+				},
+
+				TrustedGetter::merkle_roots(_who, timestamp) => {
+					let merkle_roots = match read_merkle_roots(timestamp) {
+						Ok(merkle_roots) => merkle_roots,
+						Err(e) => {
+							log::error!("Getting Merkle Roots Error, {:?}", e);
+							return None
+						},
+					};
+
+					Some(merkle_roots.encode())
+				},
+
+				// Only returns the settlements of the orders of the actors of `who`.
+				TrustedGetter::get_grid_settlements(who, timestamp) => {
+					let settlements = match read_grid_settlements_of_account(timestamp, who) {
+						Ok(settlements) => settlements,
+						Err(e) => {
+							log::error!("Getting Grid Settlements Error, {:?}", e);
+							return None
+						},
+					};
+
+					Some(settlements.encode())
+				},
+
+				// Only returns the trades, in which `who` is the buyer or the seller.
+				TrustedGetter::get_settlement_summary(who, timestamp) => {
+					let summary = match read_settlement_summary_of_account(timestamp, who) {
+						Ok(summary) => summary,
+						Err(e) => {
+							log::error!("Getting Settlement Summary Error, {:?}", e);
+							return None
+						},
+					};
+
+					Some(summary.encode())
+				},
+
+				TrustedGetter::get_matched_time_slots(_who, from, to, pagination) => {
+					let time_slots = match read_matched_time_slots_page(from, to, *pagination) {
+						Ok(time_slots) => time_slots,
+						Err(e) => {
+							log::error!("Getting Matched Time Slots Error, {:?}", e);
+							return None
+						},
+					};
+
+					Some(time_slots.encode())
+				},
+
+				TrustedGetter::get_actor_matches(who, actor_id, from, to, pagination) => {
+					if !is_actor_account(actor_id, who) {
+						log::error!(
+							"{} is not registered for actor {}",
+							account_id_to_string(who),
+							actor_id
+						);
+						return None
+					}
+
+					let matches = match read_actor_matches_page(actor_id, from, to, *pagination) {
+						Ok(matches) => matches,
+						Err(e) => {
+							log::error!("Getting Actor Matches Error, {:?}", e);
+							return None
+						},
+					};
+
+					Some(matches.encode())
+				},
 			},
 			Getter::public(g) => match g {
 				PublicGetter::some_value => Some(42u32.encode()),
+				PublicGetter::market_calendar => Some(read_market_calendar().encode()),
 			},
 		}
 	}
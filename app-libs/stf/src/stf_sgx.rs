@@ -17,7 +17,9 @@
 
 #[cfg(feature = "test")]
 use crate::test_genesis::test_genesis_setup;
-use crate::{helpers::enclave_signer_account, Stf, ENCLAVE_ACCOUNT_KEY};
+use crate::{
+	helpers::enclave_signer_account, trusted_call::close_due_slots, Stf, ENCLAVE_ACCOUNT_KEY,
+};
 use codec::{Decode, Encode};
 use frame_support::traits::{OriginTrait, UnfilteredDispatchable};
 use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
@@ -26,7 +28,8 @@ use itp_stf_interface::{
 	parentchain_pallet::ParentchainPalletInterface,
 	sudo_pallet::SudoPalletInterface,
 	system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface},
-	ExecuteCall, ExecuteGetter, InitState, StateCallInterface, StateGetterInterface, UpdateState,
+	ExecuteCall, ExecuteGetter, InitState, OnBlockInterface, StateCallInterface,
+	StateGetterInterface, UpdateState,
 };
 use itp_stf_primitives::{error::StfError, traits::TrustedCallVerification};
 use itp_storage::storage_value_key;
@@ -150,6 +153,26 @@ where
 	}
 }
 
+impl<TCS, G, State, Runtime, NodeMetadataRepository> OnBlockInterface<State, NodeMetadataRepository>
+	for Stf<TCS, G, State, Runtime>
+where
+	State: SgxExternalitiesTrait + Debug,
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	type Error = StfError;
+
+	/// Matches the time slots, whose gate has closed since the previous block.
+	fn on_block(
+		state: &mut State,
+		calls: &mut Vec<OpaqueCall>,
+		node_metadata_repo: Arc<NodeMetadataRepository>,
+	) -> Result<(), Self::Error> {
+		state.execute_with(|| close_due_slots(calls, node_metadata_repo));
+		Ok(())
+	}
+}
+
 impl<TCS, G, State, Runtime> StateGetterInterface<G, State> for Stf<TCS, G, State, Runtime>
 where
 	G: PartialEq + ExecuteGetter,
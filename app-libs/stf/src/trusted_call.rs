@@ -32,7 +32,7 @@ use frame_support::{ensure, traits::UnfilteredDispatchable};
 #[cfg(feature = "evm")]
 use ita_sgx_runtime::{AddressMapping, HashedAddressMapping};
 pub use ita_sgx_runtime::{Balance, Index};
-use ita_sgx_runtime::{Runtime, System};
+use ita_sgx_runtime::{Parentchain, Runtime, System};
 use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
 use itp_node_api_metadata::{
 	pallet_balances::BalancesCallIndexes, pallet_enclave_bridge::EnclaveBridgeCallIndexes,
@@ -53,17 +53,42 @@ use sp_core::{
 };
 use sp_io::hashing::blake2_256;
 use sp_runtime::{traits::Verify, MultiAddress, MultiSignature};
-use std::{format, prelude::v1::*, sync::Arc};
+use std::{format, prelude::v1::*, sync::Arc, vec};
 
 // Group imports that are for OLI to make upstream merges easier.
 use crate::best_energy_helpers::{
-	storage::merkle_roots_map_key, write_orders, write_results, ORDERS_DIR, RESULTS_DIR,
+	actors::{ensure_actor_account_for_orders, register_actor_account, unregister_actor_account},
+	grid_fee::{parse_grid_fee_matrix, pay_as_bid_grid_fee_matching, GridFeeMarketOutput},
+	market_calendar::{
+		ensure_gate_is_closed, time_slots_due_for_matching, write_market_calendar, MarketCalendar,
+	},
+	market_results_merkle_root,
+	order_book::{
+		amend_order, cancel_order, clear_open_orders, read_open_orders, read_open_time_slots,
+		submit_order,
+	},
+	orders_exist, orders_merkle_root_topic,
+	reserve_price::{
+		read_reserve_price_euro_per_kwh, settle_unmatched_volume, write_grid_settlements,
+		write_reserve_prices, SpotPrices,
+	},
+	results_merkle_root_topic,
+	settlement::{
+		settle_matches, write_balance_units_per_euro, write_settlement_summary, SettlementStatus,
+	},
+	storage::{
+		merkle_roots_map_key, merkle_roots_parentchain_block_map_key, results_merkle_roots_map_key,
+	},
+	validation::validate_orders,
+	write_grid_fee_results, write_orders, write_results,
 };
 use binary_merkle_tree::merkle_root;
-use itp_stf_primitives::types::OrdersString;
-use simplyr_lib::{pay_as_bid_matching, MarketInput, MarketOutput, Order};
+use itp_stf_primitives::types::{
+	ActorId, GridFeeMatrixFile, OrderId, OrderString, OrdersString, Timestamp,
+};
+use simplyr_lib::{pay_as_bid_matching, pay_as_clear_matching, MarketInput, MarketOutput, Order};
 use sp_runtime::traits::Keccak256;
-use std::{fs, time::Instant};
+use std::time::Instant;
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
@@ -74,6 +99,17 @@ pub enum TrustedCall {
 	balance_unshield(AccountId, AccountId, Balance, ShardIdentifier), // (AccountIncognito, BeneficiaryPublicAccount, Amount, Shard)
 	balance_shield(AccountId, AccountId, Balance), // (Root, AccountIncognito, Amount)
 	pay_as_bid(AccountId, OrdersString),
+	pay_as_clear(AccountId, OrdersString),
+	pay_as_bid_grid_fee(AccountId, OrdersString, GridFeeMatrixFile),
+	submit_order(AccountId, OrderString),
+	amend_order(AccountId, OrderString),
+	cancel_order(AccountId, Timestamp, OrderId),
+	close_slot(AccountId, Timestamp),                // (Root, TimeSlot)
+	register_actor(AccountId, ActorId, AccountId),   // (Root, ActorId, Account)
+	unregister_actor(AccountId, ActorId, AccountId), // (Root, ActorId, Account)
+	set_reserve_prices(AccountId, SpotPrices),       // (Enclave, SpotPrices)
+	set_market_calendar(AccountId, u64, u64),        // (Root, SlotLengthSecs, GateClosureSecs)
+	set_balance_units_per_euro(AccountId, Balance),  // (Root, BalanceUnitsPerEuro)
 	#[cfg(feature = "evm")]
 	evm_withdraw(AccountId, H160, Balance), // (Origin, Address EVM Account, Value)
 	// (Origin, Source, Target, Input, Value, Gas limit, Max fee per gas, Max priority fee per gas, Nonce, Access list)
@@ -128,6 +164,17 @@ impl TrustedCall {
 			Self::balance_unshield(sender_account, ..) => sender_account,
 			Self::balance_shield(sender_account, ..) => sender_account,
 			Self::pay_as_bid(sender_account, _orders_string) => sender_account,
+			Self::pay_as_clear(sender_account, _orders_string) => sender_account,
+			Self::pay_as_bid_grid_fee(sender_account, ..) => sender_account,
+			Self::submit_order(sender_account, ..) => sender_account,
+			Self::amend_order(sender_account, ..) => sender_account,
+			Self::cancel_order(sender_account, ..) => sender_account,
+			Self::close_slot(sender_account, ..) => sender_account,
+			Self::register_actor(sender_account, ..) => sender_account,
+			Self::unregister_actor(sender_account, ..) => sender_account,
+			Self::set_reserve_prices(sender_account, ..) => sender_account,
+			Self::set_market_calendar(sender_account, ..) => sender_account,
+			Self::set_balance_units_per_euro(sender_account, ..) => sender_account,
 			#[cfg(feature = "evm")]
 			Self::evm_withdraw(sender_account, ..) => sender_account,
 			#[cfg(feature = "evm")]
@@ -353,68 +400,111 @@ where
 				Ok(())
 			},
 
-			TrustedCall::pay_as_bid(_who, orders_string) => {
+			TrustedCall::pay_as_bid(who, orders_string) => {
 				let now = Instant::now();
+				match_orders(
+					&who,
+					&orders_string,
+					None,
+					|input| Ok((pay_as_bid_matching(input), None)),
+					calls,
+					node_metadata_repo,
+				)?;
 
-				for dir in &[ORDERS_DIR, RESULTS_DIR] {
-					fs::create_dir_all(dir).map_err(|err| {
-						StfError::Dispatch(format!("Error creating directory {:?}: {:?}", dir, err))
-					})?;
-				}
+				let elapsed = now.elapsed();
+				info!("Time Elapsed for PayAsBid Algorithm is: {:.2?}", elapsed);
+				Ok(())
+			},
 
-				let parsed_orders: Vec<Order> =
-					serde_json::from_str(&orders_string).map_err(|err| {
-						StfError::Dispatch(format!("Error serializing to JSON: {}", err))
-					})?;
+			TrustedCall::pay_as_clear(who, orders_string) => {
+				let now = Instant::now();
+				match_orders(
+					&who,
+					&orders_string,
+					None,
+					|input| Ok((pay_as_clear_matching(input), None)),
+					calls,
+					node_metadata_repo,
+				)?;
 
-				let first_order = parsed_orders
-					.get(0)
-					.ok_or_else(|| StfError::Dispatch("No [valid]orders supplied.".to_string()))?;
+				let elapsed = now.elapsed();
+				info!("Time Elapsed for PayAsClear Algorithm is: {:.2?}", elapsed);
+				Ok(())
+			},
 
-				let timestamp = &first_order.time_slot;
+			TrustedCall::pay_as_bid_grid_fee(who, orders_string, grid_fee_matrix) => {
+				let now = Instant::now();
+				let grid_fee_matrix = parse_grid_fee_matrix(&grid_fee_matrix)?;
 
-				let market_input = MarketInput { orders: parsed_orders.clone() };
-				let orders_encoded: Vec<Vec<u8>> =
-					parsed_orders.iter().map(|o| o.encode()).collect();
+				match_orders(
+					&who,
+					&orders_string,
+					Some(grid_fee_matrix.len()),
+					|input| {
+						let results = pay_as_bid_grid_fee_matching(input, &grid_fee_matrix)?;
+						Ok((results.clone().into(), Some(results)))
+					},
+					calls,
+					node_metadata_repo,
+				)?;
 
-				let orders_path = format!("{}/{}.json", ORDERS_DIR, timestamp);
+				let elapsed = now.elapsed();
+				info!("Time Elapsed for PayAsBid GridFee Algorithm is: {:.2?}", elapsed);
+				Ok(())
+			},
 
-				if fs::metadata(&orders_path).is_ok() {
-					info!("Orders file already exists for timestamp {}", timestamp);
-					return Ok(())
-				}
+			TrustedCall::submit_order(who, order_string) => {
+				debug!("submit_order({}, {})", account_id_to_string(&who), order_string);
+				submit_order(&who, parse_order(&order_string)?)
+			},
 
-				let order_merkle_root = merkle_root::<Keccak256, _>(orders_encoded);
-				let pay_as_bid: MarketOutput = pay_as_bid_matching(&market_input);
+			TrustedCall::amend_order(who, order_string) => {
+				debug!("amend_order({}, {})", account_id_to_string(&who), order_string);
+				amend_order(&who, parse_order(&order_string)?)
+			},
 
-				write_orders(timestamp, &parsed_orders)?;
+			TrustedCall::cancel_order(who, timestamp, order_id) => {
+				debug!("cancel_order({}, {}, {})", account_id_to_string(&who), timestamp, order_id);
+				cancel_order(&who, &timestamp, order_id)
+			},
 
-				write_results(timestamp, pay_as_bid)?;
+			TrustedCall::close_slot(root, timestamp) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!("close_slot({})", timestamp);
+				close_slot(&timestamp, calls, node_metadata_repo)
+			},
 
-				// store the merkle root associated with a given timestamp in the sgx state:
-				// to be defined.
-				// sp::io::set(storage_map_key());
+			TrustedCall::set_market_calendar(root, slot_length_secs, gate_closure_secs) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!("set_market_calendar({}, {})", slot_length_secs, gate_closure_secs);
+				write_market_calendar(&MarketCalendar::new(slot_length_secs, gate_closure_secs)?);
+				Ok(())
+			},
 
-				sp_io::storage::set(
-					&merkle_roots_map_key(timestamp.to_string()),
-					&order_merkle_root.encode(),
-				);
+			TrustedCall::set_balance_units_per_euro(root, balance_units_per_euro) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!("set_balance_units_per_euro({})", balance_units_per_euro);
+				write_balance_units_per_euro(balance_units_per_euro)
+			},
 
-				let elapsed = now.elapsed();
-				info!("Time Elapsed for PayAsBid Algorithm is: {:.2?}", elapsed);
+			TrustedCall::register_actor(root, actor_id, account) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!("register_actor({}, {})", actor_id, account_id_to_string(&account));
+				register_actor_account(&actor_id, account);
+				Ok(())
+			},
 
-				// Send proof of execution on chain.
-				// calls is in the scope from the outside
-				calls.push(OpaqueCall::from_tuple(&(
-					node_metadata_repo
-						.get_from_metadata(|m| m.publish_hash_call_indexes())
-						.map_err(|_| StfError::InvalidMetadata)?
-						.map_err(|_| StfError::InvalidMetadata)?,
-					order_merkle_root,
-					Vec::<itp_types::H256>::new(), // you can ignore this for now. Clients could subscribe to the hashes here to be notified when a new hash is published.
-					b"Published merkle root of an order!".to_vec(),
-				)));
+			TrustedCall::unregister_actor(root, actor_id, account) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!("unregister_actor({}, {})", actor_id, account_id_to_string(&account));
+				unregister_actor_account(&actor_id, &account)
+			},
 
+			// Submitted by the enclave for the spot prices the teeracle publishes on the parentchain.
+			TrustedCall::set_reserve_prices(enclave_account, spot_prices) => {
+				ensure_enclave_signer_account(&enclave_account)?;
+				debug!("set_reserve_prices({} hours)", spot_prices.hourly.len());
+				write_reserve_prices(&spot_prices);
 				Ok(())
 			},
 
@@ -548,6 +638,17 @@ where
 			TrustedCall::balance_unshield(_, _, _, _) => debug!("No storage updates needed..."),
 			TrustedCall::balance_shield(_, _, _) => debug!("No storage updates needed..."),
 			TrustedCall::pay_as_bid(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::pay_as_clear(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::pay_as_bid_grid_fee(_, _, _) => debug!("No storage updates needed..."),
+			TrustedCall::submit_order(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::amend_order(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::cancel_order(_, _, _) => debug!("No storage updates needed..."),
+			TrustedCall::close_slot(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::register_actor(_, _, _) => debug!("No storage updates needed..."),
+			TrustedCall::unregister_actor(_, _, _) => debug!("No storage updates needed..."),
+			TrustedCall::set_reserve_prices(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::set_market_calendar(_, _, _) => debug!("No storage updates needed..."),
+			TrustedCall::set_balance_units_per_euro(_, _) => debug!("No storage updates needed..."),
 			#[cfg(feature = "evm")]
 			_ => debug!("No storage updates needed..."),
 		};
@@ -582,6 +683,251 @@ fn shield_funds(account: AccountId, amount: u128) -> Result<(), StfError> {
 	Ok(())
 }
 
+/// Parses the order book and matches it, see `match_and_store_orders`.
+///
+/// The root account may submit the orders of any actor, other accounts only the orders of the
+/// actors they are registered for. The matches are settled from and to the submitting account,
+/// matches of orders that root submitted are not settled.
+fn match_orders<NodeMetadataRepository>(
+	who: &AccountId,
+	orders_string: &str,
+	number_of_clusters: Option<usize>,
+	matching: impl FnOnce(&MarketInput) -> Result<MatchingOutput, StfError>,
+	calls: &mut Vec<OpaqueCall>,
+	node_metadata_repo: Arc<NodeMetadataRepository>,
+) -> Result<(), StfError>
+where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	let parsed_orders: Vec<Order> = serde_json::from_str(orders_string)
+		.map_err(|err| StfError::Dispatch(format!("Error serializing to JSON: {}", err)))?;
+
+	let submitter = if is_root::<Runtime, AccountId>(who) {
+		None
+	} else {
+		ensure_actor_account_for_orders(&parsed_orders, who)?;
+		Some(who.clone())
+	};
+
+	match_and_store_orders(
+		parsed_orders,
+		number_of_clusters,
+		matching,
+		|_| submitter.clone(),
+		calls,
+		node_metadata_repo,
+	)
+}
+
+/// The matches of a matching algorithm, and the grid fees applied to them if the algorithm
+/// respects grid fees.
+type MatchingOutput = (MarketOutput, Option<GridFeeMarketOutput>);
+
+/// Runs the `matching` algorithm for the time slot of the orders and stores orders and results
+/// in the state. The merkle roots of the orders and of the matches are stored and published on
+/// the parentchain.
+///
+/// If a reserve price is published for the time slot, the unmatched volume is settled against
+/// the grid at that price. The matches are settled between the accounts given by `submitter_of`.
+///
+/// Fails if the orders are invalid, see `validate_orders`, or if orders have already been matched
+/// for the time slot.
+fn match_and_store_orders<NodeMetadataRepository>(
+	orders: Vec<Order>,
+	number_of_clusters: Option<usize>,
+	matching: impl FnOnce(&MarketInput) -> Result<MatchingOutput, StfError>,
+	submitter_of: impl Fn(&Order) -> Option<AccountId>,
+	calls: &mut Vec<OpaqueCall>,
+	node_metadata_repo: Arc<NodeMetadataRepository>,
+) -> Result<(), StfError>
+where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	validate_orders(&orders, number_of_clusters)?;
+
+	let timestamp = &orders[0].time_slot;
+	ensure_gate_is_closed(timestamp)?;
+
+	if orders_exist(timestamp) {
+		return Err(StfError::TimeSlotAlreadyMatched(timestamp.to_string()))
+	}
+
+	let orders_encoded: Vec<Vec<u8>> = orders.iter().map(|o| o.encode()).collect();
+	let market_input = MarketInput { orders: orders.clone() };
+
+	let order_merkle_root = merkle_root::<Keccak256, _>(orders_encoded);
+	let (market_output, grid_fee_output) = matching(&market_input)?;
+
+	// Fetched before anything is written, so that a failure leaves the state untouched.
+	let publish_hash_call_indexes = node_metadata_repo
+		.get_from_metadata(|m| m.publish_hash_call_indexes())
+		.map_err(|_| StfError::InvalidMetadata)?
+		.map_err(|_| StfError::InvalidMetadata)?;
+
+	write_orders(timestamp, &orders);
+
+	write_results(timestamp, &market_output);
+
+	if let Some(grid_fee_output) = &grid_fee_output {
+		write_grid_fee_results(timestamp, grid_fee_output);
+	}
+
+	// Unmatched volume is only settled against the grid if a reserve price is known.
+	match read_reserve_price_euro_per_kwh(timestamp) {
+		Ok(reserve_price) => write_grid_settlements(
+			timestamp,
+			&settle_unmatched_volume(&orders, &market_output, reserve_price),
+		),
+		Err(e) => warn!("Unmatched volume is not settled against the grid: {:?}", e),
+	}
+
+	let results_merkle_root = market_results_merkle_root(&market_output);
+
+	// store the merkle roots associated with a given timestamp in the sgx state.
+	sp_io::storage::set(&merkle_roots_map_key(timestamp.to_string()), &order_merkle_root.encode());
+	sp_io::storage::set(
+		&results_merkle_roots_map_key(timestamp.to_string()),
+		&results_merkle_root.encode(),
+	);
+	// Lets clients search the published roots from this parentchain block on.
+	sp_io::storage::set(
+		&merkle_roots_parentchain_block_map_key(timestamp.to_string()),
+		&Parentchain::block_number().encode(),
+	);
+
+	// Send proof of execution on chain.
+	calls.push(OpaqueCall::from_tuple(&(
+		publish_hash_call_indexes,
+		order_merkle_root,
+		// Clients look up the published root of a timestamp by its topic.
+		vec![orders_merkle_root_topic(timestamp)],
+		b"Published merkle root of an order!".to_vec(),
+	)));
+	calls.push(OpaqueCall::from_tuple(&(
+		publish_hash_call_indexes,
+		results_merkle_root,
+		vec![results_merkle_root_topic(timestamp)],
+		b"Published merkle root of market results!".to_vec(),
+	)));
+
+	// Settle last, the balance transfers must not happen if any previous step fails.
+	let settlements = settle_matches(
+		&orders,
+		&market_output.matches,
+		grid_fee_output.as_ref().map_or(&[][..], |output| output.matches.as_slice()),
+		submitter_of,
+		transfer_settlement,
+	);
+	write_settlement_summary(timestamp, &settlements);
+
+	Ok(())
+}
+
+/// Transfers the price of a trade from the buyer to the seller.
+fn transfer_settlement(
+	buyer: &AccountId,
+	seller: &AccountId,
+	amount: Balance,
+) -> Result<(), SettlementStatus> {
+	if System::account(buyer).data.free < amount {
+		return Err(SettlementStatus::InsufficientFunds)
+	}
+	ita_sgx_runtime::BalancesCall::<Runtime>::transfer {
+		dest: MultiAddress::Id(seller.clone()),
+		value: amount,
+	}
+	.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(buyer.clone()))
+	.map_err(|e| {
+		warn!("Settlement transfer error: {:?}", e.error);
+		SettlementStatus::Failed
+	})?;
+	Ok(())
+}
+
+/// Matches the open orders of a time slot with pay as bid.
+///
+/// Fails before the gate of the time slot has closed and if its orders have already been matched.
+/// The open orders are only removed once they have been matched. If closing fails, they stay
+/// in the order book of the time slot.
+fn close_slot<NodeMetadataRepository>(
+	timestamp: &str,
+	calls: &mut Vec<OpaqueCall>,
+	node_metadata_repo: Arc<NodeMetadataRepository>,
+) -> Result<(), StfError>
+where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	ensure_gate_is_closed(timestamp)?;
+	if orders_exist(timestamp) {
+		return Err(StfError::TimeSlotAlreadyMatched(timestamp.to_string()))
+	}
+
+	let now = Instant::now();
+
+	let submitted_orders = read_open_orders(timestamp);
+	let orders = submitted_orders.iter().map(|submitted| submitted.order.clone()).collect();
+	match_and_store_orders(
+		orders,
+		None,
+		|input| Ok((pay_as_bid_matching(input), None)),
+		|order| {
+			submitted_orders
+				.iter()
+				.find(|submitted| submitted.order.id == order.id)
+				.map(|submitted| submitted.submitter.clone())
+		},
+		calls,
+		node_metadata_repo,
+	)?;
+	clear_open_orders(timestamp);
+
+	let elapsed = now.elapsed();
+	info!("Time Elapsed for PayAsBid Algorithm is: {:.2?}", elapsed);
+	Ok(())
+}
+
+/// Closes all time slots with open orders, whose gate has closed by the current block.
+///
+/// Time slots that fail to close keep their open orders and are retried in the next block,
+/// unless closing can never succeed, see `is_permanent_close_failure`. Their open orders are
+/// dropped then.
+pub(crate) fn close_due_slots<NodeMetadataRepository>(
+	calls: &mut Vec<OpaqueCall>,
+	node_metadata_repo: Arc<NodeMetadataRepository>,
+) where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	for timestamp in time_slots_due_for_matching(read_open_time_slots()) {
+		info!("Gate of time slot {} has closed, matching its orders", timestamp);
+		match close_slot(&timestamp, calls, node_metadata_repo.clone()) {
+			Ok(()) => {},
+			Err(e) if is_permanent_close_failure(&e) => {
+				error!(
+					"Dropping the open orders of time slot {}, it can't be closed: {}",
+					timestamp, e
+				);
+				clear_open_orders(&timestamp);
+			},
+			Err(e) => warn!("Could not close time slot {}, retrying: {:?}", timestamp, e),
+		}
+	}
+}
+
+/// Closing a time slot fails with the same error in every block: its orders are invalid or it
+/// has already been matched.
+fn is_permanent_close_failure(e: &StfError) -> bool {
+	matches!(e, StfError::InvalidOrders(_) | StfError::TimeSlotAlreadyMatched(_))
+}
+
+fn parse_order(order_string: &str) -> Result<Order, StfError> {
+	serde_json::from_str(order_string)
+		.map_err(|err| StfError::Dispatch(format!("Error serializing to JSON: {}", err)))
+}
+
 fn is_root<Runtime, AccountId>(account: &AccountId) -> bool
 where
 	Runtime: frame_system::Config<AccountId = AccountId> + pallet_sudo::Config,
@@ -593,9 +939,157 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::best_energy_helpers::{
+		default_orders, order_book::read_open_orders, read_grid_fee_market_results,
+	};
+	use itp_node_api::metadata::{provider::NodeMetadataRepository, NodeMetadata};
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
 	use itp_stf_primitives::types::KeyPair;
+	use itp_storage::storage_value_key;
 	use sp_keyring::AccountKeyring;
 
+	// Gate closure of the time slot 2022-03-04T05:00:00Z in the market calendar of `set_calendar`.
+	const GATE_CLOSURE: u64 = 1_646_370_000 - 300;
+
+	fn submit_default_orders() -> Timestamp {
+		submit_default_orders_for_time_slot(&default_orders()[0].time_slot)
+	}
+
+	fn submit_default_orders_for_time_slot(time_slot: &str) -> Timestamp {
+		let submitter = AccountId::new([1u8; 32]);
+		for mut order in default_orders().into_iter().take(2) {
+			order.time_slot = time_slot.to_string();
+			register_actor_account(&order.actor_id, submitter.clone());
+			submit_order(&submitter, order).unwrap();
+		}
+		time_slot.to_string()
+	}
+
+	/// Submits the default orders for a time slot of the market calendar, before its gate closes.
+	fn set_calendar_and_submit_orders() -> Timestamp {
+		set_now(GATE_CLOSURE - 1);
+		write_market_calendar(&MarketCalendar::new(900, 300).unwrap());
+		submit_default_orders_for_time_slot("2022-03-04T05:00:00Z")
+	}
+
+	fn set_now(secs: u64) {
+		sp_io::storage::set(&storage_value_key("System", "Timestamp"), &(secs * 1000).encode());
+	}
+
+	fn default_node_metadata_repo() -> Arc<NodeMetadataRepository<NodeMetadata>> {
+		// Without node metadata, the merkle roots can't be published.
+		Arc::new(NodeMetadataRepository::<NodeMetadata>::default())
+	}
+
+	#[test]
+	fn close_slot_fails_before_gate_closure_and_keeps_the_open_orders() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			let timestamp = set_calendar_and_submit_orders();
+
+			let result = close_slot(&timestamp, &mut Vec::new(), default_node_metadata_repo());
+
+			assert!(matches!(result, Err(StfError::Dispatch(e)) if e.contains("before its gate")));
+			assert_eq!(read_open_orders(&timestamp).len(), 2);
+			assert_eq!(read_open_time_slots(), vec![timestamp]);
+		});
+	}
+
+	#[test]
+	fn close_slot_of_matched_time_slot_fails_and_keeps_the_open_orders() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			let timestamp = submit_default_orders();
+			write_orders(&timestamp, &default_orders());
+
+			let result = close_slot(&timestamp, &mut Vec::new(), default_node_metadata_repo());
+
+			assert_eq!(result, Err(StfError::TimeSlotAlreadyMatched(timestamp.clone())));
+			assert_eq!(read_open_orders(&timestamp).len(), 2);
+			assert_eq!(read_open_time_slots(), vec![timestamp]);
+		});
+	}
+
+	#[test]
+	fn due_time_slots_that_fail_to_close_stay_open() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			let timestamp = set_calendar_and_submit_orders();
+			set_now(GATE_CLOSURE);
+
+			let mut calls = Vec::new();
+			close_due_slots(&mut calls, default_node_metadata_repo());
+
+			assert!(calls.is_empty());
+			assert!(!orders_exist(&timestamp));
+			assert_eq!(read_open_orders(&timestamp).len(), 2);
+			assert_eq!(read_open_time_slots(), vec![timestamp]);
+		});
+	}
+
+	#[test]
+	fn due_time_slots_that_can_never_be_closed_are_dropped() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			let timestamp = set_calendar_and_submit_orders();
+			write_orders(&timestamp, &default_orders());
+			set_now(GATE_CLOSURE);
+
+			let mut calls = Vec::new();
+			close_due_slots(&mut calls, default_node_metadata_repo());
+
+			assert!(calls.is_empty());
+			assert!(read_open_orders(&timestamp).is_empty());
+			assert!(read_open_time_slots().is_empty());
+		});
+	}
+
+	#[test]
+	fn failing_close_slot_keeps_the_open_orders() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			let timestamp = submit_default_orders();
+
+			assert!(close_slot(&timestamp, &mut Vec::new(), default_node_metadata_repo()).is_err());
+
+			assert_eq!(read_open_orders(&timestamp).len(), 2);
+			assert_eq!(read_open_time_slots(), vec![timestamp.clone()]);
+			assert!(!orders_exist(&timestamp));
+		});
+	}
+
+	#[test]
+	fn failing_grid_fee_matching_stores_no_grid_fee_results() {
+		let mut state = SgxExternalities::default();
+
+		state.execute_with(|| {
+			let orders = default_orders();
+			let timestamp = orders[0].time_slot.clone();
+			let grid_fee_matrix = parse_grid_fee_matrix("[[0]]").unwrap();
+
+			let result = match_and_store_orders(
+				orders,
+				Some(grid_fee_matrix.len()),
+				|input| {
+					let results = pay_as_bid_grid_fee_matching(input, &grid_fee_matrix)?;
+					Ok((results.clone().into(), Some(results)))
+				},
+				|_| None,
+				&mut Vec::new(),
+				default_node_metadata_repo(),
+			);
+
+			assert_eq!(result, Err(StfError::InvalidMetadata));
+			assert!(!orders_exist(&timestamp));
+			assert!(read_grid_fee_market_results(&timestamp).is_err());
+		});
+	}
+
 	#[test]
 	fn verify_signature_works() {
 		let nonce = 21;
@@ -90,6 +90,9 @@ ORDERS_STRING="$FILE_CONTENTS"
 CLIENT="${CLIENT_BIN} -p ${NPORT} -P ${WORKER1PORT} -u ${NODEURL} -U ${WORKER1URL}"
 read -r MRENCLAVE <<< "$($CLIENT list-workers | awk '/  MRENCLAVE: / { print $2; exit }')"
 
+echo "* Registering //Alice for actor ${ACTOR_ID}"
+$CLIENT trusted --mrenclave ${MRENCLAVE} --direct register-actor //Alice ${ACTOR_ID} //Alice
+
 echo "* Executing pay-as-pid market algorithm"
 RESULT=`$CLIENT trusted --mrenclave ${MRENCLAVE} --direct pay-as-bid //Alice ${ORDERS_STRING}`
 echo "Result: ${RESULT}"
@@ -101,13 +104,14 @@ MARKET_RESULT=`$CLIENT trusted --mrenclave ${MRENCLAVE} --direct get-market-resu
 echo "Proof: ${MARKET_RESULT}"
 
 echo "* Getting merkle proof for orders"
-PROOF=`$CLIENT trusted --mrenclave ${MRENCLAVE} --direct pay-as-bid-proof //Alice ${TIMESTAMP} ${ACTOR_ID}`
+PROOF=`$CLIENT trusted --mrenclave ${MRENCLAVE} --direct pay-as-bid-proof //Alice ${TIMESTAMP} ${ACTOR_ID} --format json`
 echo "Proof: ${PROOF}"
 
 echo "* Verifying merkle proof"
-RESULT=`$CLIENT trusted --mrenclave ${MRENCLAVE} verify-proof ${PROOF}`
+RESULT=`$CLIENT trusted --mrenclave ${MRENCLAVE} verify-proof ${PROOF} | grep "Proof is valid"`
+echo "${RESULT}"
 
-if [ "${RESULT}" = true ]; then
+if [ "${RESULT}" = "Proof is valid: true" ]; then
     echo "Merkle proof is correct"
     echo ""
     exit 0
@@ -0,0 +1,473 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Benchmark of the energy market with the order books in `bin/orders`.
+//!
+//! Every order book is submitted to its own time slot. Measured are the time from submitting an
+//! order until it is in a sidechain block, the matching time and the latency of the proof getter.
+//!
+//! If a market calendar is set, the time slots are the next ones of the calendar, whose gate is
+//! still open. They are matched automatically at gate closure, the matching time is measured from
+//! gate closure until the results can be queried. Without a calendar, the time slots are an hour
+//! apart in the future and are closed by the root account, the matching time is measured from
+//! closing a slot until the matching is in a sidechain block.
+
+use super::{get_nonce, is_sidechain_block, is_submitted, TimingSummary};
+use crate::{
+	command_utils::get_worker_api_direct,
+	get_layer_two_nonce,
+	trusted_base_cli::commands::get_matched_time_slots::get_matched_time_slots,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_identifiers, get_keystore_path, get_pair_from_str},
+	trusted_operation::{get_json_request, get_state, perform_trusted_operation, wait_until},
+	Cli, CliError, CliResult, CliResultOk, SR25519_KEY_TYPE,
+};
+use chrono::{SecondsFormat, TimeZone, Utc};
+use codec::Decode;
+use hdrhistogram::Histogram;
+use ita_stf::{
+	best_energy_helpers::{history::Pagination, market_calendar::MarketCalendar},
+	Getter, Index, PublicGetter, TrustedCall, TrustedCallSigned, TrustedGetter,
+};
+use itc_rpc_client::direct_client::{DirectApi, DirectClient};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{KeyPair, TrustedOperation},
+};
+use itp_types::{Balance, ShardIdentifier};
+use log::*;
+use rayon::prelude::*;
+use serde::Serialize;
+use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
+use simplyr_lib::Order;
+use sp_core::{sr25519 as sr25519_core, Pair};
+use sp_keystore::Keystore;
+use std::{
+	boxed::Box,
+	collections::{BTreeMap, BTreeSet},
+	fs,
+	string::ToString,
+	sync::mpsc::{channel, Receiver},
+	thread,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+	vec::Vec,
+};
+use substrate_client_keystore::LocalKeystore;
+
+/// Funds of every generated actor account, to settle its matched bids.
+const ACTOR_FUNDS: Balance = 10_000_000;
+
+const SECONDS_PER_HOUR: u64 = 3_600;
+
+/// Interval, in which the results of a time slot are queried while waiting for its matching.
+const MATCHING_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Parser)]
+pub struct MarketBenchmarkCommand {
+	/// Order books in JSON, e.g. `bin/orders/order_10_users.json`
+	#[clap(required = true)]
+	order_books: Vec<String>,
+
+	/// Submit the orders of the different actors of a time slot concurrently
+	#[clap(short, long)]
+	concurrent: bool,
+
+	/// The number of clients (=threads) submitting orders concurrently
+	#[clap(long, default_value_t = 10)]
+	number_clients: usize,
+
+	/// Root account, which registers and funds the actors and closes the time slots
+	#[clap(long, default_value_t = String::from("//Alice"))]
+	root_account: String,
+
+	/// Seconds reserved for submitting the orders of a time slot before its gate closes,
+	/// if a market calendar is set
+	#[clap(long, default_value_t = 60)]
+	submission_secs: u64,
+
+	/// Seconds to wait for the automatic matching of a time slot after its gate has closed
+	#[clap(long, default_value_t = 300)]
+	matching_timeout_secs: u64,
+}
+
+/// Direct client, which watches the status of all trusted operations sent with it.
+struct WatchingClient {
+	client_api: DirectClient,
+	receiver: Option<Receiver<String>>,
+}
+
+impl WatchingClient {
+	fn new(cli: &Cli) -> Self {
+		WatchingClient { client_api: get_worker_api_direct(cli), receiver: None }
+	}
+
+	/// Sends the trusted operation and returns the time until it is in a sidechain block.
+	///
+	/// Without `wait_for_sidechain_block`, the time until it is submitted is returned.
+	fn send(&mut self, request: String, wait_for_sidechain_block: bool) -> Option<Duration> {
+		let started = Instant::now();
+		if self.receiver.is_none() {
+			let (sender, receiver) = channel();
+			self.client_api.watch(request, sender);
+			self.receiver = Some(receiver);
+		} else {
+			self.client_api.send(&request).unwrap();
+		}
+		let receiver = self.receiver.as_ref().expect("Receiver was set above; qed");
+
+		let (hash, submitted) = wait_until(receiver, is_submitted)?;
+		if !wait_for_sidechain_block {
+			return Some(submitted.duration_since(started))
+		}
+
+		// We wait for the transaction hash that actually matches the submitted hash
+		loop {
+			match wait_until(receiver, is_sidechain_block) {
+				Some((confirmed_hash, confirmed)) if confirmed_hash == hash =>
+					return Some(confirmed.duration_since(started)),
+				Some(_) => continue,
+				None => return None,
+			}
+		}
+	}
+
+	fn close(&self) {
+		if self.receiver.is_some() {
+			self.client_api.close().unwrap();
+		}
+	}
+}
+
+/// Order book of a single time slot, grouped by actor.
+struct OrderBook {
+	time_slot: String,
+	orders_by_actor: BTreeMap<String, Vec<Order>>,
+}
+
+impl OrderBook {
+	fn move_to_time_slot(&mut self, time_slot: String) {
+		for order in self.orders_by_actor.values_mut().flatten() {
+			order.time_slot = time_slot.clone();
+		}
+		self.time_slot = time_slot;
+	}
+}
+
+#[derive(Serialize)]
+struct MarketBenchmarkSummary {
+	/// From submitting an order until it is in a sidechain block.
+	submit_order: TimingSummary,
+	/// From closing a time slot until its matching is in a sidechain block.
+	matching: TimingSummary,
+	/// Latency of the `pay_as_bid_proof` getter.
+	proof_getter: TimingSummary,
+}
+
+impl MarketBenchmarkCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let mut order_books = self.read_order_books()?;
+
+		let store = LocalKeystore::open(get_keystore_path(trusted_args), None).unwrap();
+		let root = get_pair_from_str(trusted_args, &self.root_account);
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+
+		let calendar = read_market_calendar(cli, shard)?;
+		match calendar {
+			Some(calendar) => println!(
+				"Time slots of {} s are matched automatically {} s before they start",
+				calendar.slot_length_secs, calendar.gate_closure_secs
+			),
+			None => println!("No market calendar is set, the time slots are closed by root"),
+		}
+
+		let shielding_pubkey: Rsa3072PubKey = match get_worker_api_direct(cli).get_rsa_pubkey() {
+			Ok(key) => key,
+			Err(err_msg) => panic!("{}", err_msg.to_string()),
+		};
+
+		let mut root_nonce = get_layer_two_nonce!(root, cli, trusted_args);
+		let mut root_client = WatchingClient::new(cli);
+		let mut sign_and_send_as_root =
+			|call: TrustedCall, client: &mut WatchingClient, wait_for_sidechain_block: bool| {
+				let top: TrustedOperation<TrustedCallSigned, Getter> = call
+					.sign(&KeyPair::Sr25519(Box::new(root.clone())), root_nonce, &mrenclave, &shard)
+					.into_trusted_operation(trusted_args.direct);
+				root_nonce += 1;
+				client
+					.send(get_json_request(shard, &top, shielding_pubkey), wait_for_sidechain_block)
+			};
+
+		// Register and fund a new account for every actor.
+		let actor_ids: BTreeSet<String> = order_books
+			.iter()
+			.flat_map(|book| book.orders_by_actor.keys().cloned())
+			.collect();
+		let number_of_actors = actor_ids.len();
+		let mut actors: BTreeMap<String, sr25519_core::Pair> = BTreeMap::new();
+		for (i, actor_id) in actor_ids.into_iter().enumerate() {
+			println!("Initializing account of {}", actor_id);
+			let public =
+				LocalKeystore::sr25519_generate_new(&store, SR25519_KEY_TYPE, None).unwrap();
+			let account = get_pair_from_str(trusted_args, public.to_string().as_str());
+
+			sign_and_send_as_root(
+				TrustedCall::register_actor(
+					root.public().into(),
+					actor_id.clone(),
+					account.public().into(),
+				),
+				&mut root_client,
+				false,
+			);
+			// For the last account we wait for confirmation in order to ensure all accounts were setup correctly
+			let last_actor = i == number_of_actors - 1;
+			let funded = sign_and_send_as_root(
+				TrustedCall::balance_transfer(
+					root.public().into(),
+					account.public().into(),
+					ACTOR_FUNDS,
+				),
+				&mut root_client,
+				last_actor,
+			);
+			if last_actor && funded.is_none() {
+				return Err(CliError::TrustedOp { msg: "Setting up the actors failed".into() })
+			}
+			actors.insert(actor_id, account);
+		}
+
+		let pool = rayon::ThreadPoolBuilder::new()
+			.num_threads(self.number_clients)
+			.build()
+			.unwrap();
+
+		let mut submit_order_hist = Histogram::<u64>::new(1).unwrap();
+		let mut matching_hist = Histogram::<u64>::new(1).unwrap();
+		let mut proof_getter_hist = Histogram::<u64>::new(1).unwrap();
+		let overall_start = Instant::now();
+
+		for (i, book) in order_books.iter_mut().enumerate() {
+			// Chosen right before submitting, so that the gate of the time slot is still open.
+			let slot_start = match calendar {
+				Some(calendar) => next_open_slot_start(&calendar, self.submission_secs),
+				None => unix_now().as_secs() + (i as u64 + 1) * SECONDS_PER_HOUR,
+			};
+			book.move_to_time_slot(format_time_slot(slot_start));
+			let book = &*book;
+
+			println!(
+				"Submitting orders of {} actors to time slot {}",
+				book.orders_by_actor.len(),
+				book.time_slot
+			);
+
+			let submit_orders_of_actor = |(actor_id, orders): (&String, &Vec<Order>)| {
+				let account = &actors[actor_id];
+				let mut client = WatchingClient::new(cli);
+				let mut nonce = get_nonce(account.clone(), shard, &client.client_api);
+
+				let mut durations = Vec::new();
+				for order in orders {
+					let top: TrustedOperation<TrustedCallSigned, Getter> =
+						TrustedCall::submit_order(
+							account.public().into(),
+							serde_json::to_string(order).unwrap(),
+						)
+						.sign(
+							&KeyPair::Sr25519(Box::new(account.clone())),
+							nonce,
+							&mrenclave,
+							&shard,
+						)
+						.into_trusted_operation(trusted_args.direct);
+					nonce += 1;
+
+					match client.send(get_json_request(shard, &top, shielding_pubkey), true) {
+						Some(duration) => durations.push(duration),
+						None => error!("Order {} of {} was not included", order.id, actor_id),
+					}
+				}
+				client.close();
+				durations
+			};
+
+			let durations: Vec<Duration> = if self.concurrent {
+				pool.install(|| {
+					book.orders_by_actor.par_iter().map(submit_orders_of_actor).flatten().collect()
+				})
+			} else {
+				book.orders_by_actor.iter().flat_map(submit_orders_of_actor).collect()
+			};
+			for duration in durations {
+				submit_order_hist += duration.as_millis() as u64;
+			}
+
+			let matching_duration = match calendar {
+				Some(calendar) =>
+					self.wait_for_automatic_matching(cli, trusted_args, &calendar, slot_start),
+				None => {
+					println!("Closing time slot {}", book.time_slot);
+					sign_and_send_as_root(
+						TrustedCall::close_slot(root.public().into(), book.time_slot.clone()),
+						&mut root_client,
+						true,
+					)
+				},
+			};
+			match matching_duration {
+				Some(duration) => matching_hist += duration.as_millis() as u64,
+				None => {
+					error!("Matching time slot {} failed", book.time_slot);
+					continue
+				},
+			}
+
+			let direct_client = get_worker_api_direct(cli);
+			for actor_id in book.orders_by_actor.keys() {
+				let account = &actors[actor_id];
+				let getter = Getter::trusted(
+					TrustedGetter::pay_as_bid_proof(
+						account.public().into(),
+						book.time_slot.clone(),
+						actor_id.clone(),
+					)
+					.sign(&KeyPair::Sr25519(Box::new(account.clone()))),
+				);
+
+				let getter_start_timer = Instant::now();
+				match get_state(&direct_client, shard, &getter) {
+					Ok(Some(_)) =>
+						proof_getter_hist += getter_start_timer.elapsed().as_millis() as u64,
+					_ => error!("No proof of {} in time slot {}", actor_id, book.time_slot),
+				}
+			}
+		}
+
+		root_client.close();
+
+		println!(
+			"Finished market benchmark with {} order books in {} ms",
+			order_books.len(),
+			overall_start.elapsed().as_millis()
+		);
+
+		let summary = MarketBenchmarkSummary {
+			submit_order: TimingSummary::new(&submit_order_hist),
+			matching: TimingSummary::new(&matching_hist),
+			proof_getter: TimingSummary::new(&proof_getter_hist),
+		};
+		println!("{}", serde_json::to_string(&summary).unwrap());
+
+		Ok(CliResultOk::None)
+	}
+
+	/// Waits until the results of the time slot, which starts at `slot_start` seconds since the
+	/// unix epoch, can be queried and returns the time since its gate closure.
+	fn wait_for_automatic_matching(
+		&self,
+		cli: &Cli,
+		trusted_args: &TrustedCli,
+		calendar: &MarketCalendar,
+		slot_start: u64,
+	) -> Option<Duration> {
+		let time_slot = format_time_slot(slot_start);
+		let gate_closure =
+			Duration::from_secs(slot_start.saturating_sub(calendar.gate_closure_secs));
+		if let Some(until_gate_closure) = gate_closure.checked_sub(unix_now()) {
+			println!(
+				"Waiting {} s for the gate of time slot {} to close",
+				until_gate_closure.as_secs(),
+				time_slot
+			);
+			thread::sleep(until_gate_closure);
+		}
+
+		// Time slots of the calendar start at full seconds, so no other one is in this range.
+		let (from, to) = (time_slot.clone(), format_time_slot(slot_start + 1));
+		let timeout = gate_closure + Duration::from_secs(self.matching_timeout_secs);
+		loop {
+			let matched = get_matched_time_slots(
+				cli,
+				trusted_args,
+				&self.root_account,
+				from.clone(),
+				to.clone(),
+				Pagination { offset: 0, limit: 1 },
+			)
+			.map_or(false, |page| page.total > 0);
+			let now = unix_now();
+			if matched {
+				return Some(now.saturating_sub(gate_closure))
+			}
+			if now > timeout {
+				return None
+			}
+			thread::sleep(MATCHING_POLL_INTERVAL);
+		}
+	}
+
+	/// Reads the order books, their time slots are set right before they are submitted.
+	fn read_order_books(&self) -> Result<Vec<OrderBook>, CliError> {
+		self.order_books
+			.iter()
+			.map(|path| {
+				let orders: Vec<Order> = fs::read_to_string(path)
+					.map_err(|e| e.to_string())
+					.and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+					.map_err(|e| CliError::TrustedOp {
+						msg: format!("Could not read order book {}: {}", path, e),
+					})?;
+
+				let mut orders_by_actor: BTreeMap<String, Vec<Order>> = BTreeMap::new();
+				for order in orders {
+					orders_by_actor.entry(order.actor_id.clone()).or_default().push(order);
+				}
+				Ok(OrderBook { time_slot: String::new(), orders_by_actor })
+			})
+			.collect()
+	}
+}
+
+fn read_market_calendar(
+	cli: &Cli,
+	shard: ShardIdentifier,
+) -> Result<Option<MarketCalendar>, CliError> {
+	let getter = Getter::public(PublicGetter::market_calendar);
+	match get_state(&get_worker_api_direct(cli), shard, &getter) {
+		Ok(Some(encoded)) => Option::<MarketCalendar>::decode(&mut encoded.as_slice())
+			.map_err(|e| CliError::TrustedOp { msg: format!("Invalid market calendar: {:?}", e) }),
+		_ => Err(CliError::TrustedOp { msg: "Could not read the market calendar".into() }),
+	}
+}
+
+/// Start of the first time slot of the calendar, whose gate closes at least `submission_secs`
+/// from now.
+fn next_open_slot_start(calendar: &MarketCalendar, submission_secs: u64) -> u64 {
+	let earliest_start = unix_now().as_secs() + submission_secs + calendar.gate_closure_secs;
+	let slot_length = calendar.slot_length_secs;
+	(earliest_start + slot_length - 1) / slot_length * slot_length
+}
+
+fn format_time_slot(unix_seconds: u64) -> String {
+	Utc.timestamp_opt(unix_seconds as i64, 0)
+		.unwrap()
+		.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn unix_now() -> Duration {
+	SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards")
+}
@@ -15,6 +15,7 @@
 
 */
 
+use self::market::MarketBenchmarkCommand;
 use crate::{
 	command_utils::get_worker_api_direct,
 	get_layer_two_nonce,
@@ -40,12 +41,14 @@ use itp_types::{
 use log::*;
 use rand::Rng;
 use rayon::prelude::*;
+use serde::Serialize;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 use sp_application_crypto::sr25519;
 use sp_core::{sr25519 as sr25519_core, Pair};
 use sp_keystore::Keystore;
 use std::{
 	boxed::Box,
+	collections::BTreeMap,
 	string::ToString,
 	sync::mpsc::{channel, Receiver},
 	thread, time,
@@ -54,11 +57,14 @@ use std::{
 };
 use substrate_client_keystore::LocalKeystore;
 
+mod market;
+
 // Needs to be above the existential deposit minimum, otherwise an account will not
 // be created and the state is not increased.
 const EXISTENTIAL_DEPOSIT: Balance = 1000;
 
 #[derive(Parser)]
+#[clap(args_conflicts_with_subcommands = true)]
 pub struct BenchmarkCommand {
 	/// The number of clients (=threads) to be used in the benchmark
 	#[clap(default_value_t = 10)]
@@ -83,6 +89,15 @@ pub struct BenchmarkCommand {
 	/// Account to be used for initial funding of generated accounts used in benchmark
 	#[clap(default_value_t = String::from("//Alice"))]
 	funding_account: String,
+
+	#[clap(subcommand)]
+	subcommand: Option<BenchmarkSubcommand>,
+}
+
+#[derive(Subcommand)]
+pub enum BenchmarkSubcommand {
+	/// Benchmark the energy market with order books, instead of balance transfers
+	Market(MarketBenchmarkCommand),
 }
 
 struct BenchmarkClient {
@@ -118,6 +133,10 @@ struct BenchmarkTransaction {
 
 impl BenchmarkCommand {
 	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		if let Some(BenchmarkSubcommand::Market(cmd)) = &self.subcommand {
+			return cmd.run(cli, trusted_args)
+		}
+
 		let random_wait_before_transaction_ms: (u32, u32) = (
 			self.random_wait_before_transaction_min_ms,
 			self.random_wait_before_transaction_max_ms,
@@ -323,6 +342,33 @@ fn print_benchmark_statistic(outputs: Vec<Vec<BenchmarkTransaction>>, wait_for_c
 		);
 		println!("{}", text);
 	}
+	println!("{}", serde_json::to_string(&TimingSummary::new(&hist)).unwrap());
+}
+
+/// Timing summary of the benchmarked operations in ms, printed as JSON.
+#[derive(Serialize)]
+struct TimingSummary {
+	count: u64,
+	min: u64,
+	max: u64,
+	mean: f64,
+	/// Percentile -> duration in ms, in steps of 5 percent.
+	percentiles: BTreeMap<u32, u64>,
+}
+
+impl TimingSummary {
+	fn new(hist: &Histogram<u64>) -> Self {
+		TimingSummary {
+			count: hist.len(),
+			min: hist.min(),
+			max: hist.max(),
+			mean: hist.mean(),
+			percentiles: (5..=100)
+				.step_by(5)
+				.map(|i| (i, hist.value_at_quantile(i as f64 / 100.0)))
+				.collect(),
+		}
+	}
 }
 
 fn random_wait(random_wait_before_transaction_ms: (u32, u32)) {
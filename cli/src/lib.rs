@@ -46,9 +46,17 @@ pub mod trusted_cli;
 
 use crate::commands::Commands;
 use clap::Parser;
-use ita_stf::MerkleProofWithCodec;
+use ita_stf::{
+	best_energy_helpers::{
+		grid_fee::GridFeeMarketOutput,
+		history::{OpenPage, Page, TimeSlotMatch},
+		reserve_price::GridSettlement,
+		settlement::SettlementSummary,
+	},
+	MerkleProofWithCodec,
+};
 use itp_node_api::metadata::Metadata;
-use simplyr_lib::MarketOutput;
+use simplyr_lib::{MarketOutput, Order};
 use sp_application_crypto::KeyTypeId;
 use sp_core::{H160, H256};
 use thiserror::Error;
@@ -113,8 +121,16 @@ pub enum CliResultOk {
 	None,
 
 	Matches(MarketOutput),
+	GridFeeMatches(GridFeeMarketOutput),
+	GridSettlements(Vec<GridSettlement>),
+	SettlementSummary(SettlementSummary),
+	TimeSlots(Page<String>),
+	ActorMatches(OpenPage<TimeSlotMatch>),
+	Orders(Vec<Order>),
 	PayAsBidOutput(Option<Vec<u8>>),
-	PayAsBidProofOutput(MerkleProofWithCodec<H256, Vec<u8>>),
+	PayAsClearOutput(Option<Vec<u8>>),
+	PayAsBidProofOutput(Vec<MerkleProofWithCodec<H256, Vec<u8>>>),
+	MarketResultsProofOutput(Vec<MerkleProofWithCodec<H256, Vec<u8>>>),
 }
 
 #[derive(Debug, Error)]
@@ -125,6 +141,10 @@ pub enum CliError {
 	TrustedOp { msg: String },
 	#[error("EvmReadCommands error: {:?}", msg)]
 	EvmRead { msg: String },
+	#[error("invalid input: {:?}", msg)]
+	InvalidInput { msg: String },
+	#[error("verification failed: {:?}", msg)]
+	VerificationFailed { msg: String },
 }
 
 pub type CliResult = Result<CliResultOk, CliError>;
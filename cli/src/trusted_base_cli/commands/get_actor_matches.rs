@@ -0,0 +1,123 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+		http://www.apache.org/licenses/LICENSE-2.0
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliResult, CliResultOk,
+};
+
+use crate::CliError;
+use codec::Decode;
+use ita_stf::{
+	best_energy_helpers::history::{OpenPage, Pagination, TimeSlotMatch, MAX_PAGE_SIZE},
+	Getter, TrustedCallSigned, TrustedGetter,
+};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use log::debug;
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct GetActorMatchesCommand {
+	/// AccountId in ss58check format, registered for the actor
+	pub account: String,
+	pub actor_id: String,
+	/// Start of the first time slot, inclusive
+	pub from: String,
+	/// Start of the last time slot, exclusive. The range may span at most a year
+	pub to: String,
+
+	/// Number of matches to skip
+	#[clap(long, default_value_t = 0)]
+	pub offset: u32,
+
+	/// Maximum number of matches to return
+	#[clap(long, default_value_t = MAX_PAGE_SIZE)]
+	pub limit: u32,
+}
+
+impl GetActorMatchesCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let pagination = Pagination { offset: self.offset, limit: self.limit };
+		let matches = get_actor_matches(
+			cli,
+			trusted_args,
+			&self.account,
+			self.actor_id.clone(),
+			self.from.clone(),
+			self.to.clone(),
+			pagination,
+		);
+		match matches {
+			Ok(matches) => {
+				for m in matches.items.iter() {
+					println!(
+						"{}: bid {} -> ask {}: {} kWh at {} €/kWh",
+						m.time_slot,
+						m.trade.bid_id,
+						m.trade.ask_id,
+						m.trade.energy_kwh,
+						m.trade.price_euro_per_kwh
+					);
+				}
+				println!(
+					"{} matches, starting at {}{}",
+					matches.items.len(),
+					self.offset,
+					if matches.has_more { ", more to follow" } else { "" }
+				);
+				Ok(CliResultOk::ActorMatches(matches))
+			},
+			Err(e) => {
+				log::error!("Error getting actor matches: {}", e);
+				Err(CliError::TrustedOp { msg: "Error getting actor matches".into() })
+			},
+		}
+	}
+}
+
+pub(crate) fn get_actor_matches(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	actor_id: String,
+	from: String,
+	to: String,
+	pagination: Pagination,
+) -> Result<OpenPage<TimeSlotMatch>, CliError> {
+	debug!("arg_who = {:?}", arg_who);
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::get_actor_matches(who.public().into(), actor_id, from, to, pagination)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top).unwrap();
+
+	match res {
+		Some(matches) => match OpenPage::<TimeSlotMatch>::decode(&mut matches.as_slice()) {
+			Ok(matches) => Ok(matches),
+			Err(err) => {
+				log::error!("Error deserializing actor matches: {}", err);
+				Err(CliError::TrustedOp {
+					msg: format!("Error deserializing actor matches: {}", err),
+				})
+			},
+		},
+		None => {
+			log::error!("Actor matches not found");
+			Err(CliError::TrustedOp { msg: "Actor matches not found".into() })
+		},
+	}
+}
@@ -0,0 +1,93 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+		http://www.apache.org/licenses/LICENSE-2.0
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliResult, CliResultOk,
+};
+
+use crate::CliError;
+use codec::Decode;
+use ita_stf::{
+	best_energy_helpers::reserve_price::GridSettlement, Getter, TrustedCallSigned, TrustedGetter,
+};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use log::debug;
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct GetGridSettlementsCommand {
+	/// AccountId in ss58check format
+	pub account: String,
+	pub timestamp: String,
+}
+
+impl GetGridSettlementsCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let settlements =
+			get_grid_settlements(cli, trusted_args, &self.account, self.timestamp.clone());
+		match settlements {
+			Ok(settlements) => {
+				for settlement in settlements.iter() {
+					println!(
+						"{:?} {} of {}: {} kWh at {} €/kWh",
+						settlement.order_type,
+						settlement.order_id,
+						settlement.actor_id,
+						settlement.energy_kwh,
+						settlement.price_euro_per_kwh
+					);
+				}
+				Ok(CliResultOk::GridSettlements(settlements))
+			},
+			Err(e) => {
+				log::error!("Error getting grid settlements: {}", e);
+				Err(CliError::TrustedOp { msg: "Error getting grid settlements".into() })
+			},
+		}
+	}
+}
+
+pub(crate) fn get_grid_settlements(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	timestamp: String,
+) -> Result<Vec<GridSettlement>, CliError> {
+	debug!("arg_who = {:?}", arg_who);
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::get_grid_settlements(who.public().into(), timestamp)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top).unwrap();
+
+	match res {
+		Some(settlements) => match Vec::<GridSettlement>::decode(&mut settlements.as_slice()) {
+			Ok(settlements) => Ok(settlements),
+			Err(err) => {
+				log::error!("Error deserializing grid settlements: {}", err);
+				Err(CliError::TrustedOp {
+					msg: format!("Error deserializing grid settlements: {}", err),
+				})
+			},
+		},
+		None => {
+			log::error!("Grid settlements not found");
+			Err(CliError::TrustedOp { msg: "Grid settlements not found".into() })
+		},
+	}
+}
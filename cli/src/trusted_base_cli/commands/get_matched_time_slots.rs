@@ -0,0 +1,113 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+		http://www.apache.org/licenses/LICENSE-2.0
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliResult, CliResultOk,
+};
+
+use crate::CliError;
+use codec::Decode;
+use ita_stf::{
+	best_energy_helpers::history::{Page, Pagination, MAX_PAGE_SIZE},
+	Getter, TrustedCallSigned, TrustedGetter,
+};
+use itp_stf_primitives::types::{KeyPair, Timestamp, TrustedOperation};
+use log::debug;
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct GetMatchedTimeSlotsCommand {
+	/// AccountId in ss58check format
+	pub account: String,
+	/// Start of the first time slot, inclusive
+	pub from: String,
+	/// Start of the last time slot, exclusive. The range may span at most a year
+	pub to: String,
+
+	/// Number of time slots to skip
+	#[clap(long, default_value_t = 0)]
+	pub offset: u32,
+
+	/// Maximum number of time slots to return
+	#[clap(long, default_value_t = MAX_PAGE_SIZE)]
+	pub limit: u32,
+}
+
+impl GetMatchedTimeSlotsCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let pagination = Pagination { offset: self.offset, limit: self.limit };
+		let time_slots = get_matched_time_slots(
+			cli,
+			trusted_args,
+			&self.account,
+			self.from.clone(),
+			self.to.clone(),
+			pagination,
+		);
+		match time_slots {
+			Ok(time_slots) => {
+				for time_slot in time_slots.items.iter() {
+					println!("{}", time_slot);
+				}
+				println!(
+					"{} of {} time slots, starting at {}",
+					time_slots.items.len(),
+					time_slots.total,
+					self.offset
+				);
+				Ok(CliResultOk::TimeSlots(time_slots))
+			},
+			Err(e) => {
+				log::error!("Error getting matched time slots: {}", e);
+				Err(CliError::TrustedOp { msg: "Error getting matched time slots".into() })
+			},
+		}
+	}
+}
+
+pub(crate) fn get_matched_time_slots(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	from: String,
+	to: String,
+	pagination: Pagination,
+) -> Result<Page<Timestamp>, CliError> {
+	debug!("arg_who = {:?}", arg_who);
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::get_matched_time_slots(who.public().into(), from, to, pagination)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top).unwrap();
+
+	match res {
+		Some(time_slots) => match Page::<Timestamp>::decode(&mut time_slots.as_slice()) {
+			Ok(time_slots) => Ok(time_slots),
+			Err(err) => {
+				log::error!("Error deserializing matched time slots: {}", err);
+				Err(CliError::TrustedOp {
+					msg: format!("Error deserializing matched time slots: {}", err),
+				})
+			},
+		},
+		None => {
+			log::error!("Matched time slots not found");
+			Err(CliError::TrustedOp { msg: "Matched time slots not found".into() })
+		},
+	}
+}
@@ -0,0 +1,65 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+		http://www.apache.org/licenses/LICENSE-2.0
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliError, CliResult, CliResultOk,
+};
+use codec::Decode;
+use ita_stf::{Getter, TrustedCallSigned, TrustedGetter};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use log::debug;
+use simplyr_lib::Order;
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct GetOpenOrdersCommand {
+	/// AccountId in ss58check format
+	pub account: String,
+	pub timestamp: String,
+}
+
+impl GetOpenOrdersCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let orders = get_open_orders(cli, trusted_args, &self.account, self.timestamp.clone())?;
+		println!("{}", serde_json::to_string(&orders).unwrap());
+		Ok(CliResultOk::Orders(orders))
+	}
+}
+
+/// Gets the orders that `arg_who` has submitted for a time slot, which is still open.
+pub(crate) fn get_open_orders(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	timestamp: String,
+) -> Result<Vec<Order>, CliError> {
+	debug!("arg_who = {:?}", arg_who);
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::get_open_orders(who.public().into(), timestamp)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top)?;
+
+	match res {
+		Some(orders) => Vec::<Order>::decode(&mut orders.as_slice()).map_err(|err| {
+			log::error!("Error deserializing orders: {}", err);
+			CliError::TrustedOp { msg: format!("Error deserializing orders: {}", err) }
+		}),
+		None => Ok(Vec::new()),
+	}
+}
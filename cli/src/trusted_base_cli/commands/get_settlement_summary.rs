@@ -0,0 +1,100 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+		http://www.apache.org/licenses/LICENSE-2.0
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliResult, CliResultOk,
+};
+
+use crate::CliError;
+use codec::Decode;
+use ita_stf::{
+	best_energy_helpers::settlement::SettlementSummary, Getter, TrustedCallSigned, TrustedGetter,
+};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use log::debug;
+use sp_core::{crypto::Ss58Codec, Pair};
+
+#[derive(Parser)]
+pub struct GetSettlementSummaryCommand {
+	/// AccountId in ss58check format
+	pub account: String,
+	pub timestamp: String,
+}
+
+impl GetSettlementSummaryCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let summary =
+			get_settlement_summary(cli, trusted_args, &self.account, self.timestamp.clone());
+		match summary {
+			Ok(summary) => {
+				for trade in summary.trades.iter() {
+					println!(
+						"bid {} -> ask {}: {} (incl. grid fee {}) from {} to {}: {:?}",
+						trade.bid_id,
+						trade.ask_id,
+						trade.amount,
+						trade.grid_fee,
+						trade.buyer.as_ref().map_or("-".into(), |a| a.to_ss58check()),
+						trade.seller.as_ref().map_or("-".into(), |a| a.to_ss58check()),
+						trade.status
+					);
+				}
+				println!(
+					"settled: {}, unsettled trades: {}",
+					summary.total_settled(),
+					summary.unsettled_trades().count()
+				);
+				Ok(CliResultOk::SettlementSummary(summary))
+			},
+			Err(e) => {
+				log::error!("Error getting settlement summary: {}", e);
+				Err(CliError::TrustedOp { msg: "Error getting settlement summary".into() })
+			},
+		}
+	}
+}
+
+pub(crate) fn get_settlement_summary(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	timestamp: String,
+) -> Result<SettlementSummary, CliError> {
+	debug!("arg_who = {:?}", arg_who);
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::get_settlement_summary(who.public().into(), timestamp)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top).unwrap();
+
+	match res {
+		Some(summary) => match SettlementSummary::decode(&mut summary.as_slice()) {
+			Ok(summary) => Ok(summary),
+			Err(err) => {
+				log::error!("Error deserializing settlement summary: {}", err);
+				Err(CliError::TrustedOp {
+					msg: format!("Error deserializing settlement summary: {}", err),
+				})
+			},
+		},
+		None => {
+			log::error!("Settlement summary not found");
+			Err(CliError::TrustedOp { msg: "Settlement summary not found".into() })
+		},
+	}
+}
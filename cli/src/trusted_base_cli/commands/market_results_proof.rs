@@ -0,0 +1,78 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+		http://www.apache.org/licenses/LICENSE-2.0
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+*/
+
+use crate::{
+	trusted_cli::TrustedCli, trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation, Cli, CliError, CliResult, CliResultOk,
+};
+use codec::Decode;
+use ita_stf::{Getter, MerkleProofWithCodec, TrustedCallSigned, TrustedGetter};
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use log::debug;
+use sp_core::{Pair, H256};
+
+#[derive(Parser)]
+pub struct MarketResultsProofCommand {
+	/// AccountId in ss58check format
+	pub account: String,
+	pub timestamp: String,
+	pub actor_id: String,
+}
+
+impl MarketResultsProofCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let proofs = market_results_proof(
+			cli,
+			trusted_args,
+			&self.account,
+			self.timestamp.clone(),
+			self.actor_id.clone(),
+		)?;
+
+		// The proofs can be passed to the verify-proof command as is.
+		println!("{}", serde_json::to_string(&proofs).unwrap());
+		Ok(CliResultOk::MarketResultsProofOutput(proofs))
+	}
+}
+
+/// Gets the merkle proofs for all matches of `actor_id` in the market results of `timestamp`.
+pub(crate) fn market_results_proof(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	timestamp: String,
+	actor_id: String,
+) -> Result<Vec<MerkleProofWithCodec<H256, Vec<u8>>>, CliError> {
+	debug!("arg_who = {:?}", arg_who);
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::market_results_proof(who.public().into(), timestamp, actor_id)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top)?;
+
+	match res {
+		Some(proofs) => Vec::<MerkleProofWithCodec<H256, Vec<u8>>>::decode(&mut proofs.as_slice())
+			.map_err(|err| {
+				log::error!("Error deserializing proofs: {}", err);
+				CliError::TrustedOp { msg: format!("Error deserializing proofs: {}", err) }
+			}),
+		None => {
+			log::error!("Proofs not found");
+			Err(CliError::TrustedOp { msg: "Proofs not found".into() })
+		},
+	}
+}
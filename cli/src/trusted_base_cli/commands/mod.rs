@@ -1,9 +1,26 @@
+pub mod amend_order;
 pub mod balance;
+pub mod cancel_order;
+pub mod close_slot;
+pub mod get_actor_matches;
+pub mod get_grid_fee_market_results;
+pub mod get_grid_settlements;
 pub mod get_market_results;
+pub mod get_matched_time_slots;
+pub mod get_open_orders;
+pub mod get_settlement_summary;
+pub mod market_results_proof;
 pub mod nonce;
 pub mod pay_as_bid;
+pub mod pay_as_bid_grid_fee;
 pub mod pay_as_bid_proof;
+pub mod pay_as_clear;
+pub mod register_actor;
 pub mod set_balance;
+pub mod set_balance_units_per_euro;
+pub mod set_market_calendar;
+pub mod submit_order;
 pub mod transfer;
+pub mod unregister_actor;
 pub mod unshield_funds;
 pub mod verify_proof;
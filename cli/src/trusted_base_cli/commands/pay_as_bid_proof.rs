@@ -22,6 +22,7 @@ use itp_stf_primitives::types::{KeyPair, TrustedOperation};
 use log::debug;
 use sp_core::{Pair, H256};
 
+use super::verify_proof::{decode_order_leaf, describe_order, OutputFormat};
 use crate::CliError;
 use codec;
 #[derive(Parser)]
@@ -30,6 +31,10 @@ pub struct PayAsBidProofCommand {
 	pub account: String,
 	pub timestamp: String,
 	pub actor_id: String,
+
+	/// `text` additionally prints the proven orders, `json` prints only the proofs
+	#[clap(long, arg_enum, default_value = "text")]
+	pub format: OutputFormat,
 }
 
 impl PayAsBidProofCommand {
@@ -45,7 +50,18 @@ impl PayAsBidProofCommand {
 		);
 
 		match results {
-			Ok(res) => Ok(CliResultOk::PayAsBidProofOutput(res)),
+			Ok(res) => {
+				if self.format == OutputFormat::Text {
+					for proof in res.iter() {
+						let order = decode_order_leaf(proof)
+							.map(|order| describe_order(&order))
+							.unwrap_or_else(|| "leaf could not be decoded".into());
+						println!("Leaf {}: {}", proof.leaf_index, order);
+					}
+				}
+				println!("{}", serde_json::to_string(&res).unwrap());
+				Ok(CliResultOk::PayAsBidProofOutput(res))
+			},
 			Err(e) => {
 				log::error!("Error getting proof: {}", e);
 				Err(CliError::TrustedOp { msg: "Error getting proof".into() })
@@ -60,7 +76,7 @@ pub(crate) fn pay_as_bid_proof(
 	arg_who: &str,
 	timestamp: String,
 	actor_id: String,
-) -> Result<MerkleProofWithCodec<H256, Vec<u8>>, CliError> {
+) -> Result<Vec<MerkleProofWithCodec<H256, Vec<u8>>>, CliError> {
 	debug!("arg_who = {:?}", arg_who);
 	let who = get_pair_from_str(trusted_args, arg_who);
 
@@ -73,15 +89,16 @@ pub(crate) fn pay_as_bid_proof(
 	let res = perform_trusted_operation(cli, trusted_args, &top).unwrap();
 
 	match res {
-		Some(_proof) => match MerkleProofWithCodec::decode(&mut &_proof[..]) {
-			Ok(_proof) => Ok(_proof),
-			Err(err) => {
-				log::error!("Error deserializing results: {}", err);
-				Err(CliError::TrustedOp {
-					msg: format!("Error deserializing market results: {}", err),
-				})
+		Some(proofs) =>
+			match Vec::<MerkleProofWithCodec<H256, Vec<u8>>>::decode(&mut &proofs[..]) {
+				Ok(proofs) => Ok(proofs),
+				Err(err) => {
+					log::error!("Error deserializing results: {}", err);
+					Err(CliError::TrustedOp {
+						msg: format!("Error deserializing market results: {}", err),
+					})
+				},
 			},
-		},
 		None => {
 			log::error!("Results not found");
 			Err(CliError::TrustedOp { msg: "Results not found".into() })
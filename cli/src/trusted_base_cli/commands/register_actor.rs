@@ -0,0 +1,57 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_identifiers, get_pair_from_str},
+	trusted_operation::perform_trusted_operation,
+	Cli, CliResult, CliResultOk,
+};
+use codec::Decode;
+use ita_stf::{Getter, Index, TrustedCall, TrustedCallSigned};
+use itp_stf_primitives::{
+	traits::TrustedCallSigning,
+	types::{KeyPair, TrustedOperation},
+};
+use log::*;
+use sp_core::Pair;
+
+#[derive(Parser)]
+pub struct RegisterActorCommand {
+	/// Root account in ss58check format
+	pub account: String,
+	pub actor_id: String,
+	/// Account in ss58check format, which is registered for the actor
+	pub actor_account: String,
+}
+
+impl RegisterActorCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let who = get_pair_from_str(trusted_args, &self.account);
+		let actor_account = get_accountid_from_str(&self.actor_account);
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(who, cli, trusted_args);
+		let top: TrustedOperation<TrustedCallSigned, Getter> =
+			TrustedCall::register_actor(who.public().into(), self.actor_id.clone(), actor_account)
+				.sign(&KeyPair::Sr25519(Box::new(who)), nonce, &mrenclave, &shard)
+				.into_trusted_operation(trusted_args.direct);
+		let res = perform_trusted_operation(cli, trusted_args, &top).map(|_| CliResultOk::None)?;
+		info!("trusted call register_actor executed");
+		Ok(res)
+	}
+}
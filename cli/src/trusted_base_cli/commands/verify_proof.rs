@@ -15,40 +15,366 @@
 
 */
 
-use crate::{trusted_cli::TrustedCli, Cli, CliResult, CliResultOk};
+use crate::{
+	command_utils::{get_chain_api, mrenclave_from_base58},
+	trusted_cli::TrustedCli,
+	trusted_command_utils::get_pair_from_str,
+	trusted_operation::perform_trusted_operation,
+	Cli, CliError, CliResult, CliResultOk,
+};
 use binary_merkle_tree::verify_proof;
-use ita_stf::MerkleProofWithCodec;
-use log::info;
+use codec::{Decode, DecodeAll};
+use enclave_bridge_primitives::EnclaveFingerprint;
+use ita_stf::{
+	best_energy_helpers::{orders_merkle_root_topic, results_merkle_root_topic, MerkleRoots},
+	Getter, MerkleProofWithCodec, TrustedCallSigned, TrustedGetter,
+};
+use itp_node_api::api_client::ParentchainApi;
+use itp_stf_primitives::types::{KeyPair, TrustedOperation};
+use itp_types::parentchain::BlockNumber;
+use log::{error, info};
+use my_node_runtime::{pallet_enclave_bridge, Hash, RuntimeEvent};
 use primitive_types::H256;
+use serde::Serialize;
+use simplyr_lib::{Match, Order};
+use sp_core::Pair;
 use sp_runtime::traits::Keccak256;
+use substrate_api_client::{ac_node_api::EventRecord, GetChainInfo, GetStorage};
+
+/// Upper bound of `--search-depth`, every searched block costs a request to the parentchain node.
+const MAX_SEARCH_DEPTH: BlockNumber = 1000;
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+	/// Human readable output
+	Text,
+	/// JSON output for downstream tooling
+	Json,
+}
 
 #[derive(Parser)]
 pub struct VerifyMerkleProofCommand {
+	/// A single merkle proof or a list of merkle proofs as JSON
 	pub merkle_proof_json: String,
+
+	/// Time slot of the proof. If given, the root of the proof is additionally checked against
+	/// the root published on the parentchain and against the root in the enclave state.
+	#[clap(long)]
+	pub timestamp: Option<String>,
+
+	/// The proof is a proof of market results instead of a proof of orders
+	#[clap(long)]
+	pub market_results: bool,
+
+	/// AccountId in ss58check format, used to query the root in the enclave state
+	#[clap(long, default_value = "//Alice")]
+	pub account: String,
+
+	/// Number of parentchain blocks that are searched for the published root, at most
+	/// `MAX_SEARCH_DEPTH`
+	#[clap(long, default_value_t = 100)]
+	pub search_depth: BlockNumber,
+
+	/// Output format of the verification report
+	#[clap(long, arg_enum, default_value = "text")]
+	pub format: OutputFormat,
+}
+
+/// Result of verifying a single or a list of merkle proofs.
+#[derive(Debug, Serialize)]
+pub struct VerificationReport {
+	/// True if every proof is valid and all proofs share the same root.
+	pub valid: bool,
+	pub root: Option<H256>,
+	pub leaves: Vec<LeafReport>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parentchain_root: Option<RootCheck>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub enclave_state_root: Option<RootCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeafReport {
+	pub leaf_index: u32,
+	pub valid: bool,
+	/// The decoded leaf of an order proof.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub order: Option<Order>,
+	/// The decoded leaf of a market results proof.
+	#[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+	pub trade: Option<Match>,
+}
+
+/// Comparison of the proof root with a root from another source, which is `None` if no root
+/// has been found there.
+#[derive(Debug, Serialize)]
+pub struct RootCheck {
+	pub root: Option<H256>,
+	pub matches: bool,
+}
+
+impl RootCheck {
+	fn new(proof_root: Option<H256>, root: Option<H256>) -> Self {
+		Self { root, matches: root.is_some() && proof_root == root }
+	}
+}
+
+impl VerificationReport {
+	/// Reason, why the verification failed, if it did.
+	fn failure(&self) -> Option<String> {
+		let root_check_failure = |source: &str, check: &Option<RootCheck>| match check {
+			Some(RootCheck { root: None, .. }) => Some(format!("No {} root found", source)),
+			Some(RootCheck { matches: false, .. }) =>
+				Some(format!("The root does not match the {} root", source)),
+			_ => None,
+		};
+
+		if !self.valid {
+			return Some("The proof is invalid".into())
+		}
+		root_check_failure("parentchain", &self.parentchain_root)
+			.or_else(|| root_check_failure("enclave state", &self.enclave_state_root))
+	}
 }
 
 impl VerifyMerkleProofCommand {
 	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
-		info!("Proof is valid:");
-		println!("{:?}", verify_merkle_proof(cli, trusted_args, &self.merkle_proof_json));
-		Ok(CliResultOk::None)
+		let proofs_sanitized = sanitize(&self.merkle_proof_json);
+		info!("Sanitized input merkle proofs: {}", &proofs_sanitized);
+
+		let proofs = parse_merkle_proofs(&proofs_sanitized)?;
+		let mut report = verify_merkle_proofs(&proofs, self.market_results);
+
+		if let Some(timestamp) = &self.timestamp {
+			if self.search_depth > MAX_SEARCH_DEPTH {
+				return Err(CliError::InvalidInput {
+					msg: format!("The search depth must not exceed {} blocks", MAX_SEARCH_DEPTH),
+				})
+			}
+			let topic = if self.market_results {
+				results_merkle_root_topic(timestamp)
+			} else {
+				orders_merkle_root_topic(timestamp)
+			};
+
+			let enclave_roots =
+				get_merkle_roots(cli, trusted_args, &self.account, timestamp.clone())?;
+			let enclave_root = enclave_roots.as_ref().map(|roots| {
+				if self.market_results {
+					roots.market_results
+				} else {
+					roots.orders
+				}
+			});
+
+			let parentchain_root = get_published_root(
+				&get_chain_api(cli),
+				topic,
+				EnclaveFingerprint::from(mrenclave_from_base58(&trusted_args.mrenclave)),
+				enclave_roots.and_then(|roots| roots.parentchain_block_number),
+				self.search_depth,
+			);
+
+			report.parentchain_root = Some(RootCheck::new(report.root, parentchain_root));
+			report.enclave_state_root = Some(RootCheck::new(report.root, enclave_root));
+		}
+
+		match self.format {
+			OutputFormat::Text => print_report(&report),
+			OutputFormat::Json => println!("{}", serde_json::to_string(&report).unwrap()),
+		}
+
+		match report.failure() {
+			Some(msg) => Err(CliError::VerificationFailed { msg }),
+			None => Ok(CliResultOk::None),
+		}
+	}
+}
+
+fn print_report(report: &VerificationReport) {
+	for leaf in report.leaves.iter() {
+		let content = match (&leaf.order, &leaf.trade) {
+			(Some(order), _) => describe_order(order),
+			(_, Some(trade)) => describe_match(trade),
+			_ => "leaf could not be decoded".into(),
+		};
+		println!("Leaf {} ({}): {}", leaf.leaf_index, validity(leaf.valid), content);
 	}
+
+	println!("Proof is valid: {}", report.valid);
+
+	let print_root_check = |source: &str, check: &Option<RootCheck>| match check {
+		Some(RootCheck { root: Some(_), matches }) =>
+			println!("Root matches {} root: {}", source, matches),
+		Some(RootCheck { root: None, .. }) =>
+			println!("Root matches {} root: no root found", source),
+		None => {},
+	};
+	print_root_check("parentchain", &report.parentchain_root);
+	print_root_check("enclave state", &report.enclave_state_root);
 }
 
-pub(crate) fn verify_merkle_proof(
-	_cli: &Cli,
-	_trusted_args: &TrustedCli,
-	merkle_proof: &str,
-) -> bool {
+fn validity(is_valid: bool) -> &'static str {
+	if is_valid {
+		"valid"
+	} else {
+		"invalid"
+	}
+}
+
+/// Decodes the leaf of an order proof, which is a SCALE encoded `Order`.
+pub(crate) fn decode_order_leaf(proof: &MerkleProofWithCodec<H256, Vec<u8>>) -> Option<Order> {
+	Order::decode_all(&mut proof.leaf.as_slice()).ok()
+}
+
+fn decode_match_leaf(proof: &MerkleProofWithCodec<H256, Vec<u8>>) -> Option<Match> {
+	Match::decode_all(&mut proof.leaf.as_slice()).ok()
+}
+
+pub(crate) fn describe_order(order: &Order) -> String {
+	format!(
+		"{:?} {} of {} in time slot {}: {} kWh at {} €/kWh",
+		order.order_type,
+		order.id,
+		order.actor_id,
+		order.time_slot,
+		order.energy_kwh,
+		order.price_euro_per_kwh
+	)
+}
+
+fn describe_match(trade: &Match) -> String {
+	format!(
+		"Bid {} matched with ask {}: {} kWh at {} €/kWh",
+		trade.bid_id, trade.ask_id, trade.energy_kwh, trade.price_euro_per_kwh
+	)
+}
+
+/// Gets the root, which has been published with `topic` by an enclave with the given
+/// fingerprint. The topic is the same for every shard and enclave, hence the root of another
+/// enclave must not be accepted.
+///
+/// If the parentchain block, at which the root has been computed, is known, the `search_depth`
+/// blocks following it are searched. Otherwise the latest `search_depth` blocks are searched.
+fn get_published_root(
+	api: &ParentchainApi,
+	topic: H256,
+	enclave_fingerprint: EnclaveFingerprint,
+	computed_at: Option<BlockNumber>,
+	search_depth: BlockNumber,
+) -> Option<H256> {
+	let latest_block_number = match api.get_header(None) {
+		Ok(Some(header)) => header.number,
+		_ => {
+			error!("Could not get the latest parentchain header");
+			return None
+		},
+	};
+
+	let mut block_numbers: Box<dyn Iterator<Item = BlockNumber>> = match computed_at {
+		Some(from) => Box::new(from..=from.saturating_add(search_depth).min(latest_block_number)),
+		None =>
+			Box::new((latest_block_number.saturating_sub(search_depth)..=latest_block_number).rev()),
+	};
+
+	block_numbers.find_map(|block_number| {
+		let block_hash = api.get_block_hash(Some(block_number)).ok()??;
+		let events: Vec<EventRecord<RuntimeEvent, Hash>> =
+			api.get_storage_value("System", "Events", Some(block_hash)).ok()??;
+
+		events.into_iter().rev().find_map(|record| match record.event {
+			RuntimeEvent::EnclaveBridge(pallet_enclave_bridge::Event::PublishedHash {
+				enclave_fingerprint: publisher,
+				hash,
+				..
+			}) if publisher == enclave_fingerprint && record.topics.contains(&topic) => {
+				info!("Found published root {:?} in block {}", hash, block_number);
+				Some(hash)
+			},
+			_ => None,
+		})
+	})
+}
+
+/// Gets the merkle roots, which are stored in the enclave state for `timestamp`.
+fn get_merkle_roots(
+	cli: &Cli,
+	trusted_args: &TrustedCli,
+	arg_who: &str,
+	timestamp: String,
+) -> Result<Option<MerkleRoots>, CliError> {
+	let who = get_pair_from_str(trusted_args, arg_who);
+
+	let top: TrustedOperation<TrustedCallSigned, Getter> = Getter::trusted(
+		TrustedGetter::merkle_roots(who.public().into(), timestamp)
+			.sign(&KeyPair::Sr25519(Box::new(who))),
+	)
+	.into();
+
+	let res = perform_trusted_operation(cli, trusted_args, &top)?;
+
+	match res {
+		Some(roots) => MerkleRoots::decode(&mut roots.as_slice()).map(Some).map_err(|err| {
+			error!("Error deserializing merkle roots: {}", err);
+			CliError::TrustedOp { msg: format!("Error deserializing merkle roots: {}", err) }
+		}),
+		None => Ok(None),
+	}
+}
+
+/// Verifies every proof of a single proof or a list of proofs, which all need to have the same
+/// root.
+pub(crate) fn verify_merkle_proofs(
+	proofs: &[MerkleProofWithCodec<H256, Vec<u8>>],
+	market_results: bool,
+) -> VerificationReport {
+	let root = proofs.first().map(|proof| proof.root);
+	if root.is_none() {
+		info!("No proofs supplied");
+	}
+
+	let leaves: Vec<LeafReport> = proofs
+		.iter()
+		.map(|proof| {
+			let valid = Some(proof.root) == root && verify_merkle_proof(proof);
+			info!("Leaf {} is valid: {}", proof.leaf_index, valid);
+
+			let (order, trade) = if market_results {
+				(None, decode_match_leaf(proof))
+			} else {
+				(decode_order_leaf(proof), None)
+			};
+			LeafReport { leaf_index: proof.leaf_index, valid, order, trade }
+		})
+		.collect();
+
+	VerificationReport {
+		valid: !leaves.is_empty() && leaves.iter().all(|leaf| leaf.valid),
+		root,
+		leaves,
+		parentchain_root: None,
+		enclave_state_root: None,
+	}
+}
+
+fn sanitize(merkle_proofs: &str) -> String {
 	// Remove starting and trailing `"` and `\\\` in the string, which occur when we
 	// pass the proof in the bash script for whatever reason. This is probably a hack,
 	// but I don't know bash well enough to fix it in the bash script.
-	let proof_sanitized = merkle_proof.replace('\\', "").trim_matches('\"').to_string();
-	info!("Sanitized input merkle proof: {}", &proof_sanitized);
+	merkle_proofs.replace('\\', "").trim_matches('\"').to_string()
+}
 
-	let proof: MerkleProofWithCodec<H256, Vec<u8>> =
-		serde_json::from_str(&proof_sanitized).expect("Could not parse merkle proof");
+fn parse_merkle_proofs(
+	merkle_proofs: &str,
+) -> Result<Vec<MerkleProofWithCodec<H256, Vec<u8>>>, CliError> {
+	serde_json::from_str(merkle_proofs).or_else(|_| {
+		serde_json::from_str(merkle_proofs).map(|proof| vec![proof]).map_err(|e| {
+			CliError::InvalidInput { msg: format!("Could not parse merkle proof: {}", e) }
+		})
+	})
+}
 
+pub(crate) fn verify_merkle_proof(proof: &MerkleProofWithCodec<H256, Vec<u8>>) -> bool {
 	info!("Proof: {:?}", proof);
 
 	verify_proof::<Keccak256, _, _>(
@@ -36,8 +36,19 @@ pub mod cmds {
 // code.
 pub mod oli_cmds {
 	pub use super::commands::{
-		get_market_results::GetMarketResultsCommand, pay_as_bid::PayAsBidCommand,
-		pay_as_bid_proof::PayAsBidProofCommand, verify_proof::VerifyMerkleProofCommand,
+		amend_order::AmendOrderCommand, cancel_order::CancelOrderCommand,
+		close_slot::CloseSlotCommand, get_actor_matches::GetActorMatchesCommand,
+		get_grid_fee_market_results::GetGridFeeMarketResultsCommand,
+		get_grid_settlements::GetGridSettlementsCommand,
+		get_market_results::GetMarketResultsCommand,
+		get_matched_time_slots::GetMatchedTimeSlotsCommand, get_open_orders::GetOpenOrdersCommand,
+		get_settlement_summary::GetSettlementSummaryCommand,
+		market_results_proof::MarketResultsProofCommand, pay_as_bid::PayAsBidCommand,
+		pay_as_bid_grid_fee::PayAsBidGridFeeCommand, pay_as_bid_proof::PayAsBidProofCommand,
+		pay_as_clear::PayAsClearCommand, register_actor::RegisterActorCommand,
+		set_balance_units_per_euro::SetBalanceUnitsPerEuroCommand,
+		set_market_calendar::SetMarketCalendarCommand, submit_order::SubmitOrderCommand,
+		unregister_actor::UnregisterActorCommand, verify_proof::VerifyMerkleProofCommand,
 	};
 }
 
@@ -70,14 +81,65 @@ pub enum TrustedBaseCommand {
 	/// PayAsBid Command
 	PayAsBid(PayAsBidCommand),
 
+	/// PayAsClear Command
+	PayAsClear(PayAsClearCommand),
+
+	/// PayAsBid Command respecting the grid fees between clusters
+	PayAsBidGridFee(PayAsBidGridFeeCommand),
+
 	/// PayAsBidProof Command
 	PayAsBidProof(PayAsBidProofCommand),
 
+	/// Get the merkle proofs for all matches of an actor in the market results
+	MarketResultsProof(MarketResultsProofCommand),
+
 	/// VerifyProof Command
 	VerifyProof(VerifyMerkleProofCommand),
 
 	/// Get Market Results Command
 	GetMarketResults(GetMarketResultsCommand),
+
+	/// Get the time slots with market results in a time range, page by page
+	GetMatchedTimeSlots(GetMatchedTimeSlotsCommand),
+
+	/// Get the matches of an actor in a time range, page by page
+	GetActorMatches(GetActorMatchesCommand),
+
+	/// Get Grid Fee Market Results Command
+	GetGridFeeMarketResults(GetGridFeeMarketResultsCommand),
+
+	/// Get the own unmatched volume of a time slot, which is settled at the reserve price
+	GetGridSettlements(GetGridSettlementsCommand),
+
+	/// Get the own balance transfers, with which the matches of a time slot were settled
+	GetSettlementSummary(GetSettlementSummaryCommand),
+
+	/// Submit an order for a time slot, which is still open
+	SubmitOrder(SubmitOrderCommand),
+
+	/// Amend an open order that has been submitted by the same account
+	AmendOrder(AmendOrderCommand),
+
+	/// Cancel an open order that has been submitted by the same account
+	CancelOrder(CancelOrderCommand),
+
+	/// ROOT call to close a time slot and match all of its orders
+	CloseSlot(CloseSlotCommand),
+
+	/// ROOT call to set the slot length and the gate closure of the market calendar
+	SetMarketCalendar(SetMarketCalendarCommand),
+
+	/// ROOT call to set the balance units, at which one euro of a trade is settled
+	SetBalanceUnitsPerEuro(SetBalanceUnitsPerEuroCommand),
+
+	/// Get the own orders of a time slot, which is still open
+	GetOpenOrders(GetOpenOrdersCommand),
+
+	/// ROOT call to allow an account to submit orders and read market data of an actor
+	RegisterActor(RegisterActorCommand),
+
+	/// ROOT call to revoke the access of an account to an actor
+	UnregisterActor(UnregisterActorCommand),
 }
 
 impl TrustedBaseCommand {
@@ -91,9 +153,26 @@ impl TrustedBaseCommand {
 			TrustedBaseCommand::UnshieldFunds(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::Nonce(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::PayAsBid(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::PayAsClear(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::PayAsBidGridFee(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::PayAsBidProof(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::MarketResultsProof(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::VerifyProof(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::GetMarketResults(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetMatchedTimeSlots(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetActorMatches(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetGridFeeMarketResults(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetGridSettlements(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetSettlementSummary(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::SubmitOrder(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::AmendOrder(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::CancelOrder(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::CloseSlot(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::SetMarketCalendar(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::SetBalanceUnitsPerEuro(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::GetOpenOrders(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::RegisterActor(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::UnregisterActor(cmd) => cmd.run(cli, trusted_cli),
 		}
 	}
 }
@@ -25,7 +25,10 @@ pub use crate::trusted_base_cli::cmds::{
 };
 
 pub use crate::trusted_base_cli::oli_cmds::{
-	GetMarketResultsCommand, PayAsBidCommand, PayAsBidProofCommand, VerifyMerkleProofCommand,
+	AmendOrderCommand, CancelOrderCommand, CloseSlotCommand, GetGridFeeMarketResultsCommand,
+	GetMarketResultsCommand, GetOpenOrdersCommand, MarketResultsProofCommand, PayAsBidCommand,
+	PayAsBidGridFeeCommand, PayAsBidProofCommand, PayAsClearCommand, RegisterActorCommand,
+	SubmitOrderCommand, UnregisterActorCommand, VerifyMerkleProofCommand,
 };
 
 pub use crate::trusted_base_cli::TrustedBaseCommand;
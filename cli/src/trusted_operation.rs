@@ -29,7 +29,10 @@ use itc_rpc_client::direct_client::{DirectApi, DirectClient};
 use itp_node_api::api_client::{ParentchainApi, ENCLAVE_BRIDGE};
 use itp_rpc::{RpcRequest, RpcResponse, RpcReturnValue};
 use itp_sgx_crypto::ShieldingCryptoEncrypt;
-use itp_stf_primitives::types::{ShardIdentifier, TrustedOperation};
+use itp_stf_primitives::{
+	error::StfError,
+	types::{ShardIdentifier, TrustedOperation},
+};
 use itp_types::{BlockNumber, DirectRequestStatus, TrustedOperationStatus};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
 use log::*;
@@ -343,13 +346,17 @@ pub(crate) fn wait_until(
 							},
 							DirectRequestStatus::TrustedOperationStatus(status) => {
 								debug!("request status is: {:?}", status);
-								if let Ok(value) = Hash::decode(&mut return_value.value.as_slice())
-								{
+								let mut encoded_value = return_value.value.as_slice();
+								if let Ok(value) = Hash::decode(&mut encoded_value) {
 									println!("Trusted call {:?} is {:?}", value, status);
 									if until(status.clone()) {
 										return Some((value, Instant::now()))
 									} else if status == TrustedOperationStatus::Invalid {
-										error!("Invalid request");
+										// The STF appends its error to the hash of rejected calls.
+										match StfError::decode(&mut encoded_value) {
+											Ok(e) => println!("[Error] {}", e),
+											Err(_) => error!("Invalid request"),
+										}
 										return None
 									}
 								}
@@ -217,6 +217,8 @@ extern "C" {
 		crypto_currency_size: u32,
 		fiat_currency: *const u8,
 		fiat_currency_size: u32,
+		exchange_rate_source: *const u8,
+		exchange_rate_source_size: u32,
 		unchecked_extrinsic: *mut u8,
 		unchecked_extrinsic_size: u32,
 	) -> sgx_status_t;
@@ -228,6 +230,23 @@ extern "C" {
 		weather_info_longitude_size: u32,
 		weather_info_latitude: *const u8,
 		weather_info_latitude_size: u32,
+		weather_info_start_date: *const u8,
+		weather_info_start_date_size: u32,
+		weather_info_end_date: *const u8,
+		weather_info_end_date_size: u32,
+		unchecked_extrinsic: *mut u8,
+		unchecked_extrinsic_size: u32,
+	) -> sgx_status_t;
+
+	pub fn update_spot_prices_xt(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		bidding_zone: *const u8,
+		bidding_zone_size: u32,
+		start_date: *const u8,
+		start_date_size: u32,
+		end_date: *const u8,
+		end_date_size: u32,
 		unchecked_extrinsic: *mut u8,
 		unchecked_extrinsic_size: u32,
 	) -> sgx_status_t;
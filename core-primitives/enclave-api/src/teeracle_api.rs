@@ -19,14 +19,32 @@ use crate::EnclaveResult;
 
 pub trait TeeracleApi: Send + Sync + 'static {
 	/// Update the currency market data for the token oracle.
+	///
+	/// The exchange rate is fetched from the source configured by the JSON encoded
+	/// `exchange_rate_source`, or from the built-in sources if it is `None`.
 	fn update_market_data_xt(
 		&self,
 		crypto_currency: &str,
 		fiat_currency: &str,
+		exchange_rate_source: Option<&str>,
 	) -> EnclaveResult<Vec<u8>>;
 
-	/// Update weather data for the corresponding coordinates.
-	fn update_weather_data_xt(&self, longitude: &str, latitude: &str) -> EnclaveResult<Vec<u8>>;
+	/// Update the hourly weather forecast for the corresponding coordinates and date range.
+	fn update_weather_data_xt(
+		&self,
+		longitude: &str,
+		latitude: &str,
+		start_date: &str,
+		end_date: &str,
+	) -> EnclaveResult<Vec<u8>>;
+
+	/// Update the hourly day-ahead spot prices of the bidding zone for the date range.
+	fn update_spot_prices_xt(
+		&self,
+		bidding_zone: &str,
+		start_date: &str,
+		end_date: &str,
+	) -> EnclaveResult<Vec<u8>>;
 }
 
 #[cfg(feature = "implement-ffi")]
@@ -43,6 +61,7 @@ mod impl_ffi {
 			&self,
 			crypto_currency: &str,
 			fiat_currency: &str,
+			exchange_rate_source: Option<&str>,
 		) -> EnclaveResult<Vec<u8>> {
 			info!(
 				"TeeracleApi update_market_data_xt in with crypto {} and fiat {}",
@@ -54,6 +73,7 @@ mod impl_ffi {
 
 			let crypto_curr = crypto_currency.encode();
 			let fiat_curr = fiat_currency.encode();
+			let exchange_rate_source = exchange_rate_source.encode();
 
 			let res = unsafe {
 				ffi::update_market_data_xt(
@@ -63,6 +83,8 @@ mod impl_ffi {
 					crypto_curr.len() as u32,
 					fiat_curr.as_ptr(),
 					fiat_curr.len() as u32,
+					exchange_rate_source.as_ptr(),
+					exchange_rate_source.len() as u32,
 					response.as_mut_ptr(),
 					response_len,
 				)
@@ -77,10 +99,12 @@ mod impl_ffi {
 			&self,
 			longitude: &str,
 			latitude: &str,
+			start_date: &str,
+			end_date: &str,
 		) -> EnclaveResult<Vec<u8>> {
 			info!(
-				"TeeracleApi update_weather_data_xt in with latitude: {}, longitude: {}",
-				latitude, longitude
+				"TeeracleApi update_weather_data_xt in with latitude: {}, longitude: {}, from {} to {}",
+				latitude, longitude, start_date, end_date
 			);
 			let mut retval = sgx_status_t::SGX_SUCCESS;
 			let response_len = 8192;
@@ -88,6 +112,8 @@ mod impl_ffi {
 
 			let longitude_encoded: Vec<u8> = longitude.encode();
 			let latitude_encoded: Vec<u8> = latitude.encode();
+			let start_date_encoded: Vec<u8> = start_date.encode();
+			let end_date_encoded: Vec<u8> = end_date.encode();
 
 			let res = unsafe {
 				ffi::update_weather_data_xt(
@@ -97,6 +123,48 @@ mod impl_ffi {
 					longitude_encoded.len() as u32,
 					latitude_encoded.as_ptr(),
 					latitude_encoded.len() as u32,
+					start_date_encoded.as_ptr(),
+					start_date_encoded.len() as u32,
+					end_date_encoded.as_ptr(),
+					end_date_encoded.len() as u32,
+					response.as_mut_ptr(),
+					response_len,
+				)
+			};
+
+			ensure!(res == sgx_status_t::SGX_SUCCESS, Error::Sgx(res));
+			ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+			Ok(response)
+		}
+
+		fn update_spot_prices_xt(
+			&self,
+			bidding_zone: &str,
+			start_date: &str,
+			end_date: &str,
+		) -> EnclaveResult<Vec<u8>> {
+			info!(
+				"TeeracleApi update_spot_prices_xt in with bidding zone: {}, from {} to {}",
+				bidding_zone, start_date, end_date
+			);
+			let mut retval = sgx_status_t::SGX_SUCCESS;
+			let response_len = 8192;
+			let mut response: Vec<u8> = vec![0u8; response_len as usize];
+
+			let bidding_zone_encoded: Vec<u8> = bidding_zone.encode();
+			let start_date_encoded: Vec<u8> = start_date.encode();
+			let end_date_encoded: Vec<u8> = end_date.encode();
+
+			let res = unsafe {
+				ffi::update_spot_prices_xt(
+					self.eid,
+					&mut retval,
+					bidding_zone_encoded.as_ptr(),
+					bidding_zone_encoded.len() as u32,
+					start_date_encoded.as_ptr(),
+					start_date_encoded.len() as u32,
+					end_date_encoded.as_ptr(),
+					end_date_encoded.len() as u32,
 					response.as_mut_ptr(),
 					response_len,
 				)
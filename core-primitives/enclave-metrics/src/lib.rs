@@ -25,10 +25,11 @@ extern crate sgx_tstd as std;
 
 use codec::{Decode, Encode};
 use std::string::String;
-use substrate_fixed::types::U32F32;
+use substrate_fixed::types::{I32F32, U32F32};
 
 // FIXME: Copied from ita-oracle because of cyclic deps. Should be removed after integritee-network/pallets#71
 pub type ExchangeRate = U32F32;
+pub type ExchangeRateDeviation = I32F32;
 
 #[derive(Encode, Decode, Debug)]
 pub enum EnclaveMetric {
@@ -48,6 +49,8 @@ pub enum ExchangeRateOracleMetric {
 	ResponseTime(String, u128),
 	/// Increment the number of requests (Source)
 	NumberRequestsIncrement(String),
+	/// Relative deviation of a source from the aggregated exchange rate - (Source, TradingPair, Deviation)
+	ExchangeRateDeviation(String, String, ExchangeRateDeviation),
 }
 
 #[derive(Encode, Decode, Debug)]
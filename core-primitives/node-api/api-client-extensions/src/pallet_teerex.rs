@@ -40,6 +40,11 @@ pub trait PalletTeerexApi {
 		shard: &ShardIdentifier,
 		at_block: Option<Self::Hash>,
 	) -> ApiResult<Option<MultiEnclave<Vec<u8>>>>;
+	fn validateers_for_shard(
+		&self,
+		shard: &ShardIdentifier,
+		at_block: Option<Self::Hash>,
+	) -> ApiResult<Vec<AccountId>>;
 	fn latest_ipfs_hash(
 		&self,
 		shard: &ShardIdentifier,
@@ -100,6 +105,20 @@ where
 			)
 	}
 
+	fn validateers_for_shard(
+		&self,
+		shard: &ShardIdentifier,
+		at_block: Option<Self::Hash>,
+	) -> ApiResult<Vec<AccountId>> {
+		let shard_status: Option<ShardStatus> =
+			self.get_storage_map(ENCLAVE_BRIDGE, "ShardStatus", shard, at_block)?;
+		Ok(shard_status
+			.unwrap_or_default()
+			.into_iter()
+			.map(|signer_status| signer_status.signer)
+			.collect())
+	}
+
 	fn latest_ipfs_hash(
 		&self,
 		shard: &ShardIdentifier,
@@ -56,6 +56,14 @@ impl PalletTeerexApi for PalletTeerexApiMock {
 		todo!()
 	}
 
+	fn validateers_for_shard(
+		&self,
+		_shard: &ShardIdentifier,
+		_at_block: Option<Hash>,
+	) -> ApiResult<Vec<AccountId>> {
+		todo!()
+	}
+
 	fn latest_ipfs_hash(
 		&self,
 		_shard: &ShardIdentifier,
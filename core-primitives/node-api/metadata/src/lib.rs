@@ -22,7 +22,8 @@
 use crate::{
 	error::Result, pallet_balances::BalancesCallIndexes,
 	pallet_enclave_bridge::EnclaveBridgeCallIndexes, pallet_proxy::ProxyCallIndexes,
-	pallet_sidechain::SidechainCallIndexes, pallet_teerex::TeerexCallIndexes,
+	pallet_sidechain::SidechainCallIndexes, pallet_teeracle::TeeracleCallIndexes,
+	pallet_teerex::TeerexCallIndexes,
 };
 use codec::{Decode, Encode};
 use sp_core::storage::StorageKey;
@@ -47,6 +48,7 @@ pub trait NodeMetadataTrait:
 	+ SidechainCallIndexes
 	+ ProxyCallIndexes
 	+ BalancesCallIndexes
+	+ TeeracleCallIndexes
 {
 }
 impl<
@@ -54,7 +56,8 @@ impl<
 			+ EnclaveBridgeCallIndexes
 			+ SidechainCallIndexes
 			+ ProxyCallIndexes
-			+ BalancesCallIndexes,
+			+ BalancesCallIndexes
+			+ TeeracleCallIndexes,
 	> NodeMetadataTrait for T
 {
 }
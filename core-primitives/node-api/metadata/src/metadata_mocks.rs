@@ -18,7 +18,8 @@
 use crate::{
 	error::Result, pallet_balances::BalancesCallIndexes,
 	pallet_enclave_bridge::EnclaveBridgeCallIndexes, pallet_proxy::ProxyCallIndexes,
-	pallet_sidechain::SidechainCallIndexes, pallet_teerex::TeerexCallIndexes,
+	pallet_sidechain::SidechainCallIndexes, pallet_teeracle::TeeracleCallIndexes,
+	pallet_teerex::TeerexCallIndexes,
 };
 use codec::{Decode, Encode};
 
@@ -56,6 +57,11 @@ pub struct NodeMetadataMock {
 	transfer: u8,
 	transfer_keep_alive: u8,
 	transfer_allow_death: u8,
+	teeracle_module: u8,
+	add_to_whitelist: u8,
+	remove_from_whitelist: u8,
+	update_exchange_rate: u8,
+	update_oracle: u8,
 	runtime_spec_version: u32,
 	runtime_transaction_version: u32,
 }
@@ -85,6 +91,11 @@ impl NodeMetadataMock {
 			transfer: 7u8,
 			transfer_keep_alive: 3u8,
 			transfer_allow_death: 0u8,
+			teeracle_module: 55u8,
+			add_to_whitelist: 0u8,
+			remove_from_whitelist: 1u8,
+			update_exchange_rate: 2u8,
+			update_oracle: 3u8,
 			runtime_spec_version: 25,
 			runtime_transaction_version: 4,
 		}
@@ -168,3 +179,21 @@ impl BalancesCallIndexes for NodeMetadataMock {
 		Ok([self.balances_module, self.transfer_allow_death])
 	}
 }
+
+impl TeeracleCallIndexes for NodeMetadataMock {
+	fn add_to_whitelist_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.teeracle_module, self.add_to_whitelist])
+	}
+
+	fn remove_from_whitelist_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.teeracle_module, self.remove_from_whitelist])
+	}
+
+	fn update_exchange_rate_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.teeracle_module, self.update_exchange_rate])
+	}
+
+	fn update_oracle_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.teeracle_module, self.update_oracle])
+	}
+}
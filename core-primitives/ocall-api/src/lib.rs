@@ -139,6 +139,21 @@ pub trait EnclaveSidechainOCallApi: Clone + Send + Sync {
 		maybe_until_block_hash: Option<BlockHash>,
 		shard_identifier: ShardIdentifier,
 	) -> SgxResult<Vec<SignedSidechainBlock>>;
+
+	/// Number of peers the untrusted worker currently knows of.
+	fn get_peer_count(&self) -> SgxResult<u32>;
+
+	/// Send the finality votes of this validateer to all peers (including ourselves).
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		signed_votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()>;
+
+	/// Look up sidechain blocks in the sidechain storage of the untrusted worker.
+	fn query_sidechain_blocks<BlockQuery: Encode, BlockSummary: Decode>(
+		&self,
+		query: BlockQuery,
+	) -> SgxResult<Vec<BlockSummary>>;
 }
 
 /// Newtype for IPFS CID
@@ -67,7 +67,12 @@ pub mod files {
 	pub static RA_API_KEY_FILE: &str = "key.txt";
 
 	pub const SPID_MIN_LENGTH: usize = 32;
-	pub const STATE_SNAPSHOTS_CACHE_SIZE: usize = 4;
+	// A reorg of depth `d` reverts to the snapshot `d` sidechain blocks back, so the current state
+	// plus one snapshot per revertible block are kept. Each imported parentchain block writes up to
+	// two more snapshots in between (parentchain block and shard initialization).
+	pub const STATE_SNAPSHOTS_CACHE_SIZE: usize =
+		(super::sidechain::MAX_REORG_DEPTH
+			+ 1 + 2 * super::sidechain::MAX_PARENTCHAIN_IMPORTS_PER_REORG_DEPTH) as usize;
 }
 
 /// Settings concerning the worker
@@ -98,6 +103,12 @@ pub mod sidechain {
 	use core::time::Duration;
 
 	pub static SLOT_DURATION: Duration = Duration::from_millis(1000);
+	// Number of blocks below the best sidechain block for which competing forks are tracked.
+	// Switching to a fork reverts the state, which `STATE_SNAPSHOTS_CACHE_SIZE` is sized for.
+	pub const MAX_REORG_DEPTH: u64 = 3;
+	// Number of parentchain blocks that are imported at most while `MAX_REORG_DEPTH` sidechain
+	// blocks are produced. A reorg across more of them fails for lack of state snapshots.
+	pub const MAX_PARENTCHAIN_IMPORTS_PER_REORG_DEPTH: u64 = 4;
 }
 
 /// Settings concerning the enclave
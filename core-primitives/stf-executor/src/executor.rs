@@ -25,7 +25,8 @@ use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
 use itp_ocall_api::{EnclaveAttestationOCallApi, EnclaveOnChainOCallApi};
 use itp_sgx_externalities::{SgxExternalitiesTrait, StateHash};
 use itp_stf_interface::{
-	parentchain_pallet::ParentchainPalletInterface, StateCallInterface, UpdateState,
+	parentchain_pallet::ParentchainPalletInterface, OnBlockInterface, StateCallInterface,
+	UpdateState,
 };
 use itp_stf_primitives::{
 	traits::TrustedCallVerification,
@@ -69,7 +70,8 @@ where
 		> + StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>,
 	<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
 		IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)> + From<BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
-	<Stf as StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>>::Error: Debug,
+	<Stf as StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>>::Error:
+		Debug + Encode,
 	TCS: PartialEq + Encode + Decode + Debug + Clone + Send + Sync + TrustedCallVerification,
 	G: PartialEq + Encode + Decode + Debug + Clone + Send + Sync,
 {
@@ -125,7 +127,7 @@ where
 			self.node_metadata_repo.clone(),
 		) {
 			error!("Stf execute failed: {:?}", e);
-			return Ok(ExecutedOperation::failed(top_or_hash))
+			return Ok(ExecutedOperation::failed_with_error(top_or_hash, e.encode()))
 		}
 
 		let operation_hash = trusted_operation.hash();
@@ -275,12 +277,15 @@ where
 	Stf: UpdateState<
 			StateHandler::StateT,
 			<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType,
-		> + StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>,
+		> + StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>
+		+ OnBlockInterface<StateHandler::StateT, NodeMetadataRepository>,
 	<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
 		IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
 	<StateHandler::StateT as SgxExternalitiesTrait>::SgxExternalitiesDiffType:
 		From<BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
-	<Stf as StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>>::Error: Debug,
+	<Stf as StateCallInterface<TCS, StateHandler::StateT, NodeMetadataRepository>>::Error:
+		Debug + Encode,
+	<Stf as OnBlockInterface<StateHandler::StateT, NodeMetadataRepository>>::Error: Debug,
 	TCS: PartialEq + Encode + Decode + Debug + Clone + Send + Sync + TrustedCallVerification,
 	G: PartialEq + Encode + Decode + Debug + Clone + Send + Sync,
 {
@@ -304,6 +309,18 @@ where
 
 		// Execute any pre-processing steps.
 		let mut state = prepare_state_function(state);
+
+		// Execute the block hook of the STF before any trusted call.
+		let mut block_extrinsic_callbacks = Vec::<OpaqueCall>::new();
+		if let Err(e) = Stf::on_block(
+			&mut state,
+			&mut block_extrinsic_callbacks,
+			self.node_metadata_repo.clone(),
+		) {
+			error!("Stf on_block failed: {:?}", e);
+			block_extrinsic_callbacks.clear();
+		}
+
 		let mut executed_and_failed_calls = Vec::<ExecutedOperation<TCS, G>>::new();
 
 		// Iterate through all calls until time is over.
@@ -331,6 +348,7 @@ where
 		}
 
 		Ok(BatchExecutionResult {
+			block_extrinsic_callbacks,
 			executed_operations: executed_and_failed_calls,
 			state_hash_before_execution,
 			state_after_execution: state,
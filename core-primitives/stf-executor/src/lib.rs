@@ -57,11 +57,12 @@ pub mod mocks;
 /// Execution status of a trusted operation
 ///
 /// In case of success, it includes the operation hash, as well as
-/// any extrinsic callbacks (e.g. unshield extrinsics) that need to be executed on-chain
+/// any extrinsic callbacks (e.g. unshield extrinsics) that need to be executed on-chain.
+/// In case of failure, it includes the encoded error of the STF, if the call reached the STF.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExecutionStatus {
 	Success(H256, Vec<OpaqueCall>),
-	Failure,
+	Failure(Option<Vec<u8>>),
 }
 
 impl ExecutionStatus {
@@ -78,6 +79,13 @@ impl ExecutionStatus {
 			_ => None,
 		}
 	}
+
+	pub fn get_encoded_error(&self) -> Option<Vec<u8>> {
+		match self {
+			ExecutionStatus::Failure(encoded_error) => encoded_error.clone(),
+			_ => None,
+		}
+	}
 }
 
 /// Information about an executed trusted operation
@@ -112,7 +120,18 @@ where
 
 	/// Constructor for a failed trusted operation execution.
 	pub fn failed(trusted_operation_or_hash: TrustedOperationOrHash<TCS, G>) -> Self {
-		ExecutedOperation { status: ExecutionStatus::Failure, trusted_operation_or_hash }
+		ExecutedOperation { status: ExecutionStatus::Failure(None), trusted_operation_or_hash }
+	}
+
+	/// Constructor for a trusted operation that has been rejected by the STF.
+	pub fn failed_with_error(
+		trusted_operation_or_hash: TrustedOperationOrHash<TCS, G>,
+		encoded_error: Vec<u8>,
+	) -> Self {
+		ExecutedOperation {
+			status: ExecutionStatus::Failure(Some(encoded_error)),
+			trusted_operation_or_hash,
+		}
 	}
 
 	/// Returns true if the executed operation was a success.
@@ -131,6 +150,8 @@ where
 	G: PartialEq + Encode + Decode + Debug + Send + Sync,
 {
 	pub state_hash_before_execution: H256,
+	/// Extrinsic callbacks of the block hook, which is executed before the operations.
+	pub block_extrinsic_callbacks: Vec<OpaqueCall>,
 	pub executed_operations: Vec<ExecutedOperation<TCS, G>>,
 	pub state_after_execution: Externalities,
 }
@@ -142,9 +163,10 @@ where
 	G: PartialEq + Encode + Decode + Debug + Clone + Send + Sync,
 {
 	pub fn get_extrinsic_callbacks(&self) -> Vec<OpaqueCall> {
-		self.executed_operations
+		self.block_extrinsic_callbacks
 			.iter()
-			.flat_map(|e| e.status.get_extrinsic_callbacks())
+			.cloned()
+			.chain(self.executed_operations.iter().flat_map(|e| e.status.get_extrinsic_callbacks()))
 			.collect()
 	}
 
@@ -166,6 +188,18 @@ where
 			})
 			.collect()
 	}
+
+	/// Returns all operations that were rejected by the STF, together with the encoded error.
+	pub fn get_operation_errors(&self) -> Vec<(TrustedOperationOrHash<TCS, G>, Vec<u8>)> {
+		self.executed_operations
+			.iter()
+			.flat_map(|ec| {
+				ec.status
+					.get_encoded_error()
+					.map(|error| (ec.trusted_operation_or_hash.clone(), error))
+			})
+			.collect()
+	}
 }
 
 #[cfg(test)]
@@ -215,12 +249,31 @@ mod tests {
 		executed_calls: Vec<ExecutedOperation<TrustedCallSignedMock, GetterMock>>,
 	) -> BatchExecutionResult<SgxExternalities, TrustedCallSignedMock, GetterMock> {
 		BatchExecutionResult {
+			block_extrinsic_callbacks: Vec::new(),
 			executed_operations: executed_calls,
 			state_hash_before_execution: H256::default(),
 			state_after_execution: SgxExternalities::default(),
 		}
 	}
 
+	#[test]
+	fn get_operation_errors_only_returns_operations_rejected_by_the_stf() {
+		let (success, _) = create_success_operation_from_u8(1);
+		let failed = create_failed_operation_from_u8(7);
+		let rejected = ExecutedOperation::<TrustedCallSignedMock, GetterMock>::failed_with_error(
+			TrustedOperationOrHash::Hash(H256::from([9u8; 32])),
+			"invalid orders".encode(),
+		);
+		let result = batch_execution_result(vec![success, failed, rejected]);
+
+		let operation_errors = result.get_operation_errors();
+
+		assert_eq!(
+			operation_errors,
+			vec![(TrustedOperationOrHash::Hash(H256::from([9u8; 32])), "invalid orders".encode())]
+		);
+	}
+
 	fn create_failed_operation_from_u8(
 		int: u8,
 	) -> ExecutedOperation<TrustedCallSignedMock, GetterMock> {
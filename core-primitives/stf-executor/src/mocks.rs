@@ -94,6 +94,7 @@ where
 			.collect();
 
 		Ok(BatchExecutionResult {
+			block_extrinsic_callbacks: Vec::new(),
 			executed_operations,
 			state_hash_before_execution: H256::default(),
 			state_after_execution: updated_state,
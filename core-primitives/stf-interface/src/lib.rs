@@ -70,6 +70,22 @@ where
 	) -> Result<(), Self::Error>;
 }
 
+/// Interface to update a state at the beginning of every block, before its calls are executed.
+pub trait OnBlockInterface<State, NodeMetadataRepository>
+where
+	NodeMetadataRepository: AccessNodeMetadata,
+	NodeMetadataRepository::MetadataType: NodeMetadataTrait,
+{
+	type Error;
+
+	/// Execute the block hook on a specific state. Callbacks are added as an `OpaqueCall`.
+	fn on_block(
+		state: &mut State,
+		calls: &mut Vec<OpaqueCall>,
+		node_metadata_repo: Arc<NodeMetadataRepository>,
+	) -> Result<(), Self::Error>;
+}
+
 /// Interface to execute state reading getters on a state.
 pub trait StateGetterInterface<G, S> {
 	/// Execute a getter on a specific state.
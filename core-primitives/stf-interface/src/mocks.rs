@@ -20,7 +20,7 @@
 extern crate alloc;
 use crate::{
 	system_pallet::SystemPalletAccountInterface, ExecuteCall, ExecuteGetter, InitState,
-	StateCallInterface, StateGetterInterface, UpdateState,
+	OnBlockInterface, StateCallInterface, StateGetterInterface, UpdateState,
 };
 use alloc::{string::String, sync::Arc, vec::Vec};
 use codec::{Decode, Encode};
@@ -70,6 +70,20 @@ where
 	}
 }
 
+impl<State, StateDiff> OnBlockInterface<State, NodeMetadataRepository<NodeMetadataMock>>
+	for StateInterfaceMock<State, StateDiff>
+{
+	type Error = String;
+
+	fn on_block(
+		_state: &mut State,
+		_calls: &mut Vec<OpaqueCall>,
+		_node_metadata_repo: Arc<NodeMetadataRepository<NodeMetadataMock>>,
+	) -> Result<(), Self::Error> {
+		unimplemented!()
+	}
+}
+
 impl<Getter, State, StateDiff> StateGetterInterface<Getter, State>
 	for StateInterfaceMock<State, StateDiff>
 {
@@ -14,14 +14,15 @@
 	limitations under the License.
 
 */
-use crate::types::{AccountId, Nonce};
+use crate::types::{AccountId, Nonce, OrderId, Timestamp};
+use codec::{Decode, Encode};
 use derive_more::Display;
 
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 
 pub type StfResult<T> = Result<T, StfError>;
 
-#[derive(Debug, Display, PartialEq, Eq)]
+#[derive(Encode, Decode, Debug, Display, PartialEq, Eq)]
 pub enum StfError {
 	#[display(fmt = "Insufficient privileges {:?}, are you sure you are root?", _0)]
 	MissingPrivileges(AccountId),
@@ -36,4 +37,34 @@ pub enum StfError {
 	StorageHashMismatch,
 	InvalidStorageDiff,
 	InvalidMetadata,
+	#[display(fmt = "Invalid orders: {:?}", _0)]
+	InvalidOrders(Vec<InvalidOrder>),
+	#[display(fmt = "Orders have already been matched for time slot {}", _0)]
+	TimeSlotAlreadyMatched(String),
+}
+
+/// An order that has been rejected before matching.
+#[derive(Encode, Decode, Debug, Display, Clone, PartialEq, Eq)]
+#[display(fmt = "Order {}: {}", order_id, reason)]
+pub struct InvalidOrder {
+	pub order_id: OrderId,
+	pub reason: OrderValidationError,
+}
+
+#[derive(Encode, Decode, Debug, Display, Clone, PartialEq, Eq)]
+pub enum OrderValidationError {
+	#[display(fmt = "energy_kwh must be positive")]
+	InvalidEnergy,
+	#[display(fmt = "price_euro_per_kwh must not be negative")]
+	InvalidPrice,
+	#[display(
+		fmt = "time slot {} differs from the time slot {} of the order book",
+		found,
+		expected
+	)]
+	TimeSlotMismatch { expected: Timestamp, found: Timestamp },
+	#[display(fmt = "id is used by another order")]
+	DuplicateId,
+	#[display(fmt = "unknown cluster index {:?}", _0)]
+	UnknownClusterIndex(Option<u64>),
 }
@@ -38,6 +38,8 @@ pub type ShardIdentifier = H256;
 use alloc::string::String;
 pub type Timestamp = String;
 pub type OrdersString = String;
+pub type OrderString = String;
+pub type OrderId = u64;
 pub type GridFeeMatrixFile = String;
 pub type ActorId = String;
 
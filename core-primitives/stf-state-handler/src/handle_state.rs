@@ -72,4 +72,10 @@ pub trait HandleState {
 	///
 	/// Use in cases where the previous state is of no interest. Otherwise use `load_for_mutation` and `write_after_mutation`.
 	fn reset(&self, state: Self::StateT, shard: &ShardIdentifier) -> Result<Self::HashType>;
+
+	/// Revert the state of a shard to a previously written state, identified by its hash.
+	///
+	/// Any state written after that one is discarded. Only works for states that are still
+	/// kept as snapshot.
+	fn revert_to(&self, shard: &ShardIdentifier, state_hash: &Self::HashType) -> Result<()>;
 }
@@ -227,6 +227,24 @@ where
 		let state_write_lock = self.states_map_lock.write().map_err(|_| Error::LockPoisoning)?;
 		self.write_after_mutation(state, state_write_lock, shard)
 	}
+
+	fn revert_to(&self, shard: &ShardIdentifier, state_hash: &Self::HashType) -> Result<()> {
+		debug!("Reverting state to {:?}", state_hash);
+		let mut state_write_lock =
+			self.states_map_lock.write().map_err(|_| Error::LockPoisoning)?;
+
+		let state = self
+			.state_snapshot_repository
+			.write()
+			.map_err(|_| Error::LockPoisoning)?
+			.revert_to(shard, state_hash)?;
+
+		state_write_lock.insert(*shard, (state.clone(), *state_hash));
+		drop(state_write_lock);
+
+		self.state_observer.queue_state_update(*shard, state)?;
+		Ok(())
+	}
 }
 
 impl<Repository, StateObserver, StateInitializer> QueryShardState
@@ -330,6 +348,33 @@ mod tests {
 		assert_eq!((shard_id, prune_diff(reset_state)), observer_updates[2]);
 	}
 
+	#[test]
+	fn revert_to_restores_state_and_queues_observer_update() {
+		let shard_id = ShardIdentifier::default();
+		let state_observer = Arc::new(TestStateObserver::default());
+		let state_initializer = Arc::new(TestStateInitializer::new(Default::default()));
+		let state_handler = Arc::new(TestStateHandler::new(
+			default_repository(),
+			state_observer.clone(),
+			state_initializer,
+		));
+		let initial_state_hash = state_handler.initialize_shard(shard_id).unwrap();
+		let (initial_state, _) = state_handler.load_cloned(&shard_id).unwrap();
+
+		let (lock, _s) = state_handler.load_for_mutation(&shard_id).unwrap();
+		state_handler.write_after_mutation(create_state(4u64), lock, &shard_id).unwrap();
+
+		state_handler.revert_to(&shard_id, &initial_state_hash).unwrap();
+
+		let (reverted_state, reverted_state_hash) = state_handler.load_cloned(&shard_id).unwrap();
+		assert_eq!(initial_state, reverted_state);
+		assert_eq!(initial_state_hash, reverted_state_hash);
+
+		let observer_updates = state_observer.queued_updates.read().unwrap().clone();
+		assert_eq!(3, observer_updates.len());
+		assert_eq!((shard_id, initial_state), observer_updates[2]);
+	}
+
 	#[test]
 	fn load_initialized_works() {
 		let shard_id = ShardIdentifier::random();
@@ -34,14 +34,16 @@ use std::{collections::HashMap, format, vec::Vec};
 /// Mock implementation for the `HandleState` trait.
 ///
 /// Uses an in-memory state, in a `HashMap`. To be used in unit tests.
+/// All written states are kept, so any of them can be reverted to.
 #[derive(Default)]
 pub struct HandleStateMock {
 	state_map: RwLock<HashMap<ShardIdentifier, StfState>>,
+	written_states: RwLock<HashMap<H256, StfState>>,
 }
 
 impl HandleStateMock {
 	pub fn from_shard(shard: ShardIdentifier) -> Result<Self> {
-		let state_handler = HandleStateMock { state_map: Default::default() };
+		let state_handler = HandleStateMock::default();
 		state_handler.initialize_shard(shard)?;
 		Ok(state_handler)
 	}
@@ -96,14 +98,28 @@ impl HandleState for HandleStateMock {
 		mut state_lock: RwLockWriteGuard<'_, Self::WriteLockPayload>,
 		shard: &ShardIdentifier,
 	) -> Result<Self::HashType> {
+		let state_hash = state.hash();
 		state_lock.insert(*shard, state.clone());
-		Ok(state.hash())
+		self.written_states.write().unwrap().insert(state_hash, state);
+		Ok(state_hash)
 	}
 
 	fn reset(&self, state: Self::StateT, shard: &ShardIdentifier) -> Result<Self::HashType> {
 		let write_lock = self.state_map.write().unwrap();
 		self.write_after_mutation(state, write_lock, shard)
 	}
+
+	fn revert_to(&self, shard: &ShardIdentifier, state_hash: &Self::HashType) -> Result<()> {
+		let state = self
+			.written_states
+			.read()
+			.unwrap()
+			.get(state_hash)
+			.cloned()
+			.ok_or_else(|| Error::StateNotFoundInRepository(format!("{:?}", state_hash)))?;
+		self.state_map.write().unwrap().insert(*shard, state);
+		Ok(())
+	}
 }
 
 impl QueryShardState for HandleStateMock {
@@ -180,6 +196,22 @@ pub mod tests {
 		assert_eq!(*inserted_value, value.encode());
 	}
 
+	pub fn revert_to_restores_written_state() {
+		let state_handler = HandleStateMock::default();
+		let shard = ShardIdentifier::default();
+		let initial_state_hash = state_handler.initialize_shard(shard).unwrap();
+
+		let (lock, mut state) = state_handler.load_for_mutation(&shard).unwrap();
+		state.insert("my_key".encode(), "my_value".encode());
+		state_handler.write_after_mutation(state, lock, &shard).unwrap();
+
+		state_handler.revert_to(&shard, &initial_state_hash).unwrap();
+
+		let (_, loaded_state_hash) = state_handler.load_cloned(&shard).unwrap();
+		assert_eq!(initial_state_hash, loaded_state_hash);
+		assert!(state_handler.revert_to(&shard, &H256::from([7u8; 32])).is_err());
+	}
+
 	pub fn ensure_subsequent_state_loads_have_same_hash() {
 		let state_handler = HandleStateMock::default();
 		let shard = ShardIdentifier::default();
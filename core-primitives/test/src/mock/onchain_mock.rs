@@ -167,6 +167,24 @@ impl EnclaveSidechainOCallApi for OnchainMock {
 	) -> SgxResult<Vec<SignedSidechainBlock>> {
 		Ok(Vec::new())
 	}
+
+	fn get_peer_count(&self) -> SgxResult<u32> {
+		Ok(0)
+	}
+
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		_signed_votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
+
+	fn query_sidechain_blocks<BlockQuery: Encode, BlockSummary: Decode>(
+		&self,
+		_query: BlockQuery,
+	) -> SgxResult<Vec<BlockSummary>> {
+		Ok(Vec::new())
+	}
 }
 
 impl EnclaveMetricsOCallApi for OnchainMock {
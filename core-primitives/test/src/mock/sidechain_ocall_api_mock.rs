@@ -107,4 +107,22 @@ where
 			None => Err(sgx_status_t::SGX_ERROR_UNEXPECTED),
 		}
 	}
+
+	fn get_peer_count(&self) -> SgxResult<u32> {
+		Ok(0)
+	}
+
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		_signed_votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
+
+	fn query_sidechain_blocks<BlockQuery: Encode, BlockSummary: Decode>(
+		&self,
+		_query: BlockQuery,
+	) -> SgxResult<Vec<BlockSummary>> {
+		Ok(Vec::new())
+	}
 }
@@ -21,7 +21,7 @@ use itp_node_api::metadata::metadata_mocks::NodeMetadataMock;
 use itp_node_api_metadata_provider::NodeMetadataRepository;
 use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesDiffType, SgxExternalitiesTrait};
 use itp_stf_interface::{
-	ExecuteCall, InitState, StateCallInterface, StateGetterInterface, UpdateState,
+	ExecuteCall, InitState, OnBlockInterface, StateCallInterface, StateGetterInterface, UpdateState,
 };
 use itp_stf_primitives::{
 	traits::{
@@ -42,7 +42,7 @@ use std::{thread::sleep, time::Duration};
 // a few dummy types
 type NodeMetadataRepositoryMock = NodeMetadataRepository<NodeMetadataMock>;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Encode, Decode, Debug, PartialEq, Eq)]
 pub enum StfMockError {
 	Dummy,
 }
@@ -74,6 +74,18 @@ impl StateCallInterface<TrustedCallSignedMock, SgxExternalities, NodeMetadataRep
 	}
 }
 
+impl OnBlockInterface<SgxExternalities, NodeMetadataRepositoryMock> for StfMock {
+	type Error = StfMockError;
+
+	fn on_block(
+		_state: &mut SgxExternalities,
+		_calls: &mut Vec<OpaqueCall>,
+		_node_metadata_repo: Arc<NodeMetadataRepositoryMock>,
+	) -> Result<(), Self::Error> {
+		Ok(())
+	}
+}
+
 impl InitState<SgxExternalities, AccountId> for StfMock {
 	fn init_state(_enclave_account: AccountId) -> SgxExternalities {
 		SgxExternalities::new(Default::default())
@@ -242,19 +242,7 @@ where
 		shard: ShardIdentifier,
 		inblock: bool,
 	) -> Result<TxHash> {
-		let hash = match bytes_or_hash {
-			TrustedOperationOrHash::Hash(h) => Ok(h),
-			TrustedOperationOrHash::OperationEncoded(bytes) => {
-				match Decode::decode(&mut bytes.as_slice()) {
-					Ok(op) => Ok(self.top_pool.hash_of(&op)),
-					Err(e) => {
-						error!("Failed to decode trusted operation: {:?}, operation will not be removed from pool", e);
-						Err(StateRpcError::CodecError(e))
-					},
-				}
-			},
-			TrustedOperationOrHash::Operation(op) => Ok(self.top_pool.hash_of(&op)),
-		}?;
+		let hash = self.hash_of_top_or_hash(bytes_or_hash)?;
 
 		debug!("removing {:?} from top pool", hash);
 
@@ -273,6 +261,22 @@ where
 
 		Ok(removed_op_hash)
 	}
+
+	fn hash_of_top_or_hash(&self, bytes_or_hash: TrustedOperationOrHash<TCS, G>) -> Result<TxHash> {
+		match bytes_or_hash {
+			TrustedOperationOrHash::Hash(h) => Ok(h),
+			TrustedOperationOrHash::OperationEncoded(bytes) => {
+				match Decode::decode(&mut bytes.as_slice()) {
+					Ok(op) => Ok(self.top_pool.hash_of(&op)),
+					Err(e) => {
+						error!("Failed to decode trusted operation: {:?}", e);
+						Err(StateRpcError::CodecError(e))
+					},
+				}
+			},
+			TrustedOperationOrHash::Operation(op) => Ok(self.top_pool.hash_of(&op)),
+		}
+	}
 }
 
 fn map_top_error<P: TrustedOperationPool<StfTrustedOperation<TCS, G>>, TCS, G>(
@@ -397,6 +401,16 @@ where
 		failed_to_remove
 	}
 
+	fn report_failed_calls(&self, failed_calls: Vec<(TrustedOperationOrHash<TCS, G>, Vec<u8>)>) {
+		let failures = failed_calls
+			.into_iter()
+			.filter_map(|(failed_call, encoded_error)| {
+				self.hash_of_top_or_hash(failed_call).ok().map(|hash| (hash, encoded_error))
+			})
+			.collect();
+		self.top_pool.on_execution_failed(failures);
+	}
+
 	fn watch_top(&self, ext: Vec<u8>, shard: ShardIdentifier) -> PoolFuture<TxHash, RpcError> {
 		self.process_top(ext, shard, TopSubmissionMode::SubmitWatch)
 	}
@@ -25,6 +25,7 @@ use crate::{
 use codec::{Decode, Encode};
 use itp_sgx_crypto::{mocks::KeyRepositoryMock, ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
 
+use itp_stf_primitives::types::TrustedOperationOrHash;
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_test::mock::{
 	handle_state_mock::HandleStateMock,
@@ -125,6 +126,22 @@ fn submitting_indirect_call_works() {
 	assert_eq!(1, author.get_pending_trusted_calls(shard_id()).len());
 }
 
+#[test]
+fn reporting_failed_calls_forwards_the_encoded_errors_to_the_pool() {
+	let (author, top_pool, _) = create_author_with_filter(AllowAllTopsFilter::new());
+	let top_call = mock_top_direct_trusted_call_signed();
+	let top_hash = top_call.hash();
+
+	author.report_failed_calls(vec![(
+		TrustedOperationOrHash::from_top(top_call),
+		"invalid orders".encode(),
+	)]);
+
+	let execution_failures = top_pool.get_execution_failures();
+	assert_eq!(1, execution_failures.len());
+	assert_eq!(execution_failures.get(&top_hash), Some(&"invalid orders".encode()));
+}
+
 fn create_author_with_filter<F: Filter<Value = TrustedOperationMock>>(
 	filter: F,
 ) -> (TestAuthor<F>, Arc<TrustedOperationPoolMock<TrustedOperationMock>>, ShieldingCryptoMock) {
@@ -220,6 +220,8 @@ where
 		failed_to_remove
 	}
 
+	fn report_failed_calls(&self, _failed_calls: Vec<(TrustedOperationOrHash<TCS, G>, Vec<u8>)>) {}
+
 	fn watch_top(&self, _ext: Vec<u8>, _shard: ShardIdentifier) -> PoolFuture<H256, RpcError> {
 		todo!()
 	}
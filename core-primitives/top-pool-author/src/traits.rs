@@ -81,6 +81,10 @@ where
 		executed_calls: Vec<(TrustedOperationOrHash<TCS, G>, bool)>,
 	) -> Vec<TrustedOperationOrHash<TCS, G>>;
 
+	/// Report the encoded errors of trusted operations whose execution failed to their submitters.
+	/// To be called before the operations are removed from the pool.
+	fn report_failed_calls(&self, failed_calls: Vec<(TrustedOperationOrHash<TCS, G>, Vec<u8>)>);
+
 	/// Submit an extrinsic to watch.
 	///
 	/// See [`TrustedOperationStatus`](sp_transaction_pool::TrustedOperationStatus) for details on transaction
@@ -232,6 +232,10 @@ where
 		self.pool.validated_pool().on_broadcasted(propagations)
 	}
 
+	fn on_execution_failed(&self, failures: HashMap<TxHash, Vec<u8>>) {
+		self.pool.validated_pool().on_execution_failed(failures)
+	}
+
 	fn hash_of(&self, xt: &TOP) -> TxHash {
 		self.pool.hash_of(xt)
 	}
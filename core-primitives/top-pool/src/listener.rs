@@ -108,6 +108,12 @@ where
 		})
 	}
 
+	/// The execution of a TrustedOperation failed with the given error.
+	pub fn execution_failed(&mut self, tx: &TxHash, encoded_error: Vec<u8>) {
+		trace!(target: "txpool", "[{:?}] Execution failed", tx);
+		self.fire(tx, |watcher| watcher.execution_failed(encoded_error));
+	}
+
 	/// TrustedOperation was removed as invalid.
 	pub fn invalid(&mut self, tx: &TxHash) {
 		self.fire(tx, |watcher| watcher.invalid());
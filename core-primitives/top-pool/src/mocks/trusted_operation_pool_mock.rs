@@ -49,6 +49,7 @@ use std::{boxed::Box, collections::HashMap, string::String, sync::Arc, vec, vec:
 /// To be used in unit tests
 pub struct TrustedOperationPoolMock<TOP: Encode + Clone + Send + Sync + 'static> {
 	submitted_transactions: RwLock<HashMap<ShardIdentifier, TxPayload<TOP>>>,
+	execution_failures: RwLock<HashMap<TxHash, Vec<u8>>>,
 }
 
 /// Transaction payload
@@ -62,7 +63,10 @@ pub struct TxPayload<TOP: Encode + Clone + Send + Sync + 'static> {
 
 impl<TOP: Encode + Clone + Send + Sync + 'static> Default for TrustedOperationPoolMock<TOP> {
 	fn default() -> Self {
-		TrustedOperationPoolMock::<TOP> { submitted_transactions: RwLock::new(HashMap::new()) }
+		TrustedOperationPoolMock::<TOP> {
+			submitted_transactions: RwLock::new(HashMap::new()),
+			execution_failures: RwLock::new(HashMap::new()),
+		}
 	}
 }
 
@@ -72,6 +76,10 @@ impl<TOP: Encode + Clone + Send + Sync + 'static> TrustedOperationPoolMock<TOP>
 		transactions.clone()
 	}
 
+	pub fn get_execution_failures(&self) -> HashMap<TxHash, Vec<u8>> {
+		self.execution_failures.read().unwrap().clone()
+	}
+
 	fn map_stf_top_to_tx(stf_top: &TOP) -> Arc<TrustedOperation<TOP>> {
 		Arc::new(TrustedOperation::<TOP> {
 			data: stf_top.clone(),
@@ -199,6 +207,10 @@ where
 		unimplemented!()
 	}
 
+	fn on_execution_failed(&self, failures: HashMap<TxHash, Vec<u8>>) {
+		self.execution_failures.write().unwrap().extend(failures);
+	}
+
 	fn hash_of(&self, xt: &TOP) -> TxHash {
 		hash_of_top(xt)
 	}
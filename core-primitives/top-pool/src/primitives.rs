@@ -240,6 +240,10 @@ pub trait TrustedOperationPool<TOP>: Send + Sync {
 	/// Notify the pool about operations broadcast.
 	fn on_broadcasted(&self, propagations: HashMap<TxHash, Vec<String>>);
 
+	// *** Block production
+	/// Notify the pool about operations whose execution failed, along with their encoded error.
+	fn on_execution_failed(&self, failures: HashMap<TxHash, Vec<u8>>);
+
 	/// Returns operation hash
 	fn hash_of(&self, xt: &TOP) -> TxHash;
 
@@ -613,6 +613,14 @@ where
 		}
 	}
 
+	/// Invoked when the execution of extrinsics failed.
+	pub fn on_execution_failed(&self, failures: HashMap<TxHash, Vec<u8>>) {
+		let mut listener = self.listener.write().unwrap();
+		for (hash, encoded_error) in failures.into_iter() {
+			listener.execution_failed(&hash, encoded_error);
+		}
+	}
+
 	/// Remove a subtree of operations from the pool and mark them invalid.
 	///
 	/// The operations passed as an argument will be additionally banned
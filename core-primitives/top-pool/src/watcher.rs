@@ -100,6 +100,15 @@ where
 		self.is_in_block = true;
 	}
 
+	/// Extrinsic has been rejected by the STF, the client receives the encoded error.
+	pub fn execution_failed(&mut self, encoded_error: Vec<u8>) {
+		if let Err(e) = self.rpc_response_sender.send_execution_error(*self.hash(), encoded_error) {
+			error!("failed to send execution error to rpc client: {:?}", e);
+		}
+		// no more notifications, the operation is removed from the pool as invalid
+		self.is_in_block = true;
+	}
+
 	/// TrustedOperation has been dropped from the pool because of the limit.
 	pub fn dropped(&mut self) {
 		self.send(TrustedOperationStatus::Dropped);
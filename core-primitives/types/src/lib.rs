@@ -122,6 +122,28 @@ pub enum WorkerResponse<V: Encode + Decode> {
 	ChainStorage(Vec<u8>, Option<V>, Option<Vec<Vec<u8>>>), // (storage_key, storage_value, storage_proof)
 }
 
+/// Number and hash of the latest block of a chain, as known to the worker.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct ChainHead<Number> {
+	pub number: Number,
+	pub hash: H256,
+}
+
+/// Health of a worker, as returned by the `system_health` RPC.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkerHealth {
+	/// Sidechain block production is suspended while blocks are synced from a peer.
+	pub block_production_suspended: bool,
+	pub integritee_parentchain_head: Option<ChainHead<BlockNumber>>,
+	pub target_a_parentchain_head: Option<ChainHead<BlockNumber>>,
+	pub target_b_parentchain_head: Option<ChainHead<BlockNumber>>,
+	/// Latest sidechain block of every shard.
+	pub sidechain_heads: Vec<(ShardIdentifier, ChainHead<SidechainBlockNumber>)>,
+	/// Number of trusted operations in the TOP pool, over all shards.
+	pub top_pool_size: u32,
+	pub peer_count: u32,
+}
+
 impl From<WorkerResponse<Vec<u8>>> for StorageEntry<Vec<u8>> {
 	fn from(response: WorkerResponse<Vec<u8>>) -> Self {
 		match response {
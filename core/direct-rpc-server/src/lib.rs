@@ -53,6 +53,7 @@ mod builders;
 pub mod response_channel;
 pub mod rpc_connection_registry;
 pub mod rpc_responder;
+pub mod rpc_subscriptions;
 pub mod rpc_watch_extractor;
 pub mod rpc_ws_handler;
 
@@ -98,6 +99,25 @@ pub trait SendRpcResponse: Send + Sync {
 	) -> DirectRpcResult<()>;
 
 	fn send_state(&self, hash: Self::Hash, state_encoded: Vec<u8>) -> DirectRpcResult<()>;
+
+	/// Marks a trusted operation as invalid because its execution failed. The encoded error
+	/// is appended to the value of the response, right after the operation hash.
+	fn send_execution_error(&self, hash: Self::Hash, encoded_error: Vec<u8>)
+		-> DirectRpcResult<()>;
+
+	/// Sends an update of a subscription, the connection is kept alive for further updates.
+	fn send_subscription_update(&self, hash: Self::Hash, value: Vec<u8>) -> DirectRpcResult<()>;
+}
+
+/// Sends updates to all clients that have subscribed to them.
+pub trait NotifySubscribers: Send + Sync {
+	type Hash: RpcHash;
+
+	/// Adds a subscription, whose connection is registered with `subscription_id`.
+	fn subscribe(&self, subscription_id: Self::Hash);
+
+	/// Sends the value to all subscriptions and removes the ones whose connection is gone.
+	fn notify_subscribers(&self, value: Vec<u8>);
 }
 
 /// Determines if a given connection must be watched (i.e. kept alive),
@@ -16,5 +16,6 @@
 */
 
 pub mod determine_watch_mock;
+pub mod notify_subscribers_mock;
 pub mod response_channel_mock;
 pub mod send_rpc_response_mock;
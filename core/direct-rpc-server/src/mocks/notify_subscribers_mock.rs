@@ -0,0 +1,47 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{NotifySubscribers, RpcHash};
+use std::vec::Vec;
+
+/// Notify subscribers mock, records the subscriptions and the sent values.
+#[derive(Default)]
+pub struct NotifySubscribersMock<HashType> {
+	pub subscription_ids: RwLock<Vec<HashType>>,
+	pub notifications: RwLock<Vec<Vec<u8>>>,
+}
+
+impl<HashType> NotifySubscribers for NotifySubscribersMock<HashType>
+where
+	HashType: RpcHash,
+{
+	type Hash = HashType;
+
+	fn subscribe(&self, subscription_id: Self::Hash) {
+		self.subscription_ids.write().unwrap().push(subscription_id);
+	}
+
+	fn notify_subscribers(&self, value: Vec<u8>) {
+		self.notifications.write().unwrap().push(value);
+	}
+}
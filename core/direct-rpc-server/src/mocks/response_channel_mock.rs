@@ -39,6 +39,10 @@ where
 	pub fn number_of_updates(&self) -> usize {
 		self.sent_messages.read().unwrap().len()
 	}
+
+	pub fn last_message(&self) -> Option<String> {
+		self.sent_messages.read().unwrap().last().map(|(_, message)| message.clone())
+	}
 }
 
 impl<Token> ResponseChannel<Token> for ResponseChannelMock<Token>
@@ -29,6 +29,8 @@ use std::vec::Vec;
 #[derive(Default)]
 pub struct SendRpcResponseMock<HashType> {
 	pub sent_states: RwLock<Vec<(HashType, Vec<u8>)>>,
+	pub execution_errors: RwLock<Vec<(HashType, Vec<u8>)>>,
+	pub subscription_updates: RwLock<Vec<(HashType, Vec<u8>)>>,
 }
 
 impl<HashType> SendRpcResponse for SendRpcResponseMock<HashType>
@@ -50,4 +52,20 @@ where
 		states_lock.push((hash, state_encoded));
 		Ok(())
 	}
+
+	fn send_execution_error(
+		&self,
+		hash: Self::Hash,
+		encoded_error: Vec<u8>,
+	) -> DirectRpcResult<()> {
+		let mut errors_lock = self.execution_errors.write().unwrap();
+		errors_lock.push((hash, encoded_error));
+		Ok(())
+	}
+
+	fn send_subscription_update(&self, hash: Self::Hash, value: Vec<u8>) -> DirectRpcResult<()> {
+		let mut updates_lock = self.subscription_updates.write().unwrap();
+		updates_lock.push((hash, value));
+		Ok(())
+	}
 }
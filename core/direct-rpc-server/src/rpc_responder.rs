@@ -128,6 +128,52 @@ where
 		debug!("sending state successful");
 		Ok(())
 	}
+
+	fn send_execution_error(&self, hash: Hash, encoded_error: Vec<u8>) -> DirectRpcResult<()> {
+		debug!("sending execution error");
+
+		// withdraw removes it from the registry
+		let (connection_token, mut response) = self
+			.connection_registry
+			.withdraw(&hash)
+			.ok_or(DirectRpcError::InvalidConnectionHash)?;
+
+		let mut result = RpcReturnValue::from_hex(&response.result)
+			.map_err(|e| DirectRpcError::Other(format!("{:?}", e).into()))?;
+
+		// The value still starts with the operation hash, so clients that only decode
+		// the hash keep working.
+		result.value.extend(encoded_error);
+		result.do_watch = false;
+		result.status =
+			DirectRequestStatus::TrustedOperationStatus(TrustedOperationStatus::Invalid);
+		response.result = result.to_hex();
+
+		self.encode_and_send_response(connection_token, &response)?;
+
+		debug!("sending execution error successful");
+		Ok(())
+	}
+
+	fn send_subscription_update(&self, hash: Hash, value: Vec<u8>) -> DirectRpcResult<()> {
+		debug!("sending subscription update");
+
+		// withdraw removes it from the registry
+		let (connection_token, mut response) = self
+			.connection_registry
+			.withdraw(&hash)
+			.ok_or(DirectRpcError::InvalidConnectionHash)?;
+
+		let result = RpcReturnValue::new(value, true, DirectRequestStatus::Ok);
+		response.result = result.to_hex();
+
+		self.encode_and_send_response(connection_token, &response)?;
+
+		self.connection_registry.store(hash, connection_token, response);
+
+		debug!("sending subscription update successful");
+		Ok(())
+	}
 }
 
 fn continue_watching(status: &TrustedOperationStatus) -> bool {
@@ -149,7 +195,7 @@ pub mod tests {
 		mocks::response_channel_mock::ResponseChannelMock,
 		rpc_connection_registry::ConnectionRegistry,
 	};
-	use codec::Encode;
+	use codec::{Decode, Encode};
 	use std::assert_matches::assert_matches;
 
 	type TestConnectionToken = u64;
@@ -237,6 +283,56 @@ pub mod tests {
 		assert_eq!(1, websocket_responder.number_of_updates());
 	}
 
+	#[test]
+	fn sending_execution_error_appends_error_to_operation_hash_and_removes_connection() {
+		let connection_hash = String::from("conn_hash");
+		let connection_registry = create_registry_with_single_connection(connection_hash.clone());
+
+		let websocket_responder = Arc::new(TestResponseChannel::default());
+		let rpc_responder =
+			RpcResponder::new(connection_registry.clone(), websocket_responder.clone());
+
+		let result =
+			rpc_responder.send_execution_error(connection_hash.clone(), "order 3".encode());
+		assert!(result.is_ok());
+
+		verify_closed_connection(&connection_hash, connection_registry);
+
+		let sent_response: RpcResponse =
+			serde_json::from_str(&websocket_responder.last_message().unwrap()).unwrap();
+		let return_value = RpcReturnValue::from_hex(&sent_response.result).unwrap();
+		let mut value = return_value.value.as_slice();
+
+		assert_eq!(
+			return_value.status,
+			DirectRequestStatus::TrustedOperationStatus(TrustedOperationStatus::Invalid)
+		);
+		assert!(!return_value.do_watch);
+		assert_eq!(String::decode(&mut value).unwrap(), "value".to_string());
+		assert_eq!(String::decode(&mut value).unwrap(), "order 3".to_string());
+	}
+
+	#[test]
+	fn sending_subscription_update_keeps_connection() {
+		let connection_hash = String::from("conn_hash");
+		let connection_registry = create_registry_with_single_connection(connection_hash.clone());
+
+		let websocket_responder = Arc::new(TestResponseChannel::default());
+		let rpc_responder =
+			RpcResponder::new(connection_registry.clone(), websocket_responder.clone());
+
+		let first_result =
+			rpc_responder.send_subscription_update(connection_hash.clone(), "head_1".encode());
+		let second_result =
+			rpc_responder.send_subscription_update(connection_hash.clone(), "head_2".encode());
+
+		assert!(first_result.is_ok());
+		assert!(second_result.is_ok());
+
+		verify_open_connection(&connection_hash, connection_registry);
+		assert_eq!(2, websocket_responder.number_of_updates());
+	}
+
 	#[test]
 	fn test_continue_watching() {
 		assert!(!continue_watching(&TrustedOperationStatus::Invalid));
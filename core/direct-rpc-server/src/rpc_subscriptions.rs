@@ -0,0 +1,178 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{DirectRpcError, NotifySubscribers, RpcHash, SendRpcResponse};
+use log::*;
+use std::{sync::Arc, vec::Vec};
+
+/// Subscriptions to a single topic, e.g. the headers of the imported sidechain blocks.
+///
+/// The connections of the subscriptions are kept in the connection registry of the responder.
+/// A new subscription is answered before its connection is registered, so it is only removed
+/// once its connection is missing after it has been notified before.
+pub struct RpcSubscriptions<Responder, Hash>
+where
+	Responder: SendRpcResponse<Hash = Hash>,
+	Hash: RpcHash,
+{
+	rpc_responder: Arc<Responder>,
+	subscriptions: RwLock<Vec<Subscription<Hash>>>,
+}
+
+struct Subscription<Hash> {
+	id: Hash,
+	is_new: bool,
+}
+
+impl<Responder, Hash> RpcSubscriptions<Responder, Hash>
+where
+	Responder: SendRpcResponse<Hash = Hash>,
+	Hash: RpcHash,
+{
+	pub fn new(rpc_responder: Arc<Responder>) -> Self {
+		RpcSubscriptions { rpc_responder, subscriptions: RwLock::new(Vec::new()) }
+	}
+
+	pub fn number_of_subscriptions(&self) -> usize {
+		self.subscriptions.read().expect("Lock poisoning").len()
+	}
+}
+
+impl<Responder, Hash> NotifySubscribers for RpcSubscriptions<Responder, Hash>
+where
+	Responder: SendRpcResponse<Hash = Hash>,
+	Hash: RpcHash,
+{
+	type Hash = Hash;
+
+	fn subscribe(&self, subscription_id: Hash) {
+		self.subscriptions
+			.write()
+			.expect("Lock poisoning")
+			.push(Subscription { id: subscription_id, is_new: true });
+	}
+
+	fn notify_subscribers(&self, value: Vec<u8>) {
+		let mut subscriptions = self.subscriptions.write().expect("Lock poisoning");
+		subscriptions.retain_mut(|subscription| {
+			let is_new = core::mem::replace(&mut subscription.is_new, false);
+			match self
+				.rpc_responder
+				.send_subscription_update(subscription.id.clone(), value.clone())
+			{
+				Ok(()) => true,
+				// The connection of a new subscription may not be registered yet.
+				Err(DirectRpcError::InvalidConnectionHash) if is_new => true,
+				Err(e) => {
+					debug!("Removing subscription {:?}: {:?}", subscription.id, e);
+					false
+				},
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+	use crate::{
+		builders::rpc_response_builder::RpcResponseBuilder,
+		mocks::response_channel_mock::ResponseChannelMock,
+		rpc_connection_registry::ConnectionRegistry, rpc_responder::RpcResponder,
+		RpcConnectionRegistry,
+	};
+	use codec::Encode;
+
+	type TestConnectionToken = u64;
+	type TestConnectionRegistry = ConnectionRegistry<String, TestConnectionToken>;
+	type TestRpcResponder =
+		RpcResponder<TestConnectionRegistry, String, ResponseChannelMock<TestConnectionToken>>;
+
+	#[test]
+	fn all_subscribers_are_notified() {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let response_channel = Arc::new(ResponseChannelMock::default());
+		let subscriptions = RpcSubscriptions::new(Arc::new(TestRpcResponder::new(
+			connection_registry.clone(),
+			response_channel.clone(),
+		)));
+
+		for (token, subscription_id) in ["first", "second"].iter().enumerate() {
+			subscriptions.subscribe(subscription_id.to_string());
+			connection_registry.store(
+				subscription_id.to_string(),
+				token as TestConnectionToken,
+				RpcResponseBuilder::new().build(),
+			);
+		}
+
+		subscriptions.notify_subscribers("head_1".encode());
+		subscriptions.notify_subscribers("head_2".encode());
+
+		assert_eq!(4, response_channel.number_of_updates());
+		assert_eq!(2, subscriptions.number_of_subscriptions());
+	}
+
+	#[test]
+	fn new_subscription_is_kept_until_its_connection_is_registered() {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let response_channel = Arc::new(ResponseChannelMock::default());
+		let subscriptions = RpcSubscriptions::new(Arc::new(TestRpcResponder::new(
+			connection_registry.clone(),
+			response_channel.clone(),
+		)));
+
+		subscriptions.subscribe("first".to_string());
+		subscriptions.notify_subscribers("head_1".encode());
+
+		assert_eq!(0, response_channel.number_of_updates());
+		assert_eq!(1, subscriptions.number_of_subscriptions());
+
+		connection_registry.store("first".to_string(), 1, RpcResponseBuilder::new().build());
+		subscriptions.notify_subscribers("head_2".encode());
+
+		assert_eq!(1, response_channel.number_of_updates());
+		assert_eq!(1, subscriptions.number_of_subscriptions());
+	}
+
+	#[test]
+	fn subscription_without_connection_is_removed() {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let response_channel = Arc::new(ResponseChannelMock::default());
+		let subscriptions = RpcSubscriptions::new(Arc::new(TestRpcResponder::new(
+			connection_registry.clone(),
+			response_channel.clone(),
+		)));
+
+		subscriptions.subscribe("never_registered".to_string());
+		subscriptions.subscribe("closed".to_string());
+		connection_registry.store("closed".to_string(), 1, RpcResponseBuilder::new().build());
+
+		subscriptions.notify_subscribers("head_1".encode());
+		connection_registry.withdraw(&"closed".to_string());
+		subscriptions.notify_subscribers("head_2".encode());
+
+		assert_eq!(1, response_channel.number_of_updates());
+		assert_eq!(0, subscriptions.number_of_subscriptions());
+	}
+}
@@ -62,8 +62,9 @@ where
 			return Ok(None)
 		}
 
+		// Subscriptions are watched with status `Ok`, their value is the subscription id.
 		match rpc_return_value.status {
-			DirectRequestStatus::TrustedOperationStatus(_) =>
+			DirectRequestStatus::TrustedOperationStatus(_) | DirectRequestStatus::Ok =>
 				Self::Hash::decode(&mut rpc_return_value.value.as_slice())
 					.map(Some)
 					.map_err(DirectRpcError::EncodingError),
@@ -120,4 +121,20 @@ pub mod tests {
 
 		assert_eq!(Some(hash.clone()), do_watch);
 	}
+
+	#[test]
+	fn subscription_response_must_be_watched() {
+		let subscription_id = String::from("subscription_id");
+		let watch_extractor = RpcWatchExtractor::<String>::new();
+		let rpc_return_value = RpcReturnValueBuilder::new()
+			.with_do_watch(true)
+			.with_value(subscription_id.encode())
+			.with_status(DirectRequestStatus::Ok)
+			.build();
+		let rpc_response = RpcResponseBuilder::new().with_result(rpc_return_value).build();
+
+		let do_watch = watch_extractor.must_be_watched(&rpc_response).unwrap();
+
+		assert_eq!(Some(subscription_id), do_watch);
+	}
 }
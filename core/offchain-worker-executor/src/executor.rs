@@ -148,6 +148,10 @@ impl<
 				.map(|h| ExecutedOperation::success(h, TrustedOperationOrHash::Hash(h), Vec::new()))
 				.collect();
 
+			// Report the errors of calls rejected by the STF to their submitters.
+			self.top_pool_author
+				.report_failed_calls(batch_execution_result.get_operation_errors());
+
 			// Remove all not successfully executed operations from the top pool.
 			self.remove_calls_from_pool(&shard, failed_operations);
 
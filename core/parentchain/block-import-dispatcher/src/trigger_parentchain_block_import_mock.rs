@@ -30,6 +30,7 @@ use crate::{error::Result, triggered_dispatcher::TriggerParentchainBlockImport};
 pub struct TriggerParentchainBlockImportMock<SignedBlockType> {
 	latest_imported: Option<SignedBlockType>,
 	import_has_been_called: RwLock<bool>,
+	replay_has_been_called: RwLock<bool>,
 }
 
 impl<SignedBlockType> TriggerParentchainBlockImportMock<SignedBlockType> {
@@ -42,6 +43,11 @@ impl<SignedBlockType> TriggerParentchainBlockImportMock<SignedBlockType> {
 		let import_flag = self.import_has_been_called.read().unwrap();
 		*import_flag
 	}
+
+	pub fn has_replay_been_called(&self) -> bool {
+		let replay_flag = self.replay_has_been_called.read().unwrap();
+		*replay_flag
+	}
 }
 
 impl<SignedBlockType> Default for TriggerParentchainBlockImportMock<SignedBlockType> {
@@ -49,6 +55,7 @@ impl<SignedBlockType> Default for TriggerParentchainBlockImportMock<SignedBlockT
 		TriggerParentchainBlockImportMock {
 			latest_imported: None,
 			import_has_been_called: RwLock::new(false),
+			replay_has_been_called: RwLock::new(false),
 		}
 	}
 }
@@ -99,4 +106,10 @@ where
 	fn peek_latest(&self) -> Result<Option<SignedBlockType>> {
 		Ok(self.latest_imported.clone())
 	}
+
+	fn replay_latest_import(&self) -> Result<()> {
+		let mut replay_flag = self.replay_has_been_called.write().unwrap();
+		*replay_flag = true;
+		Ok(())
+	}
 }
@@ -57,6 +57,10 @@ pub trait TriggerParentchainBlockImport {
 
 	/// Peek the latest block in the import queue. Returns None if queue is empty.
 	fn peek_latest(&self) -> Result<Option<Self::SignedBlockType>>;
+
+	/// Perform the state updates of the latest imported block again, after the state has been
+	/// reverted to an earlier version.
+	fn replay_latest_import(&self) -> Result<()>;
 }
 
 /// Dispatcher for block imports that retains blocks until the import is triggered, using the
@@ -204,6 +208,11 @@ where
 		);
 		self.import_queue.peek_last().map_err(Error::ImportQueue)
 	}
+
+	fn replay_latest_import(&self) -> Result<()> {
+		trace!("Replay the state updates of the latest imported parentchain block");
+		self.block_importer.replay_state_updates().map_err(Error::BlockImport)
+	}
 }
 
 #[cfg(test)]
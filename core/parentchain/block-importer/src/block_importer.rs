@@ -21,7 +21,8 @@ use crate::{error::Result, ImportParentchainBlocks};
 use ita_stf::ParentchainHeader;
 use itc_parentchain_indirect_calls_executor::ExecuteIndirectCalls;
 use itc_parentchain_light_client::{
-	concurrent_access::ValidatorAccess, BlockNumberOps, ExtrinsicSender, Validator,
+	concurrent_access::ValidatorAccess, BlockNumberOps, ExtrinsicSender, LightClientState,
+	Validator,
 };
 use itp_extrinsics_factory::CreateExtrinsics;
 use itp_stf_executor::traits::StfUpdateState;
@@ -165,4 +166,17 @@ impl<
 
 		Ok(())
 	}
+
+	fn replay_state_updates(&self) -> Result<()> {
+		let id = self.validator_accessor.parentchain_id();
+		let header =
+			self.validator_accessor.execute_on_validator(|v| v.latest_finalized_header())?;
+
+		debug!("[{:?}] Replay state updates of block {}", id, header.number);
+		if let Err(e) = self.stf_executor.update_states(&header, &id) {
+			error!("[{:?}] Error replaying state updates of block {}", id, header.number);
+			return Err(e.into())
+		}
+		Ok(())
+	}
 }
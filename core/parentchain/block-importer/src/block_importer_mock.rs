@@ -58,4 +58,8 @@ where
 		imported_blocks_lock.extend(blocks_to_import);
 		Ok(())
 	}
+
+	fn replay_state_updates(&self) -> Result<()> {
+		Ok(())
+	}
 }
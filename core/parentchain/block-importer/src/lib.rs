@@ -55,4 +55,8 @@ pub trait ImportParentchainBlocks {
 		blocks_to_import: Vec<Self::SignedBlockType>,
 		events_to_import: Vec<Vec<u8>>,
 	) -> Result<()>;
+
+	/// Perform the state updates of the latest imported block again, e.g. after the state has been
+	/// reverted to an earlier version.
+	fn replay_state_updates(&self) -> Result<()>;
 }
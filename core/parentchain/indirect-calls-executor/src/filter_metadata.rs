@@ -19,7 +19,8 @@ use crate::{
 	error::Result,
 	event_filter::{FilterEvents, MockEvents},
 	indirect_calls::{
-		InvokeArgs, ShieldFundsArgs, TransferToAliceShieldsFundsArgs, ALICE_ACCOUNT_ID,
+		InvokeArgs, ShieldFundsArgs, TransferToAliceShieldsFundsArgs, UpdateOracleArgs,
+		ALICE_ACCOUNT_ID,
 	},
 	parentchain_parser::ParseExtrinsic,
 	IndirectDispatch, IndirectExecutor,
@@ -143,6 +144,15 @@ where
 			log::debug!("executing invoke call");
 			let args = decode_and_log_error::<InvokeArgs>(call_args)?;
 			Some(IndirectCall::Invoke(args))
+		} else if index == metadata.update_oracle_call_indexes().ok()? {
+			let args = decode_and_log_error::<UpdateOracleArgs>(call_args)?;
+			if args.is_spot_price_update() {
+				log::debug!("executing spot price update call");
+				Some(IndirectCall::UpdateReservePrices(args))
+			} else {
+				// Other oracle data is not used in the enclave.
+				None
+			}
 		} else {
 			None
 		}
@@ -205,6 +215,7 @@ pub enum IndirectCall {
 	ShieldFunds(ShieldFundsArgs),
 	Invoke(InvokeArgs),
 	TransferToAliceShieldsFunds(TransferToAliceShieldsFundsArgs),
+	UpdateReservePrices(UpdateOracleArgs),
 }
 
 impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for IndirectCall {
@@ -214,6 +225,7 @@ impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for IndirectCall {
 			IndirectCall::ShieldFunds(shieldfunds_args) => shieldfunds_args.dispatch(executor),
 			IndirectCall::Invoke(invoke_args) => invoke_args.dispatch(executor),
 			IndirectCall::TransferToAliceShieldsFunds(args) => args.dispatch(executor),
+			IndirectCall::UpdateReservePrices(args) => args.dispatch(executor),
 		}
 	}
 }
@@ -18,7 +18,9 @@
 mod invoke;
 mod shield_funds;
 mod transfer_to_alice_shields_funds;
+mod update_reserve_prices;
 
 pub use invoke::InvokeArgs;
 pub use shield_funds::ShieldFundsArgs;
 pub use transfer_to_alice_shields_funds::{TransferToAliceShieldsFundsArgs, ALICE_ACCOUNT_ID};
+pub use update_reserve_prices::UpdateOracleArgs;
@@ -0,0 +1,87 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{error::Result, IndirectDispatch, IndirectExecutor};
+use codec::{Decode, Encode};
+use ita_stf::{
+	best_energy_helpers::reserve_price::{SpotPrices, SPOT_PRICE_ORACLE_PREFIX},
+	Getter, TrustedCall, TrustedCallSigned,
+};
+use itp_stf_primitives::types::TrustedOperation;
+use log::info;
+use std::{string::String, vec::Vec};
+
+/// Arguments of the parentchain's teeracle `update_oracle` dispatchable.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct UpdateOracleArgs {
+	pub oracle_name: Vec<u8>,
+	pub data_source: Vec<u8>,
+	pub new_data: Vec<u8>,
+}
+
+impl UpdateOracleArgs {
+	/// True if the oracle data are the day-ahead spot prices of a bidding zone.
+	pub fn is_spot_price_update(&self) -> bool {
+		self.oracle_name.starts_with(SPOT_PRICE_ORACLE_PREFIX.as_bytes())
+	}
+}
+
+impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for UpdateOracleArgs {
+	/// Takes the spot prices, which a teeracle has published, as reserve prices of the market.
+	///
+	/// Only successful extrinsics are dispatched, hence the teeracle is whitelisted on the
+	/// parentchain.
+	fn dispatch(&self, executor: &Executor) -> Result<()> {
+		let spot_prices = SpotPrices::decode(&mut self.new_data.as_slice())?;
+		info!(
+			"Found spot prices of {} hours in oracle {}",
+			spot_prices.hourly.len(),
+			String::from_utf8_lossy(&self.oracle_name)
+		);
+
+		let shard = executor.get_default_shard();
+		let trusted_call =
+			TrustedCall::set_reserve_prices(executor.get_enclave_account()?, spot_prices);
+		let signed_trusted_call = executor.sign_call_with_self(&trusted_call, &shard)?;
+		let trusted_operation =
+			TrustedOperation::<TrustedCallSigned, Getter>::indirect_call(signed_trusted_call);
+
+		let encrypted_trusted_call = executor.encrypt(&trusted_operation.encode())?;
+		executor.submit_trusted_call(shard, encrypted_trusted_call);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn update_oracle_args(oracle_name: &str) -> UpdateOracleArgs {
+		UpdateOracleArgs {
+			oracle_name: oracle_name.as_bytes().to_vec(),
+			data_source: b"https://api.energy-charts.info/".to_vec(),
+			new_data: SpotPrices::default().encode(),
+		}
+	}
+
+	#[test]
+	fn only_spot_price_oracles_are_reserve_price_updates() {
+		assert!(update_oracle_args("spot_price/DE-LU").is_spot_price_update());
+		assert!(!update_oracle_args("54.32/15.37/2023-03-04/2023-03-04").is_spot_price_update());
+		assert!(!update_oracle_args("TEER/USD").is_spot_price_update());
+	}
+}
@@ -18,10 +18,12 @@
 use itp_enclave_api::direct_request::DirectRequest;
 use itp_rpc::RpcRequest;
 use itp_utils::ToHexPrefixed;
-use its_peer_fetch::block_fetch_server::BlockFetchServerModuleBuilder;
+use its_peer_fetch::{
+	block_fetch_server::BlockFetchServerModuleBuilder, finality_server::FinalityServerModuleBuilder,
+};
 use its_primitives::types::block::SignedBlock;
 use its_rpc_handler::constants::RPC_METHOD_NAME_IMPORT_BLOCKS;
-use its_storage::interface::FetchBlocks;
+use its_storage::{interface::FetchBlocks, FetchFinalizedBlock, ImportFinalityVotes};
 use jsonrpsee::{
 	types::error::CallError,
 	ws_server::{RpcModule, WsServerBuilder},
@@ -35,14 +37,16 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-pub async fn run_server<Enclave, FetchSidechainBlocks>(
+pub async fn run_server<Enclave, FetchSidechainBlocks, SidechainFinality>(
 	addr: impl ToSocketAddrs,
 	enclave: Arc<Enclave>,
 	sidechain_block_fetcher: Arc<FetchSidechainBlocks>,
+	sidechain_finality: Arc<SidechainFinality>,
 ) -> anyhow::Result<SocketAddr>
 where
 	Enclave: DirectRequest,
 	FetchSidechainBlocks: FetchBlocks<SignedBlock> + Send + Sync + 'static,
+	SidechainFinality: ImportFinalityVotes + FetchFinalizedBlock + Send + Sync + 'static,
 {
 	let mut server = WsServerBuilder::default().build(addr).await?;
 
@@ -71,6 +75,11 @@ where
 		.map_err(|e| CallError::Failed(e.to_string().into()))?; // `to_string` necessary due to no all errors implementing Send + Sync.
 	server.register_module(fetch_sidechain_blocks_module).unwrap();
 
+	let sidechain_finality_module = FinalityServerModuleBuilder::new(sidechain_finality)
+		.build()
+		.map_err(|e| CallError::Failed(e.to_string().into()))?;
+	server.register_module(sidechain_finality_module).unwrap();
+
 	let socket_addr = server.local_addr()?;
 	tokio::spawn(async move { server.start().await });
 
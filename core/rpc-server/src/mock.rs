@@ -20,9 +20,12 @@ use itp_rpc::RpcResponse;
 use itp_utils::ToHexPrefixed;
 use its_primitives::{
 	traits::ShardIdentifierFor,
-	types::{BlockHash, SignedBlock, SignedBlock as SignedSidechainBlock},
+	types::{
+		finality::{FinalizedBlock, SignedFinalityVote},
+		BlockHash, ShardIdentifier, SignedBlock, SignedBlock as SignedSidechainBlock,
+	},
 };
-use its_storage::interface::FetchBlocks;
+use its_storage::{interface::FetchBlocks, FetchFinalizedBlock, ImportFinalityVotes};
 use parity_scale_codec::Encode;
 
 pub struct TestEnclave;
@@ -53,3 +56,17 @@ impl FetchBlocks<SignedSidechainBlock> for MockSidechainBlockFetcher {
 		Ok(Vec::new())
 	}
 }
+
+pub struct MockSidechainFinality;
+
+impl ImportFinalityVotes for MockSidechainFinality {
+	fn import_finality_votes(&self, _votes: Vec<SignedFinalityVote>) -> its_storage::Result<()> {
+		Ok(())
+	}
+}
+
+impl FetchFinalizedBlock for MockSidechainFinality {
+	fn fetch_finalized_block(&self, _shard: &ShardIdentifier) -> Option<FinalizedBlock> {
+		None
+	}
+}
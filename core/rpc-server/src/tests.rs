@@ -16,7 +16,7 @@
 */
 
 use super::*;
-use crate::mock::MockSidechainBlockFetcher;
+use crate::mock::{MockSidechainBlockFetcher, MockSidechainFinality};
 use itp_rpc::RpcResponse;
 use its_rpc_handler::constants::RPC_METHOD_NAME_IMPORT_BLOCKS;
 use its_test::sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait};
@@ -35,10 +35,14 @@ fn init() {
 #[tokio::test]
 async fn test_client_calls() {
 	init();
-	let addr =
-		run_server("127.0.0.1:0", Arc::new(TestEnclave), Arc::new(MockSidechainBlockFetcher))
-			.await
-			.unwrap();
+	let addr = run_server(
+		"127.0.0.1:0",
+		Arc::new(TestEnclave),
+		Arc::new(MockSidechainBlockFetcher),
+		Arc::new(MockSidechainFinality),
+	)
+	.await
+	.unwrap();
 	info!("ServerAddress: {:?}", addr);
 
 	let url = format!("ws://{}", addr);
@@ -30,6 +30,8 @@ pub unsafe extern "C" fn update_market_data_xt(
 	_crypto_currency_size: u32,
 	_fiat_currency_ptr: *const u8,
 	_fiat_currency_size: u32,
+	_exchange_rate_source_ptr: *const u8,
+	_exchange_rate_source_size: u32,
 	_unchecked_extrinsic: *mut u8,
 	_unchecked_extrinsic_size: u32,
 ) -> sgx_types::sgx_status_t {
@@ -44,8 +46,28 @@ pub unsafe extern "C" fn update_weather_data_xt(
 	_weather_info_longitude_size: u32,
 	_weather_info_latitude: *const u8,
 	_weather_info_latitude_size: u32,
+	_weather_info_start_date: *const u8,
+	_weather_info_start_date_size: u32,
+	_weather_info_end_date: *const u8,
+	_weather_info_end_date_size: u32,
 	_unchecked_extrinsic: *mut u8,
 	_unchecked_extrinsic_size: u32,
 ) -> sgx_types::sgx_status_t {
 	unreachable!("Cannot update weather data, teeracle feature is not enabled.")
 }
+
+/// Empty Teeracle spot price implementation.
+#[cfg(not(feature = "teeracle"))]
+#[no_mangle]
+pub unsafe extern "C" fn update_spot_prices_xt(
+	_bidding_zone: *const u8,
+	_bidding_zone_size: u32,
+	_start_date: *const u8,
+	_start_date_size: u32,
+	_end_date: *const u8,
+	_end_date_size: u32,
+	_unchecked_extrinsic: *mut u8,
+	_unchecked_extrinsic_size: u32,
+) -> sgx_types::sgx_status_t {
+	unreachable!("Cannot update spot prices, teeracle feature is not enabled.")
+}
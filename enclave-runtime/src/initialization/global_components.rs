@@ -35,7 +35,8 @@ use ita_sgx_runtime::Runtime;
 use ita_stf::{Getter, State as StfState, Stf, TrustedCallSigned};
 use itc_direct_rpc_server::{
 	rpc_connection_registry::ConnectionRegistry, rpc_responder::RpcResponder,
-	rpc_watch_extractor::RpcWatchExtractor, rpc_ws_handler::RpcWsHandler,
+	rpc_subscriptions::RpcSubscriptions, rpc_watch_extractor::RpcWatchExtractor,
+	rpc_ws_handler::RpcWsHandler,
 };
 use itc_parentchain::{
 	block_import_dispatcher::{
@@ -91,7 +92,10 @@ use its_primitives::{
 use its_sidechain::{
 	aura::block_importer::BlockImporter as SidechainBlockImporter,
 	block_composer::BlockComposer,
-	consensus_common::{BlockImportConfirmationHandler, BlockImportQueueWorker, PeerBlockSync},
+	consensus_common::{
+		BlockImportConfirmationHandler, BlockImportQueueWorker, BlockProductionSuspender,
+		PeerBlockSync, SidechainForks,
+	},
 };
 use lazy_static::lazy_static;
 use sgx_crypto_helper::rsa3072::Rsa3072KeyPair;
@@ -144,6 +148,7 @@ pub type EnclaveRpcWsHandler =
 	RpcWsHandler<RpcWatchExtractor<Hash>, EnclaveRpcConnectionRegistry, Hash>;
 pub type EnclaveWebSocketServer = TungsteniteWsServer<EnclaveRpcWsHandler, FromFileConfigProvider>;
 pub type EnclaveRpcResponder = RpcResponder<EnclaveRpcConnectionRegistry, Hash, RpcResponseChannel>;
+pub type EnclaveHeadSubscriptions = RpcSubscriptions<EnclaveRpcResponder, Hash>;
 pub type EnclaveSidechainApi = SidechainApi<ParentchainBlock, EnclaveTrustedCallSigned>;
 
 // Parentchain types relevant for all parentchains
@@ -297,12 +302,14 @@ pub type EnclaveSidechainBlockImporter = SidechainBlockImporter<
 	EnclaveStateHandler,
 	EnclaveStateKeyRepository,
 	EnclaveTopPoolAuthor,
+	EnclaveHeadSubscriptions,
 	// For now the sidechain does only support one parentchain.
 	IntegriteeParentchainTriggeredBlockImportDispatcher,
 	EnclaveTrustedCallSigned,
 	EnclaveGetter,
 >;
 pub type EnclaveSidechainBlockImportQueue = ImportQueue<SignedSidechainBlock>;
+pub type EnclaveSidechainForks = SidechainForks<SignedSidechainBlock>;
 pub type EnclaveBlockImportConfirmationHandler = BlockImportConfirmationHandler<
 	ParentchainBlock,
 	<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as SidechainBlockTrait>::HeaderType,
@@ -316,6 +323,7 @@ pub type EnclaveSidechainBlockSyncer = PeerBlockSync<
 	EnclaveSidechainBlockImporter,
 	EnclaveOCallApi,
 	EnclaveBlockImportConfirmationHandler,
+	BlockProductionSuspender,
 >;
 pub type EnclaveSidechainBlockImportQueueWorker = BlockImportQueueWorker<
 	ParentchainBlock,
@@ -443,6 +451,10 @@ pub static GLOBAL_TARGET_B_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<
 pub static GLOBAL_RPC_WS_HANDLER_COMPONENT: ComponentContainer<EnclaveRpcWsHandler> =
 	ComponentContainer::new("rpc_ws_handler");
 
+/// Subscriptions to the headers of the imported sidechain blocks.
+pub static GLOBAL_HEAD_SUBSCRIPTIONS_COMPONENT: ComponentContainer<EnclaveHeadSubscriptions> =
+	ComponentContainer::new("head_subscriptions");
+
 /// Sidechain import queue.
 pub static GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockImportQueue,
@@ -462,3 +474,12 @@ pub static GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT: ComponentContainer<
 pub static GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockSyncer,
 > = ComponentContainer::new("sidechain_block_syncer");
+
+/// Sidechain forks, shared by the block import and the block production.
+pub static GLOBAL_SIDECHAIN_FORKS_COMPONENT: ComponentContainer<EnclaveSidechainForks> =
+	ComponentContainer::new("sidechain_forks");
+
+/// Sidechain block production suspender, block production is suspended while syncing from a peer.
+pub static GLOBAL_SIDECHAIN_BLOCK_PRODUCTION_SUSPENDER_COMPONENT: ComponentContainer<
+	BlockProductionSuspender,
+> = ComponentContainer::new("sidechain_block_production_suspender");
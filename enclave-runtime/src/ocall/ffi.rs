@@ -107,6 +107,22 @@ extern "C" {
 		sidechain_blocks_size: u32,
 	) -> sgx_status_t;
 
+	pub fn ocall_get_peer_count(ret_val: *mut sgx_status_t, peer_count: *mut u32) -> sgx_status_t;
+
+	pub fn ocall_propose_finality_votes(
+		ret_val: *mut sgx_status_t,
+		signed_votes: *const u8,
+		signed_votes_size: u32,
+	) -> sgx_status_t;
+
+	pub fn ocall_query_sidechain_blocks(
+		ret_val: *mut sgx_status_t,
+		query: *const u8,
+		query_size: u32,
+		block_summaries: *mut u8,
+		block_summaries_size: u32,
+	) -> sgx_status_t;
+
 	pub fn ocall_send_to_parentchain(
 		ret_val: *mut sgx_status_t,
 		extrinsics: *const u8,
@@ -109,4 +109,70 @@ impl EnclaveSidechainOCallApi for OcallApi {
 
 		Ok(decoded_signed_blocks)
 	}
+
+	fn get_peer_count(&self) -> SgxResult<u32> {
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let mut peer_count: u32 = 0;
+
+		let res = unsafe {
+			ffi::ocall_get_peer_count(&mut rt as *mut sgx_status_t, &mut peer_count as *mut u32)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Ok(peer_count)
+	}
+
+	fn propose_finality_votes<SignedFinalityVote: Encode>(
+		&self,
+		signed_votes: Vec<SignedFinalityVote>,
+	) -> SgxResult<()> {
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let signed_votes_encoded = signed_votes.encode();
+
+		let res = unsafe {
+			ffi::ocall_propose_finality_votes(
+				&mut rt as *mut sgx_status_t,
+				signed_votes_encoded.as_ptr(),
+				signed_votes_encoded.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Ok(())
+	}
+
+	fn query_sidechain_blocks<BlockQuery: Encode, BlockSummary: Decode>(
+		&self,
+		query: BlockQuery,
+	) -> SgxResult<Vec<BlockSummary>> {
+		const BLOCK_BUFFER_SIZE: usize = 262144; // Buffer size for block summaries in bytes (256KB).
+
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let query_encoded = query.encode();
+
+		// We have to pre-allocate the vector and hope it's large enough (see GitHub issue #621).
+		let mut block_summaries_encoded: Vec<u8> = vec![0; BLOCK_BUFFER_SIZE];
+
+		let res = unsafe {
+			ffi::ocall_query_sidechain_blocks(
+				&mut rt as *mut sgx_status_t,
+				query_encoded.as_ptr(),
+				query_encoded.len() as u32,
+				block_summaries_encoded.as_mut_ptr(),
+				block_summaries_encoded.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Decode::decode(&mut block_summaries_encoded.as_slice()).map_err(|e| {
+			error!("Failed to decode sidechain block summaries: {}", e);
+			sgx_status_t::SGX_ERROR_UNEXPECTED
+		})
+	}
 }
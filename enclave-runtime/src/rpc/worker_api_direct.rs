@@ -20,26 +20,71 @@ use crate::{
 		generate_dcap_ra_extrinsic_from_quote_internal,
 		generate_ias_ra_extrinsic_from_der_cert_internal,
 	},
-	utils::get_validator_accessor_from_solo_or_parachain,
+	error::Result as EnclaveResult,
+	initialization::global_components::{
+		EnclaveValidatorAccessor, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_PRODUCTION_SUSPENDER_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+	},
+	utils::{
+		get_validator_accessor_from_solo_or_parachain,
+		get_validator_accessor_from_target_a_solo_or_parachain,
+		get_validator_accessor_from_target_b_solo_or_parachain,
+	},
 };
-use codec::Encode;
+use codec::{Decode, Encode};
 use core::result::Result;
-use ita_sgx_runtime::Runtime;
+use ita_sgx_runtime::{Runtime, VERSION};
 use ita_stf::{Getter, TrustedCallSigned};
-use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, ExtrinsicSender};
+use itc_direct_rpc_server::NotifySubscribers;
+use itc_parentchain::light_client::{
+	concurrent_access::ValidatorAccess, ExtrinsicSender, LightClientState,
+};
+use itp_component_container::ComponentGetter;
+use itp_ocall_api::EnclaveSidechainOCallApi;
 use itp_primitives_cache::{GetPrimitives, GLOBAL_PRIMITIVES_CACHE};
 use itp_rpc::RpcReturnValue;
 use itp_sgx_crypto::key_repository::AccessPubkey;
 use itp_stf_executor::getter_executor::ExecuteGetter;
+use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_top_pool_author::traits::AuthorApi;
-use itp_types::{DirectRequestStatus, Request, ShardIdentifier, H256};
+use itp_types::{
+	BlockNumber, ChainHead, DirectRequestStatus, Request, ShardIdentifier, SidechainBlockNumber,
+	WorkerHealth, H256,
+};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
-use its_primitives::types::block::SignedBlock;
-use its_sidechain::rpc_handler::{direct_top_pool_api, import_block_api};
+use its_primitives::types::block::{BlockQuery, BlockSummary, SignedBlock};
+use its_sidechain::{
+	consensus_common::IsBlockProductionSuspended,
+	rpc_handler::{
+		constants::{
+			RPC_METHOD_NAME_GET_BLOCKS_IN_RANGE, RPC_METHOD_NAME_GET_BLOCK_BY_HASH,
+			RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER, RPC_METHOD_NAME_GET_LATEST_BLOCK,
+		},
+		direct_top_pool_api, import_block_api,
+	},
+	state::SidechainSystemExt,
+};
 use jsonrpc_core::{serde_json::json, IoHandler, Params, Value};
+use log::*;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
-use sp_runtime::OpaqueExtrinsic;
-use std::{borrow::ToOwned, format, str, string::String, sync::Arc, vec::Vec};
+use sp_core::hashing::blake2_256;
+use sp_runtime::{traits::Header as HeaderTrait, OpaqueExtrinsic};
+use std::{
+	borrow::ToOwned,
+	format, str,
+	string::String,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	vec::Vec,
+};
+
+/// Maximum number of blocks returned by a single `sidechain_getBlocksInRange` request. Fewer
+/// blocks are returned if they don't fit into the o-call buffer, the client pages on from the
+/// last returned block number.
+const MAX_BLOCKS_PER_RANGE_REQUEST: SidechainBlockNumber = 100;
+
 fn compute_hex_encoded_return_error(error_msg: &str) -> String {
 	RpcReturnValue::from_error_message(error_msg).to_hex()
 }
@@ -54,20 +99,22 @@ fn get_all_rpc_methods_string(io_handler: &IoHandler) -> String {
 	format!("methods: [{}]", method_string)
 }
 
-pub fn public_api_rpc_handler<Author, GetterExecutor, AccessShieldingKey>(
+pub fn public_api_rpc_handler<Author, GetterExecutor, AccessShieldingKey, HeadSubscriptions>(
 	top_pool_author: Arc<Author>,
 	getter_executor: Arc<GetterExecutor>,
 	shielding_key: Arc<AccessShieldingKey>,
+	head_subscriptions: Arc<HeadSubscriptions>,
 ) -> IoHandler
 where
 	Author: AuthorApi<H256, H256, TrustedCallSigned, Getter> + Send + Sync + 'static,
 	GetterExecutor: ExecuteGetter + Send + Sync + 'static,
 	AccessShieldingKey: AccessPubkey<KeyType = Rsa3072PubKey> + Send + Sync + 'static,
+	HeadSubscriptions: NotifySubscribers<Hash = H256> + 'static,
 {
 	let io = IoHandler::new();
 
 	// Add direct TOP pool rpc methods
-	let mut io = direct_top_pool_api::add_top_pool_direct_rpc_methods(top_pool_author, io);
+	let mut io = direct_top_pool_api::add_top_pool_direct_rpc_methods(top_pool_author.clone(), io);
 
 	// author_getShieldingKey
 	let rsa_pubkey_name: &str = "author_getShieldingKey";
@@ -123,9 +170,18 @@ where
 
 	// chain_subscribeAllHeads
 	let chain_subscribe_all_heads_name: &str = "chain_subscribeAllHeads";
-	io.add_sync_method(chain_subscribe_all_heads_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+	let subscription_counter = AtomicU64::new(0);
+	io.add_sync_method(chain_subscribe_all_heads_name, move |_: Params| {
+		// The connection is registered with the subscription id, once the response is sent.
+		// The headers of the imported sidechain blocks are sent as updates of this response.
+		let subscription_number = subscription_counter.fetch_add(1, Ordering::SeqCst);
+		let subscription_id =
+			H256::from(blake2_256(&(chain_subscribe_all_heads_name, subscription_number).encode()));
+		head_subscriptions.subscribe(subscription_id);
+
+		let json_value =
+			RpcReturnValue::new(subscription_id.encode(), true, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// state_getMetadata
@@ -139,8 +195,8 @@ where
 	// state_getRuntimeVersion
 	let state_get_runtime_version_name: &str = "state_getRuntimeVersion";
 	io.add_sync_method(state_get_runtime_version_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value = RpcReturnValue::new(VERSION.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// state_executeGetter
@@ -190,11 +246,71 @@ where
 		Ok(json!(json_value))
 	});
 
+	// sidechain_getBlockByNumber, params: hex encoded (shard, block number)
+	io.add_sync_method(RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER, |params: Params| {
+		let json_value = match query_sidechain_blocks_inner(
+			params,
+			|(shard, block_number): (ShardIdentifier, SidechainBlockNumber)| {
+				BlockQuery::ByNumber(shard, block_number)
+			},
+		) {
+			Ok(blocks) =>
+				RpcReturnValue::new(blocks.first().encode(), false, DirectRequestStatus::Ok)
+					.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+
+	// sidechain_getBlockByHash, params: hex encoded block hash
+	io.add_sync_method(RPC_METHOD_NAME_GET_BLOCK_BY_HASH, |params: Params| {
+		let json_value = match query_sidechain_blocks_inner(params, BlockQuery::ByHash) {
+			Ok(blocks) =>
+				RpcReturnValue::new(blocks.first().encode(), false, DirectRequestStatus::Ok)
+					.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+
+	// sidechain_getLatestBlock, params: hex encoded shard
+	io.add_sync_method(RPC_METHOD_NAME_GET_LATEST_BLOCK, |params: Params| {
+		let json_value = match query_sidechain_blocks_inner(params, BlockQuery::Latest) {
+			Ok(blocks) =>
+				RpcReturnValue::new(blocks.first().encode(), false, DirectRequestStatus::Ok)
+					.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+
+	// sidechain_getBlocksInRange, params: hex encoded (shard, from, until), both bounds included
+	io.add_sync_method(RPC_METHOD_NAME_GET_BLOCKS_IN_RANGE, |params: Params| {
+		let json_value = match query_sidechain_blocks_inner(
+			params,
+			|(shard, from, until): (
+				ShardIdentifier,
+				SidechainBlockNumber,
+				SidechainBlockNumber,
+			)| {
+				// Larger ranges have to be paged through by the client.
+				let until = until.min(from.saturating_add(MAX_BLOCKS_PER_RANGE_REQUEST - 1));
+				BlockQuery::NumberRange(shard, from, until)
+			},
+		) {
+			Ok(blocks) =>
+				RpcReturnValue::new(blocks.encode(), false, DirectRequestStatus::Ok).to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+
 	// system_health
 	let state_health_name: &str = "system_health";
-	io.add_sync_method(state_health_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+	io.add_sync_method(state_health_name, move |_: Params| {
+		let health = get_worker_health(top_pool_author.as_ref());
+		let json_value = RpcReturnValue::new(health.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// system_name
@@ -220,6 +336,113 @@ where
 	io
 }
 
+/// Collects the health of the worker. Components, which are not initialized (e.g. a target
+/// parentchain that is not configured), are left out.
+fn get_worker_health<Author>(top_pool_author: &Author) -> WorkerHealth
+where
+	Author: AuthorApi<H256, H256, TrustedCallSigned, Getter>,
+{
+	let block_production_suspended = GLOBAL_SIDECHAIN_BLOCK_PRODUCTION_SUSPENDER_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))
+		.and_then(|suspender| suspender.is_suspended().map_err(|e| format!("{:?}", e)))
+		.unwrap_or_else(|e| {
+			debug!("Could not query block production suspension: {}", e);
+			false
+		});
+
+	let top_pool_size = top_pool_author
+		.get_shards()
+		.into_iter()
+		.map(|shard| top_pool_author.get_status(shard))
+		.map(|status| status.ready + status.future)
+		.sum::<usize>() as u32;
+
+	let peer_count = GLOBAL_OCALL_API_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))
+		.and_then(|ocall_api| ocall_api.get_peer_count().map_err(|e| format!("{:?}", e)))
+		.unwrap_or_else(|e| {
+			warn!("Could not get the peer count: {}", e);
+			0
+		});
+
+	WorkerHealth {
+		block_production_suspended,
+		integritee_parentchain_head: latest_parentchain_head(
+			get_validator_accessor_from_solo_or_parachain(),
+		),
+		target_a_parentchain_head: latest_parentchain_head(
+			get_validator_accessor_from_target_a_solo_or_parachain(),
+		),
+		target_b_parentchain_head: latest_parentchain_head(
+			get_validator_accessor_from_target_b_solo_or_parachain(),
+		),
+		sidechain_heads: latest_sidechain_heads(),
+		top_pool_size,
+		peer_count,
+	}
+}
+
+fn latest_parentchain_head(
+	validator_accessor: EnclaveResult<Arc<EnclaveValidatorAccessor>>,
+) -> Option<ChainHead<BlockNumber>> {
+	let header = validator_accessor
+		.ok()?
+		.execute_on_validator(|v| v.latest_finalized_header())
+		.ok()?;
+	Some(ChainHead { number: *header.number(), hash: header.hash() })
+}
+
+fn latest_sidechain_heads() -> Vec<(ShardIdentifier, ChainHead<SidechainBlockNumber>)> {
+	let state_handler = match GLOBAL_STATE_HANDLER_COMPONENT.get() {
+		Ok(state_handler) => state_handler,
+		Err(_) => return Vec::new(),
+	};
+	let shards = state_handler.list_shards().unwrap_or_default();
+
+	shards
+		.into_iter()
+		.filter_map(|shard| {
+			let head = state_handler
+				.execute_on_current(&shard, |state, _| {
+					Some(ChainHead {
+						number: state.get_block_number()?,
+						hash: state.get_last_block_hash()?,
+					})
+				})
+				.ok()??;
+			Some((shard, head))
+		})
+		.collect()
+}
+
+/// Looks up sidechain blocks in the sidechain storage of the untrusted worker.
+///
+/// The single parameter is hex encoded and converted into the block query.
+fn query_sidechain_blocks_inner<Args: Decode>(
+	params: Params,
+	into_query: impl FnOnce(Args) -> BlockQuery,
+) -> Result<Vec<BlockSummary>, String> {
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+
+	if hex_encoded_params.len() != 1 {
+		return Err(format!(
+			"Wrong number of arguments for sidechain block query: {}, expected: {}",
+			hex_encoded_params.len(),
+			1
+		))
+	}
+
+	let args = Args::from_hex(&hex_encoded_params[0]).map_err(|e| format!("{:?}", e))?;
+
+	GLOBAL_OCALL_API_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))?
+		.query_sidechain_blocks(into_query(args))
+		.map_err(|e| format!("{:?}", e))
+}
+
 fn execute_getter_inner<GE: ExecuteGetter>(
 	getter_executor: &GE,
 	params: Params,
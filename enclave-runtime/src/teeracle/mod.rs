@@ -26,14 +26,23 @@ use crate::{
 use codec::{Decode, Encode};
 use core::slice;
 use ita_oracle::{
-	create_coin_gecko_oracle, create_coin_market_cap_oracle, create_open_meteo_weather_oracle,
+	create_aggregated_exchange_rate_oracle, create_energy_charts_spot_price_oracle,
+	create_open_meteo_weather_oracle,
 	metrics_exporter::ExportMetrics,
 	oracles::{
-		exchange_rate_oracle::{ExchangeRateOracle, GetExchangeRate},
-		weather_oracle::{GetLongitude, WeatherOracle},
+		aggregated_exchange_rate_oracle::{
+			AggregatedExchangeRateOracle, GetAggregatedExchangeRate,
+			AGGREGATED_EXCHANGE_RATE_SOURCE,
+		},
+		spot_price_oracle::{GetSpotPrices, SpotPriceOracle},
+		weather_oracle::{GetWeatherForecast, WeatherOracle},
+	},
+	traits::OracleFeed,
+	types::{
+		SpotPriceQuery, SpotPrices, TradingInfo, TradingPair, WeatherForecast, WeatherInfo,
+		WeatherQuery,
 	},
-	traits::OracleSource,
-	types::{TradingInfo, TradingPair, WeatherInfo, WeatherQuery},
+	ConfigurableSourceConfig,
 };
 use itp_component_container::ComponentGetter;
 use itp_extrinsics_factory::CreateExtrinsics;
@@ -53,34 +62,36 @@ fn update_weather_data_internal(weather_info: WeatherInfo) -> Result<Vec<OpaqueE
 
 	let open_meteo_weather_oracle = create_open_meteo_weather_oracle(ocall_api);
 
-	match get_longitude(weather_info, open_meteo_weather_oracle) {
+	match get_weather_forecast(weather_info, open_meteo_weather_oracle) {
 		Ok(opaque_call) => extrinsic_calls.push(opaque_call),
 		Err(e) => {
-			error!("[-] Failed to get the newest longitude from OpenMeteo. {:?}", e);
+			error!("[-] Failed to get the newest weather forecast from OpenMeteo. {:?}", e);
 		},
 	};
 	let extrinsics = extrinsics_factory.create_extrinsics(extrinsic_calls.as_slice(), None)?;
 	Ok(extrinsics)
 }
 
-fn get_longitude<OracleSourceType, MetricsExporter>(
+fn get_weather_forecast<OracleSourceType, MetricsExporter>(
 	weather_info: WeatherInfo,
 	oracle: WeatherOracle<OracleSourceType, MetricsExporter>,
 ) -> Result<OpaqueCall>
 where
-	OracleSourceType: OracleSource<
-		WeatherInfo,
-		OracleRequestResult = std::result::Result<f32, ita_oracle::error::Error>,
-	>,
+	OracleSourceType: OracleFeed<WeatherQuery, Value = WeatherForecast>,
 	MetricsExporter: ExportMetrics<WeatherInfo>,
 {
-	let longitude =
-		oracle.get_longitude(weather_info.clone()).map_err(|e| Error::Other(e.into()))?;
-
-	let base_url = oracle.get_base_url().map_err(|e| Error::Other(e.into()))?;
+	let (forecast, base_url) = oracle
+		.get_weather_forecast(weather_info.clone())
+		.map_err(|e| Error::Other(e.into()))?;
 	let source_base_url = base_url.as_str();
 
-	println!("Update the longitude:  {}, for source {}", longitude, source_base_url);
+	let weather_query_key = weather_info.weather_query.key();
+	println!(
+		"Update the weather forecast of {}: {} hours, for source {}",
+		weather_query_key,
+		forecast.hourly.len(),
+		source_base_url
+	);
 
 	let node_metadata_repository =
 		get_node_metadata_repository_from_integritee_solo_or_parachain()?;
@@ -92,9 +103,9 @@ where
 
 	let call = OpaqueCall::from_tuple(&(
 		call_ids,
-		weather_info.weather_query.key().as_bytes().to_vec(),
+		weather_query_key.as_bytes().to_vec(),
 		source_base_url.as_bytes().to_vec(),
-		longitude.encode(),
+		forecast.encode(),
 	));
 
 	Ok(call)
@@ -106,6 +117,10 @@ pub unsafe extern "C" fn update_weather_data_xt(
 	weather_info_longitude_size: u32,
 	weather_info_latitude: *const u8,
 	weather_info_latitude_size: u32,
+	weather_info_start_date: *const u8,
+	weather_info_start_date_size: u32,
+	weather_info_end_date: *const u8,
+	weather_info_end_date_size: u32,
 	unchecked_extrinsic: *mut u8,
 	unchecked_extrinsic_size: u32,
 ) -> sgx_status_t {
@@ -129,7 +144,27 @@ pub unsafe extern "C" fn update_weather_data_xt(
 		},
 	};
 
-	let weather_query = WeatherQuery { longitude, latitude, hourly: " ".into() };
+	let mut weather_info_start_date_slice =
+		slice::from_raw_parts(weather_info_start_date, weather_info_start_date_size as usize);
+	let start_date = match String::decode(&mut weather_info_start_date_slice) {
+		Ok(val) => val,
+		Err(e) => {
+			error!("Could not decode start date: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let mut weather_info_end_date_slice =
+		slice::from_raw_parts(weather_info_end_date, weather_info_end_date_size as usize);
+	let end_date = match String::decode(&mut weather_info_end_date_slice) {
+		Ok(val) => val,
+		Err(e) => {
+			error!("Could not decode end date: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let weather_query = WeatherQuery { longitude, latitude, start_date, end_date };
 	let weather_info = WeatherInfo { weather_query };
 
 	let extrinsics = match update_weather_data_internal(weather_info) {
@@ -152,13 +187,126 @@ pub unsafe extern "C" fn update_weather_data_xt(
 	sgx_status_t::SGX_SUCCESS
 }
 
-/// For now get the crypto/fiat currency exchange rate from coingecko and CoinMarketCap.
+fn update_spot_prices_internal(query: SpotPriceQuery) -> Result<Vec<OpaqueExtrinsic>> {
+	let extrinsics_factory = get_extrinsic_factory_from_integritee_solo_or_parachain()?;
+	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
+
+	let spot_price_oracle = create_energy_charts_spot_price_oracle(ocall_api);
+
+	let extrinsic_calls = vec![get_spot_prices(query, spot_price_oracle)?];
+	let extrinsics = extrinsics_factory.create_extrinsics(extrinsic_calls.as_slice(), None)?;
+	Ok(extrinsics)
+}
+
+/// Publishes the spot prices with the oracle name of the bidding zone, such that the energy
+/// STF takes the latest published prices as reserve prices of the market.
+fn get_spot_prices<OracleSourceType, MetricsExporter>(
+	query: SpotPriceQuery,
+	oracle: SpotPriceOracle<OracleSourceType, MetricsExporter>,
+) -> Result<OpaqueCall>
+where
+	OracleSourceType: OracleFeed<SpotPriceQuery, Value = SpotPrices>,
+	MetricsExporter: ExportMetrics<SpotPriceQuery>,
+{
+	let (spot_prices, base_url) =
+		oracle.get_spot_prices(query.clone()).map_err(|e| Error::Other(e.into()))?;
+	let source_base_url = base_url.as_str();
+
+	println!(
+		"Update the spot prices of {}: {} hours, for source {}",
+		query.clone().key(),
+		spot_prices.hourly.len(),
+		source_base_url
+	);
+
+	let node_metadata_repository =
+		get_node_metadata_repository_from_integritee_solo_or_parachain()?;
+
+	let call_ids = node_metadata_repository
+		.get_from_metadata(|m| m.update_oracle_call_indexes())
+		.map_err(Error::NodeMetadataProvider)?
+		.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+
+	let call = OpaqueCall::from_tuple(&(
+		call_ids,
+		query.oracle_name().as_bytes().to_vec(),
+		source_base_url.as_bytes().to_vec(),
+		spot_prices.encode(),
+	));
+
+	Ok(call)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn update_spot_prices_xt(
+	bidding_zone: *const u8,
+	bidding_zone_size: u32,
+	start_date: *const u8,
+	start_date_size: u32,
+	end_date: *const u8,
+	end_date_size: u32,
+	unchecked_extrinsic: *mut u8,
+	unchecked_extrinsic_size: u32,
+) -> sgx_status_t {
+	let mut bidding_zone_slice = slice::from_raw_parts(bidding_zone, bidding_zone_size as usize);
+	let bidding_zone = match String::decode(&mut bidding_zone_slice) {
+		Ok(val) => val,
+		Err(e) => {
+			error!("Could not decode bidding zone: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let mut start_date_slice = slice::from_raw_parts(start_date, start_date_size as usize);
+	let start_date = match String::decode(&mut start_date_slice) {
+		Ok(val) => val,
+		Err(e) => {
+			error!("Could not decode start date: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let mut end_date_slice = slice::from_raw_parts(end_date, end_date_size as usize);
+	let end_date = match String::decode(&mut end_date_slice) {
+		Ok(val) => val,
+		Err(e) => {
+			error!("Could not decode end date: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let query = SpotPriceQuery { bidding_zone, start_date, end_date };
+
+	let extrinsics = match update_spot_prices_internal(query) {
+		Ok(xts) => xts,
+		Err(e) => {
+			error!("Updating spot prices failed: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
+
+	let extrinsic_slice =
+		slice::from_raw_parts_mut(unchecked_extrinsic, unchecked_extrinsic_size as usize);
+
+	// Save created extrinsic as slice in the return value unchecked_extrinsic.
+	if let Err(e) = write_slice_and_whitespace_pad(extrinsic_slice, extrinsics.encode()) {
+		error!("Copying encoded extrinsics into return slice failed: {:?}", e);
+		return sgx_status_t::SGX_ERROR_UNEXPECTED
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+/// Get the crypto/fiat currency exchange rate that coingecko and CoinMarketCap agree on,
+/// or the one of the exchange rate source configured by the worker.
 #[no_mangle]
 pub unsafe extern "C" fn update_market_data_xt(
 	crypto_currency_ptr: *const u8,
 	crypto_currency_size: u32,
 	fiat_currency_ptr: *const u8,
 	fiat_currency_size: u32,
+	exchange_rate_source_ptr: *const u8,
+	exchange_rate_source_size: u32,
 	unchecked_extrinsic: *mut u8,
 	unchecked_extrinsic_size: u32,
 ) -> sgx_status_t {
@@ -170,13 +318,19 @@ pub unsafe extern "C" fn update_market_data_xt(
 		slice::from_raw_parts(fiat_currency_ptr, fiat_currency_size as usize);
 	let fiat_currency: String = Decode::decode(&mut fiat_currency_slice).unwrap();
 
-	let extrinsics = match update_market_data_internal(crypto_currency, fiat_currency) {
-		Ok(xts) => xts,
-		Err(e) => {
-			error!("Update market data failed: {:?}", e);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED
-		},
-	};
+	let mut exchange_rate_source_slice =
+		slice::from_raw_parts(exchange_rate_source_ptr, exchange_rate_source_size as usize);
+	let exchange_rate_source: Option<String> =
+		Decode::decode(&mut exchange_rate_source_slice).unwrap();
+
+	let extrinsics =
+		match update_market_data_internal(crypto_currency, fiat_currency, exchange_rate_source) {
+			Ok(xts) => xts,
+			Err(e) => {
+				error!("Update market data failed: {:?}", e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		};
 
 	if extrinsics.is_empty() {
 		error!("Updating market data yielded no extrinsics");
@@ -197,71 +351,76 @@ pub unsafe extern "C" fn update_market_data_xt(
 fn update_market_data_internal(
 	crypto_currency: String,
 	fiat_currency: String,
+	exchange_rate_source: Option<String>,
 ) -> Result<Vec<OpaqueExtrinsic>> {
 	let extrinsics_factory = get_extrinsic_factory_from_integritee_solo_or_parachain()?;
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 
-	let mut extrinsic_calls: Vec<OpaqueCall> = Vec::new();
+	let configured_source = exchange_rate_source
+		.map(|source| ConfigurableSourceConfig::from_json(&source))
+		.transpose()
+		.map_err(|e| Error::Other(e.into()))?;
 
 	// Get the exchange rate
 	let trading_pair = TradingPair { crypto_currency, fiat_currency };
 
-	let coin_gecko_oracle = create_coin_gecko_oracle(ocall_api.clone());
+	let aggregated_oracle = create_aggregated_exchange_rate_oracle(ocall_api, configured_source);
 
-	match get_exchange_rate(trading_pair.clone(), coin_gecko_oracle) {
-		Ok(opaque_call) => extrinsic_calls.push(opaque_call),
-		Err(e) => {
-			error!("[-] Failed to get the newest exchange rate from CoinGecko. {:?}", e);
-		},
-	};
-
-	let coin_market_cap_oracle = create_coin_market_cap_oracle(ocall_api);
-	match get_exchange_rate(trading_pair, coin_market_cap_oracle) {
-		Ok(oc) => extrinsic_calls.push(oc),
-		Err(e) => {
-			error!("[-] Failed to get the newest exchange rate from CoinMarketCap. {:?}", e);
-		},
-	};
+	let extrinsic_calls = get_aggregated_exchange_rate(trading_pair, aggregated_oracle)?;
 
 	let extrinsics = extrinsics_factory.create_extrinsics(extrinsic_calls.as_slice(), None)?;
 	Ok(extrinsics)
 }
 
-fn get_exchange_rate<OracleSourceType, MetricsExporter>(
+/// Publishes the median exchange rate of the agreeing sources once, under the data source name
+/// `AGGREGATED_EXCHANGE_RATE_SOURCE`, together with the number of agreeing sources as oracle data.
+/// The enclave has to be whitelisted for that data source name on the parentchain.
+fn get_aggregated_exchange_rate<MetricsExporter>(
 	trading_pair: TradingPair,
-	oracle: ExchangeRateOracle<OracleSourceType, MetricsExporter>,
-) -> Result<OpaqueCall>
+	oracle: AggregatedExchangeRateOracle<MetricsExporter>,
+) -> Result<Vec<OpaqueCall>>
 where
-	OracleSourceType: OracleSource<TradingInfo>,
 	MetricsExporter: ExportMetrics<TradingInfo>,
 {
-	let (rate, base_url) = oracle
-		.get_exchange_rate(trading_pair.clone())
+	let (aggregated, agreeing_sources) = oracle
+		.get_aggregated_exchange_rate(trading_pair.clone())
 		.map_err(|e| Error::Other(e.into()))?;
 
-	let source_base_url = base_url.as_str();
-
 	println!(
-		"Update the exchange rate:  {} = {:?} for source {}",
+		"Update the exchange rate:  {} = {:?}, {} of {} sources agree: {:?}",
 		trading_pair.clone().key(),
-		rate,
-		source_base_url,
+		aggregated.exchange_rate,
+		aggregated.agreeing_sources,
+		aggregated.queried_sources,
+		agreeing_sources,
 	);
 
 	let node_metadata_repository =
 		get_node_metadata_repository_from_integritee_solo_or_parachain()?;
 
-	let call_ids = node_metadata_repository
+	let exchange_rate_call_ids = node_metadata_repository
 		.get_from_metadata(|m| m.update_exchange_rate_call_indexes())
 		.map_err(Error::NodeMetadataProvider)?
 		.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
 
-	let call = OpaqueCall::from_tuple(&(
-		call_ids,
-		source_base_url.as_bytes().to_vec(),
-		trading_pair.key().as_bytes().to_vec(),
-		Some(rate),
-	));
+	let oracle_call_ids = node_metadata_repository
+		.get_from_metadata(|m| m.update_oracle_call_indexes())
+		.map_err(Error::NodeMetadataProvider)?
+		.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
 
-	Ok(call)
+	let source = AGGREGATED_EXCHANGE_RATE_SOURCE.as_bytes().to_vec();
+	Ok(vec![
+		OpaqueCall::from_tuple(&(
+			exchange_rate_call_ids,
+			source.clone(),
+			trading_pair.clone().key().as_bytes().to_vec(),
+			Some(aggregated.exchange_rate),
+		)),
+		OpaqueCall::from_tuple(&(
+			oracle_call_ids,
+			trading_pair.key().as_bytes().to_vec(),
+			source,
+			aggregated.encode(),
+		)),
+	])
 }
@@ -16,12 +16,17 @@
 
 */
 
-use crate::{rpc::worker_api_direct::public_api_rpc_handler, Hash};
+use crate::{
+	rpc::worker_api_direct::public_api_rpc_handler,
+	test::mocks::types::{TestHeadSubscriptions, TestRpcResponder},
+	Hash,
+};
 use codec::{Decode, Encode};
+use ita_sgx_runtime::VERSION;
 use ita_stf::{Getter, TrustedGetter, TrustedGetterSigned};
 use itc_direct_rpc_server::{
 	create_determine_watch, rpc_connection_registry::ConnectionRegistry,
-	rpc_ws_handler::RpcWsHandler,
+	rpc_ws_handler::RpcWsHandler, RpcConnectionRegistry,
 };
 use itc_tls_websocket_server::{ConnectionToken, WebSocketMessageHandler};
 use itp_rpc::{RpcRequest, RpcReturnValue};
@@ -32,7 +37,7 @@ use itp_stf_state_observer::mock::ObserveStateMock;
 use itp_top_pool_author::mocks::AuthorApiMock;
 use itp_types::{AccountId, DirectRequestStatus, Request, ShardIdentifier};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
-use sp_core::ed25519::Signature;
+use sp_core::{ed25519::Signature, hashing::blake2_256};
 use sp_runtime::MultiSignature;
 use std::{string::ToString, sync::Arc, vec::Vec};
 
@@ -50,9 +55,15 @@ pub fn get_state_request_works() {
 	let getter_executor =
 		Arc::new(GetterExecutor::<_, GetStateMock<TestState>, Getter>::new(state_observer));
 	let top_pool_author = Arc::new(AuthorApiMock::default());
-
-	let io_handler =
-		public_api_rpc_handler(top_pool_author, getter_executor, Arc::new(rsa_repository));
+	let head_subscriptions =
+		Arc::new(TestHeadSubscriptions::new(Arc::new(TestRpcResponder::new())));
+
+	let io_handler = public_api_rpc_handler(
+		top_pool_author,
+		getter_executor,
+		Arc::new(rsa_repository),
+		head_subscriptions,
+	);
 	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
 
 	let getter = Getter::trusted(TrustedGetterSigned::new(
@@ -84,3 +95,70 @@ pub fn get_state_request_works() {
 		Option::decode(&mut rpc_return_value.value.as_slice()).unwrap();
 	assert_eq!(decoded_value, Some(state.encode()));
 }
+
+pub fn subscribe_all_heads_registers_connection() {
+	let temp_dir = TempDir::with_prefix("subscribe_all_heads_registers_connection").unwrap();
+
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+	let rsa_repository = get_rsa3072_repository(temp_dir.path().to_path_buf()).unwrap();
+	let state_observer = Arc::new(ObserveStateMock::<u64>::new(0));
+	let getter_executor =
+		Arc::new(GetterExecutor::<_, GetStateMock<u64>, Getter>::new(state_observer));
+	let head_subscriptions =
+		Arc::new(TestHeadSubscriptions::new(Arc::new(TestRpcResponder::new())));
+
+	let io_handler = public_api_rpc_handler(
+		Arc::new(AuthorApiMock::default()),
+		getter_executor,
+		Arc::new(rsa_repository),
+		head_subscriptions.clone(),
+	);
+	let rpc_handler =
+		Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry.clone()));
+
+	let request_string =
+		RpcRequest::compose_jsonrpc_call("chain_subscribeAllHeads".to_string(), vec![]).unwrap();
+
+	let response_string =
+		rpc_handler.handle_message(ConnectionToken(1), request_string).unwrap().unwrap();
+
+	assert!(!response_string.is_empty());
+	assert_eq!(head_subscriptions.number_of_subscriptions(), 1);
+
+	// The first subscription id of the handler.
+	let subscription_id = Hash::from(blake2_256(&("chain_subscribeAllHeads", 0u64).encode()));
+	let (connection, _) = connection_registry.withdraw(&subscription_id).unwrap();
+	assert_eq!(connection, ConnectionToken(1));
+}
+
+pub fn get_runtime_version_request_works() {
+	let temp_dir = TempDir::with_prefix("get_runtime_version_request_works").unwrap();
+
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+	let rsa_repository = get_rsa3072_repository(temp_dir.path().to_path_buf()).unwrap();
+	let state_observer = Arc::new(ObserveStateMock::<u64>::new(0));
+	let getter_executor =
+		Arc::new(GetterExecutor::<_, GetStateMock<u64>, Getter>::new(state_observer));
+	let head_subscriptions =
+		Arc::new(TestHeadSubscriptions::new(Arc::new(TestRpcResponder::new())));
+
+	let io_handler = public_api_rpc_handler(
+		Arc::new(AuthorApiMock::default()),
+		getter_executor,
+		Arc::new(rsa_repository),
+		head_subscriptions,
+	);
+	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
+
+	let request_string =
+		RpcRequest::compose_jsonrpc_call("state_getRuntimeVersion".to_string(), vec![]).unwrap();
+
+	let response_string =
+		rpc_handler.handle_message(ConnectionToken(1), request_string).unwrap().unwrap();
+
+	let expected_return_value =
+		RpcReturnValue::new(VERSION.encode(), false, DirectRequestStatus::Ok).to_hex();
+	assert!(response_string.contains(&expected_return_value));
+}
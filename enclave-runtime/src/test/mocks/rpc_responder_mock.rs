@@ -46,4 +46,16 @@ where
 	fn send_state(&self, _hash: Self::Hash, _state_encoded: Vec<u8>) -> DirectRpcResult<()> {
 		Ok(())
 	}
+
+	fn send_execution_error(
+		&self,
+		_hash: Self::Hash,
+		_encoded_error: Vec<u8>,
+	) -> DirectRpcResult<()> {
+		Ok(())
+	}
+
+	fn send_subscription_update(&self, _hash: Self::Hash, _value: Vec<u8>) -> DirectRpcResult<()> {
+		Ok(())
+	}
 }
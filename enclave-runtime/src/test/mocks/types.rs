@@ -21,6 +21,7 @@
 use crate::test::mocks::rpc_responder_mock::RpcResponderMock;
 use ita_sgx_runtime::Runtime;
 use ita_stf::{Getter, Stf, TrustedCallSigned};
+use itc_direct_rpc_server::rpc_subscriptions::RpcSubscriptions;
 use itc_parentchain::block_import_dispatcher::trigger_parentchain_block_import_mock::TriggerParentchainBlockImportMock;
 use itp_node_api::metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository};
 use itp_sgx_crypto::{mocks::KeyRepositoryMock, Aes};
@@ -72,6 +73,8 @@ pub type TestStfExecutor = StfExecutor<
 
 pub type TestRpcResponder = RpcResponderMock<H256>;
 
+pub type TestHeadSubscriptions = RpcSubscriptions<TestRpcResponder, H256>;
+
 pub type TestTopPool = BasicPool<
 	SidechainApi<ParentchainBlock, TrustedCallSigned>,
 	ParentchainBlock,
@@ -100,6 +103,7 @@ pub type TestBlockImporter = BlockImporter<
 	HandleStateMock,
 	TestStateKeyRepo,
 	TestTopPoolAuthor,
+	TestHeadSubscriptions,
 	TestParentchainBlockImportTrigger,
 	TrustedCallSigned,
 	Getter,
@@ -119,6 +119,7 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		handle_state_mock::tests::from_shard_works,
 		handle_state_mock::tests::initialize_creates_default_state,
 		handle_state_mock::tests::load_mutate_and_write_works,
+		handle_state_mock::tests::revert_to_restores_written_state,
 		handle_state_mock::tests::ensure_subsequent_state_loads_have_same_hash,
 		handle_state_mock::tests::ensure_encode_and_encrypt_does_not_affect_state_hash,
 		// mra cert tests
@@ -156,6 +157,8 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		tls_ra::tests::test_state_and_key_provisioning,
 		// RPC tests
 		direct_rpc_tests::get_state_request_works,
+		direct_rpc_tests::subscribe_all_heads_registers_connection,
+		direct_rpc_tests::get_runtime_version_request_works,
 
 		// EVM tests
 		run_evm_tests,
@@ -18,7 +18,9 @@
 use crate::{
 	error::Result,
 	initialization::global_components::{
-		GLOBAL_OCALL_API_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
+		EnclaveSidechainForks, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_PRODUCTION_SUSPENDER_COMPONENT, GLOBAL_SIDECHAIN_FORKS_COMPONENT,
 		GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT, GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT,
 		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 	},
@@ -42,24 +44,31 @@ use itp_extrinsics_factory::CreateExtrinsics;
 use itp_ocall_api::{EnclaveOnChainOCallApi, EnclaveSidechainOCallApi};
 use itp_settings::sidechain::SLOT_DURATION;
 use itp_sgx_crypto::key_repository::AccessKey;
-use itp_stf_state_handler::query_shard_state::QueryShardState;
+use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_time_utils::duration_now;
-use itp_types::{Block, OpaqueCall, H256};
+use itp_types::{Block, OpaqueCall, ShardIdentifier, H256};
 use its_primitives::{
 	traits::{
 		Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor, SignedBlock,
 	},
-	types::block::SignedBlock as SignedSidechainBlock,
+	types::{
+		block::{Block as SidechainBlock, SignedBlock as SignedSidechainBlock},
+		finality::{FinalityVote, SignedFinalityVote},
+		Signature,
+	},
 };
 use its_sidechain::{
 	aura::{proposer_factory::ProposerFactory, Aura, SlotClaimStrategy},
-	consensus_common::{Environment, Error as ConsensusError, ProcessBlockImportQueue},
+	consensus_common::{
+		Environment, Error as ConsensusError, IsBlockProductionSuspended, ProcessBlockImportQueue,
+	},
 	slots::{yield_next_slot, LastSlot, PerShardSlotWorkerScheduler, SlotInfo},
+	state::LastBlockExt,
 	validateer_fetch::ValidateerFetch,
 };
 use log::*;
 use sgx_types::sgx_status_t;
-use sp_core::{crypto::UncheckedFrom, Pair};
+use sp_core::{crypto::UncheckedFrom, ed25519, Pair};
 use sp_runtime::{
 	generic::SignedBlock as SignedParentchainBlock, traits::Block as BlockTrait, MultiSignature,
 };
@@ -83,6 +92,7 @@ pub unsafe extern "C" fn execute_trusted_calls() -> sgx_status_t {
 /// *   Import all pending parentchain blocks.
 /// *   Sends sidechain `confirm_block` xt's with the produced sidechain blocks.
 /// *   Broadcast produced sidechain blocks to peer validateers.
+/// *   Broadcast the finality votes for the last sidechain block of each shard.
 fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 	let start_time = Instant::now();
 
@@ -117,12 +127,20 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 		start_time.elapsed().as_millis()
 	);
 
+	// A peer sync that is still ongoing leaves our state behind the other validateers.
+	if GLOBAL_SIDECHAIN_BLOCK_PRODUCTION_SUSPENDER_COMPONENT.get()?.is_suspended()? {
+		info!("Sidechain block production is suspended, skipping block production");
+		return Ok(())
+	}
+
 	let stf_executor = get_stf_executor_from_solo_or_parachain()?;
 
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 
 	let block_composer = GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT.get()?;
 
+	let sidechain_forks = GLOBAL_SIDECHAIN_FORKS_COMPONENT.get()?;
+
 	let extrinsics_factory = get_extrinsic_factory_from_integritee_solo_or_parachain()?;
 
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
@@ -146,10 +164,21 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 			log_remaining_slot_duration(&slot, "Before AURA");
 
 			let shards = state_handler.list_shards()?;
-			let env = ProposerFactory::<Block, _, _, _>::new(
+			let voting_authority = authority.clone();
+			let voting_shards = shards.clone();
+			let state_hashes_before_aura = shards
+				.iter()
+				.filter_map(|shard| {
+					state_handler
+						.execute_on_current(shard, |_, state_hash| (*shard, state_hash))
+						.ok()
+				})
+				.collect();
+			let env = ProposerFactory::<Block, _, _, _, _>::new(
 				top_pool_author,
 				stf_executor,
 				block_composer,
+				sidechain_forks.clone(),
 			);
 
 			let (blocks, opaque_calls) = exec_aura_on_slot::<_, _, SignedSidechainBlock, _, _, _>(
@@ -163,6 +192,12 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 			debug!("Aura executed successfully");
 
+			record_parentchain_import(
+				state_handler.as_ref(),
+				sidechain_forks.as_ref(),
+				state_hashes_before_aura,
+			);
+
 			// Drop lock as soon as we don't need it anymore.
 			drop(_enclave_write_lock);
 
@@ -171,12 +206,19 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 			send_blocks_and_extrinsics::<Block, _, _, _, _>(
 				blocks,
 				opaque_calls,
-				ocall_api,
+				ocall_api.clone(),
 				validator_access.as_ref(),
 				extrinsics_factory.as_ref(),
 			)?;
 
 			log_remaining_slot_duration(&slot, "After broadcasting and sending extrinsic");
+
+			let finality_votes =
+				sign_finality_votes(state_handler.as_ref(), &voting_authority, &voting_shards);
+			// Failing to vote only delays finality, it must not abort the slot.
+			if let Err(e) = ocall_api.propose_finality_votes(finality_votes) {
+				error!("Failed to propose finality votes: {:?}", e);
+			}
 		},
 		None => {
 			debug!("No slot yielded. Skipping block production.");
@@ -274,6 +316,66 @@ where
 	Ok(())
 }
 
+/// Records the state writes of the parentchain import of a slot in the sidechain forks, so they
+/// do not refuse a reorg, which replays them.
+///
+/// Only the parentchain import writes the state during a slot, the proposed blocks are applied
+/// when they are imported.
+pub(crate) fn record_parentchain_import<StateHandler>(
+	state_handler: &StateHandler,
+	sidechain_forks: &EnclaveSidechainForks,
+	state_hashes_before: Vec<(ShardIdentifier, H256)>,
+) where
+	StateHandler: HandleState<HashType = H256>,
+{
+	for (shard, state_hash_before) in state_hashes_before {
+		let result = state_handler
+			.execute_on_current(&shard, |_, state_hash| state_hash)
+			.map_err(|e| format!("{:?}", e))
+			.and_then(|state_hash_after| {
+				sidechain_forks
+					.on_parentchain_imported(&shard, state_hash_before, state_hash_after)
+					.map_err(|e| format!("{:?}", e))
+			});
+		// Failing to record the import only refuses a reorg across it.
+		if let Err(e) = result {
+			error!("Failed to record the parentchain import of shard {:?}: {}", shard, e);
+		}
+	}
+}
+
+/// Signs a finality vote for the last sidechain block of each of the `shards`.
+///
+/// Shards without any sidechain block yet are skipped.
+pub(crate) fn sign_finality_votes<StateHandler, Authority>(
+	state_handler: &StateHandler,
+	authority: &Authority,
+	shards: &[ShardIdentifier],
+) -> Vec<SignedFinalityVote>
+where
+	StateHandler: HandleState,
+	StateHandler::StateT: LastBlockExt<SidechainBlock>,
+	Authority: Pair<Public = ed25519::Public>,
+	Signature: From<Authority::Signature>,
+{
+	shards
+		.iter()
+		.filter_map(|shard| {
+			match state_handler.execute_on_current(shard, |state, _| state.get_last_block()) {
+				Ok(last_block) => last_block,
+				Err(e) => {
+					error!("Failed to load the last sidechain block of shard {:?}: {:?}", shard, e);
+					None
+				},
+			}
+			.map(|block| {
+				FinalityVote::new(*shard, block.header().block_number(), block.hash())
+					.sign(authority)
+			})
+		})
+		.collect()
+}
+
 fn log_remaining_slot_duration<B: BlockTrait<Hash = H256>>(
 	slot_info: &SlotInfo<B>,
 	stage_name: &str,
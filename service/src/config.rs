@@ -21,8 +21,9 @@ use itp_settings::teeracle::{DEFAULT_MARKET_DATA_UPDATE_INTERVAL, ONE_DAY, THIRT
 use parse_duration::parse;
 use serde::{Deserialize, Serialize};
 use std::{
-	fs,
+	fmt, fs,
 	path::{Path, PathBuf},
+	str::FromStr,
 	time::Duration,
 };
 
@@ -33,6 +34,8 @@ static DEFAULT_UNTRUSTED_PORT: &str = "2001";
 static DEFAULT_MU_RA_PORT: &str = "3443";
 static DEFAULT_METRICS_PORT: &str = "8787";
 static DEFAULT_UNTRUSTED_HTTP_PORT: &str = "4545";
+/// Id of the configured exchange rate source in the enclave metrics.
+static EXCHANGE_RATE_SOURCE_METRICS_ID: &str = "configurable";
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Config {
@@ -273,6 +276,8 @@ pub struct RunConfig {
 	teeracle_update_interval: Option<Duration>,
 	/// Optional teeracle reregistration interval
 	reregister_teeracle_interval: Option<Duration>,
+	/// Market data, weather and spot price feeds the teeracle publishes
+	teeracle_feeds: TeeracleFeeds,
 	/// Marblerun's Prometheus endpoint base URL
 	marblerun_base_url: Option<String>,
 }
@@ -307,6 +312,24 @@ impl RunConfig {
 		self.reregister_teeracle_interval.unwrap_or(ONE_DAY - THIRTY_MINUTES)
 	}
 
+	/// The feeds of the teeracle, defaults to the TEER/USD exchange rate if none are configured.
+	/// The exchange rate source is kept in either case.
+	pub fn teeracle_feeds(&self) -> TeeracleFeeds {
+		if self.teeracle_feeds.is_empty() {
+			return TeeracleFeeds {
+				market_data: vec![MarketDataFeed {
+					crypto_currency: "TEER".into(),
+					fiat_currency: "USD".into(),
+					interval: None,
+				}],
+				weather: Vec::new(),
+				spot_price: Vec::new(),
+				exchange_rate_source: self.teeracle_feeds.exchange_rate_source.clone(),
+			}
+		}
+		self.teeracle_feeds.clone()
+	}
+
 	pub fn marblerun_base_url(&self) -> &str {
 		// This conflicts with the default port of a substrate node, but it is indeed the
 		// default port of marblerun too:
@@ -328,6 +351,47 @@ impl From<&ArgMatches<'_>> for RunConfig {
 			parse(i).unwrap_or_else(|e| panic!("teeracle-interval parsing error {:?}", e))
 		});
 
+		let mut teeracle_feeds = m
+			.value_of("teeracle-config")
+			.map(|path| {
+				TeeracleFeeds::from_file(Path::new(path))
+					.unwrap_or_else(|e| panic!("teeracle-config parsing error {:?}", e))
+			})
+			.unwrap_or_default();
+		teeracle_feeds.market_data.extend(
+			m.values_of("market-data-feed").into_iter().flatten().map(|f| {
+				f.parse().unwrap_or_else(|e| panic!("market-data-feed parsing error {:?}", e))
+			}),
+		);
+		teeracle_feeds.weather.extend(
+			m.values_of("weather-feed").into_iter().flatten().map(|f| {
+				f.parse().unwrap_or_else(|e| panic!("weather-feed parsing error {:?}", e))
+			}),
+		);
+		teeracle_feeds
+			.spot_price
+			.extend(m.values_of("spot-price-feed").into_iter().flatten().map(|f| {
+				f.parse().unwrap_or_else(|e| panic!("spot-price-feed parsing error {:?}", e))
+			}));
+
+		if let Some(base_url) = m.value_of("exchange-rate-source-url") {
+			Url::parse(base_url)
+				.unwrap_or_else(|e| panic!("exchange-rate-source-url parsing error: {:?}", e));
+			let required = |arg: &str| {
+				m.value_of(arg)
+					.unwrap_or_else(|| panic!("{} is required for an exchange rate source", arg))
+					.to_string()
+			};
+			teeracle_feeds.exchange_rate_source = Some(ExchangeRateSource {
+				base_url: base_url.to_string(),
+				path_template: required("exchange-rate-source-path"),
+				value_pointer: required("exchange-rate-source-pointer"),
+				root_certificate_file: m
+					.value_of("exchange-rate-source-certificate")
+					.map(PathBuf::from),
+			});
+		}
+
 		let marblerun_base_url = m.value_of("marblerun-url").map(|i| {
 			Url::parse(i)
 				.unwrap_or_else(|e| panic!("marblerun-url parsing error: {:?}", e))
@@ -341,11 +405,269 @@ impl From<&ArgMatches<'_>> for RunConfig {
 			shard,
 			teeracle_update_interval,
 			reregister_teeracle_interval,
+			teeracle_feeds,
 			marblerun_base_url,
 		}
 	}
 }
 
+/// Feeds of the teeracle, each of them is updated in its own interval.
+///
+/// Can be read from a JSON file, e.g.:
+/// ```json
+/// {
+///   "market_data": [{ "crypto_currency": "TEER", "fiat_currency": "USD", "interval": "1h" }],
+///   "weather": [{ "latitude": "54.32", "longitude": "15.37", "forecast_days": 2 }],
+///   "spot_price": [{ "bidding_zone": "DE-LU", "interval": "1h" }],
+///   "exchange_rate_source": {
+///     "base_url": "https://localhost:8443",
+///     "path_template": "exchange_rate/{crypto_currency}/{fiat_currency}",
+///     "value_pointer": "/exchange_rate",
+///     "root_certificate_file": "exchange_rate_source.pem"
+///   }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TeeracleFeeds {
+	#[serde(default)]
+	pub market_data: Vec<MarketDataFeed>,
+	#[serde(default)]
+	pub weather: Vec<WeatherFeed>,
+	#[serde(default)]
+	pub spot_price: Vec<SpotPriceFeed>,
+	/// Source of the market data, CoinGecko and CoinMarketCap are used if it is not set.
+	#[serde(default)]
+	pub exchange_rate_source: Option<ExchangeRateSource>,
+}
+
+impl TeeracleFeeds {
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+		serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.market_data.is_empty() && self.weather.is_empty() && self.spot_price.is_empty()
+	}
+}
+
+/// Server the teeracle fetches the exchange rates from.
+///
+/// The placeholders `{crypto_currency}` and `{fiat_currency}` in `path_template` and
+/// `value_pointer` are replaced with the currencies of the trading pair.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRateSource {
+	pub base_url: String,
+	/// Path of the request relative to `base_url`, e.g. `api/{crypto_currency}/{fiat_currency}`.
+	pub path_template: String,
+	/// JSON pointer to the exchange rate in the response, e.g. `/data/0/price`.
+	pub value_pointer: String,
+	/// PEM file with the root certificate of the server, not needed for a plain HTTP server.
+	#[serde(default)]
+	pub root_certificate_file: Option<PathBuf>,
+}
+
+impl ExchangeRateSource {
+	/// Configuration of the source in the enclave, as JSON with the root certificate included.
+	pub fn enclave_config(&self) -> Result<String, String> {
+		let root_certificate = match &self.root_certificate_file {
+			Some(path) =>
+				fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+			None => String::new(),
+		};
+		Ok(serde_json::json!({
+			"metrics_id": EXCHANGE_RATE_SOURCE_METRICS_ID,
+			"base_url": self.base_url,
+			"path_template": self.path_template,
+			"value_pointer": self.value_pointer,
+			"root_certificate": root_certificate,
+		})
+		.to_string())
+	}
+}
+
+/// Exchange rate of a trading pair.
+///
+/// Parsed from `<crypto_currency>/<fiat_currency>[@<interval>]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarketDataFeed {
+	pub crypto_currency: String,
+	pub fiat_currency: String,
+	/// Update interval, the teeracle interval is used if it is not set.
+	#[serde(default, with = "optional_duration")]
+	pub interval: Option<Duration>,
+}
+
+impl FromStr for MarketDataFeed {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (pair, interval) = split_interval(s)?;
+		match pair.split_once('/') {
+			Some((crypto_currency, fiat_currency))
+				if !crypto_currency.is_empty() && !fiat_currency.is_empty() =>
+				Ok(Self {
+					crypto_currency: crypto_currency.into(),
+					fiat_currency: fiat_currency.into(),
+					interval,
+				}),
+			_ => Err(format!("Invalid trading pair {}, expected e.g. TEER/USD", pair)),
+		}
+	}
+}
+
+impl fmt::Display for MarketDataFeed {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.crypto_currency, self.fiat_currency)
+	}
+}
+
+/// Hourly weather forecast of a location, starting at the current day.
+///
+/// Parsed from `<latitude>,<longitude>[,<forecast_days>][@<interval>]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherFeed {
+	pub latitude: String,
+	pub longitude: String,
+	/// Number of days of the forecast, including the current day.
+	#[serde(default = "default_forecast_days")]
+	pub forecast_days: u32,
+	/// Update interval, the teeracle interval is used if it is not set.
+	#[serde(default, with = "optional_duration")]
+	pub interval: Option<Duration>,
+}
+
+fn default_forecast_days() -> u32 {
+	1
+}
+
+impl FromStr for WeatherFeed {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (location, interval) = split_interval(s)?;
+		let fields: Vec<&str> = location.split(',').map(str::trim).collect();
+		let (latitude, longitude, forecast_days) = match fields.as_slice() {
+			[latitude, longitude] => (latitude, longitude, default_forecast_days()),
+			[latitude, longitude, days] => (
+				latitude,
+				longitude,
+				days.parse().map_err(|e| format!("Invalid forecast days {}: {}", days, e))?,
+			),
+			_ => return Err(format!("Invalid location {}, expected e.g. 54.32,15.37", location)),
+		};
+		for coordinate in [latitude, longitude] {
+			coordinate
+				.parse::<f64>()
+				.map_err(|e| format!("Invalid coordinate {}: {}", coordinate, e))?;
+		}
+		if forecast_days == 0 {
+			return Err("The forecast needs to cover at least one day".into())
+		}
+
+		Ok(Self {
+			latitude: latitude.to_string(),
+			longitude: longitude.to_string(),
+			forecast_days,
+			interval,
+		})
+	}
+}
+
+impl fmt::Display for WeatherFeed {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "weather/{},{}", self.latitude, self.longitude)
+	}
+}
+
+/// Hourly day-ahead spot prices of a bidding zone, starting at the current day.
+///
+/// Parsed from `<bidding_zone>[,<days>][@<interval>]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpotPriceFeed {
+	/// Bidding zone of the day-ahead market, e.g. `DE-LU`.
+	pub bidding_zone: String,
+	/// Number of days, including the current day. The prices of the next day are
+	/// available after the day-ahead auction at noon.
+	#[serde(default = "default_spot_price_days")]
+	pub days: u32,
+	/// Update interval, the teeracle interval is used if it is not set.
+	#[serde(default, with = "optional_duration")]
+	pub interval: Option<Duration>,
+}
+
+fn default_spot_price_days() -> u32 {
+	2
+}
+
+impl FromStr for SpotPriceFeed {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (zone, interval) = split_interval(s)?;
+		let (bidding_zone, days) = match zone.split_once(',') {
+			Some((bidding_zone, days)) => (
+				bidding_zone.trim(),
+				days.trim().parse().map_err(|e| format!("Invalid days {}: {}", days, e))?,
+			),
+			None => (zone.trim(), default_spot_price_days()),
+		};
+		if bidding_zone.is_empty() {
+			return Err(format!("Invalid bidding zone {}, expected e.g. DE-LU", zone))
+		}
+		if days == 0 {
+			return Err("The spot prices need to cover at least one day".into())
+		}
+
+		Ok(Self { bidding_zone: bidding_zone.to_string(), days, interval })
+	}
+}
+
+impl fmt::Display for SpotPriceFeed {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "spot_price/{}", self.bidding_zone)
+	}
+}
+
+/// Splits an optional `@<interval>` suffix off a feed.
+fn split_interval(s: &str) -> Result<(&str, Option<Duration>), String> {
+	match s.split_once('@') {
+		Some((feed, interval)) => {
+			let interval =
+				parse(interval).map_err(|e| format!("Invalid interval {}: {:?}", interval, e))?;
+			Ok((feed, Some(interval)))
+		},
+		None => Ok((s, None)),
+	}
+}
+
+/// (De)serializes an optional interval in the syntax of the interval CLI arguments, e.g. `15m`.
+mod optional_duration {
+	use parse_duration::parse;
+	use serde::{Deserialize, Deserializer, Serializer};
+	use std::time::Duration;
+
+	pub fn serialize<S: Serializer>(
+		interval: &Option<Duration>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		match interval {
+			Some(interval) => serializer.serialize_some(&format!("{}s", interval.as_secs())),
+			None => serializer.serialize_none(),
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Option<Duration>, D::Error> {
+		Option::<String>::deserialize(deserializer)?
+			.map(|interval| {
+				parse(&interval).map_err(|e| serde::de::Error::custom(format!("{:?}", e)))
+			})
+			.transpose()
+	}
+}
+
 fn add_port_if_necessary(url: &str, port: &str) -> String {
 	// [Option("ws(s)"), ip, Option(port)]
 	match url.split(':').count() {
@@ -469,6 +791,8 @@ mod test {
 		assert_eq!(run_config.skip_ra, false);
 		assert!(run_config.shard.is_none());
 		assert!(run_config.teeracle_update_interval.is_none());
+		assert!(run_config.teeracle_feeds.is_empty());
+		assert_eq!(run_config.teeracle_feeds().market_data[0].to_string(), "TEER/USD");
 	}
 
 	#[test]
@@ -538,6 +862,155 @@ mod test {
 		assert!(result.is_err());
 	}
 
+	#[test]
+	fn teeracle_feeds_parsing_works() {
+		let mut args = ArgMatches::default();
+		args.args = HashMap::from([
+			("market-data-feed", Default::default()),
+			("weather-feed", Default::default()),
+			("spot-price-feed", Default::default()),
+		]);
+		// Workaround because MatchedArg is private.
+		args.args.get_mut("market-data-feed").unwrap().vals =
+			vec!["TEER/USD".into(), "BTC/EUR@15m".into()];
+		args.args.get_mut("weather-feed").unwrap().vals = vec!["54.32,15.37,2@1h".into()];
+		args.args.get_mut("spot-price-feed").unwrap().vals = vec!["DE-LU@1h".into()];
+
+		let feeds = RunConfig::from(&args).teeracle_feeds();
+
+		assert_eq!(
+			feeds.market_data,
+			vec![
+				MarketDataFeed {
+					crypto_currency: "TEER".into(),
+					fiat_currency: "USD".into(),
+					interval: None
+				},
+				MarketDataFeed {
+					crypto_currency: "BTC".into(),
+					fiat_currency: "EUR".into(),
+					interval: Some(Duration::from_secs(900))
+				},
+			]
+		);
+		assert_eq!(
+			feeds.weather,
+			vec![WeatherFeed {
+				latitude: "54.32".into(),
+				longitude: "15.37".into(),
+				forecast_days: 2,
+				interval: Some(Duration::from_secs(3600))
+			}]
+		);
+		assert_eq!(
+			feeds.spot_price,
+			vec![SpotPriceFeed {
+				bidding_zone: "DE-LU".into(),
+				days: 2,
+				interval: Some(Duration::from_secs(3600))
+			}]
+		);
+	}
+
+	#[test]
+	fn exchange_rate_source_parsing_works() {
+		let mut args = ArgMatches::default();
+		args.args = HashMap::from([
+			("exchange-rate-source-url", Default::default()),
+			("exchange-rate-source-path", Default::default()),
+			("exchange-rate-source-pointer", Default::default()),
+		]);
+		// Workaround because MatchedArg is private.
+		args.args.get_mut("exchange-rate-source-url").unwrap().vals =
+			vec!["http://localhost:8080".into()];
+		args.args.get_mut("exchange-rate-source-path").unwrap().vals =
+			vec!["rates/{crypto_currency}".into()];
+		args.args.get_mut("exchange-rate-source-pointer").unwrap().vals =
+			vec!["/{fiat_currency}".into()];
+
+		let feeds = RunConfig::from(&args).teeracle_feeds();
+
+		// The default feed is fetched from the configured source too.
+		assert_eq!(feeds.market_data[0].to_string(), "TEER/USD");
+		let source = feeds.exchange_rate_source.unwrap();
+		assert_eq!(
+			source,
+			ExchangeRateSource {
+				base_url: "http://localhost:8080".into(),
+				path_template: "rates/{crypto_currency}".into(),
+				value_pointer: "/{fiat_currency}".into(),
+				root_certificate_file: None,
+			}
+		);
+
+		let enclave_config: serde_json::Value =
+			serde_json::from_str(&source.enclave_config().unwrap()).unwrap();
+		assert_eq!(enclave_config["metrics_id"], "configurable");
+		assert_eq!(enclave_config["base_url"], "http://localhost:8080");
+		assert_eq!(enclave_config["path_template"], "rates/{crypto_currency}");
+		assert_eq!(enclave_config["value_pointer"], "/{fiat_currency}");
+		assert_eq!(enclave_config["root_certificate"], "");
+	}
+
+	#[test]
+	fn exchange_rate_source_without_path_is_rejected() {
+		let mut args = ArgMatches::default();
+		args.args = HashMap::from([("exchange-rate-source-url", Default::default())]);
+		// Workaround because MatchedArg is private.
+		args.args.get_mut("exchange-rate-source-url").unwrap().vals =
+			vec!["http://localhost:8080".into()];
+
+		let result = std::panic::catch_unwind(|| RunConfig::from(&args));
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn root_certificate_is_included_in_enclave_config() {
+		let certificate_file = std::env::temp_dir().join("exchange_rate_source_test.pem");
+		fs::write(&certificate_file, "-----BEGIN CERTIFICATE-----").unwrap();
+		let mut source = ExchangeRateSource {
+			base_url: "https://localhost:8443".into(),
+			path_template: "exchange_rate/{crypto_currency}/{fiat_currency}".into(),
+			value_pointer: "/exchange_rate".into(),
+			root_certificate_file: Some(certificate_file.clone()),
+		};
+
+		let enclave_config: serde_json::Value =
+			serde_json::from_str(&source.enclave_config().unwrap()).unwrap();
+		assert_eq!(enclave_config["root_certificate"], "-----BEGIN CERTIFICATE-----");
+
+		fs::remove_file(&certificate_file).unwrap();
+		source.root_certificate_file = Some(certificate_file);
+		assert!(source.enclave_config().is_err());
+	}
+
+	#[test]
+	fn invalid_teeracle_feeds_are_rejected() {
+		assert!("TEER".parse::<MarketDataFeed>().is_err());
+		assert!("TEER/@1h".parse::<MarketDataFeed>().is_err());
+		assert!("TEER/USD@soon".parse::<MarketDataFeed>().is_err());
+		assert!("54.32".parse::<WeatherFeed>().is_err());
+		assert!("north,15.37".parse::<WeatherFeed>().is_err());
+		assert!("54.32,15.37,0".parse::<WeatherFeed>().is_err());
+		assert!("@1h".parse::<SpotPriceFeed>().is_err());
+		assert!("DE-LU,0".parse::<SpotPriceFeed>().is_err());
+	}
+
+	#[test]
+	fn teeracle_feeds_are_deserialized_from_json() {
+		let feeds: TeeracleFeeds = serde_json::from_str(
+			r#"{"weather": [{"latitude": "54.32", "longitude": "15.37", "interval": "30m"}]}"#,
+		)
+		.unwrap();
+
+		assert!(feeds.market_data.is_empty());
+		assert_eq!(feeds.weather[0].forecast_days, 1);
+		assert_eq!(feeds.weather[0].interval, Some(Duration::from_secs(1800)));
+
+		let serialized = serde_json::to_string(&feeds).unwrap();
+		assert_eq!(serde_json::from_str::<TeeracleFeeds>(&serialized).unwrap(), feeds);
+	}
+
 	#[test]
 	fn external_addresses_are_returned_correctly_if_set() {
 		let trusted_ext_addr = "wss://1.1.1.2:700";
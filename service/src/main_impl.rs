@@ -1,5 +1,5 @@
 #[cfg(feature = "teeracle")]
-use crate::teeracle::{schedule_periodic_reregistration_thread, start_periodic_market_update};
+use crate::teeracle::{schedule_periodic_reregistration_thread, start_periodic_teeracle_updates};
 
 #[cfg(not(feature = "dcap"))]
 use crate::utils::check_files;
@@ -49,7 +49,10 @@ use its_peer_fetch::{
 	block_fetch_client::BlockFetcher, untrusted_peer_fetch::UntrustedPeerFetcher,
 };
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::{interface::FetchBlocks, BlockPruner, SidechainStorageLock};
+use its_storage::{
+	interface::FetchBlocks, BlockPruner, FetchFinalizedBlock, ImportFinalityVotes,
+	SidechainFinality, SidechainStorageLock,
+};
 use log::*;
 use my_node_runtime::{Hash, Header, RuntimeEvent};
 use sgx_types::*;
@@ -117,6 +120,10 @@ pub(crate) fn main() {
 		config.integritee_rpc_endpoint(),
 		AccountKeyring::Alice.pair(),
 	));
+	let sidechain_finality = Arc::new(SidechainFinality::new(
+		sidechain_blockstorage.clone(),
+		Arc::new(UntrustedPeerFetcher::new(node_api_factory.clone())),
+	));
 	let enclave = Arc::new(enclave_init(&config).unwrap());
 	let initialization_handler = Arc::new(InitializationHandler::default());
 	let worker = Arc::new(EnclaveWorker::new(
@@ -192,11 +199,12 @@ pub(crate) fn main() {
 			);
 		}
 
-		start_worker::<_, _, _, _, WorkerModeProvider>(
+		start_worker::<_, _, _, _, _, WorkerModeProvider>(
 			config,
 			&shard,
 			enclave,
 			sidechain_blockstorage,
+			sidechain_finality,
 			node_api,
 			tokio_handle,
 			initialization_handler,
@@ -270,11 +278,12 @@ pub(crate) fn main() {
 
 /// FIXME: needs some discussion (restructuring?)
 #[allow(clippy::too_many_arguments)]
-fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
+fn start_worker<E, T, D, F, InitializationHandler, WorkerModeProvider>(
 	config: Config,
 	shard: &ShardIdentifier,
 	enclave: Arc<E>,
 	sidechain_storage: Arc<D>,
+	sidechain_finality: Arc<F>,
 	integritee_rpc_api: ParentchainApi,
 	tokio_handle_getter: Arc<T>,
 	initialization_handler: Arc<InitializationHandler>,
@@ -290,6 +299,7 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		+ TeeracleApi
 		+ Clone,
 	D: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	F: ImportFinalityVotes + FetchFinalizedBlock + Sync + Send + 'static,
 	InitializationHandler: TrackInitialization + IsInitialized + Sync + Send + 'static,
 	WorkerModeProvider: ProvideWorkerMode,
 {
@@ -421,6 +431,7 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 			&config,
 			enclave.clone(),
 			sidechain_storage.clone(),
+			sidechain_finality,
 			tokio_handle,
 		);
 	}
@@ -517,8 +528,9 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 			run_config.reregister_teeracle_interval(),
 		);
 
-		start_periodic_market_update(
+		start_periodic_teeracle_updates(
 			&integritee_rpc_api,
+			run_config.teeracle_feeds(),
 			run_config.teeracle_update_interval(),
 			enclave.as_ref(),
 			&teeracle_tokio_handle,
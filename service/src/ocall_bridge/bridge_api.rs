@@ -131,6 +131,12 @@ pub enum OCallBridgeError {
 	ProposeSidechainBlock(String),
 	#[error("Failed to fetch sidechain blocks from peer: {0}")]
 	FetchSidechainBlocksFromPeer(String),
+	#[error("Failed to get the peer count: {0}")]
+	GetPeerCount(String),
+	#[error("Propose finality votes failed: {0}")]
+	ProposeFinalityVotes(String),
+	#[error("Failed to query sidechain blocks: {0}")]
+	QuerySidechainBlocks(String),
 	#[error("Sending extrinsics to parentchain failed: {0}")]
 	SendExtrinsicsToParentchain(String),
 	#[error("IPFS Error: {0}")]
@@ -236,6 +242,18 @@ pub trait SidechainBridge {
 		maybe_until_block_hash_encoded: Vec<u8>,
 		shard_identifier_encoded: Vec<u8>,
 	) -> OCallBridgeResult<Vec<u8>>;
+
+	fn get_peer_count(&self) -> OCallBridgeResult<u32>;
+
+	fn propose_finality_votes(&self, signed_votes_encoded: Vec<u8>) -> OCallBridgeResult<()>;
+
+	/// Returns the encoded summaries of the queried blocks, as many as fit into
+	/// `max_encoded_size` bytes.
+	fn query_sidechain_blocks(
+		&self,
+		query_encoded: Vec<u8>,
+		max_encoded_size: usize,
+	) -> OCallBridgeResult<Vec<u8>>;
 }
 
 /// type for IPFS
@@ -30,14 +30,17 @@ use crate::{
 		worker_on_chain_ocall::WorkerOnChainOCall,
 	},
 	prometheus_metrics::ReceiveEnclaveMetrics,
-	sync_block_broadcaster::BroadcastBlocks,
+	sync_block_broadcaster::{BroadcastBlocks, BroadcastFinalityVotes},
 	worker_peers_updater::UpdateWorkerPeers,
 };
 use itp_enclave_api::remote_attestation::RemoteAttestationCallBacks;
 use itp_node_api::node_api_factory::CreateNodeApi;
 use its_peer_fetch::FetchBlocksFromPeer;
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::BlockStorage;
+use its_storage::{
+	interface::{FinalizeBlocks, QueryBlocks},
+	BlockStorage,
+};
 use std::sync::Arc;
 
 /// Concrete implementation, should be moved out of the OCall Bridge, into the worker
@@ -135,9 +138,12 @@ impl<
 		MetricsReceiver,
 	> where
 	NodeApi: CreateNodeApi + 'static,
-	Broadcaster: BroadcastBlocks + 'static,
+	Broadcaster: BroadcastBlocks + BroadcastFinalityVotes + 'static,
 	EnclaveApi: RemoteAttestationCallBacks + 'static,
-	Storage: BlockStorage<SignedSidechainBlock> + 'static,
+	Storage: BlockStorage<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ FinalizeBlocks<SignedSidechainBlock>
+		+ 'static,
 	PeerUpdater: UpdateWorkerPeers + 'static,
 	PeerBlockFetcher: FetchBlocksFromPeer<SignedBlockType = SignedSidechainBlock> + 'static,
 	TokioHandle: GetTokioHandle + 'static,
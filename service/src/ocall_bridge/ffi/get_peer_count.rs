@@ -0,0 +1,61 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall_bridge::bridge_api::{Bridge, SidechainBridge};
+use log::*;
+use sgx_types::sgx_status_t;
+use std::sync::Arc;
+
+/// # Safety
+///
+/// FFI are always unsafe
+#[no_mangle]
+pub unsafe extern "C" fn ocall_get_peer_count(peer_count: *mut u32) -> sgx_status_t {
+	get_peer_count(peer_count, Bridge::get_sidechain_api())
+}
+
+fn get_peer_count(peer_count: *mut u32, sidechain_api: Arc<dyn SidechainBridge>) -> sgx_status_t {
+	match sidechain_api.get_peer_count() {
+		Ok(count) => {
+			unsafe {
+				*peer_count = count;
+			}
+			sgx_status_t::SGX_SUCCESS
+		},
+		Err(e) => {
+			error!("get peer count failed: {:?}", e);
+			sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ocall_bridge::test::mocks::sidechain_bridge_mock::SidechainBridgeMock;
+
+	#[test]
+	fn get_peer_count_works() {
+		let sidechain_bridge_mock = Arc::new(SidechainBridgeMock::default().with_peer_count(3));
+		let mut peer_count = 0u32;
+
+		let result = get_peer_count(&mut peer_count, sidechain_bridge_mock);
+
+		assert_eq!(result, sgx_status_t::SGX_SUCCESS);
+		assert_eq!(peer_count, 3);
+	}
+}
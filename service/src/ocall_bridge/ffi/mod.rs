@@ -23,12 +23,15 @@
 
 pub mod fetch_sidechain_blocks_from_peer;
 pub mod get_ias_socket;
+pub mod get_peer_count;
 pub mod get_quote;
 pub mod get_qve_report_on_quote;
 pub mod get_update_info;
 pub mod init_quote;
 pub mod ipfs;
+pub mod propose_finality_votes;
 pub mod propose_sidechain_blocks;
+pub mod query_sidechain_blocks;
 pub mod send_to_parentchain;
 pub mod store_sidechain_blocks;
 pub mod update_metric;
@@ -0,0 +1,72 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Copyright (C) 2017-2019 Baidu, Inc. All Rights Reserved.
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall_bridge::bridge_api::{Bridge, SidechainBridge};
+use itp_utils::write_slice_and_whitespace_pad;
+use log::*;
+use sgx_types::sgx_status_t;
+use std::{slice, sync::Arc};
+
+/// # Safety
+///
+/// FFI are always unsafe
+#[no_mangle]
+pub unsafe extern "C" fn ocall_query_sidechain_blocks(
+	query_ptr: *const u8,
+	query_size: u32,
+	block_summaries_ptr: *mut u8,
+	block_summaries_size: u32,
+) -> sgx_status_t {
+	query_sidechain_blocks(
+		query_ptr,
+		query_size,
+		block_summaries_ptr,
+		block_summaries_size,
+		Bridge::get_sidechain_api(),
+	)
+}
+
+fn query_sidechain_blocks(
+	query_ptr: *const u8,
+	query_size: u32,
+	block_summaries_ptr: *mut u8,
+	block_summaries_size: u32,
+	sidechain_api: Arc<dyn SidechainBridge>,
+) -> sgx_status_t {
+	let query_encoded = unsafe { Vec::from(slice::from_raw_parts(query_ptr, query_size as usize)) };
+
+	let block_summaries_encoded =
+		match sidechain_api.query_sidechain_blocks(query_encoded, block_summaries_size as usize) {
+			Ok(r) => r,
+			Err(e) => {
+				error!("query sidechain blocks failed: {:?}", e);
+				return sgx_status_t::SGX_ERROR_UNEXPECTED
+			},
+		};
+
+	let block_summaries_encoded_slice =
+		unsafe { slice::from_raw_parts_mut(block_summaries_ptr, block_summaries_size as usize) };
+	if let Err(e) =
+		write_slice_and_whitespace_pad(block_summaries_encoded_slice, block_summaries_encoded)
+	{
+		error!("Failed to transfer encoded block summaries to o-call buffer: {:?}", e);
+		return sgx_status_t::SGX_ERROR_UNEXPECTED
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
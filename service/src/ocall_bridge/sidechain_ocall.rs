@@ -19,14 +19,22 @@
 use crate::{
 	globals::tokio_handle::GetTokioHandle,
 	ocall_bridge::bridge_api::{OCallBridgeError, OCallBridgeResult, SidechainBridge},
-	sync_block_broadcaster::BroadcastBlocks,
+	sync_block_broadcaster::{BroadcastBlocks, BroadcastFinalityVotes},
 	worker_peers_updater::UpdateWorkerPeers,
 };
-use codec::{Decode, Encode};
+use codec::{Compact, Decode, Encode};
 use itp_types::{BlockHash, ShardIdentifier};
 use its_peer_fetch::FetchBlocksFromPeer;
-use its_primitives::{traits::Block, types::SignedBlock as SignedSidechainBlock};
-use its_storage::BlockStorage;
+use its_primitives::{
+	traits::Block,
+	types::{
+		finality::SignedFinalityVote, BlockQuery, BlockSummary, SignedBlock as SignedSidechainBlock,
+	},
+};
+use its_storage::{
+	interface::{FinalizeBlocks, QueryBlocks},
+	BlockStorage,
+};
 use log::*;
 use std::sync::Arc;
 
@@ -61,8 +69,10 @@ impl<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle>
 impl<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle> SidechainBridge
 	for SidechainOCall<BlockBroadcaster, Storage, PeerUpdater, PeerBlockFetcher, TokioHandle>
 where
-	BlockBroadcaster: BroadcastBlocks,
-	Storage: BlockStorage<SignedSidechainBlock>,
+	BlockBroadcaster: BroadcastBlocks + BroadcastFinalityVotes,
+	Storage: BlockStorage<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ FinalizeBlocks<SignedSidechainBlock>,
 	PeerUpdater: UpdateWorkerPeers,
 	PeerBlockFetcher: FetchBlocksFromPeer<SignedBlockType = SignedSidechainBlock>,
 	TokioHandle: GetTokioHandle,
@@ -187,6 +197,90 @@ where
 
 		Ok(signed_sidechain_blocks.encode())
 	}
+
+	fn get_peer_count(&self) -> OCallBridgeResult<u32> {
+		self.peer_updater
+			.number_of_peers()
+			.map(|number_of_peers| number_of_peers as u32)
+			.map_err(|e| OCallBridgeError::GetPeerCount(format!("{:?}", e)))
+	}
+
+	fn propose_finality_votes(&self, signed_votes_encoded: Vec<u8>) -> OCallBridgeResult<()> {
+		let signed_votes: Vec<SignedFinalityVote> =
+			Decode::decode(&mut signed_votes_encoded.as_slice()).map_err(|_| {
+				OCallBridgeError::ProposeFinalityVotes(
+					"Could not decode finality votes".to_string(),
+				)
+			})?;
+
+		// Our own worker is one of the peers, so our own votes are imported the same way.
+		debug!("Broadcasting {} finality vote(s) ...", signed_votes.len());
+		self.block_broadcaster
+			.broadcast_finality_votes(signed_votes)
+			.map_err(|e| OCallBridgeError::ProposeFinalityVotes(format!("{:?}", e)))
+	}
+
+	fn query_sidechain_blocks(
+		&self,
+		query_encoded: Vec<u8>,
+		max_encoded_size: usize,
+	) -> OCallBridgeResult<Vec<u8>> {
+		let query: BlockQuery = Decode::decode(&mut query_encoded.as_slice()).map_err(|_| {
+			OCallBridgeError::QuerySidechainBlocks("Could not decode block query".to_string())
+		})?;
+
+		debug!("[O-call] querying sidechain blocks: {:?}", query);
+		let signed_blocks = match query {
+			BlockQuery::ByHash(block_hash) =>
+				self.block_storage.block_by_hash(&block_hash).map(Vec::from_iter),
+			BlockQuery::ByNumber(shard, block_number) =>
+				self.block_storage.block_by_number(&shard, block_number).map(Vec::from_iter),
+			BlockQuery::Latest(shard) =>
+				self.block_storage.latest_block(&shard).map(Vec::from_iter),
+			BlockQuery::NumberRange(shard, from, until) =>
+				self.block_storage.blocks_by_number_range(&shard, from, until),
+			BlockQuery::Finalized(shard) => match self.block_storage.finalized_block(&shard) {
+				Some(finalized_block) => self
+					.block_storage
+					.block_by_hash(&finalized_block.block_hash)
+					.map(Vec::from_iter),
+				None => Ok(Vec::new()),
+			},
+		}
+		.map_err(|e| OCallBridgeError::QuerySidechainBlocks(format!("{:?}", e)))?;
+
+		let block_summaries = signed_blocks.iter().map(BlockSummary::from).collect();
+		Ok(encode_fitting_block_summaries(block_summaries, max_encoded_size))
+	}
+}
+
+/// Encodes the block summaries up to the first one that exceeds `max_encoded_size` bytes in
+/// total. The enclave decodes them from a buffer of that size, a client that queried a range of
+/// blocks pages on from the last returned block.
+fn encode_fitting_block_summaries(
+	block_summaries: Vec<BlockSummary>,
+	max_encoded_size: usize,
+) -> Vec<u8> {
+	let queried = block_summaries.len();
+	let mut summaries_size = 0;
+	let fitting: Vec<BlockSummary> = block_summaries
+		.into_iter()
+		.enumerate()
+		.take_while(|(index, summary)| {
+			summaries_size += summary.encoded_size();
+			Compact(*index as u32 + 1).encoded_size() + summaries_size <= max_encoded_size
+		})
+		.map(|(_, summary)| summary)
+		.collect();
+
+	if fitting.len() < queried {
+		warn!(
+			"Returning {} of {} queried sidechain blocks, the others exceed the o-call buffer",
+			fitting.len(),
+			queried
+		);
+	}
+	fitting.encode()
 }
 
 #[cfg(test)]
@@ -201,19 +295,105 @@ mod tests {
 	};
 	use codec::Decode;
 	use its_peer_fetch::mocks::fetch_blocks_from_peer_mock::FetchBlocksFromPeerMock;
-	use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
+	use its_primitives::{
+		traits::{Header, SignedBlock as SignedBlockT},
+		types::{
+			block::SignedBlock as SignedSidechainBlock, finality::FinalizedBlock, BlockHash,
+			BlockNumber,
+		},
+	};
 	use its_storage::{interface::BlockStorage, Result as StorageResult};
-	use its_test::sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait};
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
 	use primitive_types::H256;
 	use std::{collections::HashMap, vec::Vec};
 
-	struct BlockStorageMock;
+	struct BlockStorageMock {
+		stored_blocks: Vec<SignedSidechainBlock>,
+		finalized_block: Option<FinalizedBlock>,
+	}
+
+	impl BlockStorageMock {
+		fn blocks_of_shard<'a>(
+			&'a self,
+			shard: &'a ShardIdentifier,
+		) -> impl Iterator<Item = &'a SignedSidechainBlock> {
+			self.stored_blocks
+				.iter()
+				.filter(move |b| &b.block().header().shard_id() == shard)
+		}
+	}
+
 	impl BlockStorage<SignedSidechainBlock> for BlockStorageMock {
 		fn store_blocks(&self, _blocks: Vec<SignedSidechainBlock>) -> StorageResult<()> {
 			Ok(())
 		}
 	}
 
+	impl QueryBlocks<SignedSidechainBlock> for BlockStorageMock {
+		fn block_by_hash(
+			&self,
+			block_hash: &BlockHash,
+		) -> StorageResult<Option<SignedSidechainBlock>> {
+			Ok(self.stored_blocks.iter().find(|b| &b.hash() == block_hash).cloned())
+		}
+
+		fn block_by_number(
+			&self,
+			shard: &ShardIdentifier,
+			block_number: BlockNumber,
+		) -> StorageResult<Option<SignedSidechainBlock>> {
+			Ok(self
+				.blocks_of_shard(shard)
+				.find(|b| b.block().header().block_number() == block_number)
+				.cloned())
+		}
+
+		fn block_hash(
+			&self,
+			shard: &ShardIdentifier,
+			block_number: BlockNumber,
+		) -> StorageResult<Option<BlockHash>> {
+			Ok(self.block_by_number(shard, block_number)?.map(|b| b.hash()))
+		}
+
+		fn latest_block(
+			&self,
+			shard: &ShardIdentifier,
+		) -> StorageResult<Option<SignedSidechainBlock>> {
+			Ok(self.blocks_of_shard(shard).last().cloned())
+		}
+
+		fn blocks_by_number_range(
+			&self,
+			shard: &ShardIdentifier,
+			from: BlockNumber,
+			until: BlockNumber,
+		) -> StorageResult<Vec<SignedSidechainBlock>> {
+			Ok(self
+				.blocks_of_shard(shard)
+				.filter(|b| (from..=until).contains(&b.block().header().block_number()))
+				.cloned()
+				.collect())
+		}
+	}
+
+	impl FinalizeBlocks<SignedSidechainBlock> for BlockStorageMock {
+		fn finalize_block(
+			&self,
+			_shard: &ShardIdentifier,
+			_finalized_block: FinalizedBlock,
+		) -> StorageResult<()> {
+			Ok(())
+		}
+
+		fn finalized_block(&self, _shard: &ShardIdentifier) -> Option<FinalizedBlock> {
+			self.finalized_block
+		}
+	}
+
 	type TestSidechainOCall = SidechainOCall<
 		BroadcastBlocksMock,
 		BlockStorageMock,
@@ -248,11 +428,101 @@ mod tests {
 		assert_eq!(blocks, fetched_blocks_decoded);
 	}
 
+	#[test]
+	fn propose_finality_votes_fails_for_undecodable_votes() {
+		let sidechain_ocall = setup_sidechain_ocall_with_peer_blocks(HashMap::new());
+
+		assert!(sidechain_ocall.propose_finality_votes(vec![0xff; 3]).is_err());
+	}
+
+	#[test]
+	fn query_sidechain_blocks_returns_block_summaries() {
+		let shard_identifier = H256::random();
+		let blocks = signed_blocks_of_shard(shard_identifier, 3);
+		let sidechain_ocall = setup_sidechain_ocall(HashMap::new(), blocks.clone());
+		let query = |query: BlockQuery| -> Vec<BlockSummary> {
+			let summaries_encoded =
+				sidechain_ocall.query_sidechain_blocks(query.encode(), usize::MAX).unwrap();
+			Decode::decode(&mut summaries_encoded.as_slice()).unwrap()
+		};
+
+		assert_eq!(
+			query(BlockQuery::ByHash(blocks[0].hash())),
+			vec![BlockSummary::from(&blocks[0])]
+		);
+		assert_eq!(
+			query(BlockQuery::ByNumber(shard_identifier, 2)),
+			vec![BlockSummary::from(&blocks[1])]
+		);
+		assert_eq!(
+			query(BlockQuery::Latest(shard_identifier)),
+			vec![BlockSummary::from(&blocks[2])]
+		);
+		assert_eq!(
+			query(BlockQuery::NumberRange(shard_identifier, 2, 10)),
+			blocks[1..].iter().map(BlockSummary::from).collect::<Vec<_>>()
+		);
+		assert_eq!(
+			query(BlockQuery::Finalized(shard_identifier)),
+			vec![BlockSummary::from(&blocks[0])]
+		);
+		assert!(query(BlockQuery::Latest(H256::random())).is_empty());
+	}
+
+	#[test]
+	fn query_sidechain_blocks_returns_only_the_block_summaries_that_fit_into_the_buffer() {
+		let shard_identifier = H256::random();
+		let blocks = signed_blocks_of_shard(shard_identifier, 3);
+		let sidechain_ocall = setup_sidechain_ocall(HashMap::new(), blocks.clone());
+		let first_summaries: Vec<_> = blocks[..2].iter().map(BlockSummary::from).collect();
+
+		let summaries_encoded = sidechain_ocall
+			.query_sidechain_blocks(
+				BlockQuery::NumberRange(shard_identifier, 1, 3).encode(),
+				first_summaries.encoded_size(),
+			)
+			.unwrap();
+
+		assert_eq!(
+			Vec::<BlockSummary>::decode(&mut summaries_encoded.as_slice()).unwrap(),
+			first_summaries
+		);
+	}
+
+	fn signed_blocks_of_shard(
+		shard_identifier: ShardIdentifier,
+		count: BlockNumber,
+	) -> Vec<SignedSidechainBlock> {
+		(1..=count)
+			.map(|block_number| {
+				SidechainBlockBuilder::random()
+					.with_header(
+						SidechainHeaderBuilder::random()
+							.with_shard(shard_identifier)
+							.with_block_number(block_number)
+							.build(),
+					)
+					.build_signed()
+			})
+			.collect()
+	}
+
 	fn setup_sidechain_ocall_with_peer_blocks(
 		peer_blocks_map: HashMap<ShardIdentifier, Vec<SignedSidechainBlock>>,
+	) -> TestSidechainOCall {
+		setup_sidechain_ocall(peer_blocks_map, Vec::new())
+	}
+
+	fn setup_sidechain_ocall(
+		peer_blocks_map: HashMap<ShardIdentifier, Vec<SignedSidechainBlock>>,
+		stored_blocks: Vec<SignedSidechainBlock>,
 	) -> TestSidechainOCall {
 		let block_broadcaster_mock = Arc::new(BroadcastBlocksMock {});
-		let block_storage_mock = Arc::new(BlockStorageMock {});
+		let finalized_block = stored_blocks.first().map(|block| FinalizedBlock {
+			block_number: block.block().header().block_number(),
+			block_hash: block.hash(),
+		});
+		let block_storage_mock = Arc::new(BlockStorageMock { stored_blocks, finalized_block });
 		let peer_updater_mock = Arc::new(UpdateWorkerPeersMock {});
 		let peer_block_fetcher_mock = Arc::new(
 			FetchBlocksFromPeerMock::<SignedSidechainBlock>::default()
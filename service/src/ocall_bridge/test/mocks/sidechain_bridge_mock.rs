@@ -17,10 +17,12 @@
 */
 
 use crate::ocall_bridge::bridge_api::{OCallBridgeResult, SidechainBridge};
+use codec::Encode;
 
 #[derive(Default)]
 pub struct SidechainBridgeMock {
 	peer_blocks_encoded: Vec<u8>,
+	peer_count: u32,
 }
 
 impl SidechainBridgeMock {
@@ -28,6 +30,11 @@ impl SidechainBridgeMock {
 		self.peer_blocks_encoded = blocks_encoded;
 		self
 	}
+
+	pub fn with_peer_count(mut self, peer_count: u32) -> Self {
+		self.peer_count = peer_count;
+		self
+	}
 }
 
 impl SidechainBridge for SidechainBridgeMock {
@@ -47,4 +54,20 @@ impl SidechainBridge for SidechainBridgeMock {
 	) -> OCallBridgeResult<Vec<u8>> {
 		Ok(self.peer_blocks_encoded.clone())
 	}
+
+	fn get_peer_count(&self) -> OCallBridgeResult<u32> {
+		Ok(self.peer_count)
+	}
+
+	fn propose_finality_votes(&self, _signed_votes_encoded: Vec<u8>) -> OCallBridgeResult<()> {
+		Ok(())
+	}
+
+	fn query_sidechain_blocks(
+		&self,
+		_query_encoded: Vec<u8>,
+		_max_encoded_size: usize,
+	) -> OCallBridgeResult<Vec<u8>> {
+		Ok(Vec::<u8>::new().encode())
+	}
 }
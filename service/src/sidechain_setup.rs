@@ -31,24 +31,29 @@ use itp_settings::{
 use itp_types::Header;
 use its_consensus_slots::start_slot_worker;
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::{interface::FetchBlocks, start_sidechain_pruning_loop, BlockPruner};
+use its_storage::{
+	interface::FetchBlocks, start_sidechain_pruning_loop, BlockPruner, FetchFinalizedBlock,
+	ImportFinalityVotes,
+};
 use log::*;
 use std::{sync::Arc, thread};
 use tokio::runtime::Handle;
 
-pub(crate) fn sidechain_start_untrusted_rpc_server<Enclave, SidechainStorage>(
+pub(crate) fn sidechain_start_untrusted_rpc_server<Enclave, SidechainStorage, SidechainFinality>(
 	config: &Config,
 	enclave: Arc<Enclave>,
 	sidechain_storage: Arc<SidechainStorage>,
+	sidechain_finality: Arc<SidechainFinality>,
 	tokio_handle: Handle,
 ) where
 	Enclave: DirectRequest + Clone,
 	SidechainStorage: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	SidechainFinality: ImportFinalityVotes + FetchFinalizedBlock + Sync + Send + 'static,
 {
 	let untrusted_url = config.untrusted_worker_url();
 	println!("[+] Untrusted RPC server listening on {}", &untrusted_url);
 	let _untrusted_rpc_join_handle = tokio_handle.spawn(async move {
-		itc_rpc_server::run_server(&untrusted_url, enclave, sidechain_storage)
+		itc_rpc_server::run_server(&untrusted_url, enclave, sidechain_storage, sidechain_finality)
 			.await
 			.unwrap();
 	});
@@ -23,9 +23,11 @@ use mockall::*;
 
 use crate::{
 	globals::tokio_handle::GetTokioHandle,
-	worker::{AsyncBlockBroadcaster, WorkerResult},
+	worker::{AsyncBlockBroadcaster, AsyncFinalityVoteBroadcaster, WorkerResult},
+};
+use its_primitives::types::{
+	block::SignedBlock as SignedSidechainBlock, finality::SignedFinalityVote,
 };
-use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
 use std::sync::Arc;
 
 /// Allows to broadcast blocks, does it in a synchronous (i.e. blocking) manner
@@ -34,6 +36,12 @@ pub trait BroadcastBlocks {
 	fn broadcast_blocks(&self, blocks: Vec<SignedSidechainBlock>) -> WorkerResult<()>;
 }
 
+/// Allows to broadcast finality votes, does it in a synchronous (i.e. blocking) manner
+#[cfg_attr(test, automock)]
+pub trait BroadcastFinalityVotes {
+	fn broadcast_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> WorkerResult<()>;
+}
+
 pub struct SyncBlockBroadcaster<T, W> {
 	tokio_handle: Arc<T>,
 	worker: Arc<W>,
@@ -55,3 +63,14 @@ where
 		handle.block_on(self.worker.broadcast_blocks(blocks))
 	}
 }
+
+impl<T, W> BroadcastFinalityVotes for SyncBlockBroadcaster<T, W>
+where
+	T: GetTokioHandle,
+	W: AsyncFinalityVoteBroadcaster,
+{
+	fn broadcast_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> WorkerResult<()> {
+		let handle = self.tokio_handle.get_handle();
+		handle.block_on(self.worker.broadcast_finality_votes(votes))
+	}
+}
@@ -15,7 +15,12 @@
 
 */
 
-use crate::{error::ServiceResult, teeracle::schedule_periodic::schedule_periodic};
+use crate::{
+	config::TeeracleFeeds,
+	error::ServiceResult,
+	teeracle::schedule_periodic::{schedule_periodic, schedule_periodic_tasks},
+};
+use chrono::Utc;
 use codec::{Decode, Encode};
 use itp_enclave_api::teeracle_api::TeeracleApi;
 use itp_node_api::api_client::ParentchainApi;
@@ -65,47 +70,109 @@ pub(crate) fn schedule_periodic_reregistration_thread(
 		.unwrap();
 }
 
-/// Executes a periodic teeracle data update and sends the new data to the parentchain.
+/// Executes the periodic updates of all teeracle feeds and sends the new data to the parentchain.
 ///
-/// Note: Puts the current thread to sleep for `period`.
-pub(crate) fn start_periodic_market_update<E: TeeracleApi>(
+/// Each feed is updated in its own interval, falling back to `default_period`.
+///
+/// Note: Puts the current thread to sleep between the updates.
+pub(crate) fn start_periodic_teeracle_updates<E: TeeracleApi>(
 	api: &ParentchainApi,
-	period: Duration,
+	feeds: TeeracleFeeds,
+	default_period: Duration,
 	enclave_api: &E,
 	tokio_handle: &Handle,
 ) {
-	let updates_to_run = || {
-		if let Err(e) = execute_oracle_update(api, tokio_handle, || {
-			// Get market data for usd (hardcoded)
-			enclave_api.update_market_data_xt("TEER", "USD")
-		}) {
-			error!("Error running market update {:?}", e)
-		}
-
-		// TODO: Refactor and add this back according to ISSUE: https://github.com/integritee-network/worker/issues/1300
-		// if let Err(e) = execute_oracle_update(api, tokio_handle, || {
-		// 	enclave_api.update_weather_data_xt("54.32", "15.37")
-		// }) {
-		// 	error!("Error running weather update {:?}", e)
-		// }
-	};
+	let mut updates_to_run: Vec<(Box<dyn Fn() + '_>, Duration)> = Vec::new();
+
+	let exchange_rate_source = feeds.exchange_rate_source.as_ref().map(|source| {
+		info!("Fetch exchange rates from {}", source.base_url);
+		source
+			.enclave_config()
+			.unwrap_or_else(|e| panic!("exchange rate source configuration error: {}", e))
+	});
+
+	for feed in feeds.market_data {
+		let period = feed.interval.unwrap_or(default_period);
+		let exchange_rate_source = exchange_rate_source.clone();
+		info!("Schedule market data updates of {} every {:?}", feed, period);
+		updates_to_run.push((
+			Box::new(move || {
+				let label = feed.to_string();
+				if let Err(e) = execute_oracle_update(api, tokio_handle, &label, || {
+					enclave_api.update_market_data_xt(
+						&feed.crypto_currency,
+						&feed.fiat_currency,
+						exchange_rate_source.as_deref(),
+					)
+				}) {
+					error!("Error running market update of {}: {:?}", label, e)
+				}
+			}),
+			period,
+		));
+	}
+
+	for feed in feeds.weather {
+		let period = feed.interval.unwrap_or(default_period);
+		info!("Schedule weather updates of {} every {:?}", feed, period);
+		updates_to_run.push((
+			Box::new(move || {
+				let label = feed.to_string();
+				let (start_date, end_date) = date_range(feed.forecast_days);
+				if let Err(e) = execute_oracle_update(api, tokio_handle, &label, || {
+					enclave_api.update_weather_data_xt(
+						&feed.longitude,
+						&feed.latitude,
+						&start_date,
+						&end_date,
+					)
+				}) {
+					error!("Error running weather update of {}: {:?}", label, e)
+				}
+			}),
+			period,
+		));
+	}
+
+	for feed in feeds.spot_price {
+		let period = feed.interval.unwrap_or(default_period);
+		info!("Schedule spot price updates of {} every {:?}", feed, period);
+		updates_to_run.push((
+			Box::new(move || {
+				let label = feed.to_string();
+				let (start_date, end_date) = date_range(feed.days);
+				if let Err(e) = execute_oracle_update(api, tokio_handle, &label, || {
+					enclave_api.update_spot_prices_xt(&feed.bidding_zone, &start_date, &end_date)
+				}) {
+					error!("Error running spot price update of {}: {:?}", label, e)
+				}
+			}),
+			period,
+		));
+	}
+
 	info!("Teeracle will update now");
-	updates_to_run();
+	schedule_periodic_tasks(&updates_to_run);
+}
 
-	info!("Schedule teeracle updates every {:?}", period);
-	schedule_periodic(updates_to_run, period);
+/// First and last day of a range of `days`, starting today, as ISO 8601 dates.
+fn date_range(days: u32) -> (String, String) {
+	let today = Utc::now().date_naive();
+	let last_day = today + chrono::Duration::days(i64::from(days.saturating_sub(1)));
+	(today.to_string(), last_day.to_string())
 }
 
 fn execute_oracle_update<F>(
 	node_api: &ParentchainApi,
 	tokio_handle: &Handle,
+	feed: &str,
 	get_oracle_xt: F,
 ) -> ServiceResult<()>
 where
 	F: Fn() -> Result<Vec<u8>, itp_enclave_api::error::Error>,
 {
 	let oracle_xt = get_oracle_xt().map_err(|e| {
-		increment_number_of_request_failures();
+		increment_number_of_request_failures(feed);
 		e
 	})?;
 
@@ -114,22 +181,23 @@ where
 	// Send the extrinsics to the parentchain and wait for InBlock confirmation.
 	for call in extrinsics.into_iter() {
 		let node_api_clone = node_api.clone();
+		let feed = feed.to_owned();
 		tokio_handle.spawn(async move {
 			let encoded_extrinsic = call.encode();
 			debug!("Hex encoded extrinsic to be sent: {}", hex_encode(&encoded_extrinsic));
 
-			println!("[>] Update oracle data (send the extrinsic)");
+			println!("[>] Update oracle data of {} (send the extrinsic)", feed);
 			let extrinsic_hash = match node_api_clone.submit_and_watch_opaque_extrinsic_until(
 				&encoded_extrinsic.into(),
 				XtStatus::InBlock,
 			) {
 				Err(e) => {
 					error!("Failed to send extrinsic: {:?}", e);
-					set_extrinsics_inclusion_success(false);
+					set_extrinsics_inclusion_success(&feed, false);
 					return
 				},
 				Ok(report) => {
-					set_extrinsics_inclusion_success(true);
+					set_extrinsics_inclusion_success(&feed, true);
 					report.extrinsic_hash
 				},
 			};
@@ -140,3 +208,22 @@ where
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use chrono::NaiveDate;
+
+	#[test]
+	fn date_range_starts_today() {
+		let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+
+		let (start_date, end_date) = date_range(3);
+		assert_eq!(parse(&start_date), Utc::now().date_naive());
+		assert_eq!(parse(&end_date) - parse(&start_date), chrono::Duration::days(2));
+
+		let (start_date, end_date) = date_range(0);
+		assert_eq!(start_date, end_date);
+	}
+}
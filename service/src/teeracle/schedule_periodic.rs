@@ -44,3 +44,27 @@ where
 		}
 	}
 }
+
+/// Schedules several periodic tasks, each with its own period, in the current thread.
+///
+/// All tasks are run once immediately. Like with `schedule_periodic`, a task that takes longer
+/// than the period of the others delays them.
+pub(super) fn schedule_periodic_tasks<T>(tasks: &[(T, Duration)])
+where
+	T: Fn(),
+{
+	let mut next_runs = vec![Instant::now(); tasks.len()];
+	loop {
+		for ((task, period), next_run) in tasks.iter().zip(next_runs.iter_mut()) {
+			if *next_run <= Instant::now() {
+				*next_run = Instant::now() + *period;
+				task();
+			}
+		}
+
+		match next_runs.iter().min() {
+			Some(next_run) => thread::sleep(next_run.saturating_duration_since(Instant::now())),
+			None => return,
+		}
+	}
+}
@@ -19,8 +19,8 @@ use crate::error::{Error, ServiceResult};
 use itp_enclave_metrics::ExchangeRateOracleMetric;
 use lazy_static::lazy_static;
 use prometheus::{
-	register_gauge_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
-	register_int_gauge_vec, GaugeVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+	register_gauge_vec, register_int_counter_vec, register_int_gauge_vec, GaugeVec, IntCounterVec,
+	IntGaugeVec,
 };
 
 lazy_static! {
@@ -29,6 +29,9 @@ lazy_static! {
 	static ref EXCHANGE_RATE: GaugeVec =
 		register_gauge_vec!("integritee_teeracle_exchange_rate", "Exchange rates partitioned into source and trading pair", &["source", "trading_pair"])
 			.unwrap();
+	static ref EXCHANGE_RATE_DEVIATION: GaugeVec =
+		register_gauge_vec!("integritee_teeracle_exchange_rate_deviation", "Relative deviation of a source from the aggregated exchange rate", &["source", "trading_pair"])
+			.unwrap();
 	static ref RESPONSE_TIME: IntGaugeVec =
 		register_int_gauge_vec!("integritee_teeracle_response_times", "Response times in ms for requests that the oracle makes", &["source"])
 			.unwrap();
@@ -36,22 +39,22 @@ lazy_static! {
 		register_int_counter_vec!("integritee_teeracle_number_of_requests", "Number of requests made per source", &["source"])
 			.unwrap();
 
-	static ref NUMBER_OF_REQUEST_FAILURES: IntCounter =
-		register_int_counter!("integritee_teeracle_request_failures", "Number of requests that failed")
+	static ref NUMBER_OF_REQUEST_FAILURES: IntCounterVec =
+		register_int_counter_vec!("integritee_teeracle_request_failures", "Number of requests that failed per feed", &["feed"])
 			.unwrap();
 
-	static ref EXTRINSIC_INCLUSION_SUCCESS: IntGauge =
-		register_int_gauge!("integritee_teeracle_extrinsic_inclusion_success", "1 if extrinsics was successfully finalized, 0 if not")
+	static ref EXTRINSIC_INCLUSION_SUCCESS: IntGaugeVec =
+		register_int_gauge_vec!("integritee_teeracle_extrinsic_inclusion_success", "1 if extrinsics was successfully finalized, 0 if not, per feed", &["feed"])
 			.unwrap();
 }
 
-pub(super) fn increment_number_of_request_failures() {
-	NUMBER_OF_REQUEST_FAILURES.inc();
+pub(super) fn increment_number_of_request_failures(feed: &str) {
+	NUMBER_OF_REQUEST_FAILURES.with_label_values(&[feed]).inc();
 }
 
-pub(super) fn set_extrinsics_inclusion_success(is_successful: bool) {
+pub(super) fn set_extrinsics_inclusion_success(feed: &str, is_successful: bool) {
 	let success_values = i64::from(is_successful);
-	EXTRINSIC_INCLUSION_SUCCESS.set(success_values);
+	EXTRINSIC_INCLUSION_SUCCESS.with_label_values(&[feed]).set(success_values);
 }
 
 pub fn update_teeracle_metrics(metric: ExchangeRateOracleMetric) -> ServiceResult<()> {
@@ -62,6 +65,12 @@ pub fn update_teeracle_metrics(metric: ExchangeRateOracleMetric) -> ServiceResul
 				.map(|m| m.set(exchange_rate.to_num()))
 				.map_err(|e| Error::Custom(e.into()))?,
 
+		ExchangeRateOracleMetric::ExchangeRateDeviation(source, trading_pair, deviation) =>
+			EXCHANGE_RATE_DEVIATION
+				.get_metric_with_label_values(&[source.as_str(), trading_pair.as_str()])
+				.map(|m| m.set(deviation.to_num()))
+				.map_err(|e| Error::Custom(e.into()))?,
+
 		ExchangeRateOracleMetric::ResponseTime(source, t) => RESPONSE_TIME
 			.get_metric_with_label_values(&[source.as_str()])
 			.map(|m| m.set(t as i64))
@@ -79,6 +79,13 @@ impl PalletTeerexApi for TestNodeApi {
 	) -> ApiResult<Option<MultiEnclave<Vec<u8>>>> {
 		unreachable!()
 	}
+	fn validateers_for_shard(
+		&self,
+		_: &ShardIdentifier,
+		_at_block: Option<Hash>,
+	) -> ApiResult<Vec<AccountId>> {
+		unreachable!()
+	}
 	fn latest_ipfs_hash(
 		&self,
 		_: &ShardIdentifier,
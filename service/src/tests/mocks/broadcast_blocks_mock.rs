@@ -15,8 +15,13 @@
 
 */
 
-use crate::{sync_block_broadcaster::BroadcastBlocks, worker::WorkerResult};
-use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
+use crate::{
+	sync_block_broadcaster::{BroadcastBlocks, BroadcastFinalityVotes},
+	worker::WorkerResult,
+};
+use its_primitives::types::{
+	block::SignedBlock as SignedSidechainBlock, finality::SignedFinalityVote,
+};
 use std::vec::Vec;
 
 pub struct BroadcastBlocksMock;
@@ -26,3 +31,9 @@ impl BroadcastBlocks for BroadcastBlocksMock {
 		Ok(())
 	}
 }
+
+impl BroadcastFinalityVotes for BroadcastBlocksMock {
+	fn broadcast_finality_votes(&self, _votes: Vec<SignedFinalityVote>) -> WorkerResult<()> {
+		Ok(())
+	}
+}
@@ -23,4 +23,8 @@ impl UpdateWorkerPeers for UpdateWorkerPeersMock {
 	fn update_peers(&self) -> WorkerResult<()> {
 		Ok(())
 	}
+
+	fn number_of_peers(&self) -> WorkerResult<usize> {
+		Ok(0)
+	}
 }
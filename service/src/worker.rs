@@ -24,10 +24,12 @@ use crate::{config::Config, error::Error, initialized_service::TrackInitializati
 use async_trait::async_trait;
 use itc_rpc_client::direct_client::{DirectApi, DirectClient as DirectWorkerApi};
 use itp_node_api::{api_client::PalletTeerexApi, node_api_factory::CreateNodeApi};
-use its_primitives::types::SignedBlock as SignedSidechainBlock;
-use its_rpc_handler::constants::RPC_METHOD_NAME_IMPORT_BLOCKS;
+use its_primitives::types::{finality::SignedFinalityVote, SignedBlock as SignedSidechainBlock};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_IMPORT_BLOCKS, RPC_METHOD_NAME_IMPORT_FINALITY_VOTES,
+};
 use jsonrpsee::{
-	types::{to_json_value, traits::Client},
+	types::{to_json_value, traits::Client, JsonValue},
 	ws_client::WsClientBuilder,
 };
 use log::*;
@@ -70,6 +72,12 @@ pub trait AsyncBlockBroadcaster {
 	async fn broadcast_blocks(&self, blocks: Vec<SignedSidechainBlock>) -> WorkerResult<()>;
 }
 
+#[async_trait]
+/// Broadcast finality votes of our enclave to peers.
+pub trait AsyncFinalityVoteBroadcaster {
+	async fn broadcast_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> WorkerResult<()>;
+}
+
 #[async_trait]
 impl<NodeApiFactory, Enclave, InitializationHandler> AsyncBlockBroadcaster
 	for Worker<Config, NodeApiFactory, Enclave, InitializationHandler>
@@ -85,41 +93,75 @@ where
 		}
 
 		let blocks_json = vec![to_json_value(blocks)?];
-		let peers = self
-			.peers
+		let peers = self.current_peers()?;
+
+		self.initialization_handler.sidechain_block_produced();
+
+		broadcast_to_peers(peers, RPC_METHOD_NAME_IMPORT_BLOCKS, blocks_json);
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl<NodeApiFactory, Enclave, InitializationHandler> AsyncFinalityVoteBroadcaster
+	for Worker<Config, NodeApiFactory, Enclave, InitializationHandler>
+where
+	NodeApiFactory: CreateNodeApi + Send + Sync,
+	Enclave: Send + Sync,
+	InitializationHandler: TrackInitialization + Send + Sync,
+{
+	async fn broadcast_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> WorkerResult<()> {
+		if votes.is_empty() {
+			debug!("No finality votes to broadcast, returning");
+			return Ok(())
+		}
+
+		let votes_json = vec![to_json_value(votes)?];
+		broadcast_to_peers(
+			self.current_peers()?,
+			RPC_METHOD_NAME_IMPORT_FINALITY_VOTES,
+			votes_json,
+		);
+		Ok(())
+	}
+}
+
+impl<Config, NodeApiFactory, Enclave, InitializationHandler>
+	Worker<Config, NodeApiFactory, Enclave, InitializationHandler>
+{
+	fn current_peers(&self) -> WorkerResult<Vec<Url>> {
+		self.peers
 			.read()
 			.map_err(|e| {
 				Error::Custom(format!("Encountered poisoned lock for peers: {:?}", e).into())
 			})
-			.map(|l| l.clone())?;
+			.map(|l| l.clone())
+	}
+}
 
-		self.initialization_handler.sidechain_block_produced();
+/// Sends the request to every peer, without waiting for the responses.
+fn broadcast_to_peers(peers: Vec<Url>, method: &'static str, params: Vec<JsonValue>) {
+	for url in peers {
+		let params = params.clone();
 
-		for url in peers {
-			let blocks = blocks_json.clone();
-
-			tokio::spawn(async move {
-				debug!("Broadcasting block to peer with address: {:?}", url);
-				// FIXME: Websocket connection to a worker should stay, once established.
-				let client = match WsClientBuilder::default().build(&url).await {
-					Ok(c) => c,
-					Err(e) => {
-						error!("Failed to create websocket client for block broadcasting (target url: {}): {:?}", url, e);
-						return
-					},
-				};
-
-				if let Err(e) =
-					client.request::<Vec<u8>>(RPC_METHOD_NAME_IMPORT_BLOCKS, blocks.into()).await
-				{
+		tokio::spawn(async move {
+			debug!("Broadcasting {} to peer with address: {:?}", method, url);
+			// FIXME: Websocket connection to a worker should stay, once established.
+			let client = match WsClientBuilder::default().build(&url).await {
+				Ok(c) => c,
+				Err(e) => {
 					error!(
-						"Broadcast block request ({}) to {} failed: {:?}",
-						RPC_METHOD_NAME_IMPORT_BLOCKS, url, e
+						"Failed to create websocket client for broadcasting {} (target url: {}): {:?}",
+						method, url, e
 					);
-				}
-			});
-		}
-		Ok(())
+					return
+				},
+			};
+
+			if let Err(e) = client.request::<JsonValue>(method, params.into()).await {
+				error!("Broadcast request ({}) to {} failed: {:?}", method, url, e);
+			}
+		});
 	}
 }
 
@@ -129,6 +171,8 @@ pub trait UpdatePeers {
 
 	fn set_peers(&self, peers: Vec<Url>) -> WorkerResult<()>;
 
+	fn number_of_peers(&self) -> WorkerResult<usize>;
+
 	fn update_peers(&self) -> WorkerResult<()> {
 		let peers = self.search_peers()?;
 		self.set_peers(peers)
@@ -173,6 +217,13 @@ where
 		*peers_lock = peers;
 		Ok(())
 	}
+
+	fn number_of_peers(&self) -> WorkerResult<usize> {
+		let peers_lock = self.peers.read().map_err(|e| {
+			Error::Custom(format!("Encountered poisoned lock for peers: {:?}", e).into())
+		})?;
+		Ok(peers_lock.len())
+	}
 }
 #[cfg(test)]
 mod tests {
@@ -28,6 +28,8 @@ use std::sync::Arc;
 #[cfg_attr(test, automock)]
 pub trait UpdateWorkerPeers {
 	fn update_peers(&self) -> WorkerResult<()>;
+
+	fn number_of_peers(&self) -> WorkerResult<usize>;
 }
 
 pub struct WorkerPeersUpdater<WorkerType> {
@@ -47,4 +49,8 @@ where
 	fn update_peers(&self) -> WorkerResult<()> {
 		self.worker.update_peers()
 	}
+
+	fn number_of_peers(&self) -> WorkerResult<usize> {
+		self.worker.number_of_peers()
+	}
 }
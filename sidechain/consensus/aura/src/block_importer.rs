@@ -21,6 +21,7 @@ use codec::{Decode, Encode};
 use core::fmt::Debug;
 // Reexport BlockImport trait which implements fn block_import()
 use crate::{AuraVerifier, EnclaveOnChainOCallApi, SidechainBlockTrait};
+use itc_direct_rpc_server::NotifySubscribers;
 use itc_parentchain_block_import_dispatcher::triggered_dispatcher::TriggerParentchainBlockImport;
 use itp_enclave_metrics::EnclaveMetric;
 use itp_ocall_api::{EnclaveMetricsOCallApi, EnclaveSidechainOCallApi};
@@ -32,10 +33,14 @@ use itp_stf_state_handler::handle_state::HandleState;
 use itp_top_pool_author::traits::{AuthorApi, OnBlockImported};
 use itp_types::H256;
 pub use its_consensus_common::BlockImport;
-use its_consensus_common::Error as ConsensusError;
-use its_primitives::traits::{
-	BlockData, Header as HeaderTrait, ShardIdentifierFor, SignedBlock as SignedBlockTrait,
+use its_consensus_common::{Error as ConsensusError, SidechainForks};
+use its_primitives::{
+	traits::{
+		BlockData, Header as HeaderTrait, ShardIdentifierFor, SignedBlock as SignedBlockTrait,
+	},
+	types::{header::ImportedHeader, BlockNumber, BlockQuery, BlockSummary},
 };
+use its_state::LastBlockExt;
 use its_validateer_fetch::ValidateerFetch;
 use log::*;
 use sp_core::{crypto::UncheckedFrom, Pair};
@@ -55,6 +60,7 @@ pub struct BlockImporter<
 	StateHandler,
 	StateKeyRepository,
 	TopPoolAuthor,
+	HeadSubscriptions,
 	ParentchainBlockImporter,
 	TCS,
 	G,
@@ -62,8 +68,10 @@ pub struct BlockImporter<
 	state_handler: Arc<StateHandler>,
 	state_key_repository: Arc<StateKeyRepository>,
 	top_pool_author: Arc<TopPoolAuthor>,
+	head_subscriptions: Arc<HeadSubscriptions>,
 	parentchain_block_importer: Arc<ParentchainBlockImporter>,
 	ocall_api: Arc<OCallApi>,
+	sidechain_forks: Arc<SidechainForks<SignedSidechainBlock>>,
 	_phantom: PhantomData<(Authority, ParentchainBlock, SignedSidechainBlock, TCS, G)>,
 }
 
@@ -75,6 +83,7 @@ impl<
 		StateHandler,
 		StateKeyRepository,
 		TopPoolAuthor,
+		HeadSubscriptions,
 		ParentchainBlockImporter,
 		TCS,
 		G,
@@ -87,12 +96,13 @@ impl<
 		StateHandler,
 		StateKeyRepository,
 		TopPoolAuthor,
+		HeadSubscriptions,
 		ParentchainBlockImporter,
 		TCS,
 		G,
 	> where
 	Authority: Pair,
-	Authority::Public: std::fmt::Debug + UncheckedFrom<[u8; 32]>,
+	Authority::Public: std::fmt::Debug + UncheckedFrom<[u8; 32]> + Encode,
 	ParentchainBlock: ParentchainBlockTrait<Hash = H256>,
 	SignedSidechainBlock: SignedBlockTrait<Public = Authority::Public> + 'static,
 	<<SignedSidechainBlock as SignedBlockTrait>::Block as SidechainBlockTrait>::HeaderType:
@@ -103,10 +113,11 @@ impl<
 		+ EnclaveMetricsOCallApi
 		+ Send
 		+ Sync,
-	StateHandler: HandleState<StateT = SgxExternalities>,
+	StateHandler: HandleState<StateT = SgxExternalities, HashType = H256>,
 	StateKeyRepository: AccessKey,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 	TopPoolAuthor: AuthorApi<H256, H256, TCS, G> + OnBlockImported<Hash = H256>,
+	HeadSubscriptions: NotifySubscribers,
 	ParentchainBlockImporter: TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>
 		+ Send
 		+ Sync,
@@ -117,15 +128,19 @@ impl<
 		state_handler: Arc<StateHandler>,
 		state_key_repository: Arc<StateKeyRepository>,
 		top_pool_author: Arc<TopPoolAuthor>,
+		head_subscriptions: Arc<HeadSubscriptions>,
 		parentchain_block_importer: Arc<ParentchainBlockImporter>,
 		ocall_api: Arc<OCallApi>,
+		sidechain_forks: Arc<SidechainForks<SignedSidechainBlock>>,
 	) -> Self {
 		Self {
 			state_handler,
 			state_key_repository,
 			top_pool_author,
+			head_subscriptions,
 			parentchain_block_importer,
 			ocall_api,
+			sidechain_forks,
 			_phantom: Default::default(),
 		}
 	}
@@ -156,6 +171,13 @@ impl<
 		// 	error!("Could not remove call {:?} from top pool", call_failed_to_remove);
 		// }
 	}
+
+	/// Sends the header of an imported block, including the blocks produced by this validateer,
+	/// which are imported from the import queue like the blocks of its peers.
+	fn notify_head_subscribers(&self, sidechain_block: &SignedSidechainBlock::Block) {
+		self.head_subscriptions
+			.notify_subscribers(ImportedHeader::from_block(sidechain_block).encode());
+	}
 }
 
 impl<
@@ -166,6 +188,7 @@ impl<
 		StateHandler,
 		StateKeyRepository,
 		TopPoolAuthor,
+		HeadSubscriptions,
 		ParentchainBlockImporter,
 		TCS,
 		G,
@@ -178,12 +201,13 @@ impl<
 		StateHandler,
 		StateKeyRepository,
 		TopPoolAuthor,
+		HeadSubscriptions,
 		ParentchainBlockImporter,
 		TCS,
 		G,
 	> where
 	Authority: Pair,
-	Authority::Public: std::fmt::Debug + UncheckedFrom<[u8; 32]>,
+	Authority::Public: std::fmt::Debug + UncheckedFrom<[u8; 32]> + Encode,
 	ParentchainBlock: ParentchainBlockTrait<Hash = H256>,
 	SignedSidechainBlock: SignedBlockTrait<Public = Authority::Public> + 'static,
 	<<SignedSidechainBlock as SignedBlockTrait>::Block as SidechainBlockTrait>::HeaderType:
@@ -194,10 +218,11 @@ impl<
 		+ EnclaveMetricsOCallApi
 		+ Send
 		+ Sync,
-	StateHandler: HandleState<StateT = SgxExternalities>,
+	StateHandler: HandleState<StateT = SgxExternalities, HashType = H256>,
 	StateKeyRepository: AccessKey,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 	TopPoolAuthor: AuthorApi<H256, H256, TCS, G> + OnBlockImported<Hash = H256>,
+	HeadSubscriptions: NotifySubscribers,
 	ParentchainBlockImporter: TriggerParentchainBlockImport<SignedBlockType = SignedParentchainBlock<ParentchainBlock>>
 		+ Send
 		+ Sync,
@@ -223,7 +248,7 @@ impl<
 		&self,
 		shard: &ShardIdentifierFor<SignedSidechainBlock>,
 		mutating_function: F,
-	) -> Result<(), ConsensusError>
+	) -> Result<H256, ConsensusError>
 	where
 		F: FnOnce(Self::SidechainState) -> Result<Self::SidechainState, ConsensusError>,
 	{
@@ -238,9 +263,43 @@ impl<
 
 		self.state_handler
 			.write_after_mutation(updated_state, write_lock, shard)
-			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))?;
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
 
-		Ok(())
+	fn revert_state(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state_hash: &H256,
+	) -> Result<(), ConsensusError> {
+		self.state_handler
+			.revert_to(shard, state_hash)
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
+	fn current_head(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<(SignedSidechainBlock::Block, H256)>, ConsensusError> {
+		self.state_handler
+			.execute_on_current(shard, |state, state_hash| {
+				state.get_last_block().map(|block| (block, state_hash))
+			})
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
+	fn sidechain_forks(&self) -> &SidechainForks<SignedSidechainBlock> {
+		&self.sidechain_forks
+	}
+
+	fn finalized_block_number(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<BlockNumber>, ConsensusError> {
+		let finalized_blocks: Vec<BlockSummary> = self
+			.ocall_api
+			.query_sidechain_blocks(BlockQuery::Finalized(*shard))
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))?;
+		Ok(finalized_blocks.first().map(|block| block.header.block_number))
 	}
 
 	fn verify_import<F>(
@@ -333,12 +392,20 @@ impl<
 			})
 	}
 
+	fn replay_parentchain_import(&self) -> Result<(), ConsensusError> {
+		self.parentchain_block_importer
+			.replay_latest_import()
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
 	fn cleanup(&self, signed_sidechain_block: &SignedSidechainBlock) -> Result<(), ConsensusError> {
 		let sidechain_block = signed_sidechain_block.block();
 
 		// Remove all successfully applied trusted calls from the top pool.
 		self.update_top_pool(sidechain_block);
 
+		self.notify_head_subscribers(sidechain_block);
+
 		// Send metric about sidechain block height (i.e. block number)
 		let block_height_metric =
 			EnclaveMetric::SetSidechainBlockHeight(sidechain_block.header().block_number());
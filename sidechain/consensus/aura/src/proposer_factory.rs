@@ -24,12 +24,12 @@ use itp_stf_executor::traits::StateUpdateProposer;
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::H256;
 use its_block_composer::ComposeBlock;
-use its_consensus_common::{Environment, Error as ConsensusError};
+use its_consensus_common::{Environment, Error as ConsensusError, SidechainForks};
 use its_primitives::traits::{
 	Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor,
 	SignedBlock as SignedSidechainBlockTrait,
 };
-use its_state::{SidechainState, SidechainSystemExt};
+use its_state::{LastBlockExt, SidechainState, SidechainSystemExt};
 use sp_runtime::{
 	traits::{Block, NumberFor},
 	MultiSignature,
@@ -38,25 +38,40 @@ use std::{marker::PhantomData, sync::Arc};
 
 ///! `ProposerFactory` instance containing all the data to create the `SlotProposer` for the
 /// next `Slot`.
-pub struct ProposerFactory<ParentchainBlock: Block, TopPoolAuthor, StfExecutor, BlockComposer> {
+pub struct ProposerFactory<
+	ParentchainBlock: Block,
+	SignedSidechainBlock: SignedSidechainBlockTrait,
+	TopPoolAuthor,
+	StfExecutor,
+	BlockComposer,
+> {
 	top_pool_author: Arc<TopPoolAuthor>,
 	stf_executor: Arc<StfExecutor>,
 	block_composer: Arc<BlockComposer>,
+	sidechain_forks: Arc<SidechainForks<SignedSidechainBlock>>,
 	_phantom: PhantomData<ParentchainBlock>,
 }
 
-impl<ParentchainBlock: Block, TopPoolAuthor, StfExecutor, BlockComposer>
-	ProposerFactory<ParentchainBlock, TopPoolAuthor, StfExecutor, BlockComposer>
+impl<
+		ParentchainBlock: Block,
+		SignedSidechainBlock: SignedSidechainBlockTrait,
+		TopPoolAuthor,
+		StfExecutor,
+		BlockComposer,
+	>
+	ProposerFactory<ParentchainBlock, SignedSidechainBlock, TopPoolAuthor, StfExecutor, BlockComposer>
 {
 	pub fn new(
 		top_pool_executor: Arc<TopPoolAuthor>,
 		stf_executor: Arc<StfExecutor>,
 		block_composer: Arc<BlockComposer>,
+		sidechain_forks: Arc<SidechainForks<SignedSidechainBlock>>,
 	) -> Self {
 		Self {
 			top_pool_author: top_pool_executor,
 			stf_executor,
 			block_composer,
+			sidechain_forks,
 			_phantom: Default::default(),
 		}
 	}
@@ -69,8 +84,13 @@ impl<
 		StfExecutor,
 		BlockComposer,
 	> Environment<ParentchainBlock, SignedSidechainBlock>
-	for ProposerFactory<ParentchainBlock, TopPoolAuthor, StfExecutor, BlockComposer>
-where
+	for ProposerFactory<
+		ParentchainBlock,
+		SignedSidechainBlock,
+		TopPoolAuthor,
+		StfExecutor,
+		BlockComposer,
+	> where
 	NumberFor<ParentchainBlock>: BlockNumberOps,
 	SignedSidechainBlock: SignedSidechainBlockTrait<Public = sp_core::ed25519::Public, Signature = MultiSignature>
 		+ 'static,
@@ -80,8 +100,11 @@ where
 	TopPoolAuthor:
 		AuthorApi<H256, ParentchainBlock::Hash, TrustedCallSigned, Getter> + Send + Sync + 'static,
 	StfExecutor: StateUpdateProposer<TrustedCallSigned, Getter> + Send + Sync + 'static,
-	ExternalitiesFor<StfExecutor>:
-		SgxExternalitiesTrait + SidechainState + SidechainSystemExt + StateHash,
+	ExternalitiesFor<StfExecutor>: SgxExternalitiesTrait
+		+ SidechainState
+		+ SidechainSystemExt
+		+ StateHash
+		+ LastBlockExt<SignedSidechainBlock::Block>,
 	<ExternalitiesFor<StfExecutor> as SgxExternalitiesTrait>::SgxExternalitiesType: Encode,
 	BlockComposer: ComposeBlock<
 			ExternalitiesFor<StfExecutor>,
@@ -109,6 +132,7 @@ where
 			top_pool_author: self.top_pool_author.clone(),
 			stf_executor: self.stf_executor.clone(),
 			block_composer: self.block_composer.clone(),
+			sidechain_forks: self.sidechain_forks.clone(),
 			parentchain_header: parent_header,
 			shard,
 			_phantom: PhantomData,
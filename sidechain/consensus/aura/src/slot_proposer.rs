@@ -24,12 +24,12 @@ use itp_time_utils::now_as_millis;
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::H256;
 use its_block_composer::ComposeBlock;
-use its_consensus_common::{Error as ConsensusError, Proposal, Proposer};
+use its_consensus_common::{Error as ConsensusError, Proposal, Proposer, SidechainForks};
 use its_primitives::traits::{
 	Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor,
 	SignedBlock as SignedSidechainBlockTrait,
 };
-use its_state::{SidechainState, SidechainSystemExt};
+use its_state::{LastBlockExt, SidechainState, SidechainSystemExt};
 use log::*;
 use sp_runtime::{
 	traits::{Block, NumberFor},
@@ -49,6 +49,7 @@ pub struct SlotProposer<
 	pub(crate) top_pool_author: Arc<TopPoolAuthor>,
 	pub(crate) stf_executor: Arc<StfExecutor>,
 	pub(crate) block_composer: Arc<BlockComposer>,
+	pub(crate) sidechain_forks: Arc<SidechainForks<SignedSidechainBlock>>,
 	pub(crate) parentchain_header: ParentchainBlock::Header,
 	pub(crate) shard: ShardIdentifierFor<SignedSidechainBlock>,
 	pub(crate) _phantom: PhantomData<ParentchainBlock>,
@@ -66,8 +67,11 @@ where
 	<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as SidechainBlockTrait>::HeaderType:
 		HeaderTrait<ShardIdentifier = H256>,
 	StfExecutor: StateUpdateProposer<TrustedCallSigned, Getter>,
-	ExternalitiesFor<StfExecutor>:
-		SgxExternalitiesTrait + SidechainState + SidechainSystemExt + StateHash,
+	ExternalitiesFor<StfExecutor>: SgxExternalitiesTrait
+		+ SidechainState
+		+ SidechainSystemExt
+		+ StateHash
+		+ LastBlockExt<SignedSidechainBlock::Block>,
 	<ExternalitiesFor<StfExecutor> as SgxExternalitiesTrait>::SgxExternalitiesType: Encode,
 	TopPoolAuthor:
 		AuthorApi<H256, ParentchainBlock::Hash, TrustedCallSigned, Getter> + Send + Sync + 'static,
@@ -85,6 +89,7 @@ where
 	/// 1) Retrieve all trusted calls from the top pool.
 	/// 2) Calculate a new state that will be proposed in the sidechain block.
 	/// 3) Compose the sidechain block and the parentchain confirmation.
+	/// 4) Record the block in the sidechain forks.
 	fn propose(
 		&self,
 		max_duration: Duration,
@@ -122,6 +127,11 @@ where
 			batch_execution_result.get_executed_operation_hashes().to_vec();
 		let number_executed_transactions = executed_operation_hashes.len();
 
+		// Report the errors of calls rejected by the STF to their submitters, before they are
+		// removed from the top pool.
+		self.top_pool_author
+			.report_failed_calls(batch_execution_result.get_operation_errors());
+
 		// Remove all not successfully executed operations from the top pool.
 		let failed_operations = batch_execution_result.get_failed_operations();
 		self.top_pool_author.remove_calls_from_pool(
@@ -147,6 +157,17 @@ where
 			)
 			.map_err(|e| ConsensusError::Other(e.to_string().into()))?;
 
+		// 4) Record the block, it is applied to the state once it is imported.
+		if let Some(parent) = batch_execution_result.state_after_execution.get_last_block() {
+			if let Err(e) = self.sidechain_forks.on_block_proposed(
+				&self.shard,
+				(parent, batch_execution_result.state_hash_before_execution),
+				&sidechain_block,
+			) {
+				warn!("Failed to track proposed sidechain block in the sidechain forks: {:?}", e);
+			}
+		}
+
 		info!(
 			"Queue/Timeslot/Transactions: {:?};{};{}",
 			trusted_calls.len(),
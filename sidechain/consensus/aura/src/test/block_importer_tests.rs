@@ -16,12 +16,14 @@
 */
 
 use crate::{block_importer::BlockImporter, ShardIdentifierFor};
-use codec::Encode;
+use codec::{Decode, Encode};
 use core::assert_matches::assert_matches;
+use itc_direct_rpc_server::mocks::notify_subscribers_mock::NotifySubscribersMock;
 use itc_parentchain_block_import_dispatcher::trigger_parentchain_block_import_mock::TriggerParentchainBlockImportMock;
 use itc_parentchain_test::{ParentchainBlockBuilder, ParentchainHeaderBuilder};
+use itp_settings::sidechain::{MAX_REORG_DEPTH, SLOT_DURATION};
 use itp_sgx_crypto::{aes::Aes, mocks::KeyRepositoryMock, StateCrypto};
-use itp_sgx_externalities::SgxExternalitiesDiffType;
+use itp_sgx_externalities::{SgxExternalitiesDiffType, SgxExternalitiesTrait};
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_test::mock::{
 	handle_state_mock::HandleStateMock,
@@ -31,10 +33,10 @@ use itp_test::mock::{
 use itp_time_utils::{duration_now, now_as_millis};
 use itp_top_pool_author::mocks::AuthorApiMock;
 use itp_types::{Block as ParentchainBlock, Header as ParentchainHeader, H256};
-use its_consensus_common::{BlockImport, Error as ConsensusError};
+use its_consensus_common::{BlockImport, Error as ConsensusError, SidechainForks};
 use its_primitives::{
-	traits::{SignBlock, SignedBlock},
-	types::SignedBlock as SignedSidechainBlock,
+	traits::{Block as SidechainBlockTrait, BlockData, SignBlock, SignedBlock},
+	types::{header::ImportedHeader, SignedBlock as SignedSidechainBlock},
 };
 use its_state::StateUpdate;
 use its_test::{
@@ -42,7 +44,11 @@ use its_test::{
 	sidechain_block_data_builder::SidechainBlockDataBuilder,
 	sidechain_header_builder::SidechainHeaderBuilder,
 };
-use sp_core::{blake2_256, ed25519::Pair};
+use sp_core::{
+	blake2_256,
+	ed25519::{Pair, Public},
+	Pair as PairTrait,
+};
 use sp_keyring::ed25519::Keyring;
 use sp_runtime::generic::SignedBlock as SignedParentchainBlock;
 use std::sync::Arc;
@@ -51,6 +57,7 @@ type TestTopPoolAuthor = AuthorApiMock<H256, H256, TrustedCallSignedMock, Getter
 type TestParentchainBlockImportTrigger =
 	TriggerParentchainBlockImportMock<SignedParentchainBlock<ParentchainBlock>>;
 type TestStateKeyRepo = KeyRepositoryMock<Aes>;
+type TestHeadSubscriptions = NotifySubscribersMock<H256>;
 type TestBlockImporter = BlockImporter<
 	Pair,
 	ParentchainBlock,
@@ -59,6 +66,7 @@ type TestBlockImporter = BlockImporter<
 	HandleStateMock,
 	TestStateKeyRepo,
 	TestTopPoolAuthor,
+	TestHeadSubscriptions,
 	TestParentchainBlockImportTrigger,
 	TrustedCallSignedMock,
 	GetterMock,
@@ -79,6 +87,18 @@ fn default_authority() -> Pair {
 fn test_fixtures(
 	parentchain_header: &ParentchainHeader,
 	parentchain_block_import_trigger: Arc<TestParentchainBlockImportTrigger>,
+) -> (TestBlockImporter, Arc<HandleStateMock>, Arc<TestTopPoolAuthor>) {
+	test_fixtures_with_head_subscriptions(
+		parentchain_header,
+		parentchain_block_import_trigger,
+		Arc::new(TestHeadSubscriptions::default()),
+	)
+}
+
+fn test_fixtures_with_head_subscriptions(
+	parentchain_header: &ParentchainHeader,
+	parentchain_block_import_trigger: Arc<TestParentchainBlockImportTrigger>,
+	head_subscriptions: Arc<TestHeadSubscriptions>,
 ) -> (TestBlockImporter, Arc<HandleStateMock>, Arc<TestTopPoolAuthor>) {
 	let state_handler = Arc::new(HandleStateMock::from_shard(shard()).unwrap());
 	let top_pool_author = Arc::new(TestTopPoolAuthor::default());
@@ -93,8 +113,10 @@ fn test_fixtures(
 		state_handler.clone(),
 		state_key_repository,
 		top_pool_author.clone(),
+		head_subscriptions,
 		parentchain_block_import_trigger,
 		ocall_api,
+		Arc::new(SidechainForks::new(SLOT_DURATION, MAX_REORG_DEPTH)),
 	);
 
 	(block_importer, state_handler, top_pool_author)
@@ -227,6 +249,29 @@ fn cleanup_removes_tops_from_pool() {
 	assert_eq!(2, *top_pool_author.remove_attempts.read().unwrap());
 }
 
+#[test]
+fn cleanup_notifies_head_subscribers() {
+	let parentchain_header = ParentchainHeaderBuilder::default().build();
+	let head_subscriptions = Arc::new(TestHeadSubscriptions::default());
+	let (block_importer, state_handler, _) = test_fixtures_with_head_subscriptions(
+		&parentchain_header,
+		Arc::new(TestParentchainBlockImportTrigger::default()),
+		head_subscriptions.clone(),
+	);
+	let signed_sidechain_block =
+		default_authority_signed_block(&parentchain_header, state_handler.as_ref());
+
+	block_importer.cleanup(&signed_sidechain_block).unwrap();
+
+	let notifications = head_subscriptions.notifications.read().unwrap();
+	assert_eq!(1, notifications.len());
+	let imported_header =
+		ImportedHeader::<Public>::decode(&mut notifications[0].as_slice()).unwrap();
+	assert_eq!(imported_header.block_hash, signed_sidechain_block.hash());
+	assert_eq!(imported_header.shard_id, shard());
+	assert_eq!(imported_header.block_author, default_authority().public());
+}
+
 #[test]
 fn sidechain_block_import_triggers_parentchain_block_import() {
 	let previous_parentchain_header = ParentchainHeaderBuilder::default().with_number(4).build();
@@ -309,3 +354,135 @@ fn peek_parentchain_block_returns_error_if_no_corresponding_block_can_be_found()
 
 	assert_matches!(peek_result, Err(ConsensusError::Other(_)));
 }
+
+#[test]
+fn block_on_better_fork_triggers_reorg() {
+	let parentchain_header = ParentchainHeaderBuilder::default().build();
+	let (block_importer, state_handler, _) =
+		test_fixtures_with_default_import_trigger(&parentchain_header);
+	let now = now_as_millis();
+
+	let block_1 = child_block(&parentchain_header, state_handler.as_ref(), None, now - 3_000);
+	block_importer.import_block(block_1.clone(), &parentchain_header).unwrap();
+
+	let late_block_2 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&block_1), now - 1_000);
+	let early_block_2 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&block_1), now - 2_000);
+
+	block_importer.import_block(late_block_2.clone(), &parentchain_header).unwrap();
+	block_importer.import_block(early_block_2.clone(), &parentchain_header).unwrap();
+
+	// Both blocks have the same number, the one authored in the earlier slot wins.
+	let (head, _) = block_importer.current_head(&shard()).unwrap().unwrap();
+	assert_eq!(head.hash(), early_block_2.hash());
+	assert_eq!(
+		block_importer.sidechain_forks().head(&shard()).unwrap(),
+		Some(early_block_2.hash())
+	);
+	assert_matches!(
+		block_importer.import_block(late_block_2, &parentchain_header),
+		Err(ConsensusError::BlockAlreadyImported(2, 2))
+	);
+}
+
+#[test]
+fn reorg_replays_the_parentchain_import_since_the_common_ancestor() {
+	let parentchain_header = ParentchainHeaderBuilder::default().build();
+	let parentchain_block_import_trigger = Arc::new(TestParentchainBlockImportTrigger::default());
+	let (block_importer, state_handler, _) =
+		test_fixtures(&parentchain_header, parentchain_block_import_trigger.clone());
+	let now = now_as_millis();
+
+	let block_1 = child_block(&parentchain_header, state_handler.as_ref(), None, now - 3_000);
+	block_importer.import_block(block_1.clone(), &parentchain_header).unwrap();
+	let early_block_2 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&block_1), now - 2_000);
+
+	// A parentchain block import in a slot writes the state after block 1 was applied.
+	let (_, state_hash_before) = block_importer.current_head(&shard()).unwrap().unwrap();
+	let (state_lock, mut state) = state_handler.load_for_mutation(&shard()).unwrap();
+	state.insert("parentchain_block".encode(), 2u32.encode());
+	let state_hash_after = state_handler.write_after_mutation(state, state_lock, &shard()).unwrap();
+	block_importer
+		.sidechain_forks()
+		.on_parentchain_imported(&shard(), state_hash_before, state_hash_after)
+		.unwrap();
+
+	let late_block_2 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&block_1), now - 1_000);
+	block_importer.import_block(late_block_2, &parentchain_header).unwrap();
+	block_importer.import_block(early_block_2.clone(), &parentchain_header).unwrap();
+
+	let (head, _) = block_importer.current_head(&shard()).unwrap().unwrap();
+	assert_eq!(head.hash(), early_block_2.hash());
+	assert!(parentchain_block_import_trigger.has_replay_been_called());
+}
+
+#[test]
+fn failed_reorg_restores_the_previous_chain() {
+	let parentchain_header = ParentchainHeaderBuilder::default().build();
+	let (block_importer, state_handler, _) =
+		test_fixtures_with_default_import_trigger(&parentchain_header);
+	let now = now_as_millis();
+
+	let block_1 = child_block(&parentchain_header, state_handler.as_ref(), None, now - 5_000);
+	block_importer.import_block(block_1.clone(), &parentchain_header).unwrap();
+
+	let block_2 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&block_1), now - 3_000);
+	let fork_block_2 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&block_1), now - 2_000);
+	// The state update does not apply on top of the state of `fork_block_2`.
+	let fork_block_3 =
+		child_block(&parentchain_header, state_handler.as_ref(), Some(&fork_block_2), now - 1_000);
+
+	block_importer.import_block(block_2.clone(), &parentchain_header).unwrap();
+	let (_, state_hash) = block_importer.current_head(&shard()).unwrap().unwrap();
+
+	block_importer.import_block(fork_block_2.clone(), &parentchain_header).unwrap();
+	assert!(block_importer.import_block(fork_block_3.clone(), &parentchain_header).is_err());
+
+	assert_eq!(
+		block_importer.current_head(&shard()).unwrap(),
+		Some((block_2.block.clone(), state_hash))
+	);
+	assert_eq!(block_importer.sidechain_forks().head(&shard()).unwrap(), Some(block_2.hash()));
+	assert!(block_importer
+		.sidechain_forks()
+		.contains(&shard(), &fork_block_2.hash())
+		.unwrap());
+	assert!(!block_importer
+		.sidechain_forks()
+		.contains(&shard(), &fork_block_3.hash())
+		.unwrap());
+}
+
+fn child_block(
+	parentchain_header: &ParentchainHeader,
+	state_handler: &HandleStateMock,
+	maybe_parent: Option<&SignedSidechainBlock>,
+	timestamp: u64,
+) -> SignedSidechainBlock {
+	let state_update = empty_encrypted_state_update(state_handler);
+
+	let block_data = SidechainBlockDataBuilder::default()
+		.with_timestamp(timestamp)
+		.with_layer_one_head(parentchain_header.hash())
+		.with_signer(default_authority())
+		.with_payload(state_update)
+		.build();
+
+	let header = SidechainHeaderBuilder::default()
+		.with_parent_hash(maybe_parent.map(|p| p.hash()).unwrap_or_default())
+		.with_block_number(maybe_parent.map(|p| p.block.header.block_number + 1).unwrap_or(1))
+		.with_shard(shard())
+		.with_block_data_hash(block_data.hash())
+		.build();
+
+	SidechainBlockBuilder::default()
+		.with_header(header)
+		.with_block_data(block_data)
+		.with_signer(default_authority())
+		.build_signed()
+}
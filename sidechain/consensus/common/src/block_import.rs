@@ -17,13 +17,17 @@
 
 //! Abstraction around block import
 
-use crate::{Error, Verifier};
+use crate::{Error, Reorg, SidechainForks, Verifier};
 use codec::Decode;
 use itp_ocall_api::EnclaveSidechainOCallApi;
 use itp_sgx_crypto::StateCrypto;
-use its_primitives::traits::{
-	Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
-	SignedBlock as SignedSidechainBlockTrait,
+use itp_types::H256;
+use its_primitives::{
+	traits::{
+		Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
+		SignedBlock as SignedSidechainBlockTrait,
+	},
+	types::BlockNumber,
 };
 use its_state::{LastBlockExt, SidechainState};
 use log::*;
@@ -59,14 +63,38 @@ where
 	) -> Self::Verifier;
 
 	/// Apply a state update by providing a mutating function.
+	///
+	/// Returns the hash of the updated state.
 	fn apply_state_update<F>(
 		&self,
 		shard: &ShardIdentifierFor<SignedSidechainBlock>,
 		mutating_function: F,
-	) -> Result<(), Error>
+	) -> Result<H256, Error>
 	where
 		F: FnOnce(Self::SidechainState) -> Result<Self::SidechainState, Error>;
 
+	/// Revert the state to a previous state, identified by its hash.
+	fn revert_state(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state_hash: &H256,
+	) -> Result<(), Error>;
+
+	/// Last sidechain block that was applied to the state, together with the current state hash.
+	fn current_head(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<(SignedSidechainBlock::Block, H256)>, Error>;
+
+	/// Competing forks of the sidechain, used to choose the best chain upon import.
+	fn sidechain_forks(&self) -> &SidechainForks<SignedSidechainBlock>;
+
+	/// Number of the last finalized sidechain block, if any. Reorgs must not retract it.
+	fn finalized_block_number(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<BlockNumber>, Error>;
+
 	/// Verify a sidechain block that is to be imported.
 	fn verify_import<F>(
 		&self,
@@ -102,14 +130,180 @@ where
 		sidechain_block: &SignedSidechainBlock::Block,
 		last_imported_parentchain_header: &ParentchainBlock::Header,
 	) -> Result<ParentchainBlock::Header, Error>;
+
+	/// Perform the state updates of the latest imported parentchain block again, after the state
+	/// has been reverted.
+	fn replay_parentchain_import(&self) -> Result<(), Error>;
+
 	/// Cleanup task after import is done.
 	fn cleanup(&self, signed_sidechain_block: &SignedSidechainBlock) -> Result<(), Error>;
 
-	/// Import a sidechain block and mutate state by `apply_state_update`.
+	/// Import a sidechain block.
+	///
+	/// A block that extends the current head is applied to the state by `import_on_head`. A block
+	/// that extends any other known block is tracked as fork block by `import_fork_block`.
 	fn import_block(
 		&self,
 		signed_sidechain_block: SignedSidechainBlock,
 		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<ParentchainBlock::Header, Error> {
+		let shard = signed_sidechain_block.block().header().shard_id();
+		let parent_hash = signed_sidechain_block.block().header().parent_hash();
+
+		let maybe_head = self.current_head(&shard)?;
+		let extends_head = match &maybe_head {
+			Some((head, _)) => head.hash() == parent_hash,
+			None => true,
+		};
+		self.sidechain_forks().track_head(&shard, maybe_head)?;
+
+		// Blocks with an unknown parent take the regular import path as well, which reports the
+		// ancestry mismatch.
+		if extends_head || !self.sidechain_forks().contains(&shard, &parent_hash)? {
+			self.import_on_head(signed_sidechain_block, parentchain_header)
+		} else {
+			self.import_fork_block(signed_sidechain_block, parentchain_header)
+		}
+	}
+
+	/// Track a sidechain block on a fork. Reorgs to its fork in case it becomes the best chain.
+	fn import_fork_block(
+		&self,
+		signed_sidechain_block: SignedSidechainBlock,
+		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<ParentchainBlock::Header, Error> {
+		let sidechain_block = signed_sidechain_block.block().clone();
+		let shard = sidechain_block.header().shard_id();
+		let parent_hash = sidechain_block.header().parent_hash();
+
+		debug!(
+			"Attempting to import sidechain block on a fork (number: {}, hash: {:?}, parent: {:?})",
+			sidechain_block.header().block_number(),
+			sidechain_block.hash(),
+			parent_hash
+		);
+
+		let peeked_parentchain_header =
+			self.peek_parentchain_header(&sidechain_block, parentchain_header)
+				.unwrap_or_else(|e| {
+					warn!("Could not peek parentchain block, returning latest parentchain block ({:?})", e);
+					parentchain_header.clone()
+				});
+
+		if let Some(finalized_number) = self.finalized_block_number(&shard)? {
+			self.sidechain_forks().on_finalized(&shard, finalized_number)?;
+		}
+
+		let verifier = self.verifier(self.sidechain_forks().block(&shard, &parent_hash)?);
+		let verified_block = verifier.verify(
+			signed_sidechain_block,
+			&peeked_parentchain_header,
+			shard,
+			self.get_context(),
+		)?;
+
+		match self.sidechain_forks().import_fork_block(&shard, verified_block)? {
+			Some(reorg) => self.reorg(&shard, reorg, parentchain_header),
+			None => Ok(parentchain_header.clone()),
+		}
+	}
+
+	/// Switch to a better fork: Revert the state to the common ancestor and apply the fork blocks.
+	/// The state updates of the parentchain blocks imported since the common ancestor are lost
+	/// with the revert, so the latest parentchain import is replayed on the common ancestor.
+	///
+	/// In case a fork block fails to import, it is discarded together with its descendants and the
+	/// previous chain is restored by reverting to the common ancestor again and applying the
+	/// retracted blocks.
+	///
+	/// Every applied block is cleaned up like any imported block, i.e. the header of a restored
+	/// block is sent to the head subscribers again, as it becomes the head once more.
+	fn reorg(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		reorg: Reorg<SignedSidechainBlock>,
+		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<ParentchainBlock::Header, Error> {
+		let Reorg { common_ancestor, common_ancestor_state_hash, enacted, retracted } = reorg;
+		warn!(
+			"Sidechain reorg: reverting to block {:?} and applying {} blocks of a better fork",
+			common_ancestor,
+			enacted.len()
+		);
+
+		let (failed_block_hash, e) = match self.apply_on_common_ancestor(
+			shard,
+			(&common_ancestor, &common_ancestor_state_hash),
+			enacted,
+			parentchain_header,
+		)? {
+			Ok(latest_parentchain_header) => return Ok(latest_parentchain_header),
+			Err(failure) => failure,
+		};
+
+		error!(
+			"Failed to import block {:?} of the better fork, restoring the previous chain: {:?}",
+			failed_block_hash, e
+		);
+		self.sidechain_forks().discard(shard, &failed_block_hash)?;
+
+		if let Err((block_hash, restore_error)) = self.apply_on_common_ancestor(
+			shard,
+			(&common_ancestor, &common_ancestor_state_hash),
+			retracted,
+			parentchain_header,
+		)? {
+			error!(
+				"Failed to restore the previous chain at block {:?}: {:?}",
+				block_hash, restore_error
+			);
+		}
+		Err(e)
+	}
+
+	/// Revert the state to the common ancestor of a reorg, i.e. its block hash and state hash,
+	/// and apply the given blocks on top of it.
+	///
+	/// Fails if the state cannot be reverted. If a block fails to import, the inner result holds
+	/// the hash of that block together with the error.
+	#[allow(clippy::type_complexity)]
+	fn apply_on_common_ancestor(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		(common_ancestor, common_ancestor_state_hash): (&H256, &H256),
+		blocks: Vec<SignedSidechainBlock>,
+		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<Result<ParentchainBlock::Header, (H256, Error)>, Error> {
+		self.revert_state(shard, common_ancestor_state_hash)?;
+		self.sidechain_forks().on_reverted(shard, common_ancestor)?;
+
+		// The parentchain state updates only depend on the latest imported parentchain block.
+		if let Err(e) = self.replay_parentchain_import() {
+			error!("Failed to replay the parentchain import on the common ancestor: {:?}", e);
+		} else if let Some((_, state_hash)) = self.current_head(shard)? {
+			self.sidechain_forks().on_parentchain_imported(
+				shard,
+				*common_ancestor_state_hash,
+				state_hash,
+			)?;
+		}
+
+		let mut latest_parentchain_header = parentchain_header.clone();
+		for signed_sidechain_block in blocks {
+			let block_hash = signed_sidechain_block.hash();
+			match self.import_on_head(signed_sidechain_block, &latest_parentchain_header) {
+				Ok(header) => latest_parentchain_header = header,
+				Err(e) => return Ok(Err((block_hash, e))),
+			}
+		}
+		Ok(Ok(latest_parentchain_header))
+	}
+
+	/// Import a sidechain block on top of the current head and mutate state by `apply_state_update`.
+	fn import_on_head(
+		&self,
+		signed_sidechain_block: SignedSidechainBlock,
+		parentchain_header: &ParentchainBlock::Header,
 	) -> Result<ParentchainBlock::Header, Error> {
 		let start_time = Instant::now();
 
@@ -141,13 +335,17 @@ where
 			)
 		})?;
 
+		// Taken before the parentchain import, its state updates are part of the state transition
+		// of the block.
+		let parent_state_hash = self.current_head(&shard)?.map(|(_, state_hash)| state_hash);
+
 		let latest_parentchain_header =
 			self.import_parentchain_block(&sidechain_block, parentchain_header)?;
 
 		let state_key = self.state_key()?;
 
 		let state_update_start_time = Instant::now();
-		self.apply_state_update(&shard, |mut state| {
+		let state_hash = self.apply_state_update(&shard, |mut state| {
 			let encrypted_state_diff =
 				block_import_params.block().block_data().encrypted_state_diff();
 
@@ -171,6 +369,15 @@ where
 			state_update_start_time.elapsed().as_millis()
 		);
 
+		if let Err(e) = self.sidechain_forks().on_block_applied(
+			&shard,
+			&signed_sidechain_block,
+			parent_state_hash,
+			state_hash,
+		) {
+			warn!("Failed to track imported sidechain block in the sidechain forks: {:?}", e);
+		}
+
 		self.cleanup(&signed_sidechain_block)?;
 
 		// Store block in storage.
@@ -74,6 +74,11 @@ impl SuspendBlockProduction for BlockProductionSuspender {
 	fn resume(&self) -> Result<()> {
 		let mut suspended_lock = self.is_suspended.write().map_err(|_| Error::LockPoisoning)?;
 		*suspended_lock = false;
+
+		let mut sync_is_ongoing_lock =
+			self.sync_is_ongoing.write().map_err(|_| Error::LockPoisoning)?;
+		*sync_is_ongoing_lock = false;
+
 		info!("Resume sidechain block production");
 		Ok(())
 	}
@@ -105,8 +110,10 @@ mod tests {
 
 		block_production_suspender.suspend_for_sync().unwrap();
 		assert!(block_production_suspender.is_suspended().unwrap());
+		assert!(block_production_suspender.is_sync_ongoing().unwrap());
 
 		block_production_suspender.resume().unwrap();
 		assert!(!block_production_suspender.is_suspended().unwrap());
+		assert!(!block_production_suspender.is_sync_ongoing().unwrap());
 	}
 }
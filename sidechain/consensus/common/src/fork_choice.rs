@@ -0,0 +1,1084 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Tracking of competing sidechain forks and the fork-choice rule to select among them.
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::{Error, Result};
+use core::{cmp::Ordering, time::Duration};
+use fork_tree::{Error as ForkTreeError, FilterAction, ForkTree};
+use itp_types::H256;
+use its_primitives::{
+	traits::{
+		Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
+		SignedBlock as SignedSidechainBlockTrait,
+	},
+	types::{BlockHash, BlockNumber},
+};
+use log::*;
+use std::{
+	collections::{HashMap, HashSet},
+	format,
+	vec::Vec,
+};
+
+/// Blocks to apply in order to switch to a better fork.
+pub struct Reorg<SignedSidechainBlock> {
+	/// Last block that the current chain and the better fork have in common.
+	pub common_ancestor: BlockHash,
+	/// Hash of the state after the common ancestor was applied. The state is reverted to it.
+	pub common_ancestor_state_hash: H256,
+	/// Blocks of the better fork after the common ancestor, oldest first.
+	pub enacted: Vec<SignedSidechainBlock>,
+	/// Blocks of the current chain after the common ancestor, oldest first. They are applied
+	/// again in case the reorg fails.
+	pub retracted: Vec<SignedSidechainBlock>,
+}
+
+/// Competing sidechain forks of all shards.
+///
+/// Imported sidechain blocks are tracked in a [`ForkTree`] per shard. Blocks that extend the
+/// current head are applied to the state right away. Blocks on a competing fork are kept until the
+/// fork-choice rule prefers their fork. The state is then reverted to the common ancestor and the
+/// fork's blocks are applied (a reorg).
+///
+/// Fork-choice rule: the longest chain wins. Among chains of equal length, the one whose head was
+/// authored in the earlier slot wins (the lower block hash decides within the same slot).
+///
+/// Blocks proposed by this validateer are tracked as well, they take part in the fork choice once
+/// they are imported.
+///
+/// Forks are only tracked down to `max_reorg_depth` blocks below the head, because a reorg needs
+/// the state snapshot of the common ancestor. A reorg replays the latest parentchain block import,
+/// so state writes by parentchain block imports do not get lost. A reorg is refused if the state
+/// was written in any other way than by applying the retracted blocks or by a recorded parentchain
+/// block import since the common ancestor, because reverting the state would lose these writes.
+/// Forks off below the finalized block are refused as well, a reorg must not retract final blocks.
+pub struct SidechainForks<SignedSidechainBlock: SignedSidechainBlockTrait> {
+	slot_duration: Duration,
+	max_reorg_depth: BlockNumber,
+	shards:
+		RwLock<HashMap<ShardIdentifierFor<SignedSidechainBlock>, ShardForks<SignedSidechainBlock>>>,
+}
+
+impl<SignedSidechainBlock: SignedSidechainBlockTrait> SidechainForks<SignedSidechainBlock> {
+	pub fn new(slot_duration: Duration, max_reorg_depth: BlockNumber) -> Self {
+		SidechainForks { slot_duration, max_reorg_depth, shards: RwLock::new(HashMap::new()) }
+	}
+
+	/// Make sure the forks of a shard are based on the given head, i.e. the last block of the
+	/// sidechain state and the hash of that state.
+	///
+	/// If the head is unknown (e.g. after a restart), the forks are reset to only contain the head.
+	pub fn track_head(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		maybe_head: Option<(SignedSidechainBlock::Block, H256)>,
+	) -> Result<()> {
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = shards_lock.entry(*shard).or_default();
+
+		match maybe_head {
+			Some((head, state_hash)) => {
+				let head_hash = head.hash();
+				let is_tracked = forks.head == Some(head_hash)
+					&& forks.blocks.get(&head_hash).map_or(false, |b| b.state_hash.is_some());
+				if !is_tracked {
+					debug!("Resetting sidechain forks of shard to head {:?}", head_hash);
+					forks.reset(ForkBlock::applied(head, None, None, state_hash))?;
+				}
+				forks.state_hash = Some(state_hash);
+			},
+			None => *forks = ShardForks::default(),
+		}
+		Ok(())
+	}
+
+	/// Record the number of the last finalized block of a shard.
+	pub fn on_finalized(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		block_number: BlockNumber,
+	) -> Result<()> {
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = shards_lock.entry(*shard).or_default();
+		forks.finalized_number = forks.finalized_number.max(Some(block_number));
+		Ok(())
+	}
+
+	/// Checks if a block is tracked, either on the current chain or on a fork.
+	pub fn contains(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		block_hash: &BlockHash,
+	) -> Result<bool> {
+		let shards_lock = self.shards.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(shards_lock
+			.get(shard)
+			.map_or(false, |forks| forks.blocks.contains_key(block_hash)))
+	}
+
+	/// Get a tracked block.
+	pub fn block(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		block_hash: &BlockHash,
+	) -> Result<Option<SignedSidechainBlock::Block>> {
+		let shards_lock = self.shards.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(shards_lock
+			.get(shard)
+			.and_then(|forks| forks.blocks.get(block_hash))
+			.map(|fork_block| fork_block.block.clone()))
+	}
+
+	/// Get the head, i.e. the block that was applied last to the state.
+	pub fn head(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<BlockHash>> {
+		let shards_lock = self.shards.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(shards_lock.get(shard).and_then(|forks| forks.head))
+	}
+
+	/// Record a block that was applied to the state and is therefore the new head.
+	///
+	/// `parent_state_hash` is the hash of the state the block was applied on. It differs from the
+	/// state hash of the parent block if the state was written in between.
+	///
+	/// Forks that are deeper than `max_reorg_depth` below the new head are pruned.
+	pub fn on_block_applied(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		signed_block: &SignedSidechainBlock,
+		parent_state_hash: Option<H256>,
+		state_hash: H256,
+	) -> Result<()> {
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = shards_lock.entry(*shard).or_default();
+
+		let block_hash = signed_block.hash();
+		let parent_hash = signed_block.block().header().parent_hash();
+		let fork_block = ForkBlock::applied(
+			signed_block.block().clone(),
+			Some(signed_block.clone()),
+			parent_state_hash,
+			state_hash,
+		);
+
+		if let Some(tracked_block) = forks.blocks.get_mut(&block_hash) {
+			*tracked_block = fork_block;
+		} else if forks.blocks.contains_key(&parent_hash) {
+			forks.insert(fork_block)?;
+		} else {
+			forks.reset(fork_block)?;
+		}
+		forks.head = Some(block_hash);
+		forks.state_hash = Some(state_hash);
+		forks.prune(self.max_reorg_depth);
+		Ok(())
+	}
+
+	/// Record a block that was proposed by this validateer on top of `parent`, i.e. the last
+	/// block of the state and the hash of the state the block was proposed on.
+	///
+	/// The proposed block is applied to the state once it is imported like the blocks of the
+	/// other validateers.
+	pub fn on_block_proposed(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		parent: (SignedSidechainBlock::Block, H256),
+		signed_block: &SignedSidechainBlock,
+	) -> Result<()> {
+		self.track_head(shard, Some(parent))?;
+
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = shards_lock.entry(*shard).or_default();
+
+		if forks.blocks.contains_key(&signed_block.hash()) {
+			return Ok(())
+		}
+		forks.insert(ForkBlock {
+			block: signed_block.block().clone(),
+			signed_block: Some(signed_block.clone()),
+			parent_state_hash: None,
+			state_hash: None,
+			imported_state_hash: None,
+			is_proposed: true,
+		})
+	}
+
+	/// Record that parentchain blocks were imported on top of the head, which wrote the state from
+	/// `state_hash_before` to `state_hash_after`.
+	///
+	/// The import is ignored if the state was written by other means before, a reorg is then
+	/// refused.
+	pub fn on_parentchain_imported(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state_hash_before: H256,
+		state_hash_after: H256,
+	) -> Result<()> {
+		if state_hash_before == state_hash_after {
+			return Ok(())
+		}
+
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = match shards_lock.get_mut(shard) {
+			Some(forks) => forks,
+			None => return Ok(()),
+		};
+		let head = match forks.head.and_then(|head| forks.blocks.get_mut(&head)) {
+			Some(head) => head,
+			None => return Ok(()),
+		};
+
+		if head.imported_state_hash.or(head.state_hash) != Some(state_hash_before) {
+			debug!("Ignoring parentchain import on top of a state that was written by other means");
+			return Ok(())
+		}
+		head.imported_state_hash = Some(state_hash_after);
+		forks.state_hash = Some(state_hash_after);
+		Ok(())
+	}
+
+	/// Track a block that does not extend the head, but a block further down the current chain or
+	/// a block on a fork.
+	///
+	/// Returns the reorg to perform in case the block makes its fork the best chain.
+	pub fn import_fork_block(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		signed_block: SignedSidechainBlock,
+	) -> Result<Option<Reorg<SignedSidechainBlock>>> {
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = shards_lock.entry(*shard).or_default();
+
+		let block_hash = signed_block.hash();
+		let block_number = signed_block.block().header().block_number();
+		let head_number = forks.head_number().unwrap_or_default();
+
+		match forks.blocks.get_mut(&block_hash) {
+			// A block we proposed ourselves, while another block was applied in the meantime.
+			Some(fork_block) if fork_block.is_proposed => fork_block.is_proposed = false,
+			Some(_) => return Err(Error::BlockAlreadyImported(block_number, head_number)),
+			None => {
+				let parent_number =
+					match forks.blocks.get(&signed_block.block().header().parent_hash()) {
+						Some(parent) => parent.block.header().block_number(),
+						None =>
+							return Err(Error::BlockAncestryMismatch(
+								head_number,
+								forks.head.unwrap_or_default(),
+								format!("Parent of fork block {:?} is not known", block_hash),
+							)),
+					};
+				if parent_number + self.max_reorg_depth < head_number {
+					return Err(Error::BadSidechainBlock(
+						block_hash,
+						"Block forks off below the maximum reorg depth".into(),
+					))
+				}
+				if forks.finalized_number.map_or(false, |finalized| parent_number < finalized) {
+					return Err(Error::BadSidechainBlock(
+						block_hash,
+						"Block forks off below the finalized block".into(),
+					))
+				}
+
+				forks.insert(ForkBlock {
+					block: signed_block.block().clone(),
+					signed_block: Some(signed_block),
+					parent_state_hash: None,
+					state_hash: None,
+					imported_state_hash: None,
+					is_proposed: false,
+				})?;
+			},
+		}
+
+		let best = match forks.best_block(self.slot_duration) {
+			Some(best) if Some(best) != forks.head => best,
+			_ => return Ok(None),
+		};
+		let mut reorg = forks.reorg_to(best)?;
+		if forks.is_below_finalized(&reorg.common_ancestor) {
+			warn!(
+				"Refusing sidechain reorg to block {:?}: the common ancestor {:?} is below the \
+				finalized block",
+				best, reorg.common_ancestor
+			);
+			return Ok(None)
+		}
+		if !forks.can_revert_to(&reorg.common_ancestor) {
+			warn!(
+				"Refusing sidechain reorg to block {:?}: the state was written by other means than \
+				the blocks to retract since the common ancestor {:?}",
+				best, reorg.common_ancestor
+			);
+			return Ok(None)
+		}
+		reorg.retracted = match forks.retracted_blocks(&reorg.common_ancestor) {
+			Some(retracted) => retracted,
+			None => {
+				warn!(
+					"Refusing sidechain reorg to block {:?}: the blocks to retract since the common \
+					ancestor {:?} are not available",
+					best, reorg.common_ancestor
+				);
+				return Ok(None)
+			},
+		};
+		Ok(Some(reorg))
+	}
+
+	/// Stop tracking a block and all of its descendants, e.g. because the block failed to import.
+	pub fn discard(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		block_hash: &BlockHash,
+	) -> Result<()> {
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = match shards_lock.get_mut(shard) {
+			Some(forks) => forks,
+			None => return Ok(()),
+		};
+
+		let _ = forks.tree.drain_filter(|hash, _, _| {
+			if hash == block_hash {
+				FilterAction::Remove
+			} else {
+				FilterAction::KeepNode
+			}
+		});
+
+		let remaining: HashSet<BlockHash> = forks.tree.iter().map(|(hash, _, _)| *hash).collect();
+		forks.blocks.retain(|hash, _| remaining.contains(hash));
+		if forks.head.map_or(false, |head| !remaining.contains(&head)) {
+			forks.head = None;
+		}
+		Ok(())
+	}
+
+	/// Record that the state has been reverted to the given block of the current chain.
+	pub fn on_reverted(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		block_hash: &BlockHash,
+	) -> Result<()> {
+		let mut shards_lock = self.shards.write().map_err(|_| Error::LockPoisoning)?;
+		let forks = shards_lock.get_mut(shard).ok_or_else(|| {
+			Error::Other(format!("No sidechain forks tracked for shard {:?}", shard).into())
+		})?;
+
+		let reverted_number = forks
+			.blocks
+			.get(block_hash)
+			.map(|b| b.block.header().block_number())
+			.ok_or_else(|| {
+				Error::Other(format!("Reverted to unknown block {:?}", block_hash).into())
+			})?;
+
+		// The snapshots of any newer state are gone with the revert.
+		forks
+			.blocks
+			.values_mut()
+			.filter(|b| b.block.header().block_number() > reverted_number)
+			.for_each(|b| {
+				b.parent_state_hash = None;
+				b.state_hash = None;
+				b.imported_state_hash = None;
+			});
+		if let Some(reverted_block) = forks.blocks.get_mut(block_hash) {
+			reverted_block.imported_state_hash = None;
+		}
+		forks.head = Some(*block_hash);
+		forks.state_hash = forks.blocks.get(block_hash).and_then(|b| b.state_hash);
+		Ok(())
+	}
+}
+
+/// A sidechain block that is tracked in the fork tree.
+struct ForkBlock<SignedSidechainBlock: SignedSidechainBlockTrait> {
+	block: SignedSidechainBlock::Block,
+	/// Needed to apply the block on a reorg. Not available for a head that was
+	/// only loaded from the state.
+	signed_block: Option<SignedSidechainBlock>,
+	/// Hash of the state the block was applied on. Only set for the blocks of the current chain.
+	parent_state_hash: Option<H256>,
+	/// Hash of the state after the block was applied. Only set for the blocks of the current chain.
+	state_hash: Option<H256>,
+	/// Hash of the state after parentchain blocks were imported on top of the block, while it was
+	/// the head.
+	imported_state_hash: Option<H256>,
+	/// Proposed by this validateer and not imported yet.
+	is_proposed: bool,
+}
+
+impl<SignedSidechainBlock: SignedSidechainBlockTrait> ForkBlock<SignedSidechainBlock> {
+	fn applied(
+		block: SignedSidechainBlock::Block,
+		signed_block: Option<SignedSidechainBlock>,
+		parent_state_hash: Option<H256>,
+		state_hash: H256,
+	) -> Self {
+		ForkBlock {
+			block,
+			signed_block,
+			parent_state_hash,
+			state_hash: Some(state_hash),
+			imported_state_hash: None,
+			is_proposed: false,
+		}
+	}
+}
+
+/// Competing forks of a single shard.
+struct ShardForks<SignedSidechainBlock: SignedSidechainBlockTrait> {
+	tree: ForkTree<BlockHash, BlockNumber, ()>,
+	blocks: HashMap<BlockHash, ForkBlock<SignedSidechainBlock>>,
+	head: Option<BlockHash>,
+	/// Hash of the current state.
+	state_hash: Option<H256>,
+	/// Number of the last finalized block.
+	finalized_number: Option<BlockNumber>,
+}
+
+impl<SignedSidechainBlock: SignedSidechainBlockTrait> Default for ShardForks<SignedSidechainBlock> {
+	fn default() -> Self {
+		ShardForks {
+			tree: ForkTree::new(),
+			blocks: HashMap::new(),
+			head: None,
+			state_hash: None,
+			finalized_number: None,
+		}
+	}
+}
+
+impl<SignedSidechainBlock: SignedSidechainBlockTrait> ShardForks<SignedSidechainBlock> {
+	fn reset(&mut self, head: ForkBlock<SignedSidechainBlock>) -> Result<()> {
+		let head_hash = head.block.hash();
+		let finalized_number = self.finalized_number;
+		*self = ShardForks { finalized_number, ..ShardForks::default() };
+		self.insert(head)?;
+		self.head = Some(head_hash);
+		Ok(())
+	}
+
+	fn insert(&mut self, fork_block: ForkBlock<SignedSidechainBlock>) -> Result<()> {
+		let block_hash = fork_block.block.hash();
+		let block_number = fork_block.block.header().block_number();
+
+		// The block has to be known to `is_descendent_of` before it is imported into the tree.
+		self.blocks.insert(block_hash, fork_block);
+
+		let blocks = &self.blocks;
+		let is_descendent_of = |base: &BlockHash, target: &BlockHash| {
+			Ok::<_, Error>(is_descendent_of(blocks, base, target))
+		};
+		if let Err(e) = self.tree.import(block_hash, block_number, (), &is_descendent_of) {
+			self.blocks.remove(&block_hash);
+			return Err(fork_tree_error(e, block_hash, block_number, self.head_number()))
+		}
+		Ok(())
+	}
+
+	fn head_number(&self) -> Option<BlockNumber> {
+		self.head
+			.and_then(|head| self.blocks.get(&head))
+			.map(|head| head.block.header().block_number())
+	}
+
+	/// Best block according to the fork-choice rule. Proposed blocks are only considered once
+	/// they are imported.
+	fn best_block(&self, slot_duration: Duration) -> Option<BlockHash> {
+		self.blocks
+			.values()
+			.filter(|b| !b.is_proposed)
+			.max_by(|a, b| fork_choice(&a.block, &b.block, slot_duration))
+			.map(|best| best.block.hash())
+	}
+
+	fn reorg_to(&self, target: BlockHash) -> Result<Reorg<SignedSidechainBlock>> {
+		let mut enacted = Vec::new();
+		let mut current = target;
+		loop {
+			let fork_block = self.blocks.get(&current).ok_or_else(|| {
+				Error::Other(format!("Fork of block {:?} is not fully known", target).into())
+			})?;
+
+			// Only blocks of the current chain have a state hash.
+			if let Some(state_hash) = fork_block.state_hash {
+				enacted.reverse();
+				return Ok(Reorg {
+					common_ancestor: current,
+					common_ancestor_state_hash: state_hash,
+					enacted,
+					retracted: Vec::new(),
+				})
+			}
+
+			let signed_block = fork_block.signed_block.clone().ok_or_else(|| {
+				Error::Other(format!("Fork block {:?} is not available", current).into())
+			})?;
+			enacted.push(signed_block);
+			current = fork_block.block.header().parent_hash();
+		}
+	}
+
+	fn is_below_finalized(&self, block_hash: &BlockHash) -> bool {
+		match (self.blocks.get(block_hash), self.finalized_number) {
+			(Some(fork_block), Some(finalized)) =>
+				fork_block.block.header().block_number() < finalized,
+			_ => false,
+		}
+	}
+
+	/// Checks that the state was only written by applying the blocks of the current chain after
+	/// `ancestor` and by the recorded parentchain imports, which a reorg replays. Only then the
+	/// state can be reverted to `ancestor` without losing any writes.
+	fn can_revert_to(&self, ancestor: &BlockHash) -> bool {
+		let mut expected_state_hash = self.state_hash;
+		let mut current = match self.head {
+			Some(head) => head,
+			None => return false,
+		};
+		loop {
+			let fork_block = match self.blocks.get(&current) {
+				Some(fork_block) => fork_block,
+				None => return false,
+			};
+			let is_expected = expected_state_hash.is_some()
+				&& (fork_block.state_hash == expected_state_hash
+					|| fork_block.imported_state_hash == expected_state_hash);
+			if fork_block.state_hash.is_none() || !is_expected {
+				return false
+			}
+			if current == *ancestor {
+				return true
+			}
+			expected_state_hash = fork_block.parent_state_hash;
+			current = fork_block.block.header().parent_hash();
+		}
+	}
+
+	/// Blocks of the current chain after `ancestor`, oldest first. `None` if any of them is not
+	/// available.
+	fn retracted_blocks(&self, ancestor: &BlockHash) -> Option<Vec<SignedSidechainBlock>> {
+		let mut retracted = Vec::new();
+		let mut current = self.head?;
+		while current != *ancestor {
+			let fork_block = self.blocks.get(&current)?;
+			retracted.push(fork_block.signed_block.clone()?);
+			current = fork_block.block.header().parent_hash();
+		}
+		retracted.reverse();
+		Some(retracted)
+	}
+
+	/// Prune everything that forks off deeper than `max_reorg_depth` blocks below the head.
+	fn prune(&mut self, max_reorg_depth: BlockNumber) {
+		let head_number = match self.head_number() {
+			Some(number) => number,
+			None => return,
+		};
+		// The block `max_reorg_depth` below the head is the deepest common ancestor a fork may have.
+		let keep_number = match head_number.checked_sub(max_reorg_depth) {
+			Some(number) if number > 0 => number,
+			_ => return,
+		};
+		let keep_hash = match self.head.and_then(|head| self.ancestor_at(head, keep_number)) {
+			Some(hash) => hash,
+			None => return,
+		};
+
+		let blocks = &self.blocks;
+		let is_descendent_of = |base: &BlockHash, target: &BlockHash| {
+			Ok::<_, Error>(is_descendent_of(blocks, base, target))
+		};
+		if let Err(e) = self.tree.prune(&keep_hash, &keep_number, &is_descendent_of, &|_| true) {
+			warn!("Failed to prune sidechain forks below block {}: {:?}", keep_number, e);
+			return
+		}
+
+		let remaining: HashSet<BlockHash> = self.tree.iter().map(|(hash, _, _)| *hash).collect();
+		self.blocks.retain(|hash, _| remaining.contains(hash));
+	}
+
+	fn ancestor_at(&self, block_hash: BlockHash, number: BlockNumber) -> Option<BlockHash> {
+		let mut current = self.blocks.get(&block_hash)?;
+		while current.block.header().block_number() > number {
+			current = self.blocks.get(&current.block.header().parent_hash())?;
+		}
+		Some(current.block.hash())
+	}
+}
+
+/// Fork-choice rule: `Ordering::Greater` if block `a` is a better head than block `b`.
+fn fork_choice<Block: SidechainBlockTrait>(
+	a: &Block,
+	b: &Block,
+	slot_duration: Duration,
+) -> Ordering {
+	let slot =
+		|block: &Block| block.block_data().timestamp() / (slot_duration.as_millis() as u64).max(1);
+	a.header()
+		.block_number()
+		.cmp(&b.header().block_number())
+		.then_with(|| slot(b).cmp(&slot(a)))
+		.then_with(|| b.hash().cmp(&a.hash()))
+}
+
+/// Checks if `target` is a descendant of `base`, by following the parent hashes of the known blocks.
+fn is_descendent_of<SignedSidechainBlock: SignedSidechainBlockTrait>(
+	blocks: &HashMap<BlockHash, ForkBlock<SignedSidechainBlock>>,
+	base: &BlockHash,
+	target: &BlockHash,
+) -> bool {
+	let base_number = match blocks.get(base) {
+		Some(base) => base.block.header().block_number(),
+		None => return false,
+	};
+
+	let mut current = *target;
+	while let Some(block) = blocks.get(&current) {
+		let header = block.block.header();
+		if header.block_number() <= base_number {
+			return false
+		}
+		if header.parent_hash() == *base {
+			return true
+		}
+		current = header.parent_hash();
+	}
+	false
+}
+
+fn fork_tree_error(
+	e: ForkTreeError<Error>,
+	block_hash: BlockHash,
+	block_number: BlockNumber,
+	head_number: Option<BlockNumber>,
+) -> Error {
+	match e {
+		ForkTreeError::Duplicate =>
+			Error::BlockAlreadyImported(block_number, head_number.unwrap_or_default()),
+		ForkTreeError::Revert => Error::BadSidechainBlock(
+			block_hash,
+			"Block forks off below the maximum reorg depth".into(),
+		),
+		ForkTreeError::Client(e) => e,
+		e => Error::Other(format!("{:?}", e).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+	use itp_types::ShardIdentifier;
+	use its_primitives::types::SignedBlock as SignedSidechainBlock;
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_block_data_builder::SidechainBlockDataBuilder,
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
+
+	const SLOT_DURATION: Duration = Duration::from_millis(1000);
+
+	type TestForks = SidechainForks<SignedSidechainBlock>;
+
+	#[test]
+	fn applying_blocks_on_head_does_not_reorg() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[2].hash()));
+		assert!(chain.iter().all(|b| forks.contains(&shard, &b.hash()).unwrap()));
+	}
+
+	#[test]
+	fn shorter_fork_does_not_reorg() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		let fork_block = block(2, chain[0].hash(), 10_000);
+		assert!(forks.import_fork_block(&shard, fork_block.clone()).unwrap().is_none());
+		assert!(forks.contains(&shard, &fork_block.hash()).unwrap());
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[2].hash()));
+	}
+
+	#[test]
+	fn longer_fork_triggers_reorg_from_common_ancestor() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 12_000);
+		assert!(forks.import_fork_block(&shard, fork_block_2.clone()).unwrap().is_none());
+		assert!(forks.import_fork_block(&shard, fork_block_3.clone()).unwrap().is_none());
+		let reorg = forks.import_fork_block(&shard, fork_block_4.clone()).unwrap().unwrap();
+
+		assert_eq!(reorg.common_ancestor, chain[0].hash());
+		assert_eq!(reorg.common_ancestor_state_hash, state_hash(1));
+		assert_eq!(reorg.enacted, vec![fork_block_2, fork_block_3, fork_block_4]);
+		assert_eq!(reorg.retracted, vec![chain[1].clone(), chain[2].clone()]);
+	}
+
+	#[test]
+	fn discarding_a_block_discards_its_descendants() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		forks.import_fork_block(&shard, fork_block_2.clone()).unwrap();
+		forks.import_fork_block(&shard, fork_block_3.clone()).unwrap();
+
+		forks.discard(&shard, &fork_block_2.hash()).unwrap();
+
+		assert!(!forks.contains(&shard, &fork_block_2.hash()).unwrap());
+		assert!(!forks.contains(&shard, &fork_block_3.hash()).unwrap());
+		assert!(chain.iter().all(|b| forks.contains(&shard, &b.hash()).unwrap()));
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[2].hash()));
+	}
+
+	#[test]
+	fn fork_below_the_finalized_block_is_refused() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		// Tracked before block 2 was finalized.
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		forks.import_fork_block(&shard, fork_block_2.clone()).unwrap();
+		forks.on_finalized(&shard, 2).unwrap();
+
+		assert_matches!(
+			forks.import_fork_block(&shard, block(2, chain[0].hash(), 9_000)),
+			Err(Error::BadSidechainBlock(_, _))
+		);
+
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 12_000);
+		forks.import_fork_block(&shard, fork_block_3).unwrap();
+		assert!(forks.import_fork_block(&shard, fork_block_4).unwrap().is_none());
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[2].hash()));
+
+		// Forks off at the finalized block are fine.
+		let fork_block_3 = block(3, chain[1].hash(), 13_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 14_000);
+		let fork_block_5 = block(5, fork_block_4.hash(), 15_000);
+		forks.import_fork_block(&shard, fork_block_3).unwrap();
+		forks.import_fork_block(&shard, fork_block_4).unwrap();
+		let reorg = forks.import_fork_block(&shard, fork_block_5).unwrap().unwrap();
+		assert_eq!(reorg.common_ancestor, chain[1].hash());
+	}
+
+	#[test]
+	fn equally_long_fork_authored_in_earlier_slot_wins() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let first_block = block(1, BlockHash::default(), 1_000);
+		forks.on_block_applied(&shard, &first_block, None, state_hash(1)).unwrap();
+
+		let late_block = block(2, first_block.hash(), 3_000);
+		forks
+			.on_block_applied(&shard, &late_block, Some(state_hash(1)), state_hash(2))
+			.unwrap();
+
+		let later_block = block(2, first_block.hash(), 4_000);
+		assert!(forks.import_fork_block(&shard, later_block).unwrap().is_none());
+
+		let early_block = block(2, first_block.hash(), 2_000);
+		let reorg = forks.import_fork_block(&shard, early_block.clone()).unwrap().unwrap();
+		assert_eq!(reorg.common_ancestor, first_block.hash());
+		assert_eq!(reorg.enacted, vec![early_block]);
+	}
+
+	#[test]
+	fn reverting_invalidates_states_of_retracted_blocks() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		forks.on_reverted(&shard, &chain[0].hash()).unwrap();
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[0].hash()));
+
+		// The retracted blocks are now a fork without any state, a reorg has to start at block 1.
+		let block_4 = block(4, chain[2].hash(), 10_000);
+		let reorg = forks.import_fork_block(&shard, block_4.clone()).unwrap().unwrap();
+		assert_eq!(reorg.common_ancestor, chain[0].hash());
+		assert_eq!(reorg.enacted, vec![chain[1].clone(), chain[2].clone(), block_4]);
+	}
+
+	#[test]
+	fn importing_known_fork_block_fails() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		let fork_block = block(2, chain[0].hash(), 10_000);
+		forks.import_fork_block(&shard, fork_block.clone()).unwrap();
+
+		assert_matches!(
+			forks.import_fork_block(&shard, fork_block),
+			Err(Error::BlockAlreadyImported(2, 3))
+		);
+		assert_matches!(
+			forks.import_fork_block(&shard, chain[1].clone()),
+			Err(Error::BlockAlreadyImported(2, 3))
+		);
+	}
+
+	#[test]
+	fn forks_deeper_than_max_reorg_depth_are_pruned() {
+		let forks = TestForks::new(SLOT_DURATION, 2);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		let fork_block = block(2, chain[0].hash(), 10_000);
+		forks.import_fork_block(&shard, fork_block.clone()).unwrap();
+
+		let chain = apply_chain(&forks, &shard, chain[2].hash(), 4, 2);
+
+		assert!(!forks.contains(&shard, &fork_block.hash()).unwrap());
+		assert!(forks.contains(&shard, &chain[1].hash()).unwrap());
+		assert_matches!(
+			forks.import_fork_block(&shard, block(3, fork_block.hash(), 11_000)),
+			Err(Error::BlockAncestryMismatch(5, _, _))
+		);
+	}
+
+	#[test]
+	fn reorg_is_refused_if_the_state_was_written_since_the_common_ancestor() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		// E.g. the shard vault initialization after block 3 was applied.
+		forks
+			.track_head(&shard, Some((chain[2].block().clone(), state_hash(30))))
+			.unwrap();
+
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 12_000);
+		forks.import_fork_block(&shard, fork_block_2).unwrap();
+		forks.import_fork_block(&shard, fork_block_3).unwrap();
+
+		assert!(forks.import_fork_block(&shard, fork_block_4.clone()).unwrap().is_none());
+		assert!(forks.contains(&shard, &fork_block_4.hash()).unwrap());
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[2].hash()));
+	}
+
+	#[test]
+	fn reorg_is_not_refused_for_parentchain_imports_since_the_common_ancestor() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 2);
+
+		// Parentchain blocks were imported after block 2 and block 3 were applied.
+		forks.on_parentchain_imported(&shard, state_hash(2), state_hash(20)).unwrap();
+		let block_3 = block(3, chain[1].hash(), 3_000);
+		forks
+			.on_block_applied(&shard, &block_3, Some(state_hash(20)), state_hash(3))
+			.unwrap();
+		forks.on_parentchain_imported(&shard, state_hash(3), state_hash(30)).unwrap();
+		forks
+			.track_head(&shard, Some((block_3.block().clone(), state_hash(30))))
+			.unwrap();
+
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 12_000);
+		forks.import_fork_block(&shard, fork_block_2).unwrap();
+		forks.import_fork_block(&shard, fork_block_3).unwrap();
+
+		let reorg = forks.import_fork_block(&shard, fork_block_4).unwrap().unwrap();
+		assert_eq!(reorg.common_ancestor, chain[0].hash());
+		assert_eq!(reorg.common_ancestor_state_hash, state_hash(1));
+	}
+
+	#[test]
+	fn parentchain_import_on_an_unrecorded_write_is_ignored() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		forks
+			.track_head(&shard, Some((chain[2].block().clone(), state_hash(30))))
+			.unwrap();
+		forks.on_parentchain_imported(&shard, state_hash(30), state_hash(31)).unwrap();
+
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 12_000);
+		forks.import_fork_block(&shard, fork_block_2).unwrap();
+		forks.import_fork_block(&shard, fork_block_3).unwrap();
+
+		assert!(forks.import_fork_block(&shard, fork_block_4).unwrap().is_none());
+		assert_eq!(forks.head(&shard).unwrap(), Some(chain[2].hash()));
+	}
+
+	#[test]
+	fn reorg_is_refused_if_the_state_was_written_between_retracted_blocks() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 2);
+
+		// Block 3 was applied on a state that was written after block 2 was applied.
+		let block_3 = block(3, chain[1].hash(), 3_000);
+		forks
+			.on_block_applied(&shard, &block_3, Some(state_hash(20)), state_hash(3))
+			.unwrap();
+
+		let fork_block_2 = block(2, chain[0].hash(), 1_500);
+		let fork_block_3 = block(3, fork_block_2.hash(), 2_000);
+		forks.import_fork_block(&shard, fork_block_2).unwrap();
+
+		assert!(forks.import_fork_block(&shard, fork_block_3).unwrap().is_none());
+		assert_eq!(forks.head(&shard).unwrap(), Some(block_3.hash()));
+	}
+
+	#[test]
+	fn self_authored_head_keeps_the_forks() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 2);
+
+		// We propose block 3 and import it like the blocks of the other validateers.
+		let proposed_block = block(3, chain[1].hash(), 3_000);
+		forks
+			.on_block_proposed(&shard, (chain[1].block().clone(), state_hash(2)), &proposed_block)
+			.unwrap();
+		forks
+			.on_block_applied(&shard, &proposed_block, Some(state_hash(2)), state_hash(3))
+			.unwrap();
+		forks
+			.track_head(&shard, Some((proposed_block.block().clone(), state_hash(3))))
+			.unwrap();
+
+		assert_eq!(forks.head(&shard).unwrap(), Some(proposed_block.hash()));
+		assert!(forks.contains(&shard, &chain[0].hash()).unwrap());
+
+		let fork_block_2 = block(2, chain[0].hash(), 10_000);
+		let fork_block_3 = block(3, fork_block_2.hash(), 11_000);
+		let fork_block_4 = block(4, fork_block_3.hash(), 12_000);
+		forks.import_fork_block(&shard, fork_block_2.clone()).unwrap();
+		forks.import_fork_block(&shard, fork_block_3.clone()).unwrap();
+		let reorg = forks.import_fork_block(&shard, fork_block_4.clone()).unwrap().unwrap();
+
+		assert_eq!(reorg.common_ancestor, chain[0].hash());
+		assert_eq!(reorg.enacted, vec![fork_block_2, fork_block_3, fork_block_4]);
+	}
+
+	#[test]
+	fn proposed_block_takes_part_in_fork_choice_once_imported() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 2);
+
+		let proposed_block = block(3, chain[1].hash(), 3_000);
+		forks
+			.on_block_proposed(&shard, (chain[1].block().clone(), state_hash(2)), &proposed_block)
+			.unwrap();
+
+		// A block of a later slot is applied before our own block is imported.
+		let peer_block = block(3, chain[1].hash(), 4_000);
+		forks
+			.on_block_applied(&shard, &peer_block, Some(state_hash(2)), state_hash(3))
+			.unwrap();
+
+		let reorg = forks.import_fork_block(&shard, proposed_block.clone()).unwrap().unwrap();
+		assert_eq!(reorg.common_ancestor, chain[1].hash());
+		assert_eq!(reorg.enacted, vec![proposed_block.clone()]);
+		assert_matches!(
+			forks.import_fork_block(&shard, proposed_block),
+			Err(Error::BlockAlreadyImported(3, 3))
+		);
+	}
+
+	#[test]
+	fn unknown_head_resets_forks() {
+		let forks = TestForks::new(SLOT_DURATION, 3);
+		let shard = ShardIdentifier::default();
+		let chain = apply_chain(&forks, &shard, BlockHash::default(), 1, 3);
+
+		let head = block(7, BlockHash::random(), 10);
+		forks.track_head(&shard, Some((head.block().clone(), state_hash(7)))).unwrap();
+
+		assert_eq!(forks.head(&shard).unwrap(), Some(head.hash()));
+		assert!(!forks.contains(&shard, &chain[2].hash()).unwrap());
+
+		forks.track_head(&shard, None).unwrap();
+		assert_eq!(forks.head(&shard).unwrap(), None);
+	}
+
+	/// Applies `count` blocks, starting with `first_number` on top of `parent_hash`.
+	fn apply_chain(
+		forks: &TestForks,
+		shard: &ShardIdentifier,
+		parent_hash: BlockHash,
+		first_number: BlockNumber,
+		count: u64,
+	) -> Vec<SignedSidechainBlock> {
+		let mut parent_hash = parent_hash;
+		(first_number..first_number + count)
+			.map(|number| {
+				let signed_block = block(number, parent_hash, number * 1_000);
+				forks
+					.on_block_applied(
+						shard,
+						&signed_block,
+						Some(state_hash(number - 1)),
+						state_hash(number),
+					)
+					.unwrap();
+				parent_hash = signed_block.hash();
+				signed_block
+			})
+			.collect()
+	}
+
+	fn block(number: BlockNumber, parent_hash: BlockHash, timestamp: u64) -> SignedSidechainBlock {
+		let block_data = SidechainBlockDataBuilder::default().with_timestamp(timestamp).build();
+		let header = SidechainHeaderBuilder::default()
+			.with_block_number(number)
+			.with_parent_hash(parent_hash)
+			.with_block_data_hash(block_data.hash())
+			.build();
+		SidechainBlockBuilder::default()
+			.with_header(header)
+			.with_block_data(block_data)
+			.build_signed()
+	}
+
+	fn state_hash(block_number: BlockNumber) -> H256 {
+		H256::from_low_u64_be(block_number)
+	}
+}
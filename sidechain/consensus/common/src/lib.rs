@@ -35,7 +35,9 @@ use std::{time::Duration, vec::Vec};
 mod block_import;
 mod block_import_confirmation_handler;
 mod block_import_queue_worker;
+mod block_production_suspension;
 mod error;
+mod fork_choice;
 mod header_db;
 mod peer_block_sync;
 
@@ -49,7 +51,9 @@ mod test;
 pub use block_import::*;
 pub use block_import_confirmation_handler::*;
 pub use block_import_queue_worker::*;
+pub use block_production_suspension::*;
 pub use error::*;
+pub use fork_choice::*;
 pub use peer_block_sync::*;
 
 pub trait Verifier<ParentchainBlock, SignedSidechainBlock>: Send + Sync
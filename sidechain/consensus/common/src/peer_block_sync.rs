@@ -15,7 +15,7 @@
 
 */
 
-use crate::{BlockImport, ConfirmBlockImport, Error, Result};
+use crate::{BlockImport, ConfirmBlockImport, Error, Result, SuspendBlockProduction};
 use core::marker::PhantomData;
 use itp_ocall_api::EnclaveSidechainOCallApi;
 use itp_types::H256;
@@ -53,10 +53,12 @@ pub struct PeerBlockSync<
 	BlockImporter,
 	SidechainOCallApi,
 	ImportConfirmationHandler,
+	BlockProductionSuspender,
 > {
 	importer: Arc<BlockImporter>,
 	sidechain_ocall_api: Arc<SidechainOCallApi>,
 	import_confirmation_handler: Arc<ImportConfirmationHandler>,
+	block_production_suspender: Arc<BlockProductionSuspender>,
 	_phantom: PhantomData<(ParentchainBlock, SignedSidechainBlock)>,
 }
 
@@ -66,6 +68,7 @@ impl<
 		BlockImporter,
 		SidechainOCallApi,
 		ImportConfirmationHandler,
+		BlockProductionSuspender,
 	>
 	PeerBlockSync<
 		ParentchainBlock,
@@ -73,6 +76,7 @@ impl<
 		BlockImporter,
 		SidechainOCallApi,
 		ImportConfirmationHandler,
+		BlockProductionSuspender,
 	> where
 	ParentchainBlock: ParentchainBlockTrait,
 	SignedSidechainBlock: SignedSidechainBlockTrait,
@@ -83,20 +87,42 @@ impl<
 	ImportConfirmationHandler: ConfirmBlockImport<
 		<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as BlockTrait>::HeaderType,
 	>,
+	BlockProductionSuspender: SuspendBlockProduction,
 {
 	pub fn new(
 		importer: Arc<BlockImporter>,
 		sidechain_ocall_api: Arc<SidechainOCallApi>,
 		import_confirmation_handler: Arc<ImportConfirmationHandler>,
+		block_production_suspender: Arc<BlockProductionSuspender>,
 	) -> Self {
 		PeerBlockSync {
 			importer,
 			sidechain_ocall_api,
 			import_confirmation_handler,
+			block_production_suspender,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Fetches the missing blocks from a peer and imports them, while block production is suspended.
+	fn sync_from_peer(
+		&self,
+		last_imported_sidechain_block_hash: BlockHash,
+		import_until_block_hash: BlockHash,
+		current_parentchain_header: &ParentchainBlock::Header,
+		shard_identifier: ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<ParentchainBlock::Header> {
+		self.block_production_suspender.suspend_for_sync()?;
+		let sync_result = self.fetch_and_import_blocks_from_peer(
+			last_imported_sidechain_block_hash,
+			import_until_block_hash,
+			current_parentchain_header,
+			shard_identifier,
+		);
+		self.block_production_suspender.resume()?;
+		sync_result
+	}
+
 	fn fetch_and_import_blocks_from_peer(
 		&self,
 		last_imported_sidechain_block_hash: BlockHash,
@@ -145,9 +171,9 @@ impl<
 	}
 }
 
-impl<ParentchainBlock, SignedSidechainBlock, BlockImporter, SidechainOCallApi, ImportConfirmationHandler>
+impl<ParentchainBlock, SignedSidechainBlock, BlockImporter, SidechainOCallApi, ImportConfirmationHandler, BlockProductionSuspender>
 	SyncBlockFromPeer<ParentchainBlock::Header, SignedSidechainBlock>
-	for PeerBlockSync<ParentchainBlock, SignedSidechainBlock, BlockImporter, SidechainOCallApi, ImportConfirmationHandler>
+	for PeerBlockSync<ParentchainBlock, SignedSidechainBlock, BlockImporter, SidechainOCallApi, ImportConfirmationHandler, BlockProductionSuspender>
 where
 	ParentchainBlock: ParentchainBlockTrait,
 	SignedSidechainBlock: SignedSidechainBlockTrait,
@@ -156,6 +182,7 @@ where
 	BlockImporter: BlockImport<ParentchainBlock, SignedSidechainBlock>,
 	SidechainOCallApi: EnclaveSidechainOCallApi,
 	ImportConfirmationHandler: ConfirmBlockImport<<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as BlockTrait>::HeaderType>,
+	BlockProductionSuspender: SuspendBlockProduction,
 {
 	fn sync_block(
 		&self,
@@ -172,7 +199,7 @@ where
 			Err(e) => match e {
 				Error::BlockAncestryMismatch(_block_number, block_hash, _) => {
 					warn!("Got ancestry mismatch error upon block import. Attempting to fetch missing blocks from peer");
-					let updated_parentchain_header = self.fetch_and_import_blocks_from_peer(
+					let updated_parentchain_header = self.sync_from_peer(
 						block_hash,
 						sidechain_block_hash,
 						current_parentchain_header,
@@ -184,7 +211,7 @@ where
 				Error::InvalidFirstBlock(block_number, _) => {
 					warn!("Got invalid first block error upon block import (expected first block, but got block with number {}). \
 							Attempting to fetch missing blocks from peer", block_number);
-					let updated_parentchain_header = self.fetch_and_import_blocks_from_peer(
+					let updated_parentchain_header = self.sync_from_peer(
 						Default::default(), // This is the parent hash of the first block. So we import everything.
 						sidechain_block_hash,
 						current_parentchain_header,
@@ -219,8 +246,11 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::test::mocks::{
-		block_importer_mock::BlockImportMock, confirm_block_import_mock::ConfirmBlockImportMock,
+	use crate::{
+		test::mocks::{
+			block_importer_mock::BlockImportMock, confirm_block_import_mock::ConfirmBlockImportMock,
+		},
+		BlockProductionSuspender, IsBlockProductionSuspended,
 	};
 	use core::assert_matches::assert_matches;
 	use itc_parentchain_test::ParentchainHeaderBuilder;
@@ -237,6 +267,7 @@ mod tests {
 		TestBlockImport,
 		TestOCallApi,
 		ConfirmBlockImportMock,
+		BlockProductionSuspender,
 	>;
 
 	#[test]
@@ -310,11 +341,44 @@ mod tests {
 		assert_eq!(1, sidechain_ocall_api.number_of_fetch_calls());
 	}
 
+	#[test]
+	fn block_production_is_resumed_if_fetching_from_peer_fails() {
+		let block_importer_mock =
+			Arc::new(BlockImportMock::<ParentchainBlock, _>::default().with_import_result_once(
+				Err(Error::BlockAncestryMismatch(1, H256::random(), "".to_string())),
+			));
+
+		// Without blocks to fetch, the o-call mock returns an error.
+		let sidechain_ocall_api =
+			Arc::new(SidechainOCallApiMock::<SignedSidechainBlock>::default());
+		let block_production_suspender = Arc::new(BlockProductionSuspender::default());
+
+		let peer_syncer = TestPeerBlockSync::new(
+			block_importer_mock,
+			sidechain_ocall_api.clone(),
+			Arc::new(ConfirmBlockImportMock {}),
+			block_production_suspender.clone(),
+		);
+
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let signed_sidechain_block = SidechainBlockBuilder::default().build_signed();
+
+		assert!(peer_syncer.sync_block(signed_sidechain_block, &parentchain_header).is_err());
+		assert_eq!(1, sidechain_ocall_api.number_of_fetch_calls());
+		assert!(!block_production_suspender.is_sync_ongoing().unwrap());
+		assert!(!block_production_suspender.is_suspended().unwrap());
+	}
+
 	fn create_peer_syncer(
 		block_importer: Arc<TestBlockImport>,
 		ocall_api: Arc<TestOCallApi>,
 	) -> TestPeerBlockSync {
 		let import_confirmation_handler = Arc::new(ConfirmBlockImportMock {});
-		TestPeerBlockSync::new(block_importer, ocall_api, import_confirmation_handler)
+		TestPeerBlockSync::new(
+			block_importer,
+			ocall_api,
+			import_confirmation_handler,
+			Arc::new(BlockProductionSuspender::default()),
+		)
 	}
 }
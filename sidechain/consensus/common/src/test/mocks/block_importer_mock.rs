@@ -15,13 +15,16 @@
 
 */
 
-use crate::{test::mocks::verifier_mock::VerifierMock, BlockImport, Error, Result};
+use crate::{test::mocks::verifier_mock::VerifierMock, BlockImport, Error, Result, SidechainForks};
 use core::marker::PhantomData;
 use itp_sgx_crypto::aes::Aes;
 use itp_sgx_externalities::SgxExternalities;
 use itp_test::mock::onchain_mock::OnchainMock;
 use itp_types::H256;
-use its_primitives::traits::{ShardIdentifierFor, SignedBlock as SignedSidechainBlockTrait};
+use its_primitives::{
+	traits::{ShardIdentifierFor, SignedBlock as SignedSidechainBlockTrait},
+	types::BlockNumber,
+};
 use sp_core::Pair;
 use sp_runtime::traits::Block as ParentchainBlockTrait;
 use std::{collections::VecDeque, sync::RwLock};
@@ -107,13 +110,39 @@ where
 		&self,
 		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
 		_mutating_function: F,
-	) -> Result<()>
+	) -> Result<H256>
 	where
 		F: FnOnce(Self::SidechainState) -> Result<Self::SidechainState>,
 	{
 		todo!()
 	}
 
+	fn revert_state(
+		&self,
+		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		_state_hash: &H256,
+	) -> Result<()> {
+		todo!()
+	}
+
+	fn current_head(
+		&self,
+		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<(SignedSidechainBlock::Block, H256)>> {
+		todo!()
+	}
+
+	fn sidechain_forks(&self) -> &SidechainForks<SignedSidechainBlock> {
+		todo!()
+	}
+
+	fn finalized_block_number(
+		&self,
+		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<Option<BlockNumber>> {
+		todo!()
+	}
+
 	fn verify_import<F>(
 		&self,
 		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
@@ -149,6 +178,10 @@ where
 		todo!()
 	}
 
+	fn replay_parentchain_import(&self) -> Result<()> {
+		todo!()
+	}
+
 	fn cleanup(&self, _signed_sidechain_block: &SignedSidechainBlock) -> Result<()> {
 		todo!()
 	}
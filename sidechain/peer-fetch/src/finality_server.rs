@@ -0,0 +1,158 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::error::Result;
+use its_primitives::types::{finality::SignedFinalityVote, ShardIdentifier};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_GET_FINALIZED_BLOCK, RPC_METHOD_NAME_IMPORT_FINALITY_VOTES,
+};
+use its_storage::{FetchFinalizedBlock, ImportFinalityVotes};
+use jsonrpsee::{types::error::CallError, RpcModule};
+use log::*;
+use std::sync::Arc;
+
+/// RPC server module builder for receiving finality votes from peers
+/// and querying the last finalized sidechain block.
+pub struct FinalityServerModuleBuilder<SidechainFinality> {
+	sidechain_finality: Arc<SidechainFinality>,
+}
+
+impl<SidechainFinality> FinalityServerModuleBuilder<SidechainFinality>
+where
+	SidechainFinality: ImportFinalityVotes + FetchFinalizedBlock + Send + Sync + 'static,
+{
+	pub fn new(sidechain_finality: Arc<SidechainFinality>) -> Self {
+		FinalityServerModuleBuilder { sidechain_finality }
+	}
+
+	pub fn build(self) -> Result<RpcModule<Arc<SidechainFinality>>> {
+		let mut finality_module = RpcModule::new(self.sidechain_finality);
+		finality_module.register_method(
+			RPC_METHOD_NAME_IMPORT_FINALITY_VOTES,
+			|params, sidechain_finality| {
+				debug!("{}: {:?}", RPC_METHOD_NAME_IMPORT_FINALITY_VOTES, params);
+
+				let votes = params.one::<Vec<SignedFinalityVote>>()?;
+				sidechain_finality.import_finality_votes(votes).map_err(|e| {
+					error!("Failed to import finality votes: {:?}", e);
+					CallError::Failed(e.into())
+				})
+			},
+		)?;
+		finality_module.register_method(
+			RPC_METHOD_NAME_GET_FINALIZED_BLOCK,
+			|params, sidechain_finality| {
+				debug!("{}: {:?}", RPC_METHOD_NAME_GET_FINALIZED_BLOCK, params);
+
+				let shard_identifier = params.one::<ShardIdentifier>()?;
+				Ok(sidechain_finality.fetch_finalized_block(&shard_identifier))
+			},
+		)?;
+		Ok(finality_module)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use its_primitives::types::finality::{FinalityVote, FinalizedBlock};
+	use its_storage::Result as StorageResult;
+	use jsonrpsee::{
+		types::to_json_value,
+		ws_client::{traits::Client, WsClientBuilder},
+		ws_server::WsServerBuilder,
+	};
+	use sp_core::{ed25519, Pair, H256};
+	use std::{net::SocketAddr, sync::RwLock};
+
+	/// Finalizes every block it receives a vote for.
+	#[derive(Default)]
+	struct FinalizeOnFirstVote {
+		finalized_block: RwLock<Option<FinalizedBlock>>,
+	}
+
+	impl ImportFinalityVotes for FinalizeOnFirstVote {
+		fn import_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> StorageResult<()> {
+			for signed_vote in votes {
+				*self.finalized_block.write().unwrap() = Some(FinalizedBlock {
+					block_number: signed_vote.vote.block_number,
+					block_hash: signed_vote.vote.block_hash,
+				});
+			}
+			Ok(())
+		}
+	}
+
+	impl FetchFinalizedBlock for FinalizeOnFirstVote {
+		fn fetch_finalized_block(&self, _shard: &ShardIdentifier) -> Option<FinalizedBlock> {
+			*self.finalized_block.read().unwrap()
+		}
+	}
+
+	async fn run_server(web_socket_url: &str) -> anyhow::Result<SocketAddr> {
+		let mut server = WsServerBuilder::default().build(web_socket_url).await?;
+
+		let module = FinalityServerModuleBuilder::new(Arc::new(FinalizeOnFirstVote::default()))
+			.build()
+			.unwrap();
+		server.register_module(module).unwrap();
+
+		let socket_addr = server.local_addr()?;
+		tokio::spawn(async move { server.start().await });
+		Ok(socket_addr)
+	}
+
+	#[tokio::test]
+	async fn imported_finality_votes_finalize_block() {
+		const W1_URL: &str = "127.0.0.1:2234";
+		run_server(W1_URL).await.unwrap();
+		let client = WsClientBuilder::default().build(&format!("ws://{}", W1_URL)).await.unwrap();
+
+		let shard = ShardIdentifier::default();
+		let signer = ed25519::Pair::from_string("//Alice", None).unwrap();
+		let vote = FinalityVote::new(shard, 3, H256::random());
+
+		let finalized_block = client
+			.request::<Option<FinalizedBlock>>(
+				RPC_METHOD_NAME_GET_FINALIZED_BLOCK,
+				vec![to_json_value(shard).unwrap()].into(),
+			)
+			.await
+			.unwrap();
+		assert!(finalized_block.is_none());
+
+		client
+			.request::<()>(
+				RPC_METHOD_NAME_IMPORT_FINALITY_VOTES,
+				vec![to_json_value(vec![vote.sign(&signer)]).unwrap()].into(),
+			)
+			.await
+			.unwrap();
+
+		let finalized_block = client
+			.request::<Option<FinalizedBlock>>(
+				RPC_METHOD_NAME_GET_FINALIZED_BLOCK,
+				vec![to_json_value(shard).unwrap()].into(),
+			)
+			.await
+			.unwrap();
+		assert_eq!(
+			finalized_block,
+			Some(FinalizedBlock { block_number: 3, block_hash: vote.block_hash })
+		);
+	}
+}
@@ -18,7 +18,9 @@
 use crate::error::{Error, Result};
 use itc_rpc_client::direct_client::{DirectApi, DirectClient as DirectWorkerApi};
 use itp_node_api::{api_client::PalletTeerexApi, node_api_factory::CreateNodeApi};
+use itp_types::{AccountId, H256};
 use its_primitives::types::ShardIdentifier;
+use its_storage::{Error as StorageError, FetchValidateers, Result as StorageResult};
 use std::sync::Arc;
 
 /// Trait to fetch untrusted peer servers.
@@ -62,3 +64,21 @@ where
 		Ok(trusted_worker_client.get_untrusted_worker_url()?)
 	}
 }
+
+impl<NodeApiFactory> FetchValidateers for UntrustedPeerFetcher<NodeApiFactory>
+where
+	NodeApiFactory: CreateNodeApi + Send + Sync,
+{
+	fn validateers_at(
+		&self,
+		shard: &ShardIdentifier,
+		parentchain_block_hash: &H256,
+	) -> StorageResult<Vec<AccountId>> {
+		let fetch_validateers = || -> Result<Vec<AccountId>> {
+			let node_api = self.node_api_factory.create_api()?;
+			Ok(node_api.validateers_for_shard(shard, Some(*parentchain_block_hash))?)
+		};
+		fetch_validateers()
+			.map_err(|e| StorageError::FetchValidateers(format!("{:?}", shard), e.to_string()))
+	}
+}
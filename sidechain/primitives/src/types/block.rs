@@ -22,6 +22,7 @@ use crate::{
 use codec::{Decode, Encode};
 use sp_core::{ed25519, H256};
 use sp_runtime::{traits::Verify, MultiSignature};
+use sp_std::vec::Vec;
 
 pub type BlockHash = H256;
 pub type BlockNumber = u64;
@@ -108,6 +109,46 @@ impl SignedBlockTrait for SignedBlock {
 	}
 }
 
+/// Sidechain block without its encrypted state diff, as returned by the block explorer RPC methods.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BlockSummary {
+	pub block_hash: BlockHash,
+	pub header: Header,
+	pub timestamp: Timestamp,
+	/// Parentchain header the block is based on.
+	pub layer_one_head: H256,
+	pub block_author: ed25519::Public,
+	/// Hashes of the trusted operations executed in the block.
+	pub signed_top_hashes: Vec<H256>,
+}
+
+impl From<&SignedBlock> for BlockSummary {
+	fn from(signed_block: &SignedBlock) -> Self {
+		let block_data = signed_block.block.block_data();
+		BlockSummary {
+			block_hash: signed_block.hash(),
+			header: signed_block.block.header,
+			timestamp: block_data.timestamp,
+			layer_one_head: block_data.layer_one_head,
+			block_author: block_data.block_author,
+			signed_top_hashes: block_data.signed_top_hashes.clone(),
+		}
+	}
+}
+
+/// Lookup of sidechain blocks in the storage of the untrusted worker, for the block explorer RPC.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+pub enum BlockQuery {
+	ByHash(BlockHash),
+	ByNumber(ShardIdentifier, BlockNumber),
+	Latest(ShardIdentifier),
+	/// Blocks of a shard with a block number from the first to the second one (both included).
+	NumberRange(ShardIdentifier, BlockNumber, BlockNumber),
+	/// The last finalized block of a shard.
+	Finalized(ShardIdentifier),
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -156,4 +197,22 @@ mod tests {
 
 		assert!(!signed_block.verify_signature());
 	}
+
+	#[test]
+	fn block_summary_contains_block_metadata() {
+		let signer = ed25519::Pair::from_string("//Alice", None).unwrap();
+		let mut block = test_block();
+		block.block_data.signed_top_hashes = vec![H256::random()];
+		block.block_data.encrypted_state_diff = vec![1, 2, 3];
+		let signed_block: SignedBlock = block.sign_block(&signer);
+
+		let summary = BlockSummary::from(&signed_block);
+
+		assert_eq!(summary.block_hash, signed_block.hash());
+		assert_eq!(summary.header, signed_block.block.header);
+		assert_eq!(summary.timestamp, signed_block.block.block_data.timestamp);
+		assert_eq!(summary.layer_one_head, signed_block.block.block_data.layer_one_head);
+		assert_eq!(summary.block_author, signer.public());
+		assert_eq!(summary.signed_top_hashes, signed_block.block.block_data.signed_top_hashes);
+	}
 }
@@ -0,0 +1,116 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Votes exchanged by validateers to finalize sidechain blocks.
+
+use crate::types::block::{BlockHash, BlockNumber, ShardIdentifier, Signature};
+use codec::{Decode, Encode};
+use sp_core::ed25519;
+use sp_runtime::traits::Verify;
+
+#[cfg(feature = "full_crypto")]
+use sp_core::Pair;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Statement of a validateer that it has imported a sidechain block.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FinalityVote {
+	pub shard: ShardIdentifier,
+	pub block_number: BlockNumber,
+	pub block_hash: BlockHash,
+}
+
+impl FinalityVote {
+	pub fn new(shard: ShardIdentifier, block_number: BlockNumber, block_hash: BlockHash) -> Self {
+		Self { shard, block_number, block_hash }
+	}
+
+	/// Sign the vote with the enclave signing key of the validateer.
+	#[cfg(feature = "full_crypto")]
+	pub fn sign<P: Pair<Public = ed25519::Public>>(self, signer: &P) -> SignedFinalityVote
+	where
+		Signature: From<P::Signature>,
+	{
+		let signature = self.using_encoded(|v| signer.sign(v)).into();
+		SignedFinalityVote { vote: self, signer: signer.public(), signature }
+	}
+}
+
+/// Finality vote together with the signature of the voting validateer.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SignedFinalityVote {
+	pub vote: FinalityVote,
+	/// Enclave signing key of the voting validateer.
+	pub signer: ed25519::Public,
+	pub signature: Signature,
+}
+
+impl SignedFinalityVote {
+	/// Verifies that the vote was signed by `signer`.
+	pub fn verify_signature(&self) -> bool {
+		self.vote.using_encoded(|v| self.signature.verify(v, &self.signer.into()))
+	}
+}
+
+/// Sidechain block that has been signed by a supermajority of the validateers.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Debug, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FinalizedBlock {
+	pub block_number: BlockNumber,
+	pub block_hash: BlockHash,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{Pair, H256};
+
+	fn alice() -> ed25519::Pair {
+		ed25519::Pair::from_string("//Alice", None).unwrap()
+	}
+
+	#[test]
+	fn signing_works() {
+		let vote = FinalityVote::new(H256::random(), 3, H256::random());
+
+		let signed_vote = vote.sign(&alice());
+
+		assert_eq!(signed_vote.vote, vote);
+		assert_eq!(signed_vote.signer, alice().public());
+		assert!(signed_vote.verify_signature());
+	}
+
+	#[test]
+	fn tampered_vote_verify_signature_fails() {
+		let mut signed_vote = FinalityVote::new(H256::random(), 3, H256::random()).sign(&alice());
+		signed_vote.vote.block_number = 4;
+
+		assert!(!signed_vote.verify_signature());
+	}
+
+	#[test]
+	fn vote_of_other_signer_verify_signature_fails() {
+		let mut signed_vote = FinalityVote::new(H256::random(), 3, H256::random()).sign(&alice());
+		signed_vote.signer = ed25519::Pair::from_string("//Bob", None).unwrap().public();
+
+		assert!(!signed_vote.verify_signature());
+	}
+}
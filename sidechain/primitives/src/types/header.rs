@@ -16,7 +16,7 @@
 */
 
 //!Primitives for the sidechain
-use crate::traits::Header as HeaderTrait;
+use crate::traits::{Block as BlockTrait, BlockData, Header as HeaderTrait};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_core::H256;
@@ -89,3 +89,45 @@ impl HeaderTrait for SidechainHeader {
 		}
 	}
 }
+
+/// Summary of an imported sidechain block, which is sent to the subscribers of
+/// `chain_subscribeAllHeads`.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ImportedHeader<Public> {
+	/// The block number.
+	pub block_number: u64,
+
+	/// The block hash.
+	pub block_hash: H256,
+
+	/// The parent hash.
+	pub parent_hash: H256,
+
+	/// The Shard id.
+	pub shard_id: ShardIdentifier,
+
+	/// The author of the block.
+	pub block_author: Public,
+
+	/// The number of trusted operations in the block.
+	pub signed_top_hashes_count: u32,
+}
+
+impl<Public: Clone> ImportedHeader<Public> {
+	pub fn from_block<Block>(block: &Block) -> Self
+	where
+		Block: BlockTrait<Public = Public>,
+		Block::HeaderType: HeaderTrait<ShardIdentifier = ShardIdentifier>,
+	{
+		let header = block.header();
+		ImportedHeader {
+			block_number: header.block_number(),
+			block_hash: block.hash(),
+			parent_hash: header.parent_hash(),
+			shard_id: header.shard_id(),
+			block_author: block.block_data().block_author().clone(),
+			signed_top_hashes_count: block.block_data().signed_top_hashes().len() as u32,
+		}
+	}
+}
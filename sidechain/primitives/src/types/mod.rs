@@ -17,6 +17,7 @@
 
 pub mod block;
 pub mod block_data;
+pub mod finality;
 pub mod header;
 
 pub use block::*;
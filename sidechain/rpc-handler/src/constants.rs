@@ -20,3 +20,9 @@
 // RPC method names.
 pub const RPC_METHOD_NAME_IMPORT_BLOCKS: &str = "sidechain_importBlock";
 pub const RPC_METHOD_NAME_FETCH_BLOCKS_FROM_PEER: &str = "sidechain_fetchBlocksFromPeer";
+pub const RPC_METHOD_NAME_IMPORT_FINALITY_VOTES: &str = "sidechain_importFinalityVotes";
+pub const RPC_METHOD_NAME_GET_FINALIZED_BLOCK: &str = "sidechain_getFinalizedBlock";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER: &str = "sidechain_getBlockByNumber";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_HASH: &str = "sidechain_getBlockByHash";
+pub const RPC_METHOD_NAME_GET_LATEST_BLOCK: &str = "sidechain_getLatestBlock";
+pub const RPC_METHOD_NAME_GET_BLOCKS_IN_RANGE: &str = "sidechain_getBlocksInRange";
@@ -23,6 +23,7 @@ use std::path::PathBuf;
 /// Sidechain DB Storage structure:
 /// STORED_SHARDS_KEY -> Vec<(Shard)>
 /// (LAST_BLOCK_KEY, Shard) -> (Blockhash, BlockNr) (look up current blockchain state)
+/// (FINALIZED_BLOCK_KEY, Shard) -> (BlockNr, Blockhash) (safe point for block pruning)
 /// (Shard , Block number) -> Blockhash (needed for block pruning)
 /// Blockhash -> Signed Block (actual block storage)
 
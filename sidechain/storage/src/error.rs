@@ -15,6 +15,7 @@
 
 */
 
+use its_primitives::types::BlockNumber;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -31,4 +32,8 @@ pub enum Error {
 	Decode(#[from] codec::Error),
 	#[error("Given block is not a successor of the last known block")]
 	HeaderAncestryMismatch,
+	#[error("Block {0} forks off below the finalized block {1} of shard {2}")]
+	ForkBelowFinalizedBlock(BlockNumber, BlockNumber, String),
+	#[error("Could not fetch the validateers of shard {0}: {1}")]
+	FetchValidateers(String, String),
 }
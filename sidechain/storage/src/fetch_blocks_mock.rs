@@ -15,10 +15,13 @@
 
 */
 
-use crate::{error::Result, interface::FetchBlocks};
+use crate::{
+	error::Result,
+	interface::{FetchBlocks, QueryBlocks},
+};
 use its_primitives::{
-	traits::ShardIdentifierFor,
-	types::{BlockHash, SignedBlock},
+	traits::{Block, Header, ShardIdentifierFor, SignedBlock as SignedBlockT},
+	types::{BlockHash, BlockNumber, SignedBlock},
 };
 
 #[derive(Default)]
@@ -31,6 +34,15 @@ impl FetchBlocksMock {
 		self.blocks_to_be_fetched = blocks;
 		self
 	}
+
+	fn blocks_of_shard<'a>(
+		&'a self,
+		shard_identifier: &'a ShardIdentifierFor<SignedBlock>,
+	) -> impl Iterator<Item = &'a SignedBlock> {
+		self.blocks_to_be_fetched
+			.iter()
+			.filter(move |b| &b.block().header().shard_id() == shard_identifier)
+	}
 }
 
 impl FetchBlocks<SignedBlock> for FetchBlocksMock {
@@ -51,3 +63,52 @@ impl FetchBlocks<SignedBlock> for FetchBlocksMock {
 		Ok(self.blocks_to_be_fetched.clone())
 	}
 }
+
+impl QueryBlocks<SignedBlock> for FetchBlocksMock {
+	fn block_by_hash(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
+		Ok(self.blocks_to_be_fetched.iter().find(|b| &b.hash() == block_hash).cloned())
+	}
+
+	fn block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		Ok(self
+			.blocks_of_shard(shard_identifier)
+			.find(|b| number_of(b) == block_number)
+			.cloned())
+	}
+
+	fn block_hash(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<BlockHash>> {
+		Ok(self.block_by_number(shard_identifier, block_number)?.map(|b| b.hash()))
+	}
+
+	fn latest_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<SignedBlock>> {
+		Ok(self.blocks_of_shard(shard_identifier).max_by_key(|b| number_of(b)).cloned())
+	}
+
+	fn blocks_by_number_range(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		from: BlockNumber,
+		until: BlockNumber,
+	) -> Result<Vec<SignedBlock>> {
+		Ok(self
+			.blocks_of_shard(shard_identifier)
+			.filter(|b| (from..=until).contains(&number_of(b)))
+			.cloned()
+			.collect())
+	}
+}
+
+fn number_of(block: &SignedBlock) -> BlockNumber {
+	block.block().header().block_number()
+}
@@ -0,0 +1,421 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Finality of sidechain blocks, based on the votes of the validateers.
+//!
+//! Every validateer signs the hashes of the sidechain blocks it has imported and sends these
+//! votes to its peers. A block is final once a supermajority (more than two thirds) of the
+//! validateers of its shard has voted for it and it is the block this validateer has stored at
+//! that block number. All its ancestors are final as well.
+
+use crate::{
+	interface::{FinalizeBlocks, QueryBlocks},
+	Result,
+};
+use itp_types::{AccountId, H256};
+use its_primitives::{
+	traits::{Block as BlockT, BlockData, SignedBlock as SignedBlockT},
+	types::{
+		finality::{FinalityVote, FinalizedBlock, SignedFinalityVote},
+		ShardIdentifier, SignedBlock as SignedSidechainBlock,
+	},
+};
+use log::*;
+use parking_lot::RwLock;
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+/// Fetches the validateers of a shard, whose votes count towards finality.
+pub trait FetchValidateers {
+	/// Validateers of the shard at the given parentchain block.
+	fn validateers_at(
+		&self,
+		shard: &ShardIdentifier,
+		parentchain_block_hash: &H256,
+	) -> Result<Vec<AccountId>>;
+}
+
+/// Import finality votes of validateers.
+pub trait ImportFinalityVotes {
+	fn import_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> Result<()>;
+}
+
+/// Fetch the last finalized sidechain block of a shard.
+pub trait FetchFinalizedBlock {
+	fn fetch_finalized_block(&self, shard: &ShardIdentifier) -> Option<FinalizedBlock>;
+}
+
+/// Validateers of a shard at the parentchain block the last sidechain block was built on.
+struct ShardValidateers {
+	parentchain_block_hash: H256,
+	validateers: Arc<Vec<AccountId>>,
+}
+
+/// Collects the finality votes of the validateers and finalizes the sidechain blocks
+/// that reached a supermajority.
+pub struct SidechainFinality<Storage, ValidateerFetcher> {
+	storage: Arc<Storage>,
+	validateer_fetcher: Arc<ValidateerFetcher>,
+	validateers: RwLock<HashMap<ShardIdentifier, ShardValidateers>>,
+	votes: RwLock<HashMap<FinalityVote, HashSet<AccountId>>>,
+}
+
+impl<Storage, ValidateerFetcher> SidechainFinality<Storage, ValidateerFetcher>
+where
+	Storage: FinalizeBlocks<SignedSidechainBlock> + QueryBlocks<SignedSidechainBlock>,
+	ValidateerFetcher: FetchValidateers,
+{
+	pub fn new(storage: Arc<Storage>, validateer_fetcher: Arc<ValidateerFetcher>) -> Self {
+		SidechainFinality {
+			storage,
+			validateer_fetcher,
+			validateers: RwLock::new(HashMap::new()),
+			votes: RwLock::new(HashMap::new()),
+		}
+	}
+
+	fn is_finalized(&self, vote: &FinalityVote) -> bool {
+		self.storage
+			.finalized_block(&vote.shard)
+			.map(|finalized_block| finalized_block.block_number >= vote.block_number)
+			.unwrap_or(false)
+	}
+
+	/// Validateers of the shard at the parentchain block our last sidechain block was built on.
+	///
+	/// They are fetched from the parentchain only once per parentchain block.
+	/// Returns `None` if we do not have any block of the shard yet.
+	fn validateers_of(&self, shard: &ShardIdentifier) -> Result<Option<Arc<Vec<AccountId>>>> {
+		let parentchain_block_hash = match self.storage.latest_block(shard)? {
+			Some(block) => block.block().block_data().layer_one_head(),
+			None => return Ok(None),
+		};
+		if let Some(cached) = self.validateers.read().get(shard) {
+			if cached.parentchain_block_hash == parentchain_block_hash {
+				return Ok(Some(cached.validateers.clone()))
+			}
+		}
+
+		let validateers =
+			Arc::new(self.validateer_fetcher.validateers_at(shard, &parentchain_block_hash)?);
+		self.validateers.write().insert(
+			*shard,
+			ShardValidateers { parentchain_block_hash, validateers: validateers.clone() },
+		);
+		Ok(Some(validateers))
+	}
+
+	/// The vote is for the block we have stored at that block number.
+	fn is_local_block(&self, vote: &FinalityVote) -> Result<bool> {
+		Ok(self.storage.block_hash(&vote.shard, vote.block_number)? == Some(vote.block_hash))
+	}
+}
+
+impl<Storage, ValidateerFetcher> ImportFinalityVotes
+	for SidechainFinality<Storage, ValidateerFetcher>
+where
+	Storage: FinalizeBlocks<SignedSidechainBlock> + QueryBlocks<SignedSidechainBlock>,
+	ValidateerFetcher: FetchValidateers,
+{
+	fn import_finality_votes(&self, votes: Vec<SignedFinalityVote>) -> Result<()> {
+		for signed_vote in votes {
+			let vote = signed_vote.vote;
+			if !signed_vote.verify_signature() {
+				warn!("Ignoring finality vote with invalid signature: {:?}", vote);
+				continue
+			}
+			if self.is_finalized(&vote) {
+				trace!("Ignoring finality vote for already finalized block: {:?}", vote);
+				continue
+			}
+
+			// Fetched before taking the votes lock, this may query the parentchain.
+			let shard_validateers = match self.validateers_of(&vote.shard)? {
+				Some(validateers) => validateers,
+				None => {
+					debug!("Ignoring finality vote for shard without local blocks: {:?}", vote);
+					continue
+				},
+			};
+			let signer = AccountId::from(signed_vote.signer);
+			if !shard_validateers.contains(&signer) {
+				warn!(
+					"Ignoring finality vote of {:?}, which is not a validateer: {:?}",
+					signer, vote
+				);
+				continue
+			}
+
+			let mut collected_votes = self.votes.write();
+			let signers = collected_votes.entry(vote).or_default();
+			signers.insert(signer);
+			if !is_supermajority(signers.len(), shard_validateers.len()) {
+				continue
+			}
+			// A block of another fork, or one we have not imported yet, must not become the
+			// safe point of our storage.
+			if !self.is_local_block(&vote)? {
+				warn!(
+					"Not finalizing sidechain block (nr: {}, hash: {:?}) of shard {:?}, it is not our block at that number",
+					vote.block_number, vote.block_hash, vote.shard
+				);
+				continue
+			}
+
+			info!(
+				"Finalized sidechain block (nr: {}, hash: {:?}) of shard {:?}",
+				vote.block_number, vote.block_hash, vote.shard
+			);
+			self.storage.finalize_block(
+				&vote.shard,
+				FinalizedBlock { block_number: vote.block_number, block_hash: vote.block_hash },
+			)?;
+			// Votes for the finalized block or any block below are not needed anymore.
+			collected_votes
+				.retain(|v, _| v.shard != vote.shard || v.block_number > vote.block_number);
+		}
+		Ok(())
+	}
+}
+
+impl<Storage, ValidateerFetcher> FetchFinalizedBlock
+	for SidechainFinality<Storage, ValidateerFetcher>
+where
+	Storage: FinalizeBlocks<SignedSidechainBlock>,
+{
+	fn fetch_finalized_block(&self, shard: &ShardIdentifier) -> Option<FinalizedBlock> {
+		self.storage.finalized_block(shard)
+	}
+}
+
+/// More than two thirds of the validateers have voted.
+fn is_supermajority(number_of_votes: usize, number_of_validateers: usize) -> bool {
+	3 * number_of_votes > 2 * number_of_validateers
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		interface::BlockStorage,
+		test_utils::{create_signed_block_with_shard, create_temp_dir},
+		SidechainStorageLock,
+	};
+	use sp_core::{ed25519, Pair};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use temp_dir::TempDir;
+
+	type TestFinality = SidechainFinality<SidechainStorageLock<SignedSidechainBlock>, Validateers>;
+
+	/// Number of the last block in the storage of the test finality.
+	const LAST_BLOCK_NUMBER: u64 = 7;
+
+	struct Validateers {
+		validateers: Vec<AccountId>,
+		number_of_fetches: AtomicUsize,
+	}
+
+	impl FetchValidateers for Validateers {
+		fn validateers_at(
+			&self,
+			_shard: &ShardIdentifier,
+			_parentchain_block_hash: &H256,
+		) -> Result<Vec<AccountId>> {
+			self.number_of_fetches.fetch_add(1, Ordering::SeqCst);
+			Ok(self.validateers.clone())
+		}
+	}
+
+	#[test]
+	fn block_is_finalized_once_a_supermajority_voted() {
+		let (finality, _temp_dir) = finality_with_validateers(4);
+		let vote = vote_for_local_block(&finality, 5);
+
+		finality.import_finality_votes(signed_votes(vote, 0..2)).unwrap();
+		assert!(finality.fetch_finalized_block(&shard()).is_none());
+
+		finality.import_finality_votes(signed_votes(vote, 2..3)).unwrap();
+		assert_eq!(
+			finality.fetch_finalized_block(&shard()),
+			Some(FinalizedBlock { block_number: 5, block_hash: vote.block_hash })
+		);
+	}
+
+	#[test]
+	fn repeated_votes_of_the_same_validateer_count_once() {
+		let (finality, _temp_dir) = finality_with_validateers(4);
+		let vote = FinalityVote::new(shard(), 5, H256::random());
+
+		for _ in 0..3 {
+			finality.import_finality_votes(signed_votes(vote, 0..1)).unwrap();
+		}
+		finality.import_finality_votes(signed_votes(vote, 1..2)).unwrap();
+
+		assert!(finality.fetch_finalized_block(&shard()).is_none());
+	}
+
+	#[test]
+	fn votes_of_non_validateers_are_ignored() {
+		let (finality, _temp_dir) = finality_with_validateers(3);
+		let vote = FinalityVote::new(shard(), 5, H256::random());
+
+		finality.import_finality_votes(signed_votes(vote, 1..5)).unwrap();
+
+		assert!(finality.fetch_finalized_block(&shard()).is_none());
+	}
+
+	#[test]
+	fn votes_with_invalid_signature_are_ignored() {
+		let (finality, _temp_dir) = finality_with_validateers(1);
+		let vote = FinalityVote::new(shard(), 5, H256::random());
+		let mut signed_votes = signed_votes(vote, 0..1);
+		signed_votes[0].vote.block_number = 6;
+
+		finality.import_finality_votes(signed_votes).unwrap();
+
+		assert!(finality.fetch_finalized_block(&shard()).is_none());
+	}
+
+	#[test]
+	fn votes_below_the_finalized_block_do_not_revert_finality() {
+		let (finality, _temp_dir) = finality_with_validateers(1);
+		let vote = vote_for_local_block(&finality, 5);
+		let older_vote = FinalityVote::new(shard(), 4, H256::random());
+
+		finality.import_finality_votes(signed_votes(vote, 0..1)).unwrap();
+		finality.import_finality_votes(signed_votes(older_vote, 0..1)).unwrap();
+
+		assert_eq!(finality.fetch_finalized_block(&shard()).unwrap().block_number, 5);
+	}
+
+	#[test]
+	fn votes_for_finalized_blocks_are_discarded() {
+		let (finality, _temp_dir) = finality_with_validateers(4);
+		let fork_vote = FinalityVote::new(shard(), 5, H256::random());
+		let vote = vote_for_local_block(&finality, 6);
+		let next_vote = FinalityVote::new(shard(), 7, H256::random());
+
+		finality.import_finality_votes(signed_votes(fork_vote, 0..1)).unwrap();
+		finality.import_finality_votes(signed_votes(next_vote, 0..1)).unwrap();
+		finality.import_finality_votes(signed_votes(vote, 0..3)).unwrap();
+
+		let collected_votes = finality.votes.read();
+		assert_eq!(collected_votes.len(), 1);
+		assert!(collected_votes.contains_key(&next_vote));
+	}
+
+	#[test]
+	fn block_that_is_not_the_local_one_is_not_finalized() {
+		let (finality, _temp_dir) = finality_with_validateers(1);
+		let fork_vote = FinalityVote::new(shard(), 5, H256::random());
+		let future_vote = FinalityVote::new(shard(), LAST_BLOCK_NUMBER + 1, H256::random());
+
+		finality.import_finality_votes(signed_votes(fork_vote, 0..1)).unwrap();
+		finality.import_finality_votes(signed_votes(future_vote, 0..1)).unwrap();
+
+		assert!(finality.fetch_finalized_block(&shard()).is_none());
+	}
+
+	#[test]
+	fn validateers_are_fetched_once_per_parentchain_block() {
+		let (finality, _temp_dir) = finality_with_validateers(4);
+		let vote = vote_for_local_block(&finality, 5);
+
+		finality.import_finality_votes(signed_votes(vote, 0..1)).unwrap();
+		finality.import_finality_votes(signed_votes(vote, 1..3)).unwrap();
+		assert_eq!(finality.validateer_fetcher.number_of_fetches.load(Ordering::SeqCst), 1);
+
+		// The new block is built on another parentchain block.
+		finality
+			.storage
+			.store_blocks(vec![create_signed_block_with_shard(LAST_BLOCK_NUMBER + 1, shard())])
+			.unwrap();
+		finality
+			.import_finality_votes(signed_votes(vote_for_local_block(&finality, 6), 0..1))
+			.unwrap();
+		assert_eq!(finality.validateer_fetcher.number_of_fetches.load(Ordering::SeqCst), 2);
+	}
+
+	#[test]
+	fn votes_are_ignored_without_local_blocks_of_the_shard() {
+		let (finality, _temp_dir) = finality_with_validateers(1);
+		let vote = FinalityVote::new(H256::from_low_u64_be(2), 5, H256::random());
+
+		finality.import_finality_votes(signed_votes(vote, 0..1)).unwrap();
+
+		assert!(finality.votes.read().is_empty());
+		assert_eq!(finality.validateer_fetcher.number_of_fetches.load(Ordering::SeqCst), 0);
+	}
+
+	#[test]
+	fn supermajority_requires_more_than_two_thirds() {
+		assert!(is_supermajority(1, 1));
+		assert!(!is_supermajority(1, 2));
+		assert!(is_supermajority(2, 2));
+		assert!(!is_supermajority(2, 3));
+		assert!(is_supermajority(3, 3));
+		assert!(!is_supermajority(2, 4));
+		assert!(is_supermajority(3, 4));
+		assert!(!is_supermajority(4, 6));
+		assert!(is_supermajority(5, 6));
+	}
+
+	fn finality_with_validateers(number_of_validateers: u8) -> (TestFinality, TempDir) {
+		let temp_dir = create_temp_dir();
+		let storage = Arc::new(
+			SidechainStorageLock::<SignedSidechainBlock>::from_base_path(
+				temp_dir.path().to_path_buf(),
+			)
+			.unwrap(),
+		);
+		storage
+			.store_blocks(
+				(1..=LAST_BLOCK_NUMBER)
+					.map(|number| create_signed_block_with_shard(number, shard()))
+					.collect(),
+			)
+			.unwrap();
+		let validateers = (0..number_of_validateers)
+			.map(|i| AccountId::from(validateer(i).public()))
+			.collect();
+		let validateer_fetcher =
+			Validateers { validateers, number_of_fetches: AtomicUsize::new(0) };
+		(SidechainFinality::new(storage, Arc::new(validateer_fetcher)), temp_dir)
+	}
+
+	fn vote_for_local_block(finality: &TestFinality, block_number: u64) -> FinalityVote {
+		let block_hash = finality.storage.block_hash(&shard(), block_number).unwrap().unwrap();
+		FinalityVote::new(shard(), block_number, block_hash)
+	}
+
+	fn signed_votes(
+		vote: FinalityVote,
+		validateers: std::ops::Range<u8>,
+	) -> Vec<SignedFinalityVote> {
+		validateers.map(|i| vote.sign(&validateer(i))).collect()
+	}
+
+	fn validateer(index: u8) -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[index; 32])
+	}
+
+	fn shard() -> ShardIdentifier {
+		H256::from_low_u64_be(1)
+	}
+}
@@ -23,7 +23,7 @@ use mockall::*;
 use super::{storage::SidechainStorage, Result};
 use its_primitives::{
 	traits::{ShardIdentifierFor, SignedBlock as SignedBlockT},
-	types::{BlockHash, BlockNumber},
+	types::{finality::FinalizedBlock, BlockHash, BlockNumber},
 };
 use parking_lot::RwLock;
 use std::path::PathBuf;
@@ -55,6 +55,23 @@ pub trait BlockPruner {
 	fn prune_blocks_except(&self, number_of_blocks_to_keep: u64);
 }
 
+/// Keeps track of the last finalized block of every shard.
+#[cfg_attr(test, automock)]
+pub trait FinalizeBlocks<SignedBlock: SignedBlockT> {
+	/// Set the last finalized block of a shard. Blocks below the current one are ignored.
+	fn finalize_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		finalized_block: FinalizedBlock,
+	) -> Result<()>;
+
+	/// Get the last finalized block of a shard, if any.
+	fn finalized_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<FinalizedBlock>;
+}
+
 #[cfg_attr(test, automock)]
 pub trait FetchBlocks<SignedBlock: SignedBlockT> {
 	/// Fetch all child blocks of a specified block.
@@ -79,6 +96,43 @@ pub trait FetchBlocks<SignedBlock: SignedBlockT> {
 	) -> Result<Vec<SignedBlock>>;
 }
 
+/// Look up single blocks or ranges of blocks, e.g. for a block explorer.
+#[cfg_attr(test, automock)]
+pub trait QueryBlocks<SignedBlock: SignedBlockT> {
+	/// Fetch the block with the given hash, if it is in the storage.
+	fn block_by_hash(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>>;
+
+	/// Fetch the block of a shard with the given block number, if it is in the storage.
+	fn block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>>;
+
+	/// Fetch the hash of the block of a shard with the given block number, if it is in the storage.
+	fn block_hash(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<BlockHash>>;
+
+	/// Fetch the last block of a shard, if there is any.
+	fn latest_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<SignedBlock>>;
+
+	/// Fetch the blocks of a shard with a block number from `from` to `until` (both included).
+	///
+	/// Blocks that are not in the storage (anymore) are skipped.
+	fn blocks_by_number_range(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		from: BlockNumber,
+		until: BlockNumber,
+	) -> Result<Vec<SignedBlock>>;
+}
+
 impl<SignedBlock: SignedBlockT> BlockStorage<SignedBlock> for SidechainStorageLock<SignedBlock> {
 	fn store_blocks(&self, blocks: Vec<SignedBlock>) -> Result<()> {
 		self.storage.write().store_blocks(blocks)
@@ -91,6 +145,23 @@ impl<SignedBlock: SignedBlockT> BlockPruner for SidechainStorageLock<SignedBlock
 	}
 }
 
+impl<SignedBlock: SignedBlockT> FinalizeBlocks<SignedBlock> for SidechainStorageLock<SignedBlock> {
+	fn finalize_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		finalized_block: FinalizedBlock,
+	) -> Result<()> {
+		self.storage.write().finalize_block(shard_identifier, finalized_block)
+	}
+
+	fn finalized_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<FinalizedBlock> {
+		self.storage.read().finalized_block_of_shard(shard_identifier).copied()
+	}
+}
+
 impl<SignedBlock: SignedBlockT> FetchBlocks<SignedBlock> for SidechainStorageLock<SignedBlock> {
 	fn fetch_all_blocks_after(
 		&self,
@@ -111,3 +182,45 @@ impl<SignedBlock: SignedBlockT> FetchBlocks<SignedBlock> for SidechainStorageLoc
 			.get_blocks_in_range(block_hash_from, block_hash_until, shard_identifier)
 	}
 }
+
+impl<SignedBlock: SignedBlockT> QueryBlocks<SignedBlock> for SidechainStorageLock<SignedBlock> {
+	fn block_by_hash(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
+		self.storage.read().get_block(block_hash)
+	}
+
+	fn block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		self.storage.read().get_block_by_number(shard_identifier, block_number)
+	}
+
+	fn block_hash(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<BlockHash>> {
+		self.storage.read().get_block_hash(shard_identifier, block_number)
+	}
+
+	fn latest_block(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<SignedBlock>> {
+		let storage = self.storage.read();
+		match storage.last_block_of_shard(shard_identifier) {
+			Some(last_block) => storage.get_block(&last_block.hash),
+			None => Ok(None),
+		}
+	}
+
+	fn blocks_by_number_range(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		from: BlockNumber,
+		until: BlockNumber,
+	) -> Result<Vec<SignedBlock>> {
+		self.storage.read().get_blocks_by_number_range(shard_identifier, from, until)
+	}
+}
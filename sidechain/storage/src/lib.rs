@@ -26,6 +26,7 @@ use std::{
 
 mod db;
 mod error;
+pub mod finality;
 pub mod interface;
 mod storage;
 
@@ -42,6 +43,7 @@ mod test_utils;
 pub mod fetch_blocks_mock;
 
 pub use error::{Error, Result};
+pub use finality::{FetchFinalizedBlock, FetchValidateers, ImportFinalityVotes, SidechainFinality};
 pub use interface::{BlockPruner, BlockStorage, SidechainStorageLock};
 
 pub fn start_sidechain_pruning_loop<D>(
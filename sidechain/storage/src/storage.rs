@@ -20,7 +20,7 @@ use codec::{Decode, Encode};
 use itp_settings::files::SIDECHAIN_STORAGE_PATH;
 use its_primitives::{
 	traits::{Block as BlockTrait, Header as HeaderTrait, SignedBlock as SignedBlockT},
-	types::{BlockHash, BlockNumber},
+	types::{finality::FinalizedBlock, BlockHash, BlockNumber},
 };
 use log::*;
 use rocksdb::WriteBatch;
@@ -31,6 +31,8 @@ use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 const LAST_BLOCK_KEY: &[u8] = b"last_sidechainblock";
 /// key value of the stored shards vector
 const STORED_SHARDS_KEY: &[u8] = b"stored_shards";
+/// key value of sidechain db of the last finalized block
+const FINALIZED_BLOCK_KEY: &[u8] = b"finalized_sidechainblock";
 
 /// ShardIdentifier type
 type ShardIdentifierFor<B> =
@@ -55,6 +57,8 @@ pub struct SidechainStorage<SignedBlock: SignedBlockT> {
 	shards: Vec<ShardIdentifierFor<SignedBlock>>,
 	/// map to last sidechain block of every shard
 	last_blocks: HashMap<ShardIdentifierFor<SignedBlock>, LastSidechainBlock>,
+	/// map to last finalized sidechain block of every shard
+	finalized_blocks: HashMap<ShardIdentifierFor<SignedBlock>, FinalizedBlock>,
 }
 
 impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
@@ -64,7 +68,12 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	pub fn load_from_base_path(base_path: PathBuf) -> Result<SidechainStorage<SignedBlock>> {
 		// load db
 		let db = SidechainDB::open_default(base_path.join(SIDECHAIN_STORAGE_PATH))?;
-		let mut storage = SidechainStorage { db, shards: vec![], last_blocks: HashMap::new() };
+		let mut storage = SidechainStorage {
+			db,
+			shards: vec![],
+			last_blocks: HashMap::new(),
+			finalized_blocks: HashMap::new(),
+		};
 		storage.shards = storage.load_shards_from_db()?;
 		// get last block of each shard
 		for shard in storage.shards.iter() {
@@ -74,6 +83,9 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 				// an empty shard sidechain storage should not exist. Consider deleting this shard from the shards list.
 				error!("Sidechain storage of shard {:?} is empty", shard);
 			}
+			if let Some(finalized_block) = storage.load_finalized_block_from_db(shard)? {
+				storage.finalized_blocks.insert(*shard, finalized_block);
+			}
 		}
 		Ok(storage)
 	}
@@ -91,6 +103,34 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		self.last_blocks.get(shard)
 	}
 
+	/// gets the last finalized block of the given shard
+	pub fn finalized_block_of_shard(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+	) -> Option<&FinalizedBlock> {
+		self.finalized_blocks.get(shard)
+	}
+
+	/// Marks a block as the last finalized block of the given shard.
+	///
+	/// Blocks that are not newer than the currently finalized block are ignored.
+	pub fn finalize_block(
+		&mut self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		finalized_block: FinalizedBlock,
+	) -> Result<()> {
+		if let Some(current) = self.finalized_block_of_shard(shard) {
+			if current.block_number >= finalized_block.block_number {
+				return Ok(())
+			}
+		}
+		let mut batch = WriteBatch::default();
+		SidechainDB::add_to_batch(&mut batch, (FINALIZED_BLOCK_KEY, *shard), finalized_block);
+		self.db.write(batch)?;
+		self.finalized_blocks.insert(*shard, finalized_block);
+		Ok(())
+	}
+
 	/// gets the block hash of the sidechain block of the given shard and block number, if there is such a block
 	pub fn get_block_hash(
 		&self,
@@ -106,6 +146,40 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		self.db.get(block_hash)
 	}
 
+	/// gets the block of the given shard and block number, if there is such a block
+	pub fn get_block_by_number(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		match self.get_block_hash(shard, block_number)? {
+			Some(block_hash) => self.get_block(&block_hash),
+			None => Ok(None),
+		}
+	}
+
+	/// Get the blocks of a shard with a block number from `from` to `until` (both included).
+	///
+	/// Blocks that are not in the storage (e.g. pruned ones) are skipped.
+	pub fn get_blocks_by_number_range(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		from: BlockNumber,
+		until: BlockNumber,
+	) -> Result<Vec<SignedBlock>> {
+		let last_block_number = match self.last_block_of_shard(shard) {
+			Some(last_block) => last_block.number,
+			None => return Ok(Vec::new()),
+		};
+		let mut blocks = Vec::new();
+		for block_number in from..=until.min(last_block_number) {
+			if let Some(block) = self.get_block_by_number(shard, block_number)? {
+				blocks.push(block);
+			}
+		}
+		Ok(blocks)
+	}
+
 	/// Get all blocks after (i.e. children of) a specified block.
 	pub fn get_blocks_after(
 		&self,
@@ -199,6 +273,9 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 			current_block_number = previous_block.number;
 			self.delete_block(&mut batch, &previous_block.hash, &current_block_number, shard);
 		}
+		// Remove finalized block.
+		SidechainDB::delete_to_batch(&mut batch, (FINALIZED_BLOCK_KEY, *shard));
+		self.finalized_blocks.remove(shard);
 		// Remove shard from list.
 		// STORED_SHARDS_KEY -> Vec<(Shard)>
 		self.shards.retain(|&x| x != *shard);
@@ -234,11 +311,27 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	}
 
 	/// Prunes all shards except for the newest blocks (according to blocknumber).
+	///
+	/// The last finalized block is the safe point: neither it nor any block above it is pruned.
+	/// Shards without a finalized block are not pruned at all.
 	pub fn prune_shards(&mut self, number_of_blocks_to_keep: BlockNumber) {
 		for shard in self.shards().clone() {
 			// get last block:
 			if let Some(last_block) = self.last_block_of_shard(&shard) {
-				let threshold_block = last_block.number - number_of_blocks_to_keep;
+				let finalized_block_number = match self.finalized_block_of_shard(&shard) {
+					Some(finalized_block) => finalized_block.block_number,
+					None => {
+						debug!("No finalized block in shard {:?}, skipping pruning", shard);
+						continue
+					},
+				};
+				let threshold_block = last_block
+					.number
+					.saturating_sub(number_of_blocks_to_keep)
+					.min(finalized_block_number.saturating_sub(1));
+				if threshold_block == 0 {
+					continue
+				}
 				if let Err(e) = self.prune_shard_from_block_number(&shard, threshold_block) {
 					error!("Could not purge shard {:?} due to {:?}", shard, e);
 				}
@@ -256,7 +349,14 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	) -> Result<()> {
 		let shard = &signed_block.block().header().shard_id();
 		if self.shards.contains(shard) {
-			if !self.verify_block_ancestry(signed_block.block()) {
+			if self.is_fork_of_stored_chain(signed_block.block())? {
+				// The block replaces the stored blocks from its block number onward (reorg).
+				self.retract_blocks_from(
+					batch,
+					shard,
+					signed_block.block().header().block_number(),
+				)?;
+			} else if !self.verify_block_ancestry(signed_block.block()) {
 				// Do not include block if its not a direct ancestor of the last block in line.
 				return Err(Error::HeaderAncestryMismatch)
 			}
@@ -288,6 +388,64 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		true
 	}
 
+	/// Checks if the block does not extend the last block, but forks off a block further down
+	/// the stored chain. This is the case after the sidechain switched to a competing fork.
+	fn is_fork_of_stored_chain(
+		&self,
+		block: &<SignedBlock as SignedBlockT>::Block,
+	) -> Result<bool> {
+		let shard = &block.header().shard_id();
+		let block_number = block.header().block_number();
+		let last_block = match self.last_block_of_shard(shard) {
+			Some(last_block) => last_block,
+			None => return Ok(false),
+		};
+		if block_number == 0 || block_number > last_block.number {
+			return Ok(false)
+		}
+		if self.get_block_hash(shard, block_number)? == Some(block.hash()) {
+			// The block itself is already stored.
+			return Ok(false)
+		}
+
+		let parent_hash = block.header().parent_hash();
+		if block_number == 1 {
+			return Ok(parent_hash == BlockHash::default())
+		}
+		Ok(self.get_block_hash(shard, block_number - 1)? == Some(parent_hash))
+	}
+
+	/// Adds delete commands for all stored blocks of a shard, starting at the given block number.
+	///
+	/// Fails if a finalized block would be retracted.
+	fn retract_blocks_from(
+		&self,
+		batch: &mut WriteBatch,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<()> {
+		if let Some(finalized_block) = self.finalized_block_of_shard(shard) {
+			if block_number <= finalized_block.block_number {
+				return Err(Error::ForkBelowFinalizedBlock(
+					block_number,
+					finalized_block.block_number,
+					format!("{:?}", *shard),
+				))
+			}
+		}
+		let last_block = self.get_last_block_of_shard(shard)?;
+		for number in block_number..=last_block.number {
+			if let Some(block_hash) = self.get_block_hash(shard, number)? {
+				info!(
+					"[Sidechain DB] Retracting block (nr: {:?}, hash: {:?}) of shard {:?}",
+					number, block_hash, *shard
+				);
+				self.delete_block(batch, &block_hash, &number, shard);
+			}
+		}
+		Ok(())
+	}
+
 	/// Implementations of helper functions, not meant for pub use
 	/// gets the previous block of given shard and block number, if there is one.
 	fn get_previous_block(
@@ -311,6 +469,13 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		self.db.get((LAST_BLOCK_KEY, *shard))
 	}
 
+	fn load_finalized_block_from_db(
+		&self,
+		shard: &ShardIdentifierFor<SignedBlock>,
+	) -> Result<Option<FinalizedBlock>> {
+		self.db.get((FINALIZED_BLOCK_KEY, *shard))
+	}
+
 	fn get_last_block_of_shard(
 		&self,
 		shard: &ShardIdentifierFor<SignedBlock>,
@@ -388,10 +553,15 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 mod test {
 	use super::*;
 	use crate::test_utils::{
-		create_signed_block_with_shard as create_signed_block, create_temp_dir, get_storage,
+		create_signed_block_with_parenthash, create_signed_block_with_shard as create_signed_block,
+		create_temp_dir, get_storage,
 	};
 	use itp_types::ShardIdentifier;
 	use its_primitives::{traits::SignedBlock as SignedBlockT, types::SignedBlock};
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
 	use sp_core::H256;
 
 	#[test]
@@ -926,6 +1096,88 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn block_forking_off_the_stored_chain_replaces_retracted_blocks() {
+		let temp_dir = create_temp_dir();
+		let block_one = create_signed_block_with_parenthash(1, BlockHash::default());
+		let block_two = create_signed_block_with_parenthash(2, block_one.hash());
+		let block_three = create_signed_block_with_parenthash(3, block_two.hash());
+		let fork_block_two = SidechainBlockBuilder::default()
+			.with_header(
+				SidechainHeaderBuilder::default()
+					.with_parent_hash(block_one.hash())
+					.with_block_number(2)
+					.with_block_data_hash(H256::random())
+					.build(),
+			)
+			.build_signed();
+		let shard = block_one.block().header().shard_id();
+
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db
+				.store_blocks(vec![block_one.clone(), block_two.clone(), block_three.clone()])
+				.unwrap();
+			sidechain_db.store_blocks(vec![fork_block_two.clone()]).unwrap();
+		}
+
+		{
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			let last_block = updated_sidechain_db.last_block_of_shard(&shard).unwrap();
+			assert_eq!(last_block.number, 2);
+			assert_eq!(last_block.hash, fork_block_two.hash());
+			assert_eq!(
+				updated_sidechain_db.get_block_hash(&shard, 2).unwrap(),
+				Some(fork_block_two.hash())
+			);
+			assert!(updated_sidechain_db.get_block_hash(&shard, 3).unwrap().is_none());
+			assert!(updated_sidechain_db.get_block(&block_two.hash()).unwrap().is_none());
+			assert!(updated_sidechain_db.get_block(&block_three.hash()).unwrap().is_none());
+			assert_eq!(
+				updated_sidechain_db.get_block(&block_one.hash()).unwrap().unwrap(),
+				block_one
+			);
+		}
+	}
+
+	#[test]
+	fn block_forking_off_below_the_finalized_block_is_rejected() {
+		let temp_dir = create_temp_dir();
+		let block_one = create_signed_block_with_parenthash(1, BlockHash::default());
+		let block_two = create_signed_block_with_parenthash(2, block_one.hash());
+		let block_three = create_signed_block_with_parenthash(3, block_two.hash());
+		let fork_block_two = SidechainBlockBuilder::default()
+			.with_header(
+				SidechainHeaderBuilder::default()
+					.with_parent_hash(block_one.hash())
+					.with_block_number(2)
+					.with_block_data_hash(H256::random())
+					.build(),
+			)
+			.build_signed();
+		let shard = block_one.block().header().shard_id();
+
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db
+				.store_blocks(vec![block_one.clone(), block_two.clone(), block_three.clone()])
+				.unwrap();
+			sidechain_db.finalize_block(&shard, finalized(&block_two)).unwrap();
+			sidechain_db.store_blocks(vec![fork_block_two.clone()]).unwrap();
+		}
+
+		{
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			let last_block = updated_sidechain_db.last_block_of_shard(&shard).unwrap();
+			assert_eq!(last_block.hash, block_three.hash());
+			assert_eq!(
+				updated_sidechain_db.get_block_hash(&shard, 2).unwrap(),
+				Some(block_two.hash())
+			);
+			assert!(updated_sidechain_db.get_block(&fork_block_two.hash()).unwrap().is_none());
+		}
+	}
+
 	#[test]
 	fn get_previous_block_returns_correct_block() {
 		let temp_dir = create_temp_dir();
@@ -966,6 +1218,46 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn get_block_by_number_works() {
+		let shard = H256::from_low_u64_be(1);
+		let signed_block_one = create_signed_block(20, shard);
+		let signed_block_two = create_signed_block(21, shard);
+		let temp_dir = create_temp_dir();
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		sidechain_db
+			.store_blocks(vec![signed_block_one.clone(), signed_block_two.clone()])
+			.unwrap();
+
+		assert_eq!(sidechain_db.get_block_by_number(&shard, 20).unwrap(), Some(signed_block_one));
+		assert_eq!(sidechain_db.get_block_by_number(&shard, 21).unwrap(), Some(signed_block_two));
+		assert!(sidechain_db.get_block_by_number(&shard, 22).unwrap().is_none());
+		assert!(sidechain_db
+			.get_block_by_number(&H256::from_low_u64_be(2), 20)
+			.unwrap()
+			.is_none());
+	}
+
+	#[test]
+	fn get_blocks_by_number_range_returns_stored_blocks_of_the_range() {
+		let shard = H256::from_low_u64_be(1);
+		let blocks: Vec<_> = (20..25).map(|n| create_signed_block(n, shard)).collect();
+		let temp_dir = create_temp_dir();
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		sidechain_db.store_blocks(blocks.clone()).unwrap();
+
+		assert_eq!(
+			sidechain_db.get_blocks_by_number_range(&shard, 21, 23).unwrap(),
+			blocks[1..4].to_vec()
+		);
+		assert_eq!(sidechain_db.get_blocks_by_number_range(&shard, 0, u64::MAX).unwrap(), blocks);
+		assert!(sidechain_db.get_blocks_by_number_range(&shard, 23, 21).unwrap().is_empty());
+		assert!(sidechain_db
+			.get_blocks_by_number_range(&H256::from_low_u64_be(2), 0, 30)
+			.unwrap()
+			.is_empty());
+	}
+
 	#[test]
 	fn purge_shard_works() {
 		let temp_dir = create_temp_dir();
@@ -1116,6 +1408,8 @@ mod test {
 				.store_blocks(vec![block_three.clone(), block_three_s.clone()])
 				.unwrap();
 			sidechain_db.store_blocks(vec![block_four_s.clone()]).unwrap();
+			sidechain_db.finalize_block(&shard_one, finalized(&block_three)).unwrap();
+			sidechain_db.finalize_block(&shard_two, finalized(&block_four_s)).unwrap();
 
 			sidechain_db.prune_shards(2);
 		}
@@ -1172,4 +1466,101 @@ mod test {
 			assert!(updated_sidechain_db.get_block(&block_two_s.hash()).unwrap().is_none());
 		}
 	}
+
+	#[test]
+	fn prune_shards_keeps_blocks_from_the_finalized_block_onward() {
+		let temp_dir = create_temp_dir();
+		let shard = H256::from_low_u64_be(1);
+		let block_one = create_signed_block(1, shard);
+		let block_two = create_signed_block(2, shard);
+		let block_three = create_signed_block(3, shard);
+		let block_four = create_signed_block(4, shard);
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db
+				.store_blocks(vec![
+					block_one.clone(),
+					block_two.clone(),
+					block_three.clone(),
+					block_four.clone(),
+				])
+				.unwrap();
+			sidechain_db.finalize_block(&shard, finalized(&block_two)).unwrap();
+
+			sidechain_db.prune_shards(1);
+		}
+
+		{
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			assert!(updated_sidechain_db.get_block(&block_one.hash()).unwrap().is_none());
+			assert!(updated_sidechain_db.get_block_hash(&shard, 1).unwrap().is_none());
+			for block in [block_two, block_three, block_four] {
+				let block_number = block.block().header().block_number();
+				assert_eq!(
+					updated_sidechain_db.get_block_hash(&shard, block_number).unwrap().unwrap(),
+					block.hash()
+				);
+				assert_eq!(updated_sidechain_db.get_block(&block.hash()).unwrap().unwrap(), block);
+			}
+		}
+	}
+
+	#[test]
+	fn prune_shards_does_not_prune_shards_without_finalized_block() {
+		let temp_dir = create_temp_dir();
+		let shard = H256::from_low_u64_be(1);
+		let block_one = create_signed_block(1, shard);
+		let block_two = create_signed_block(2, shard);
+		let block_three = create_signed_block(3, shard);
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		sidechain_db
+			.store_blocks(vec![block_one.clone(), block_two.clone(), block_three.clone()])
+			.unwrap();
+
+		sidechain_db.prune_shards(1);
+
+		assert_eq!(sidechain_db.get_block(&block_one.hash()).unwrap().unwrap(), block_one);
+		assert_eq!(sidechain_db.get_block(&block_two.hash()).unwrap().unwrap(), block_two);
+		assert_eq!(sidechain_db.get_block(&block_three.hash()).unwrap().unwrap(), block_three);
+	}
+
+	#[test]
+	fn finalized_block_is_loaded_from_db() {
+		let temp_dir = create_temp_dir();
+		let shard = H256::from_low_u64_be(1);
+		let block_one = create_signed_block(1, shard);
+		let block_two = create_signed_block(2, shard);
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db.store_blocks(vec![block_one, block_two.clone()]).unwrap();
+			sidechain_db.finalize_block(&shard, finalized(&block_two)).unwrap();
+		}
+
+		let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_eq!(
+			*updated_sidechain_db.finalized_block_of_shard(&shard).unwrap(),
+			finalized(&block_two)
+		);
+	}
+
+	#[test]
+	fn finalize_block_ignores_blocks_below_the_finalized_block() {
+		let temp_dir = create_temp_dir();
+		let shard = H256::from_low_u64_be(1);
+		let block_one = create_signed_block(1, shard);
+		let block_two = create_signed_block(2, shard);
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+
+		sidechain_db.finalize_block(&shard, finalized(&block_two)).unwrap();
+		sidechain_db.finalize_block(&shard, finalized(&block_one)).unwrap();
+
+		assert_eq!(*sidechain_db.finalized_block_of_shard(&shard).unwrap(), finalized(&block_two));
+	}
+
+	fn finalized(block: &SignedBlock) -> FinalizedBlock {
+		FinalizedBlock {
+			block_number: block.block().header().block_number(),
+			block_hash: block.hash(),
+		}
+	}
 }